@@ -0,0 +1,172 @@
+//! Where commands read accounts from and send transactions to: an RPC node, or LiteSVM
+//! in tests.
+
+use anyhow::{anyhow, Result};
+use solana_account_decoder_client_types::UiAccountEncoding;
+use solana_client::rpc_client::RpcClient;
+use solana_client::rpc_config::{RpcAccountInfoConfig, RpcProgramAccountsConfig};
+use solana_client::rpc_filter::{Memcmp, RpcFilterType};
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::hash::Hash;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+use solana_sdk::transaction::Transaction;
+
+/// Result of a sent or simulated transaction.
+#[derive(Debug)]
+pub struct Outcome {
+    pub signature: Signature,
+    pub logs: Vec<String>,
+    /// Set when the transaction failed; only simulations report failures this way.
+    pub error: Option<String>,
+}
+
+pub trait Backend {
+    fn account_data(&self, address: &Pubkey) -> Result<Option<Vec<u8>>>;
+
+    /// Program accounts whose data starts with `discriminator`.
+    fn program_accounts(&self, discriminator: &[u8]) -> Result<Vec<(Pubkey, Vec<u8>)>>;
+
+    fn latest_blockhash(&self) -> Result<Hash>;
+
+    /// Sends `tx` and waits for confirmation. Failed transactions are errors.
+    fn send(&mut self, tx: Transaction) -> Result<Outcome>;
+
+    fn simulate(&self, tx: Transaction) -> Result<Outcome>;
+}
+
+pub struct RpcBackend {
+    client: RpcClient,
+}
+
+impl RpcBackend {
+    pub fn new(url: String) -> Self {
+        Self {
+            client: RpcClient::new_with_commitment(url, CommitmentConfig::confirmed()),
+        }
+    }
+}
+
+impl Backend for RpcBackend {
+    fn account_data(&self, address: &Pubkey) -> Result<Option<Vec<u8>>> {
+        let account = self
+            .client
+            .get_account_with_commitment(address, self.client.commitment())?
+            .value;
+        Ok(account.map(|account| account.data))
+    }
+
+    fn program_accounts(&self, discriminator: &[u8]) -> Result<Vec<(Pubkey, Vec<u8>)>> {
+        let config = RpcProgramAccountsConfig {
+            filters: Some(vec![RpcFilterType::Memcmp(Memcmp::new_raw_bytes(
+                0,
+                discriminator.to_vec(),
+            ))]),
+            account_config: RpcAccountInfoConfig {
+                encoding: Some(UiAccountEncoding::Base64),
+                ..RpcAccountInfoConfig::default()
+            },
+            ..RpcProgramAccountsConfig::default()
+        };
+        let accounts = self
+            .client
+            .get_program_accounts_with_config(&rational_dex::ID, config)?;
+        Ok(accounts
+            .into_iter()
+            .map(|(address, account)| (address, account.data))
+            .collect())
+    }
+
+    fn latest_blockhash(&self) -> Result<Hash> {
+        Ok(self.client.get_latest_blockhash()?)
+    }
+
+    fn send(&mut self, tx: Transaction) -> Result<Outcome> {
+        let signature = self.client.send_and_confirm_transaction(&tx)?;
+        Ok(Outcome {
+            signature,
+            logs: Vec::new(),
+            error: None,
+        })
+    }
+
+    fn simulate(&self, tx: Transaction) -> Result<Outcome> {
+        let result = self.client.simulate_transaction(&tx)?.value;
+        Ok(Outcome {
+            signature: tx.signatures[0],
+            logs: result.logs.unwrap_or_default(),
+            error: result.err.map(|err| err.to_string()),
+        })
+    }
+}
+
+/// LiteSVM has no account index, so the backend remembers every account its transactions
+/// touched and lists the ones the program owns.
+#[cfg(feature = "litesvm")]
+pub struct LiteSvmBackend {
+    pub svm: litesvm::LiteSVM,
+    touched: std::collections::BTreeSet<Pubkey>,
+}
+
+#[cfg(feature = "litesvm")]
+impl LiteSvmBackend {
+    pub fn new(svm: litesvm::LiteSVM) -> Self {
+        Self {
+            svm,
+            touched: Default::default(),
+        }
+    }
+}
+
+#[cfg(feature = "litesvm")]
+impl Backend for LiteSvmBackend {
+    fn account_data(&self, address: &Pubkey) -> Result<Option<Vec<u8>>> {
+        Ok(self.svm.get_account(address).map(|account| account.data))
+    }
+
+    fn program_accounts(&self, discriminator: &[u8]) -> Result<Vec<(Pubkey, Vec<u8>)>> {
+        Ok(self
+            .touched
+            .iter()
+            .filter_map(|address| Some((*address, self.svm.get_account(address)?)))
+            .filter(|(_, account)| {
+                account.owner == rational_dex::ID && account.data.starts_with(discriminator)
+            })
+            .map(|(address, account)| (address, account.data))
+            .collect())
+    }
+
+    fn latest_blockhash(&self) -> Result<Hash> {
+        Ok(self.svm.latest_blockhash())
+    }
+
+    fn send(&mut self, tx: Transaction) -> Result<Outcome> {
+        let signature = tx.signatures[0];
+        self.touched.extend(tx.message.account_keys.iter().copied());
+        let meta = self
+            .svm
+            .send_transaction(tx)
+            .map_err(|failed| anyhow!("{} {:?}", failed.err, failed.meta.logs))?;
+        Ok(Outcome {
+            signature,
+            logs: meta.logs,
+            error: None,
+        })
+    }
+
+    fn simulate(&self, tx: Transaction) -> Result<Outcome> {
+        let signature = tx.signatures[0];
+        Ok(match self.svm.simulate_transaction(tx) {
+            Ok(info) => Outcome {
+                signature,
+                logs: info.meta.logs,
+                error: None,
+            },
+            Err(failed) => Outcome {
+                signature,
+                logs: failed.meta.logs,
+                error: Some(failed.err.to_string()),
+            },
+        })
+    }
+}
@@ -0,0 +1,163 @@
+//! Operator commands for the `rational_dex` program. `main.rs` wires them to the command
+//! line; tests drive them against LiteSVM through the `litesvm` feature.
+
+pub mod backend;
+
+use anchor_lang::{AccountDeserialize, Discriminator};
+use anyhow::{anyhow, bail, Context, Result};
+use sha2::{Digest, Sha256};
+use solana_sdk::instruction::Instruction;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{Keypair, Signer};
+use solana_sdk::transaction::Transaction;
+
+use rational_dex::instruction as args;
+use rational_dex::state::conviction_state::{AuthorState, EvidenceRef, HashAlgorithm, Proposal};
+use rational_dex::state::pool_state::Pool;
+use rational_dex::state::swap_state::Offer;
+use rational_dex_client::accounts::decode;
+use rational_dex_client::arcium::{self, MXEAccount};
+use rational_dex_client::instructions::{self, PoolKeys, ProposalKeys};
+use rational_dex_client::pda;
+
+use backend::{Backend, Outcome};
+
+/// A signer and the backend its transactions go to. With `dry_run` set, transactions are
+/// simulated and never sent.
+pub struct Session<B> {
+    pub backend: B,
+    pub payer: Keypair,
+    pub dry_run: bool,
+}
+
+impl<B: Backend> Session<B> {
+    pub fn new(backend: B, payer: Keypair, dry_run: bool) -> Self {
+        Self {
+            backend,
+            payer,
+            dry_run,
+        }
+    }
+
+    /// Signs `ixs` with the payer and sends or simulates them.
+    pub fn submit(&mut self, ixs: &[Instruction]) -> Result<Outcome> {
+        let blockhash = self.backend.latest_blockhash()?;
+        let tx = Transaction::new_signed_with_payer(
+            ixs,
+            Some(&self.payer.pubkey()),
+            &[&self.payer],
+            blockhash,
+        );
+        if self.dry_run {
+            self.backend.simulate(tx)
+        } else {
+            self.backend.send(tx)
+        }
+    }
+
+    /// Creates the pool for `keys`, with the payer as its authority.
+    pub fn init_pool(&mut self, keys: &PoolKeys, fee_num: u64, fee_den: u64) -> Result<Outcome> {
+        let ix = instructions::initialize_pool(self.payer.pubkey(), keys, fee_num, fee_den);
+        self.submit(&[ix])
+    }
+
+    /// Publishes a consensus price for `pool`, which the payer must be the authority of.
+    pub fn set_price(&mut self, pool: Pubkey, price: u64) -> Result<Outcome> {
+        let ix = instructions::update_consensus_price(self.payer.pubkey(), pool, price);
+        self.submit(&[ix])
+    }
+
+    /// Creates poll `id` on `mint0`/`mint1` starting from `price`. The tally is set up by
+    /// an Arcium computation, so this needs a cluster attached to the program's MXE.
+    pub fn create_poll(
+        &mut self,
+        id: u32,
+        price: u64,
+        mint0: Pubkey,
+        mint1: Pubkey,
+    ) -> Result<Outcome> {
+        let mxe: MXEAccount = self
+            .fetch(&arcium::mxe_account())?
+            .context("the program's MXE account is not initialized")?;
+        let ix = arcium::create_new_poll(
+            self.payer.pubkey(),
+            &mxe,
+            args::CreateNewPoll {
+                computation_offset: rand::random(),
+                id,
+                price,
+                mint0,
+                mint1,
+                nonce: rand::random(),
+            },
+        )?;
+        self.submit(&[ix])
+    }
+
+    /// Creates the payer's next proposal, backed by `evidence` hashed with SHA-256.
+    /// Returns the proposal count it was created under.
+    pub fn create_proposal(
+        &mut self,
+        evidence: &[u8],
+        uri: Option<String>,
+        voting_period: i64,
+        min_quorum_weight: u64,
+    ) -> Result<(u64, Outcome)> {
+        let author = self.payer.pubkey();
+        let proposal_count = self
+            .fetch::<AuthorState>(&pda::author_state(&author))?
+            .map_or(0, |state| state.proposal_count);
+        let evidence = EvidenceRef {
+            algorithm: HashAlgorithm::Sha256,
+            digest: Sha256::digest(evidence).into(),
+            uri,
+        };
+        let ix = instructions::initialize_proposal(
+            author,
+            proposal_count,
+            evidence,
+            voting_period,
+            min_quorum_weight,
+        );
+        Ok((proposal_count, self.submit(&[ix])?))
+    }
+
+    /// Casts the payer's conviction vote, locking tokens of `keys.mint`.
+    pub fn vote(&mut self, keys: &ProposalKeys, score: u8, conviction: u8) -> Result<Outcome> {
+        if score > 10 {
+            bail!("score must be between 0 and 10");
+        }
+        let ix = instructions::conviction_vote(self.payer.pubkey(), keys, score, conviction);
+        self.submit(&[ix])
+    }
+
+    pub fn pools(&self) -> Result<Vec<(Pubkey, Pool)>> {
+        self.list()
+    }
+
+    pub fn offers(&self) -> Result<Vec<(Pubkey, Offer)>> {
+        self.list()
+    }
+
+    pub fn proposals(&self) -> Result<Vec<(Pubkey, Proposal)>> {
+        self.list()
+    }
+
+    fn fetch<T: AccountDeserialize>(&self, address: &Pubkey) -> Result<Option<T>> {
+        self.backend
+            .account_data(address)?
+            .map(|data| decode(&data).map_err(|err| anyhow!("{address}: {err}")))
+            .transpose()
+    }
+
+    fn list<T: AccountDeserialize + Discriminator>(&self) -> Result<Vec<(Pubkey, T)>> {
+        self.backend
+            .program_accounts(T::DISCRIMINATOR)?
+            .into_iter()
+            .map(|(address, data)| {
+                let account = decode(&data).map_err(|err| anyhow!("{address}: {err}"))?;
+                Ok((address, account))
+            })
+            .collect()
+    }
+}
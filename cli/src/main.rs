@@ -0,0 +1,240 @@
+use std::path::PathBuf;
+
+use anyhow::{anyhow, bail, Result};
+use clap::{Parser, Subcommand, ValueEnum};
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::read_keypair_file;
+
+use rational_dex_cli::backend::{Outcome, RpcBackend};
+use rational_dex_cli::Session;
+use rational_dex_client::instructions::{PoolKeys, ProposalKeys};
+
+const TOKEN_PROGRAM_ID: &str = "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA";
+
+#[derive(Parser)]
+#[command(name = "rational-dex", about = "Operate the rational_dex program")]
+struct Cli {
+    /// RPC endpoint
+    #[arg(long, global = true, default_value = "http://127.0.0.1:8899")]
+    url: String,
+
+    /// Keypair that signs and pays, defaults to the Solana CLI keypair
+    #[arg(long, global = true)]
+    keypair: Option<PathBuf>,
+
+    /// Simulate transactions and print their logs instead of sending them
+    #[arg(long, global = true)]
+    dry_run: bool,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Create a constant product pool; the signer must be the config admin
+    InitPool {
+        #[arg(long)]
+        mint_a: Pubkey,
+        #[arg(long)]
+        mint_b: Pubkey,
+        #[arg(long)]
+        mint_lp: Pubkey,
+        #[arg(long, default_value_t = 3)]
+        fee_num: u64,
+        #[arg(long, default_value_t = 1000)]
+        fee_den: u64,
+        #[arg(long, default_value = TOKEN_PROGRAM_ID)]
+        token_program: Pubkey,
+    },
+    /// Publish a consensus price for a pool
+    SetPrice {
+        #[arg(long)]
+        pool: Pubkey,
+        #[arg(long)]
+        price: u64,
+    },
+    /// Create a price poll, queueing its encrypted tally setup on Arcium
+    CreatePoll {
+        #[arg(long)]
+        id: u32,
+        #[arg(long)]
+        price: u64,
+        #[arg(long)]
+        mint0: Pubkey,
+        #[arg(long)]
+        mint1: Pubkey,
+    },
+    /// Create a proposal backed by the SHA-256 of an evidence file
+    CreateProposal {
+        #[arg(long)]
+        evidence: PathBuf,
+        /// Where the evidence can be fetched, e.g. ipfs://<cid>
+        #[arg(long)]
+        uri: Option<String>,
+        /// Voting period in seconds
+        #[arg(long, default_value_t = 7 * 24 * 60 * 60)]
+        voting_period: i64,
+        #[arg(long, default_value_t = 1)]
+        min_quorum_weight: u64,
+    },
+    /// Cast a conviction vote, locking voting tokens
+    Vote {
+        #[arg(long)]
+        author: Pubkey,
+        /// The author's proposal count the proposal was created under
+        #[arg(long)]
+        proposal_count: u64,
+        /// Mint of the voting token
+        #[arg(long)]
+        mint: Pubkey,
+        #[arg(long)]
+        score: u8,
+        #[arg(long)]
+        conviction: u8,
+        #[arg(long, default_value = TOKEN_PROGRAM_ID)]
+        token_program: Pubkey,
+    },
+    /// List program accounts of one kind
+    List {
+        #[arg(value_enum)]
+        kind: ListKind,
+    },
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum ListKind {
+    Offers,
+    Pools,
+    Proposals,
+}
+
+fn main() -> Result<()> {
+    let cli = Cli::parse();
+    let keypair_path = match cli.keypair {
+        Some(path) => path,
+        None => default_keypair_path()?,
+    };
+    let payer = read_keypair_file(&keypair_path)
+        .map_err(|err| anyhow!("reading {}: {err}", keypair_path.display()))?;
+    let mut session = Session::new(RpcBackend::new(cli.url), payer, cli.dry_run);
+
+    let outcome = match cli.command {
+        Command::InitPool {
+            mint_a,
+            mint_b,
+            mint_lp,
+            fee_num,
+            fee_den,
+            token_program,
+        } => {
+            let keys = PoolKeys {
+                mint_a,
+                mint_b,
+                mint_lp,
+                token_program,
+            };
+            println!("pool {}", keys.pool());
+            session.init_pool(&keys, fee_num, fee_den)?
+        }
+        Command::SetPrice { pool, price } => session.set_price(pool, price)?,
+        Command::CreatePoll {
+            id,
+            price,
+            mint0,
+            mint1,
+        } => session.create_poll(id, price, mint0, mint1)?,
+        Command::CreateProposal {
+            evidence,
+            uri,
+            voting_period,
+            min_quorum_weight,
+        } => {
+            let evidence = std::fs::read(&evidence)
+                .map_err(|err| anyhow!("reading {}: {err}", evidence.display()))?;
+            let (proposal_count, outcome) =
+                session.create_proposal(&evidence, uri, voting_period, min_quorum_weight)?;
+            println!("proposal count {proposal_count}");
+            outcome
+        }
+        Command::Vote {
+            author,
+            proposal_count,
+            mint,
+            score,
+            conviction,
+            token_program,
+        } => {
+            let keys = ProposalKeys {
+                author,
+                proposal_count,
+                mint,
+                token_program,
+            };
+            session.vote(&keys, score, conviction)?
+        }
+        Command::List { kind } => return list(&session, kind),
+    };
+
+    report(outcome)
+}
+
+fn list(session: &Session<RpcBackend>, kind: ListKind) -> Result<()> {
+    match kind {
+        ListKind::Offers => {
+            for (address, offer) in session.offers()? {
+                println!(
+                    "{address} id={} maker={} {} -> {} remaining={}",
+                    offer.id,
+                    offer.maker,
+                    offer.token_mint_a,
+                    offer.token_mint_b,
+                    offer.remaining_amount
+                );
+            }
+        }
+        ListKind::Pools => {
+            for (address, pool) in session.pools()? {
+                println!(
+                    "{address} {}/{} lp_supply={} consensus_price={} reference_price={}",
+                    pool.mint_a,
+                    pool.mint_b,
+                    pool.total_lp_supply,
+                    pool.consensus_price,
+                    pool.reference_price
+                );
+            }
+        }
+        ListKind::Proposals => {
+            for (address, proposal) in session.proposals()? {
+                println!(
+                    "{address} author={} votes={} ends_at={} final_score={:?}",
+                    proposal.author,
+                    proposal.vote_count,
+                    proposal.voting_ends_at,
+                    proposal.final_score
+                );
+            }
+        }
+    }
+    Ok(())
+}
+
+fn default_keypair_path() -> Result<PathBuf> {
+    let home = std::env::var_os("HOME").ok_or_else(|| anyhow!("HOME is not set"))?;
+    Ok(PathBuf::from(home).join(".config/solana/id.json"))
+}
+
+/// Prints the logs and signature of a transaction, failing if its simulation failed.
+fn report(outcome: Outcome) -> Result<()> {
+    for log in &outcome.logs {
+        println!("  {log}");
+    }
+    match outcome.error {
+        Some(error) => bail!("simulation failed: {error}"),
+        None => {
+            println!("{}", outcome.signature);
+            Ok(())
+        }
+    }
+}
@@ -0,0 +1,97 @@
+//! Decoders for the program's accounts.
+
+use anchor_lang::prelude::*;
+use anchor_lang::{Discriminator, ZeroCopy};
+
+use rational_dex::state::batch_auction_state::{Epoch, Intent};
+use rational_dex::state::clmm_state::{ClPool, ClPosition, TickArray};
+use rational_dex::state::config_state::GlobalConfig;
+use rational_dex::state::conviction_state::{AuthorState, Proposal, Scores, Voter};
+use rational_dex::state::oracle_state::ObservationBuffer;
+use rational_dex::state::order_book_state::{Market, OpenOrders, OrderBook};
+use rational_dex::state::poll_state::PollAccount;
+use rational_dex::state::pool_state::{LpPosition, Pool};
+use rational_dex::state::reputation_state::Reputation;
+use rational_dex::state::reward_state::{RewardProgram, RewardStake};
+use rational_dex::state::sealed_offer_state::{SealedBid, SealedOffer};
+use rational_dex::state::swap_state::{Offer, Price};
+use rational_dex::state::vote_states::{VoteAccount, VoteRound};
+
+/// Decodes account data of type `T`, checking its discriminator.
+pub fn decode<T: AccountDeserialize>(data: &[u8]) -> Result<T> {
+    T::try_deserialize(&mut &data[..])
+}
+
+/// Decodes a zero-copy account such as the `OrderBook`, checking its discriminator.
+pub fn decode_zero_copy<T: ZeroCopy + Discriminator>(data: &[u8]) -> Result<T> {
+    let body = data
+        .strip_prefix(T::DISCRIMINATOR)
+        .ok_or(ErrorCode::AccountDiscriminatorMismatch)?;
+    let size = std::mem::size_of::<T>();
+    require_gte!(body.len(), size, ErrorCode::AccountDidNotDeserialize);
+    Ok(bytemuck::pod_read_unaligned(&body[..size]))
+}
+
+macro_rules! dex_accounts {
+    ($($name:ident),* $(,)?) => {
+        /// Any account owned by the program, identified by its discriminator.
+        pub enum DexAccount {
+            $($name(Box<$name>),)*
+            OrderBook(Box<OrderBook>),
+        }
+
+        impl DexAccount {
+            /// Returns `None` for data that does not start with a known discriminator.
+            pub fn decode(data: &[u8]) -> Option<Self> {
+                $(
+                    if data.starts_with($name::DISCRIMINATOR) {
+                        return decode::<$name>(data)
+                            .ok()
+                            .map(|account| Self::$name(Box::new(account)));
+                    }
+                )*
+                if data.starts_with(OrderBook::DISCRIMINATOR) {
+                    return decode_zero_copy::<OrderBook>(data)
+                        .ok()
+                        .map(|book| Self::OrderBook(Box::new(book)));
+                }
+                None
+            }
+
+            pub fn name(&self) -> &'static str {
+                match self {
+                    $(Self::$name(_) => stringify!($name),)*
+                    Self::OrderBook(_) => "OrderBook",
+                }
+            }
+        }
+    };
+}
+
+dex_accounts!(
+    GlobalConfig,
+    Pool,
+    LpPosition,
+    ObservationBuffer,
+    ClPool,
+    TickArray,
+    ClPosition,
+    RewardProgram,
+    RewardStake,
+    Offer,
+    Price,
+    SealedOffer,
+    SealedBid,
+    Epoch,
+    Intent,
+    Market,
+    OpenOrders,
+    PollAccount,
+    VoteRound,
+    VoteAccount,
+    Proposal,
+    AuthorState,
+    Scores,
+    Voter,
+    Reputation,
+);
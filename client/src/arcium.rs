@@ -0,0 +1,298 @@
+//! Arcium accounts passed by the instructions that queue an MPC computation, derived with
+//! the same macros as the program's constraints.
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_spl::associated_token;
+use arcium_anchor::prelude::*;
+
+use rational_dex::error::ErrorCode;
+use rational_dex::{accounts, instruction as args};
+use rational_dex::{
+    COMP_DEF_OFFSET_INIT_SEALED_ASK, COMP_DEF_OFFSET_INIT_VOTE_STATS,
+    COMP_DEF_OFFSET_MATCH_SEALED_BID, COMP_DEF_OFFSET_REVEAL, COMP_DEF_OFFSET_VOTE,
+};
+use rational_dex::{ID, ID_CONST};
+
+pub use arcium_anchor::prelude::MXEAccount;
+
+use crate::instructions::{build, SealedOfferKeys};
+use crate::pda;
+
+/// Address of the program's MXE account, which names the cluster computations run on.
+pub fn mxe_account() -> Pubkey {
+    derive_mxe_pda!()
+}
+
+/// Accounts shared by every computation-queuing instruction.
+#[derive(Clone, Copy, Debug)]
+pub struct QueueAccounts {
+    pub sign_pda_account: Pubkey,
+    pub mxe_account: Pubkey,
+    pub mempool_account: Pubkey,
+    pub executing_pool: Pubkey,
+    pub computation_account: Pubkey,
+    pub comp_def_account: Pubkey,
+    pub cluster_account: Pubkey,
+    pub pool_account: Pubkey,
+    pub clock_account: Pubkey,
+    pub arcium_program: Pubkey,
+}
+
+impl QueueAccounts {
+    /// `mxe` is the decoded account at [`mxe_account`]. Fails if it has no cluster yet.
+    pub fn new(mxe: &MXEAccount, comp_def_offset: u32, computation_offset: u64) -> Result<Self> {
+        Ok(Self {
+            sign_pda_account: derive_sign_pda!(),
+            mxe_account: derive_mxe_pda!(),
+            mempool_account: derive_mempool_pda!(),
+            executing_pool: derive_execpool_pda!(),
+            computation_account: derive_comp_pda!(computation_offset),
+            comp_def_account: derive_comp_def_pda!(comp_def_offset),
+            cluster_account: derive_cluster_pda!(mxe),
+            pool_account: ARCIUM_FEE_POOL_ACCOUNT_ADDRESS,
+            clock_account: ARCIUM_CLOCK_ACCOUNT_ADDRESS,
+            arcium_program: Arcium::id(),
+        })
+    }
+}
+
+/// Creates poll `args.id` for `payer` and queues the encrypted tally initialization.
+pub fn create_new_poll(
+    payer: Pubkey,
+    mxe: &MXEAccount,
+    args: args::CreateNewPoll,
+) -> Result<Instruction> {
+    let queue = QueueAccounts::new(
+        mxe,
+        COMP_DEF_OFFSET_INIT_VOTE_STATS,
+        args.computation_offset,
+    )?;
+    Ok(build(
+        accounts::CreateNewPoll {
+            payer,
+            sign_pda_account: queue.sign_pda_account,
+            mxe_account: queue.mxe_account,
+            mempool_account: queue.mempool_account,
+            executing_pool: queue.executing_pool,
+            computation_account: queue.computation_account,
+            comp_def_account: queue.comp_def_account,
+            cluster_account: queue.cluster_account,
+            pool_account: queue.pool_account,
+            clock_account: queue.clock_account,
+            system_program: system_program::ID,
+            arcium_program: queue.arcium_program,
+            poll_acc: pda::poll(&payer, args.id),
+            config: pda::config(),
+        },
+        args,
+    ))
+}
+
+/// Casts `payer`'s encrypted vote on poll `args._id` of `authority`. `reputation` passes
+/// the payer's `Reputation` account, which must exist, so the vote counts with its weight.
+pub fn vote(
+    payer: Pubkey,
+    authority: Pubkey,
+    reputation: bool,
+    mxe: &MXEAccount,
+    args: args::Vote,
+) -> Result<Instruction> {
+    let queue = QueueAccounts::new(mxe, COMP_DEF_OFFSET_VOTE, args.computation_offset)?;
+    Ok(build(
+        accounts::Vote {
+            payer,
+            sign_pda_account: queue.sign_pda_account,
+            mxe_account: queue.mxe_account,
+            mempool_account: queue.mempool_account,
+            executing_pool: queue.executing_pool,
+            computation_account: queue.computation_account,
+            comp_def_account: queue.comp_def_account,
+            cluster_account: queue.cluster_account,
+            pool_account: queue.pool_account,
+            clock_account: queue.clock_account,
+            system_program: system_program::ID,
+            arcium_program: queue.arcium_program,
+            authority,
+            poll_acc: pda::poll(&authority, args._id),
+            reputation: reputation.then(|| pda::reputation(&payer)),
+            config: pda::config(),
+        },
+        args,
+    ))
+}
+
+/// Reveals the tally of `payer`'s poll `args.id`. Pools pick the price up through
+/// [`crate::instructions::apply_poll_price`].
+pub fn reveal_result(
+    payer: Pubkey,
+    mxe: &MXEAccount,
+    args: args::RevealResult,
+) -> Result<Instruction> {
+    let queue = QueueAccounts::new(mxe, COMP_DEF_OFFSET_REVEAL, args.computation_offset)?;
+    Ok(build(
+        accounts::RevealVotingResult {
+            payer,
+            sign_pda_account: queue.sign_pda_account,
+            mxe_account: queue.mxe_account,
+            mempool_account: queue.mempool_account,
+            executing_pool: queue.executing_pool,
+            computation_account: queue.computation_account,
+            comp_def_account: queue.comp_def_account,
+            cluster_account: queue.cluster_account,
+            pool_account: queue.pool_account,
+            clock_account: queue.clock_account,
+            system_program: system_program::ID,
+            arcium_program: queue.arcium_program,
+            poll_acc: pda::poll(&payer, args.id),
+        },
+        args,
+    ))
+}
+
+/// Escrows `args.offered_amount` of token A from the maker's associated token account and
+/// queues the encryption of the ask. `args.id` must be `keys.id`.
+pub fn make_sealed_offer(
+    keys: &SealedOfferKeys,
+    mxe: &MXEAccount,
+    args: args::MakeSealedOffer,
+) -> Result<Instruction> {
+    let queue = QueueAccounts::new(
+        mxe,
+        COMP_DEF_OFFSET_INIT_SEALED_ASK,
+        args.computation_offset,
+    )?;
+    Ok(build(
+        accounts::MakeSealedOffer {
+            payer: keys.maker,
+            sign_pda_account: queue.sign_pda_account,
+            mxe_account: queue.mxe_account,
+            mempool_account: queue.mempool_account,
+            executing_pool: queue.executing_pool,
+            computation_account: queue.computation_account,
+            comp_def_account: queue.comp_def_account,
+            cluster_account: queue.cluster_account,
+            pool_account: queue.pool_account,
+            clock_account: queue.clock_account,
+            system_program: system_program::ID,
+            arcium_program: queue.arcium_program,
+            config: pda::config(),
+            token_mint_a: keys.token_mint_a,
+            token_mint_b: keys.token_mint_b,
+            maker_token_account_a: pda::ata(&keys.maker, &keys.token_mint_a, &keys.token_program),
+            sealed_offer: keys.sealed_offer(),
+            vault: keys.vault(),
+            token_program: keys.token_program,
+            associated_token_program: associated_token::ID,
+        },
+        args,
+    ))
+}
+
+/// Escrows `args.deposit` of token B from `taker`'s associated token account and queues the
+/// comparison of the encrypted bid against the ask.
+pub fn submit_sealed_bid(
+    taker: Pubkey,
+    keys: &SealedOfferKeys,
+    mxe: &MXEAccount,
+    args: args::SubmitSealedBid,
+) -> Result<Instruction> {
+    let queue = QueueAccounts::new(
+        mxe,
+        COMP_DEF_OFFSET_MATCH_SEALED_BID,
+        args.computation_offset,
+    )?;
+    let sealed_bid = keys.sealed_bid(&taker);
+    Ok(build(
+        accounts::SubmitSealedBid {
+            payer: taker,
+            sign_pda_account: queue.sign_pda_account,
+            mxe_account: queue.mxe_account,
+            mempool_account: queue.mempool_account,
+            executing_pool: queue.executing_pool,
+            computation_account: queue.computation_account,
+            comp_def_account: queue.comp_def_account,
+            cluster_account: queue.cluster_account,
+            pool_account: queue.pool_account,
+            clock_account: queue.clock_account,
+            system_program: system_program::ID,
+            arcium_program: queue.arcium_program,
+            config: pda::config(),
+            sealed_offer: keys.sealed_offer(),
+            token_mint_b: keys.token_mint_b,
+            taker_token_account_b: pda::ata(&taker, &keys.token_mint_b, &keys.token_program),
+            sealed_bid,
+            bid_vault: pda::ata(&sealed_bid, &keys.token_mint_b, &keys.token_program),
+            token_program: keys.token_program,
+            associated_token_program: associated_token::ID,
+        },
+        args,
+    ))
+}
+
+// Computation definitions, initialized once per program deployment
+
+pub fn init_vote_stats_comp_def(payer: Pubkey) -> Instruction {
+    build(
+        accounts::InitVoteStatsCompDef {
+            payer,
+            mxe_account: derive_mxe_pda!(),
+            comp_def_account: derive_comp_def_pda!(COMP_DEF_OFFSET_INIT_VOTE_STATS),
+            arcium_program: Arcium::id(),
+            system_program: system_program::ID,
+        },
+        args::InitVoteStatsCompDef {},
+    )
+}
+
+pub fn init_vote_comp_def(payer: Pubkey) -> Instruction {
+    build(
+        accounts::InitVoteCompDef {
+            payer,
+            mxe_account: derive_mxe_pda!(),
+            comp_def_account: derive_comp_def_pda!(COMP_DEF_OFFSET_VOTE),
+            arcium_program: Arcium::id(),
+            system_program: system_program::ID,
+        },
+        args::InitVoteCompDef {},
+    )
+}
+
+pub fn init_reveal_result_comp_def(payer: Pubkey) -> Instruction {
+    build(
+        accounts::InitRevealResultCompDef {
+            payer,
+            mxe_account: derive_mxe_pda!(),
+            comp_def_account: derive_comp_def_pda!(COMP_DEF_OFFSET_REVEAL),
+            arcium_program: Arcium::id(),
+            system_program: system_program::ID,
+        },
+        args::InitRevealResultCompDef {},
+    )
+}
+
+pub fn init_sealed_ask_comp_def(payer: Pubkey) -> Instruction {
+    build(
+        accounts::InitSealedAskCompDef {
+            payer,
+            mxe_account: derive_mxe_pda!(),
+            comp_def_account: derive_comp_def_pda!(COMP_DEF_OFFSET_INIT_SEALED_ASK),
+            arcium_program: Arcium::id(),
+            system_program: system_program::ID,
+        },
+        args::InitSealedAskCompDef {},
+    )
+}
+
+pub fn init_match_sealed_bid_comp_def(payer: Pubkey) -> Instruction {
+    build(
+        accounts::InitMatchSealedBidCompDef {
+            payer,
+            mxe_account: derive_mxe_pda!(),
+            comp_def_account: derive_comp_def_pda!(COMP_DEF_OFFSET_MATCH_SEALED_BID),
+            arcium_program: Arcium::id(),
+            system_program: system_program::ID,
+        },
+        args::InitMatchSealedBidCompDef {},
+    )
+}
@@ -0,0 +1,141 @@
+//! Decoders for the events the program emits through `emit!`.
+
+use anchor_lang::{AnchorDeserialize, Discriminator};
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+
+use rational_dex::events::*;
+use rational_dex::instructions::reveal::RevealResultEvent;
+use rational_dex::instructions::vote::VoteEvent;
+
+const PROGRAM_DATA: &str = "Program data: ";
+
+macro_rules! dex_events {
+    ($($name:ident),* $(,)?) => {
+        /// Any event emitted by the program.
+        pub enum DexEvent {
+            $($name($name),)*
+        }
+
+        impl DexEvent {
+            /// Decodes an event from its discriminator and borsh body. Returns `None` for
+            /// data that is not a known event.
+            pub fn decode(data: &[u8]) -> Option<Self> {
+                $(
+                    if let Some(body) = data.strip_prefix($name::DISCRIMINATOR) {
+                        return $name::try_from_slice(body).ok().map(Self::$name);
+                    }
+                )*
+                None
+            }
+
+            pub fn name(&self) -> &'static str {
+                match self {
+                    $(Self::$name(_) => stringify!($name),)*
+                }
+            }
+
+            /// Layout version of the event, `EVENT_VERSION` at the time it was emitted.
+            pub fn version(&self) -> u8 {
+                match self {
+                    $(Self::$name(event) => event.version,)*
+                }
+            }
+        }
+    };
+}
+
+dex_events!(
+    OfferCreated,
+    OfferTaken,
+    OfferCancelled,
+    ProposalCreated,
+    EvidenceUpdated,
+    CircuitBreakerTriggered,
+    OrderPlaced,
+    OrderCancelled,
+    OrderFilled,
+    SealedOfferCreated,
+    SealedBidResolved,
+    EpochCleared,
+    FlashLoanRepaid,
+    ActiveRangeRecentered,
+    PositionUpdated,
+    RewardsFunded,
+    RewardsClaimed,
+    PoolInitialized,
+    LiquidityChanged,
+    Swapped,
+    ConsensusPriceUpdated,
+    PollCreated,
+    VoteCast,
+    ConvictionVoteCast,
+    ConvictionVoteRetracted,
+    TokensUnlocked,
+    ProposalFinalized,
+    VoteEvent,
+    RevealResultEvent,
+    ProtocolFeesCollected,
+    BlendPolicyUpdated,
+    FlashLoanBorrowed,
+    VoterReputationSettled,
+    PausedChanged,
+    AdminProposed,
+    AdminAccepted,
+    FeeRecipientsUpdated,
+    EpochOpened,
+    IntentSubmitted,
+    FillClaimed,
+    LpStakeChanged,
+    VoteWeightSynced,
+    ClPoolCreated,
+    ClPositionOpened,
+    ClPositionClosed,
+    ClSwapped,
+);
+
+/// Decodes the events in a transaction's log messages. Unknown or malformed entries are
+/// skipped.
+pub fn parse_logs<S: AsRef<str>>(logs: &[S]) -> Vec<DexEvent> {
+    program_data(logs)
+        .iter()
+        .filter_map(|data| DexEvent::decode(data))
+        .collect()
+}
+
+/// Raw `Program data:` entries in a transaction's log messages, in order.
+///
+/// Only entries logged while this program is the one executing are returned, so events of
+/// other programs invoked in the same transaction are skipped. Entries that are not valid
+/// base64 are dropped.
+pub fn program_data<S: AsRef<str>>(logs: &[S]) -> Vec<Vec<u8>> {
+    let program_id = rational_dex::ID.to_string();
+    let mut call_stack: Vec<&str> = Vec::new();
+    let mut entries = Vec::new();
+
+    for log in logs {
+        let log = log.as_ref();
+        if let Some(data) = log.strip_prefix(PROGRAM_DATA) {
+            if call_stack.last() != Some(&program_id.as_str()) {
+                continue;
+            }
+            if let Ok(bytes) = STANDARD.decode(data) {
+                entries.push(bytes);
+            }
+        } else if let Some(rest) = log
+            .strip_prefix("Program ")
+            .filter(|rest| !rest.starts_with("log:"))
+        {
+            let mut words = rest.split_whitespace();
+            match (words.next(), words.next()) {
+                (Some(program), Some("invoke")) => call_stack.push(program),
+                (Some(_), Some("success" | "failed:")) => {
+                    call_stack.pop();
+                }
+                _ => {}
+            }
+        }
+    }
+
+    entries
+}
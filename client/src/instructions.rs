@@ -0,0 +1,1377 @@
+//! Instruction builders.
+//!
+//! Every instruction can be built with [`build`] from the account and argument structs
+//! Anchor generates in `rational_dex::accounts` and `rational_dex::instruction`. The
+//! helpers below fill in every PDA, associated token account and program address
+//! themselves. Builders for the instructions that queue an Arcium computation, which also
+//! need the MXE, cluster and computation accounts, live in [`crate::arcium`].
+
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::instruction::{AccountMeta, Instruction};
+use anchor_lang::solana_program::sysvar;
+use anchor_lang::{system_program, InstructionData, ToAccountMetas};
+use anchor_spl::associated_token;
+use anchor_spl::token::spl_token::native_mint;
+
+use rational_dex::instructions::pool::ROUTE_HOP_ACCOUNTS;
+use rational_dex::state::config_state::Subsystem;
+use rational_dex::state::conviction_state::EvidenceRef;
+use rational_dex::state::order_book_state::Side;
+use rational_dex::state::pool_state::BlendPolicy;
+use rational_dex::state::swap_state::{OfferAccess, PricingMode};
+use rational_dex::{accounts, instruction as args, TICK_ARRAY_SIZE};
+
+use crate::pda;
+
+/// Builds any program instruction from its generated account and argument structs.
+pub fn build(accounts: impl ToAccountMetas, args: impl InstructionData) -> Instruction {
+    build_with_remaining(accounts, args, Vec::new())
+}
+
+/// Like [`build`], with `remaining` appended after the named accounts: tick arrays for
+/// `cl_swap`, hops for `route_swap` and orders for `match_orders`.
+pub fn build_with_remaining(
+    accounts: impl ToAccountMetas,
+    args: impl InstructionData,
+    remaining: Vec<AccountMeta>,
+) -> Instruction {
+    let mut metas = accounts.to_account_metas(None);
+    metas.extend(remaining);
+    Instruction {
+        program_id: rational_dex::ID,
+        accounts: metas,
+        data: args.data(),
+    }
+}
+
+/// Mints and token program of a constant product pool.
+#[derive(Clone, Copy, Debug)]
+pub struct PoolKeys {
+    pub mint_a: Pubkey,
+    pub mint_b: Pubkey,
+    pub mint_lp: Pubkey,
+    pub token_program: Pubkey,
+}
+
+impl PoolKeys {
+    pub fn pool(&self) -> Pubkey {
+        pda::pool(&self.mint_a, &self.mint_b)
+    }
+}
+
+/// Paired pool, tick spacing and token program of a concentrated-liquidity pool.
+#[derive(Clone, Copy, Debug)]
+pub struct ClPoolKeys {
+    pub mint_a: Pubkey,
+    pub mint_b: Pubkey,
+    pub tick_spacing: u16,
+    pub token_program: Pubkey,
+}
+
+impl ClPoolKeys {
+    pub fn pool(&self) -> Pubkey {
+        pda::pool(&self.mint_a, &self.mint_b)
+    }
+
+    pub fn cl_pool(&self) -> Pubkey {
+        pda::cl_pool(&self.pool())
+    }
+
+    /// Tick array holding `tick`.
+    pub fn tick_array(&self, tick: i32) -> Pubkey {
+        pda::tick_array(&self.cl_pool(), tick_array_start(tick, self.tick_spacing))
+    }
+}
+
+/// Start index of the tick array holding `tick`.
+pub fn tick_array_start(tick: i32, tick_spacing: u16) -> i32 {
+    let span = tick_spacing as i32 * TICK_ARRAY_SIZE as i32;
+    tick.div_euclid(span) * span
+}
+
+/// Maker, id, mints and token program of an offer.
+#[derive(Clone, Copy, Debug)]
+pub struct OfferKeys {
+    pub maker: Pubkey,
+    pub id: u64,
+    pub token_mint_a: Pubkey,
+    pub token_mint_b: Pubkey,
+    pub token_program: Pubkey,
+}
+
+impl OfferKeys {
+    pub fn offer(&self) -> Pubkey {
+        pda::offer(&self.maker, self.id)
+    }
+
+    pub fn vault(&self) -> Pubkey {
+        pda::ata(&self.offer(), &self.token_mint_a, &self.token_program)
+    }
+}
+
+/// Maker, id, mints and token program of a sealed-bid offer.
+#[derive(Clone, Copy, Debug)]
+pub struct SealedOfferKeys {
+    pub maker: Pubkey,
+    pub id: u64,
+    pub token_mint_a: Pubkey,
+    pub token_mint_b: Pubkey,
+    pub token_program: Pubkey,
+}
+
+impl SealedOfferKeys {
+    pub fn sealed_offer(&self) -> Pubkey {
+        pda::sealed_offer(&self.maker, self.id)
+    }
+
+    pub fn vault(&self) -> Pubkey {
+        pda::ata(
+            &self.sealed_offer(),
+            &self.token_mint_a,
+            &self.token_program,
+        )
+    }
+
+    pub fn sealed_bid(&self, taker: &Pubkey) -> Pubkey {
+        pda::sealed_bid(&self.sealed_offer(), taker)
+    }
+}
+
+/// Poll, id, mints and token program of a batch auction epoch. The mints are the poll's
+/// `mint0` (base) and `mint1` (quote).
+#[derive(Clone, Copy, Debug)]
+pub struct EpochKeys {
+    pub poll: Pubkey,
+    pub id: u64,
+    pub base_mint: Pubkey,
+    pub quote_mint: Pubkey,
+    pub token_program: Pubkey,
+}
+
+impl EpochKeys {
+    pub fn epoch(&self) -> Pubkey {
+        pda::epoch(&self.poll, self.id)
+    }
+
+    pub fn base_vault(&self) -> Pubkey {
+        pda::ata(&self.epoch(), &self.base_mint, &self.token_program)
+    }
+
+    pub fn quote_vault(&self) -> Pubkey {
+        pda::ata(&self.epoch(), &self.quote_mint, &self.token_program)
+    }
+}
+
+/// Mints, order book and token program of an order book market.
+#[derive(Clone, Copy, Debug)]
+pub struct MarketKeys {
+    pub base_mint: Pubkey,
+    pub quote_mint: Pubkey,
+    pub order_book: Pubkey,
+    pub token_program: Pubkey,
+}
+
+impl MarketKeys {
+    pub fn market(&self) -> Pubkey {
+        pda::market(&self.base_mint, &self.quote_mint)
+    }
+}
+
+// Config
+
+pub fn initialize() -> Instruction {
+    build(accounts::Initialize {}, args::Initialize {})
+}
+
+pub fn initialize_config(admin: Pubkey, fee_recipients: Vec<Pubkey>) -> Instruction {
+    build(
+        accounts::InitializeConfig {
+            admin,
+            program: rational_dex::ID,
+            program_data: pda::program_data(),
+            config: pda::config(),
+            system_program: system_program::ID,
+        },
+        args::InitializeConfig { fee_recipients },
+    )
+}
+
+pub fn set_paused(admin: Pubkey, subsystem: Subsystem, paused: bool) -> Instruction {
+    build(
+        accounts::AdminOnly {
+            admin,
+            config: pda::config(),
+        },
+        args::SetPaused { subsystem, paused },
+    )
+}
+
+/// First step of the admin rotation; `None` cancels a pending transfer.
+pub fn propose_admin(admin: Pubkey, new_admin: Option<Pubkey>) -> Instruction {
+    build(
+        accounts::AdminOnly {
+            admin,
+            config: pda::config(),
+        },
+        args::ProposeAdmin { new_admin },
+    )
+}
+
+pub fn accept_admin(new_admin: Pubkey) -> Instruction {
+    build(
+        accounts::AcceptAdmin {
+            new_admin,
+            config: pda::config(),
+        },
+        args::AcceptAdmin {},
+    )
+}
+
+pub fn set_fee_recipients(admin: Pubkey, fee_recipients: Vec<Pubkey>) -> Instruction {
+    build(
+        accounts::AdminOnly {
+            admin,
+            config: pda::config(),
+        },
+        args::SetFeeRecipients { fee_recipients },
+    )
+}
+
+// Pools
+
+pub fn initialize_pool(
+    authority: Pubkey,
+    keys: &PoolKeys,
+    fee_num: u64,
+    fee_den: u64,
+) -> Instruction {
+    let pool = keys.pool();
+    build(
+        accounts::InitializePool {
+            authority,
+            config: pda::config(),
+            mint_a: keys.mint_a,
+            mint_b: keys.mint_b,
+            pool,
+            observations: pda::observations(&pool),
+            vault_a: pda::vault_a(&pool),
+            vault_b: pda::vault_b(&pool),
+            mint_lp: keys.mint_lp,
+            token_program: keys.token_program,
+            system_program: system_program::ID,
+        },
+        args::InitializePool { fee_num, fee_den },
+    )
+}
+
+/// Deposits from and mints LP tokens to `user`'s associated token accounts.
+pub fn add_liquidity(
+    user: Pubkey,
+    keys: &PoolKeys,
+    max_amount_a: u64,
+    max_amount_b: u64,
+    min_lp_out: u64,
+) -> Instruction {
+    let pool = keys.pool();
+    build(
+        accounts::AddLiquidity {
+            user,
+            config: pda::config(),
+            pool,
+            lp_position: pda::lp_position(&pool, &user),
+            vault_a: pda::vault_a(&pool),
+            vault_b: pda::vault_b(&pool),
+            mint_a: keys.mint_a,
+            mint_b: keys.mint_b,
+            mint_lp: keys.mint_lp,
+            user_token_a: pda::ata(&user, &keys.mint_a, &keys.token_program),
+            user_token_b: pda::ata(&user, &keys.mint_b, &keys.token_program),
+            user_token_lp: pda::ata(&user, &keys.mint_lp, &keys.token_program),
+            system_program: system_program::ID,
+            token_program: keys.token_program,
+            associated_token_program: associated_token::ID,
+        },
+        args::AddLiquidity {
+            max_amount_a,
+            max_amount_b,
+            min_lp_out,
+        },
+    )
+}
+
+/// Burns LP tokens from and pays out to `user`'s associated token accounts.
+pub fn remove_liquidity(
+    user: Pubkey,
+    keys: &PoolKeys,
+    lp_amount: u64,
+    min_amount_a: u64,
+    min_amount_b: u64,
+) -> Instruction {
+    let pool = keys.pool();
+    build(
+        accounts::RemoveLiquidity {
+            user,
+            pool,
+            lp_position: pda::lp_position(&pool, &user),
+            vault_a: pda::vault_a(&pool),
+            vault_b: pda::vault_b(&pool),
+            mint_a: keys.mint_a,
+            mint_b: keys.mint_b,
+            mint_lp: keys.mint_lp,
+            user_token_a: pda::ata(&user, &keys.mint_a, &keys.token_program),
+            user_token_b: pda::ata(&user, &keys.mint_b, &keys.token_program),
+            user_token_lp: pda::ata(&user, &keys.mint_lp, &keys.token_program),
+            unwrap_account: pda::unwrap(&user),
+            system_program: system_program::ID,
+            token_program: keys.token_program,
+            associated_token_program: associated_token::ID,
+        },
+        args::RemoveLiquidity {
+            lp_amount,
+            min_amount_a,
+            min_amount_b,
+        },
+    )
+}
+
+/// Swaps between `user`'s associated token accounts of the pool's mints, which the program
+/// creates when missing. Native SOL is wrapped into and paid out of the wSOL account in
+/// lamports, which leaves the account open with the balance it had.
+pub fn swap(
+    user: Pubkey,
+    keys: &PoolKeys,
+    amount_in: u64,
+    min_amount_out: u64,
+    a_to_b: bool,
+) -> Instruction {
+    let pool = keys.pool();
+    build(
+        accounts::Swap {
+            user,
+            config: pda::config(),
+            pool,
+            observations: pda::observations(&pool),
+            vault_a: pda::vault_a(&pool),
+            vault_b: pda::vault_b(&pool),
+            mint_a: keys.mint_a,
+            mint_b: keys.mint_b,
+            user_token_a: pda::ata(&user, &keys.mint_a, &keys.token_program),
+            user_token_b: pda::ata(&user, &keys.mint_b, &keys.token_program),
+            unwrap_account: pda::unwrap(&user),
+            system_program: system_program::ID,
+            token_program: keys.token_program,
+            associated_token_program: associated_token::ID,
+        },
+        args::Swap {
+            amount_in,
+            min_amount_out,
+            a_to_b,
+        },
+    )
+}
+
+/// Swaps `mint_in` through `hops` in order, paying out whichever mint the last hop leaves.
+/// Each hop uses its own token program; `user`'s input and output accounts are the
+/// associated token accounts under the first and last hop's program.
+///
+/// Panics if `hops` is empty.
+pub fn route_swap(
+    user: Pubkey,
+    mint_in: Pubkey,
+    hops: &[PoolKeys],
+    amount_in: u64,
+    min_amount_out: u64,
+) -> Instruction {
+    let (first, last) = (hops[0], hops[hops.len() - 1]);
+    let mut mint_out = mint_in;
+    let mut remaining = Vec::with_capacity(hops.len() * ROUTE_HOP_ACCOUNTS);
+    for hop in hops {
+        mint_out = if mint_out == hop.mint_a {
+            hop.mint_b
+        } else {
+            hop.mint_a
+        };
+        let pool = hop.pool();
+        remaining.extend([
+            AccountMeta::new(pool, false),
+            AccountMeta::new(pda::observations(&pool), false),
+            AccountMeta::new(pda::vault_a(&pool), false),
+            AccountMeta::new(pda::vault_b(&pool), false),
+            AccountMeta::new_readonly(hop.mint_a, false),
+            AccountMeta::new_readonly(hop.mint_b, false),
+            AccountMeta::new_readonly(hop.token_program, false),
+        ]);
+    }
+    build_with_remaining(
+        accounts::RouteSwap {
+            user,
+            config: pda::config(),
+            mint_in,
+            mint_out,
+            user_token_in: pda::ata(&user, &mint_in, &first.token_program),
+            user_token_out: pda::ata(&user, &mint_out, &last.token_program),
+            unwrap_account: pda::unwrap(&user),
+            system_program: system_program::ID,
+            token_program: first.token_program,
+            token_program_out: last.token_program,
+            associated_token_program: associated_token::ID,
+        },
+        args::RouteSwap {
+            amount_in,
+            min_amount_out,
+        },
+        remaining,
+    )
+}
+
+/// Lends `amount` of token A (or B) to `user`'s associated token account. Only succeeds in
+/// a transaction that later includes [`flash_repay`] for the same pool.
+pub fn flash_borrow(user: Pubkey, keys: &PoolKeys, amount: u64, token_a: bool) -> Instruction {
+    build(
+        flash_loan_accounts(user, keys),
+        args::FlashBorrow { amount, token_a },
+    )
+}
+
+/// Repays the pool's outstanding flash loan plus its fee from `user`'s account.
+pub fn flash_repay(user: Pubkey, keys: &PoolKeys) -> Instruction {
+    build(flash_loan_accounts(user, keys), args::FlashRepay {})
+}
+
+fn flash_loan_accounts(user: Pubkey, keys: &PoolKeys) -> accounts::FlashLoan {
+    let pool = keys.pool();
+    accounts::FlashLoan {
+        user,
+        config: pda::config(),
+        pool,
+        vault_a: pda::vault_a(&pool),
+        vault_b: pda::vault_b(&pool),
+        mint_a: keys.mint_a,
+        mint_b: keys.mint_b,
+        user_token_a: pda::ata(&user, &keys.mint_a, &keys.token_program),
+        user_token_b: pda::ata(&user, &keys.mint_b, &keys.token_program),
+        instructions_sysvar: sysvar::instructions::ID,
+        token_program: keys.token_program,
+    }
+}
+
+pub fn update_consensus_price(authority: Pubkey, pool: Pubkey, price: u64) -> Instruction {
+    build(
+        accounts::UpdateConsensusPrice {
+            authority,
+            pool,
+            observations: pda::observations(&pool),
+        },
+        args::UpdateConsensusPrice { price },
+    )
+}
+
+/// Moves `pool` to the revealed consensus price of `poll`, which the pool authority opened.
+pub fn apply_poll_price(pool: Pubkey, poll: Pubkey) -> Instruction {
+    build(
+        accounts::ApplyPollPrice {
+            pool,
+            observations: pda::observations(&pool),
+            poll,
+            config: pda::config(),
+        },
+        args::ApplyPollPrice {},
+    )
+}
+
+pub fn set_blend_policy(authority: Pubkey, pool: Pubkey, policy: BlendPolicy) -> Instruction {
+    build(
+        accounts::SetBlendPolicy {
+            authority,
+            pool,
+            observations: pda::observations(&pool),
+        },
+        args::SetBlendPolicy { policy },
+    )
+}
+
+/// Returns the pool's TWAP over the last `window` seconds as return data.
+pub fn get_twap(pool: Pubkey, window: u32) -> Instruction {
+    build(
+        accounts::GetTwap {
+            pool,
+            observations: pda::observations(&pool),
+        },
+        args::GetTwap { window },
+    )
+}
+
+/// Returns the pool's blended reference price as return data.
+pub fn get_reference_price(pool: Pubkey) -> Instruction {
+    build(
+        accounts::GetReferencePrice { pool },
+        args::GetReferencePrice {},
+    )
+}
+
+/// Returns the value of `owner`'s LP position as return data.
+pub fn position_value(pool: Pubkey, owner: Pubkey) -> Instruction {
+    build(
+        accounts::GetPositionValue {
+            pool,
+            lp_position: pda::lp_position(&pool, &owner),
+            vault_a: pda::vault_a(&pool),
+            vault_b: pda::vault_b(&pool),
+        },
+        args::PositionValue {},
+    )
+}
+
+/// Pays the pool's protocol fees to `fee_recipient`'s associated token accounts.
+pub fn collect_protocol_fees(fee_recipient: Pubkey, keys: &PoolKeys) -> Instruction {
+    let pool = keys.pool();
+    build(
+        accounts::CollectProtocolFees {
+            fee_recipient,
+            config: pda::config(),
+            pool,
+            vault_a: pda::vault_a(&pool),
+            vault_b: pda::vault_b(&pool),
+            mint_a: keys.mint_a,
+            mint_b: keys.mint_b,
+            recipient_token_a: pda::ata(&fee_recipient, &keys.mint_a, &keys.token_program),
+            recipient_token_b: pda::ata(&fee_recipient, &keys.mint_b, &keys.token_program),
+            token_program: keys.token_program,
+        },
+        args::CollectProtocolFees {},
+    )
+}
+
+// Concentrated liquidity
+
+pub fn create_cl_pool(
+    authority: Pubkey,
+    keys: &ClPoolKeys,
+    fee_bps: u16,
+    active_range_ticks: u32,
+) -> Instruction {
+    let cl_pool = keys.cl_pool();
+    build(
+        accounts::CreateClPool {
+            authority,
+            config: pda::config(),
+            pool: keys.pool(),
+            cl_pool,
+            vault_a: pda::cl_vault_a(&cl_pool),
+            vault_b: pda::cl_vault_b(&cl_pool),
+            mint_a: keys.mint_a,
+            mint_b: keys.mint_b,
+            token_program: keys.token_program,
+            system_program: system_program::ID,
+        },
+        args::CreateClPool {
+            tick_spacing: keys.tick_spacing,
+            fee_bps,
+            active_range_ticks,
+        },
+    )
+}
+
+pub fn recenter_range(keys: &ClPoolKeys) -> Instruction {
+    build(
+        accounts::RecenterRange {
+            cl_pool: keys.cl_pool(),
+            pool: keys.pool(),
+        },
+        args::RecenterRange {},
+    )
+}
+
+/// `start_tick_index` must be a [`tick_array_start`].
+pub fn init_tick_array(payer: Pubkey, keys: &ClPoolKeys, start_tick_index: i32) -> Instruction {
+    let cl_pool = keys.cl_pool();
+    build(
+        accounts::InitTickArray {
+            payer,
+            cl_pool,
+            tick_array: pda::tick_array(&cl_pool, start_tick_index),
+            system_program: system_program::ID,
+        },
+        args::InitTickArray { start_tick_index },
+    )
+}
+
+/// Deposits from `owner`'s associated token accounts. The tick arrays holding both bounds
+/// must already exist.
+pub fn open_position(
+    owner: Pubkey,
+    keys: &ClPoolKeys,
+    tick_lower: i32,
+    tick_upper: i32,
+    liquidity: u64,
+    max_amount_a: u64,
+    max_amount_b: u64,
+) -> Instruction {
+    let cl_pool = keys.cl_pool();
+    build(
+        accounts::OpenPosition {
+            owner,
+            config: pda::config(),
+            cl_pool,
+            position: pda::cl_position(&cl_pool, &owner, tick_lower, tick_upper),
+            tick_array_lower: keys.tick_array(tick_lower),
+            tick_array_upper: keys.tick_array(tick_upper),
+            vault_a: pda::cl_vault_a(&cl_pool),
+            vault_b: pda::cl_vault_b(&cl_pool),
+            mint_a: keys.mint_a,
+            mint_b: keys.mint_b,
+            owner_token_a: pda::ata(&owner, &keys.mint_a, &keys.token_program),
+            owner_token_b: pda::ata(&owner, &keys.mint_b, &keys.token_program),
+            token_program: keys.token_program,
+            system_program: system_program::ID,
+        },
+        args::OpenPosition {
+            tick_lower,
+            tick_upper,
+            liquidity,
+            max_amount_a,
+            max_amount_b,
+        },
+    )
+}
+
+pub fn collect_position_fees(
+    owner: Pubkey,
+    keys: &ClPoolKeys,
+    tick_lower: i32,
+    tick_upper: i32,
+) -> Instruction {
+    build(
+        modify_position_accounts(owner, keys, tick_lower, tick_upper),
+        args::CollectPositionFees {},
+    )
+}
+
+pub fn close_position(
+    owner: Pubkey,
+    keys: &ClPoolKeys,
+    tick_lower: i32,
+    tick_upper: i32,
+) -> Instruction {
+    build(
+        modify_position_accounts(owner, keys, tick_lower, tick_upper),
+        args::ClosePosition {},
+    )
+}
+
+fn modify_position_accounts(
+    owner: Pubkey,
+    keys: &ClPoolKeys,
+    tick_lower: i32,
+    tick_upper: i32,
+) -> accounts::ModifyPosition {
+    let cl_pool = keys.cl_pool();
+    accounts::ModifyPosition {
+        owner,
+        cl_pool,
+        position: pda::cl_position(&cl_pool, &owner, tick_lower, tick_upper),
+        tick_array_lower: keys.tick_array(tick_lower),
+        tick_array_upper: keys.tick_array(tick_upper),
+        vault_a: pda::cl_vault_a(&cl_pool),
+        vault_b: pda::cl_vault_b(&cl_pool),
+        mint_a: keys.mint_a,
+        mint_b: keys.mint_b,
+        owner_token_a: pda::ata(&owner, &keys.mint_a, &keys.token_program),
+        owner_token_b: pda::ata(&owner, &keys.mint_b, &keys.token_program),
+        token_program: keys.token_program,
+    }
+}
+
+/// Swaps between `user`'s associated token accounts. `tick_arrays` start with the one
+/// holding the current price and follow in the swap's direction; the swap stops early at
+/// the last one.
+pub fn cl_swap(
+    user: Pubkey,
+    keys: &ClPoolKeys,
+    tick_arrays: &[Pubkey],
+    amount_in: u64,
+    min_amount_out: u64,
+    a_to_b: bool,
+) -> Instruction {
+    let cl_pool = keys.cl_pool();
+    let tick_arrays = tick_arrays
+        .iter()
+        .map(|tick_array| AccountMeta::new(*tick_array, false))
+        .collect();
+    build_with_remaining(
+        accounts::ClSwap {
+            user,
+            config: pda::config(),
+            cl_pool,
+            vault_a: pda::cl_vault_a(&cl_pool),
+            vault_b: pda::cl_vault_b(&cl_pool),
+            mint_a: keys.mint_a,
+            mint_b: keys.mint_b,
+            user_token_a: pda::ata(&user, &keys.mint_a, &keys.token_program),
+            user_token_b: pda::ata(&user, &keys.mint_b, &keys.token_program),
+            token_program: keys.token_program,
+        },
+        args::ClSwap {
+            amount_in,
+            min_amount_out,
+            a_to_b,
+        },
+        tick_arrays,
+    )
+}
+
+// Rewards
+
+/// `reward_token_program` owns the reward mint, which may differ from the pool's.
+pub fn create_reward_program(
+    authority: Pubkey,
+    keys: &PoolKeys,
+    reward_mint: Pubkey,
+    reward_token_program: Pubkey,
+    lp_share_bps: u16,
+) -> Instruction {
+    let pool = keys.pool();
+    let reward_program = pda::reward_program(&pool, &reward_mint);
+    build(
+        accounts::CreateRewardProgram {
+            authority,
+            config: pda::config(),
+            pool,
+            reward_program,
+            reward_vault: pda::reward_vault(&reward_program),
+            lp_vault: pda::reward_lp_vault(&reward_program),
+            reward_mint,
+            mint_lp: keys.mint_lp,
+            token_program: keys.token_program,
+            reward_token_program,
+            system_program: system_program::ID,
+        },
+        args::CreateRewardProgram { lp_share_bps },
+    )
+}
+
+/// Streams `amount` from `authority`'s associated token account over `duration` seconds.
+pub fn fund_rewards(
+    authority: Pubkey,
+    pool: Pubkey,
+    reward_mint: Pubkey,
+    reward_token_program: Pubkey,
+    amount: u64,
+    duration: i64,
+) -> Instruction {
+    let reward_program = pda::reward_program(&pool, &reward_mint);
+    build(
+        accounts::FundRewards {
+            authority,
+            config: pda::config(),
+            reward_program,
+            reward_vault: pda::reward_vault(&reward_program),
+            reward_mint,
+            authority_token_account: pda::ata(&authority, &reward_mint, &reward_token_program),
+            token_program: reward_token_program,
+        },
+        args::FundRewards { amount, duration },
+    )
+}
+
+pub fn stake_lp(owner: Pubkey, keys: &PoolKeys, reward_mint: Pubkey, amount: u64) -> Instruction {
+    build(
+        stake_lp_accounts(owner, keys, reward_mint),
+        args::StakeLp { amount },
+    )
+}
+
+pub fn unstake_lp(owner: Pubkey, keys: &PoolKeys, reward_mint: Pubkey, amount: u64) -> Instruction {
+    build(
+        stake_lp_accounts(owner, keys, reward_mint),
+        args::UnstakeLp { amount },
+    )
+}
+
+fn stake_lp_accounts(owner: Pubkey, keys: &PoolKeys, reward_mint: Pubkey) -> accounts::StakeLp {
+    let pool = keys.pool();
+    let reward_program = pda::reward_program(&pool, &reward_mint);
+    accounts::StakeLp {
+        owner,
+        reward_program,
+        pool,
+        lp_position: pda::lp_position(&pool, &owner),
+        reward_stake: pda::reward_stake(&reward_program, &owner),
+        lp_vault: pda::reward_lp_vault(&reward_program),
+        mint_lp: keys.mint_lp,
+        owner_token_lp: pda::ata(&owner, &keys.mint_lp, &keys.token_program),
+        token_program: keys.token_program,
+        system_program: system_program::ID,
+    }
+}
+
+/// Sets `voter`'s vote weight to their conviction vote on `proposal`. Anyone can pay to
+/// refresh the vote already registered.
+pub fn sync_vote_weight(
+    payer: Pubkey,
+    voter: Pubkey,
+    proposal: Pubkey,
+    pool: Pubkey,
+    reward_mint: Pubkey,
+) -> Instruction {
+    let reward_program = pda::reward_program(&pool, &reward_mint);
+    build(
+        accounts::SyncVoteWeight {
+            payer,
+            voter,
+            proposal,
+            reward_program,
+            reward_stake: pda::reward_stake(&reward_program, &voter),
+            voter_account: pda::voter(&proposal, &voter),
+            system_program: system_program::ID,
+        },
+        args::SyncVoteWeight {},
+    )
+}
+
+/// `reward_token_program` owns the reward mint, which may differ from the pool's.
+pub fn claim_rewards(
+    owner: Pubkey,
+    pool: Pubkey,
+    reward_mint: Pubkey,
+    reward_token_program: Pubkey,
+) -> Instruction {
+    let reward_program = pda::reward_program(&pool, &reward_mint);
+    build(
+        accounts::ClaimRewards {
+            owner,
+            reward_program,
+            reward_stake: pda::reward_stake(&reward_program, &owner),
+            reward_vault: pda::reward_vault(&reward_program),
+            reward_mint,
+            owner_reward_account: pda::ata(&owner, &reward_mint, &reward_token_program),
+            token_program: reward_token_program,
+        },
+        args::ClaimRewards {},
+    )
+}
+
+// Offers
+
+/// `Price` PDAs and reference pool the offer's pricing reads, as `(price_of_token_a,
+/// price_of_token_b, reference_pool)`.
+fn price_accounts(
+    keys: &OfferKeys,
+    pricing: &PricingMode,
+) -> (Option<Pubkey>, Option<Pubkey>, Option<Pubkey>) {
+    match *pricing {
+        PricingMode::Fixed { .. } => (None, None, None),
+        PricingMode::OracleRelative {
+            reference_pool: Some(pool),
+            ..
+        } => (None, None, Some(pool)),
+        PricingMode::OracleRelative {
+            reference_pool: None,
+            ..
+        } => (
+            Some(pda::price(&keys.token_mint_a)),
+            Some(pda::price(&keys.token_mint_b)),
+            None,
+        ),
+    }
+}
+
+/// Passes whichever price source `pricing` names.
+pub fn make_offer(
+    keys: &OfferKeys,
+    token_a_offered_amount: u64,
+    pricing: PricingMode,
+    access: OfferAccess,
+) -> Instruction {
+    let (price_of_token_a, price_of_token_b, reference_pool) = price_accounts(keys, &pricing);
+    build(
+        accounts::MakeOffer {
+            maker: keys.maker,
+            config: pda::config(),
+            token_mint_a: keys.token_mint_a,
+            token_mint_b: keys.token_mint_b,
+            maker_token_account_a: pda::ata(&keys.maker, &keys.token_mint_a, &keys.token_program),
+            offer: keys.offer(),
+            price_of_token_a,
+            price_of_token_b,
+            reference_pool,
+            vault: keys.vault(),
+            system_program: system_program::ID,
+            token_program: keys.token_program,
+            associated_token_program: associated_token::ID,
+        },
+        args::MakeOffer {
+            id: keys.id,
+            token_a_offered_amount,
+            pricing,
+            access,
+        },
+    )
+}
+
+/// `pricing` is the offer's, whose price source is passed. The taker pays at most
+/// `max_token_b`, transfer fee included. Token B accounts are left out when token B is
+/// native SOL, which is paid in lamports.
+pub fn take_offer(
+    taker: Pubkey,
+    keys: &OfferKeys,
+    pricing: &PricingMode,
+    taker_amount: u64,
+    max_token_b: u64,
+    proof: Vec<[u8; 32]>,
+) -> Instruction {
+    let (price_of_token_a, price_of_token_b, reference_pool) = price_accounts(keys, pricing);
+    let token_b_account = |owner: &Pubkey| {
+        (keys.token_mint_b != native_mint::ID)
+            .then(|| pda::ata(owner, &keys.token_mint_b, &keys.token_program))
+    };
+    build(
+        accounts::TakeOffer {
+            taker,
+            maker: keys.maker,
+            config: pda::config(),
+            token_mint_a: keys.token_mint_a,
+            token_mint_b: keys.token_mint_b,
+            taker_token_account_a: pda::ata(&taker, &keys.token_mint_a, &keys.token_program),
+            taker_token_account_b: token_b_account(&taker),
+            maker_token_account_b: token_b_account(&keys.maker),
+            price_of_token_a,
+            price_of_token_b,
+            reference_pool,
+            offer: keys.offer(),
+            vault: keys.vault(),
+            unwrap_account: pda::unwrap(&taker),
+            system_program: system_program::ID,
+            token_program: keys.token_program,
+            associated_token_program: associated_token::ID,
+        },
+        args::TakeOffer {
+            taker_amount,
+            max_token_b,
+            proof,
+        },
+    )
+}
+
+pub fn cancel_offer(keys: &OfferKeys) -> Instruction {
+    build(
+        accounts::CancelOffer {
+            maker: keys.maker,
+            token_mint_a: keys.token_mint_a,
+            maker_token_account_a: pda::ata(&keys.maker, &keys.token_mint_a, &keys.token_program),
+            offer: keys.offer(),
+            vault: keys.vault(),
+            unwrap_account: pda::unwrap(&keys.maker),
+            system_program: system_program::ID,
+            token_program: keys.token_program,
+            associated_token_program: associated_token::ID,
+        },
+        args::CancelOffer {},
+    )
+}
+
+// Sealed offers
+
+/// Settles `taker`'s resolved bid. The offer vault and the taker's token A account are only
+/// passed for a `matched` bid.
+pub fn settle_sealed_bid(
+    payer: Pubkey,
+    keys: &SealedOfferKeys,
+    taker: Pubkey,
+    matched: bool,
+) -> Instruction {
+    let sealed_bid = keys.sealed_bid(&taker);
+    build(
+        accounts::SettleSealedBid {
+            payer,
+            maker: keys.maker,
+            taker,
+            sealed_offer: keys.sealed_offer(),
+            sealed_bid,
+            token_mint_a: keys.token_mint_a,
+            token_mint_b: keys.token_mint_b,
+            vault: matched.then(|| keys.vault()),
+            bid_vault: pda::ata(&sealed_bid, &keys.token_mint_b, &keys.token_program),
+            taker_token_account_a: matched
+                .then(|| pda::ata(&taker, &keys.token_mint_a, &keys.token_program)),
+            taker_token_account_b: pda::ata(&taker, &keys.token_mint_b, &keys.token_program),
+            maker_token_account_b: pda::ata(&keys.maker, &keys.token_mint_b, &keys.token_program),
+            system_program: system_program::ID,
+            token_program: keys.token_program,
+            associated_token_program: associated_token::ID,
+        },
+        args::SettleSealedBid {},
+    )
+}
+
+pub fn cancel_sealed_offer(keys: &SealedOfferKeys) -> Instruction {
+    build(
+        accounts::CancelSealedOffer {
+            maker: keys.maker,
+            sealed_offer: keys.sealed_offer(),
+            token_mint_a: keys.token_mint_a,
+            maker_token_account_a: pda::ata(&keys.maker, &keys.token_mint_a, &keys.token_program),
+            vault: keys.vault(),
+            token_program: keys.token_program,
+        },
+        args::CancelSealedOffer {},
+    )
+}
+
+// Batch auction
+
+pub fn open_epoch(payer: Pubkey, keys: &EpochKeys, duration: i64) -> Instruction {
+    build(
+        accounts::OpenEpoch {
+            payer,
+            poll_acc: keys.poll,
+            base_mint: keys.base_mint,
+            quote_mint: keys.quote_mint,
+            epoch: keys.epoch(),
+            base_vault: keys.base_vault(),
+            quote_vault: keys.quote_vault(),
+            system_program: system_program::ID,
+            token_program: keys.token_program,
+            associated_token_program: associated_token::ID,
+        },
+        args::OpenEpoch {
+            id: keys.id,
+            duration,
+        },
+    )
+}
+
+/// Deposits from `owner`'s associated token accounts: quote to buy, base to sell.
+pub fn submit_intent(owner: Pubkey, keys: &EpochKeys, side: Side, amount: u64) -> Instruction {
+    let epoch = keys.epoch();
+    build(
+        accounts::SubmitIntent {
+            owner,
+            config: pda::config(),
+            epoch,
+            intent: pda::intent(&epoch, &owner),
+            base_mint: keys.base_mint,
+            quote_mint: keys.quote_mint,
+            base_vault: keys.base_vault(),
+            quote_vault: keys.quote_vault(),
+            owner_base_account: pda::ata(&owner, &keys.base_mint, &keys.token_program),
+            owner_quote_account: pda::ata(&owner, &keys.quote_mint, &keys.token_program),
+            system_program: system_program::ID,
+            token_program: keys.token_program,
+        },
+        args::SubmitIntent { side, amount },
+    )
+}
+
+pub fn close_epoch(keys: &EpochKeys) -> Instruction {
+    build(
+        accounts::CloseEpoch {
+            epoch: keys.epoch(),
+            poll_acc: keys.poll,
+        },
+        args::CloseEpoch {},
+    )
+}
+
+pub fn claim_fill(owner: Pubkey, keys: &EpochKeys) -> Instruction {
+    let epoch = keys.epoch();
+    build(
+        accounts::ClaimFill {
+            owner,
+            epoch,
+            intent: pda::intent(&epoch, &owner),
+            base_mint: keys.base_mint,
+            quote_mint: keys.quote_mint,
+            base_vault: keys.base_vault(),
+            quote_vault: keys.quote_vault(),
+            owner_base_account: pda::ata(&owner, &keys.base_mint, &keys.token_program),
+            owner_quote_account: pda::ata(&owner, &keys.quote_mint, &keys.token_program),
+            system_program: system_program::ID,
+            token_program: keys.token_program,
+            associated_token_program: associated_token::ID,
+        },
+        args::ClaimFill {},
+    )
+}
+
+// Order book
+
+/// `keys.order_book` must already be allocated with the program as owner and
+/// `8 + size_of::<OrderBook>()` zeroed bytes.
+pub fn create_market(payer: Pubkey, keys: &MarketKeys) -> Instruction {
+    let market = keys.market();
+    build(
+        accounts::CreateMarket {
+            payer,
+            base_mint: keys.base_mint,
+            quote_mint: keys.quote_mint,
+            market,
+            base_vault: pda::market_base_vault(&market),
+            quote_vault: pda::market_quote_vault(&market),
+            order_book: keys.order_book,
+            token_program: keys.token_program,
+            system_program: system_program::ID,
+        },
+        args::CreateMarket {},
+    )
+}
+
+/// Places an order funded from `owner`'s associated token accounts.
+pub fn place_order(
+    owner: Pubkey,
+    keys: &MarketKeys,
+    side: Side,
+    price: u64,
+    quantity: u64,
+) -> Instruction {
+    let market = keys.market();
+    build(
+        accounts::PlaceOrder {
+            owner,
+            config: pda::config(),
+            market,
+            order_book: keys.order_book,
+            open_orders: pda::open_orders(&market, &owner),
+            base_mint: keys.base_mint,
+            quote_mint: keys.quote_mint,
+            base_vault: pda::market_base_vault(&market),
+            quote_vault: pda::market_quote_vault(&market),
+            owner_base_account: pda::ata(&owner, &keys.base_mint, &keys.token_program),
+            owner_quote_account: pda::ata(&owner, &keys.quote_mint, &keys.token_program),
+            token_program: keys.token_program,
+            system_program: system_program::ID,
+        },
+        args::PlaceOrder {
+            side,
+            price,
+            quantity,
+        },
+    )
+}
+
+pub fn cancel_order(owner: Pubkey, keys: &MarketKeys, side: Side, order_id: u64) -> Instruction {
+    let market = keys.market();
+    build(
+        accounts::CancelOrder {
+            owner,
+            market,
+            order_book: keys.order_book,
+            open_orders: pda::open_orders(&market, &owner),
+        },
+        args::CancelOrder { side, order_id },
+    )
+}
+
+/// `owners` must include the owner of every order the crank may touch.
+pub fn match_orders(keys: &MarketKeys, owners: &[Pubkey], limit: u16) -> Instruction {
+    let market = keys.market();
+    let open_orders = owners
+        .iter()
+        .map(|owner| AccountMeta::new(pda::open_orders(&market, owner), false))
+        .collect();
+    build_with_remaining(
+        accounts::MatchOrders {
+            market,
+            order_book: keys.order_book,
+            config: pda::config(),
+        },
+        args::MatchOrders { limit },
+        open_orders,
+    )
+}
+
+/// Withdraws `owner`'s free balances to their associated token accounts.
+pub fn settle_funds(owner: Pubkey, keys: &MarketKeys) -> Instruction {
+    let market = keys.market();
+    build(
+        accounts::SettleFunds {
+            owner,
+            market,
+            open_orders: pda::open_orders(&market, &owner),
+            base_mint: keys.base_mint,
+            quote_mint: keys.quote_mint,
+            base_vault: pda::market_base_vault(&market),
+            quote_vault: pda::market_quote_vault(&market),
+            owner_base_account: pda::ata(&owner, &keys.base_mint, &keys.token_program),
+            owner_quote_account: pda::ata(&owner, &keys.quote_mint, &keys.token_program),
+            token_program: keys.token_program,
+        },
+        args::SettleFunds {},
+    )
+}
+
+// Conviction Voting
+
+/// `proposal_count` is the author's current count, which seeds the new proposal.
+pub fn initialize_proposal(
+    author: Pubkey,
+    proposal_count: u64,
+    evidence: EvidenceRef,
+    voting_period: i64,
+    min_quorum_weight: u64,
+) -> Instruction {
+    let proposal = pda::proposal(&author, proposal_count);
+    build(
+        accounts::InitializeProposal {
+            author,
+            config: pda::config(),
+            author_state: pda::author_state(&author),
+            proposal,
+            scores: pda::scores(&proposal),
+            system_program: system_program::ID,
+        },
+        args::InitializeProposal {
+            evidence,
+            voting_period,
+            min_quorum_weight,
+        },
+    )
+}
+
+pub fn update_evidence(author: Pubkey, proposal_count: u64, evidence: EvidenceRef) -> Instruction {
+    build(
+        accounts::UpdateEvidence {
+            author,
+            proposal: pda::proposal(&author, proposal_count),
+        },
+        args::UpdateEvidence {
+            _proposal_count: proposal_count,
+            evidence,
+        },
+    )
+}
+
+/// Proposal, voting mint and token program a conviction vote locks tokens against.
+#[derive(Clone, Copy, Debug)]
+pub struct ProposalKeys {
+    pub author: Pubkey,
+    pub proposal_count: u64,
+    pub mint: Pubkey,
+    pub token_program: Pubkey,
+}
+
+impl ProposalKeys {
+    pub fn proposal(&self) -> Pubkey {
+        pda::proposal(&self.author, self.proposal_count)
+    }
+}
+
+pub fn conviction_vote(
+    voter: Pubkey,
+    keys: &ProposalKeys,
+    score: u8,
+    conviction: u8,
+) -> Instruction {
+    let proposal = keys.proposal();
+    let scores = pda::scores(&proposal);
+    build(
+        accounts::VoteProposal {
+            voter,
+            config: pda::config(),
+            author: keys.author,
+            proposal,
+            mint: keys.mint,
+            voter_token_account: pda::ata(&voter, &keys.mint, &keys.token_program),
+            vault_token_account: pda::ata(&scores, &keys.mint, &keys.token_program),
+            scores,
+            voter_account: pda::voter(&proposal, &voter),
+            system_program: system_program::ID,
+            token_program: keys.token_program,
+            associated_token_program: associated_token::ID,
+        },
+        args::ConvictionVote {
+            _proposal_count: keys.proposal_count,
+            score,
+            conviction,
+        },
+    )
+}
+
+pub fn change_conviction_vote(
+    voter: Pubkey,
+    keys: &ProposalKeys,
+    new_score: u8,
+    new_conviction: u8,
+) -> Instruction {
+    build(
+        update_conviction_vote_accounts(voter, keys),
+        args::ChangeConvictionVote {
+            _proposal_count: keys.proposal_count,
+            new_score,
+            new_conviction,
+        },
+    )
+}
+
+pub fn retract_conviction_vote(voter: Pubkey, keys: &ProposalKeys) -> Instruction {
+    build(
+        update_conviction_vote_accounts(voter, keys),
+        args::RetractConvictionVote {
+            proposal_count: keys.proposal_count,
+        },
+    )
+}
+
+pub fn unlock_conviction_tokens(voter: Pubkey, keys: &ProposalKeys) -> Instruction {
+    build(
+        update_conviction_vote_accounts(voter, keys),
+        args::UnlockConvictionTokens {
+            proposal_count: keys.proposal_count,
+        },
+    )
+}
+
+fn update_conviction_vote_accounts(
+    voter: Pubkey,
+    keys: &ProposalKeys,
+) -> accounts::UpdateConvictionVote {
+    let proposal = keys.proposal();
+    let scores = pda::scores(&proposal);
+    accounts::UpdateConvictionVote {
+        voter,
+        config: pda::config(),
+        author: keys.author,
+        proposal,
+        mint: keys.mint,
+        voter_token_account: pda::ata(&voter, &keys.mint, &keys.token_program),
+        vault_token_account: pda::ata(&scores, &keys.mint, &keys.token_program),
+        scores,
+        voter_account: pda::voter(&proposal, &voter),
+        token_program: keys.token_program,
+    }
+}
+
+pub fn finalize_proposal(payer: Pubkey, author: Pubkey, proposal_count: u64) -> Instruction {
+    let proposal = pda::proposal(&author, proposal_count);
+    build(
+        accounts::FinalizeProposal {
+            payer,
+            author,
+            author_state: pda::author_state(&author),
+            proposal,
+            scores: pda::scores(&proposal),
+            author_reputation: pda::reputation(&author),
+            system_program: system_program::ID,
+        },
+        args::FinalizeProposal {
+            _proposal_count: proposal_count,
+        },
+    )
+}
+
+pub fn settle_voter_reputation(voter: Pubkey, author: Pubkey, proposal_count: u64) -> Instruction {
+    let proposal = pda::proposal(&author, proposal_count);
+    build(
+        accounts::SettleVoterReputation {
+            voter,
+            author,
+            proposal,
+            voter_account: pda::voter(&proposal, &voter),
+            reputation: pda::reputation(&voter),
+            system_program: system_program::ID,
+        },
+        args::SettleVoterReputation {
+            _proposal_count: proposal_count,
+        },
+    )
+}
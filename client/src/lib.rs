@@ -0,0 +1,11 @@
+//! Client for the `rational_dex` program: typed instruction builders, PDA derivation,
+//! account decoders and event decoders, shared by the integration tests and off-chain
+//! tooling.
+
+pub mod accounts;
+pub mod arcium;
+pub mod events;
+pub mod instructions;
+pub mod pda;
+
+pub use rational_dex::ID as PROGRAM_ID;
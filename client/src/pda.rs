@@ -0,0 +1,184 @@
+//! Addresses of the program's PDAs, derived from the same seeds as the on-chain constraints.
+
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::bpf_loader_upgradeable;
+use anchor_spl::associated_token::get_associated_token_address_with_program_id;
+
+fn find(seeds: &[&[u8]]) -> Pubkey {
+    Pubkey::find_program_address(seeds, &rational_dex::ID).0
+}
+
+/// Associated token account of `owner` for `mint` under `token_program`.
+pub fn ata(owner: &Pubkey, mint: &Pubkey, token_program: &Pubkey) -> Pubkey {
+    get_associated_token_address_with_program_id(owner, mint, token_program)
+}
+
+/// Token account through which native SOL is paid out to `owner`. It only exists while an
+/// instruction runs.
+pub fn unwrap(owner: &Pubkey) -> Pubkey {
+    find(&[b"unwrap", owner.as_ref()])
+}
+
+// Config
+
+pub fn config() -> Pubkey {
+    find(&[b"config"])
+}
+
+/// ProgramData account of the deployed program, which records its upgrade authority.
+pub fn program_data() -> Pubkey {
+    Pubkey::find_program_address(&[rational_dex::ID.as_ref()], &bpf_loader_upgradeable::ID).0
+}
+
+// Pools
+
+pub fn pool(mint_a: &Pubkey, mint_b: &Pubkey) -> Pubkey {
+    find(&[b"pool", mint_a.as_ref(), mint_b.as_ref()])
+}
+
+pub fn observations(pool: &Pubkey) -> Pubkey {
+    find(&[b"observations", pool.as_ref()])
+}
+
+pub fn vault_a(pool: &Pubkey) -> Pubkey {
+    find(&[b"vault_a", pool.as_ref()])
+}
+
+pub fn vault_b(pool: &Pubkey) -> Pubkey {
+    find(&[b"vault_b", pool.as_ref()])
+}
+
+pub fn lp_position(pool: &Pubkey, owner: &Pubkey) -> Pubkey {
+    find(&[b"lp_position", pool.as_ref(), owner.as_ref()])
+}
+
+// Concentrated liquidity
+
+pub fn cl_pool(pool: &Pubkey) -> Pubkey {
+    find(&[b"cl_pool", pool.as_ref()])
+}
+
+pub fn cl_vault_a(cl_pool: &Pubkey) -> Pubkey {
+    find(&[b"cl_vault_a", cl_pool.as_ref()])
+}
+
+pub fn cl_vault_b(cl_pool: &Pubkey) -> Pubkey {
+    find(&[b"cl_vault_b", cl_pool.as_ref()])
+}
+
+pub fn tick_array(cl_pool: &Pubkey, start_tick_index: i32) -> Pubkey {
+    find(&[
+        b"tick_array",
+        cl_pool.as_ref(),
+        &start_tick_index.to_le_bytes(),
+    ])
+}
+
+pub fn cl_position(cl_pool: &Pubkey, owner: &Pubkey, tick_lower: i32, tick_upper: i32) -> Pubkey {
+    find(&[
+        b"cl_position",
+        cl_pool.as_ref(),
+        owner.as_ref(),
+        &tick_lower.to_le_bytes(),
+        &tick_upper.to_le_bytes(),
+    ])
+}
+
+// Rewards
+
+pub fn reward_program(pool: &Pubkey, reward_mint: &Pubkey) -> Pubkey {
+    find(&[b"reward_program", pool.as_ref(), reward_mint.as_ref()])
+}
+
+pub fn reward_vault(reward_program: &Pubkey) -> Pubkey {
+    find(&[b"reward_vault", reward_program.as_ref()])
+}
+
+pub fn reward_lp_vault(reward_program: &Pubkey) -> Pubkey {
+    find(&[b"reward_lp_vault", reward_program.as_ref()])
+}
+
+pub fn reward_stake(reward_program: &Pubkey, owner: &Pubkey) -> Pubkey {
+    find(&[b"reward_stake", reward_program.as_ref(), owner.as_ref()])
+}
+
+// Offers
+
+pub fn offer(maker: &Pubkey, id: u64) -> Pubkey {
+    find(&[b"offer", maker.as_ref(), &id.to_le_bytes()])
+}
+
+pub fn price(mint: &Pubkey) -> Pubkey {
+    find(&[b"price", mint.as_ref()])
+}
+
+pub fn sealed_offer(maker: &Pubkey, id: u64) -> Pubkey {
+    find(&[b"sealed_offer", maker.as_ref(), &id.to_le_bytes()])
+}
+
+pub fn sealed_bid(sealed_offer: &Pubkey, taker: &Pubkey) -> Pubkey {
+    find(&[b"sealed_bid", sealed_offer.as_ref(), taker.as_ref()])
+}
+
+// Batch auction
+
+pub fn epoch(poll: &Pubkey, id: u64) -> Pubkey {
+    find(&[b"epoch", poll.as_ref(), &id.to_le_bytes()])
+}
+
+pub fn intent(epoch: &Pubkey, owner: &Pubkey) -> Pubkey {
+    find(&[b"intent", epoch.as_ref(), owner.as_ref()])
+}
+
+// Order book
+
+pub fn market(base_mint: &Pubkey, quote_mint: &Pubkey) -> Pubkey {
+    find(&[b"market", base_mint.as_ref(), quote_mint.as_ref()])
+}
+
+pub fn market_base_vault(market: &Pubkey) -> Pubkey {
+    find(&[b"market_base", market.as_ref()])
+}
+
+pub fn market_quote_vault(market: &Pubkey) -> Pubkey {
+    find(&[b"market_quote", market.as_ref()])
+}
+
+pub fn open_orders(market: &Pubkey, owner: &Pubkey) -> Pubkey {
+    find(&[b"open_orders", market.as_ref(), owner.as_ref()])
+}
+
+// Polls
+
+pub fn poll(authority: &Pubkey, id: u32) -> Pubkey {
+    find(&[b"poll", authority.as_ref(), &id.to_le_bytes()])
+}
+
+// Conviction voting
+
+pub fn author_state(author: &Pubkey) -> Pubkey {
+    find(&[b"author_state", author.as_ref()])
+}
+
+/// `proposal_count` is the author's count when the proposal was created, seeded as a
+/// decimal string.
+pub fn proposal(author: &Pubkey, proposal_count: u64) -> Pubkey {
+    find(&[
+        b"proposal",
+        author.as_ref(),
+        proposal_count.to_string().as_bytes(),
+    ])
+}
+
+/// Tally of `proposal`, which also owns the vault of its locked tokens.
+pub fn scores(proposal: &Pubkey) -> Pubkey {
+    find(&[b"scores", proposal.as_ref()])
+}
+
+pub fn voter(proposal: &Pubkey, voter: &Pubkey) -> Pubkey {
+    find(&[b"voter", proposal.as_ref(), voter.as_ref()])
+}
+
+pub fn reputation(wallet: &Pubkey) -> Pubkey {
+    find(&[b"reputation", wallet.as_ref()])
+}
@@ -1,5 +1,7 @@
 use arcis_imports::*;
 
+pub mod reference;
+
 #[encrypted]
 mod circuits {
     use arcis_imports::*;
@@ -38,6 +40,7 @@ mod circuits {
     #[instruction]
     pub fn vote(
         vote_ctxt: Enc<Shared, UserVote>,
+        weight: u64,
         vote_stats_ctxt: Enc<Mxe, VoteStats>,
     ) -> Enc<Mxe, VoteStats> {
         let user_vote = vote_ctxt.to_arcis();
@@ -54,8 +57,8 @@ mod circuits {
             vote_stats.three,
         ];
 
-        // Increment the appropriate counter
-        counts[user_vote.vote_idx as usize] += 1;
+        // Increment the appropriate counter by the voter's reputation weight
+        counts[user_vote.vote_idx as usize] += weight;
 
         // Assign back to struct
         vote_stats.minus_three = counts[0];
@@ -85,4 +88,41 @@ mod circuits {
 
         (counts).reveal()
     }
+
+    /// A maker's secret ask: token B wanted for the whole sealed offer.
+    pub struct SealedAsk {
+        wanted_amount: u64,
+    }
+
+    /// A taker's secret bid: token B offered for the whole sealed offer.
+    pub struct SealedBid {
+        bid_amount: u64,
+    }
+
+    /// Re-encrypts the maker's ask for the MXE so later bids can be checked against it.
+    #[instruction]
+    pub fn init_sealed_ask(ask_ctxt: Enc<Shared, SealedAsk>, mxe: Mxe) -> Enc<Mxe, SealedAsk> {
+        let ask = ask_ctxt.to_arcis();
+        mxe.from_arcis(ask)
+    }
+
+    /// Reveals the settlement amount, or 0 if the bid does not meet the ask.
+    /// A match settles at the ask, so the bid itself is never revealed.
+    /// `deposit` is the token B the taker escrowed and caps what can be settled.
+    #[instruction]
+    pub fn match_sealed_bid(
+        bid_ctxt: Enc<Shared, SealedBid>,
+        deposit: u64,
+        ask_ctxt: Enc<Mxe, SealedAsk>,
+    ) -> u64 {
+        let bid = bid_ctxt.to_arcis();
+        let ask = ask_ctxt.to_arcis();
+
+        let matched = ask.wanted_amount > 0
+            && bid.bid_amount >= ask.wanted_amount
+            && ask.wanted_amount <= deposit;
+        let settlement_amount = if matched { ask.wanted_amount } else { 0 };
+
+        settlement_amount.reveal()
+    }
 }
@@ -0,0 +1,42 @@
+//! Plaintext reference for the circuits: what each one computes once encryption is taken
+//! away. Tallies are laid out like the output of `reveal_result`, counter `i` holding the
+//! weight voted for `i - 3`.
+
+/// Number of vote options, -3 to 3.
+pub const VOTE_OPTIONS: usize = 7;
+
+/// `init_vote_stats`: nobody has voted.
+pub fn init_vote_stats() -> [u64; VOTE_OPTIONS] {
+    [0; VOTE_OPTIONS]
+}
+
+/// `vote`: adds `weight` to option `vote_idx`. `None` for an option that does not exist,
+/// whose index is out of bounds in the circuit: the computation aborts and the poll keeps
+/// its tally.
+pub fn vote(
+    vote_idx: u8,
+    weight: u64,
+    vote_stats: [u64; VOTE_OPTIONS],
+) -> Option<[u64; VOTE_OPTIONS]> {
+    if vote_idx as usize >= VOTE_OPTIONS {
+        return None;
+    }
+    let mut counts = vote_stats;
+    counts[vote_idx as usize] += weight;
+    Some(counts)
+}
+
+/// `reveal_result`: the tally as is.
+pub fn reveal_result(vote_stats: [u64; VOTE_OPTIONS]) -> [u64; VOTE_OPTIONS] {
+    vote_stats
+}
+
+/// `match_sealed_bid`: the ask if the bid meets it and the deposit covers it, otherwise 0.
+pub fn match_sealed_bid(bid_amount: u64, deposit: u64, wanted_amount: u64) -> u64 {
+    let matched = wanted_amount > 0 && bid_amount >= wanted_amount && wanted_amount <= deposit;
+    if matched {
+        wanted_amount
+    } else {
+        0
+    }
+}
@@ -0,0 +1,203 @@
+//! Read-only JSON API over the indexed tables.
+//!
+//! - `GET /offers?status=open|taken|cancelled`
+//! - `GET /offers/<maker>/<offer_id>`, with its fills
+//! - `GET /polls`
+//! - `GET /polls/<poll>`, with its votes and reveal history
+//! - `GET /proposals?status=Active|Finalized|Failed`
+//! - `GET /proposals/<proposal>`, with its conviction votes
+//! - `GET /events?name=<event>&limit=<n>`, newest first with base64 data and the reason
+//!   any event was not applied
+
+use anyhow::{anyhow, Result};
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use rusqlite::types::ValueRef;
+use rusqlite::{Connection, Params};
+use serde_json::{json, Map, Value};
+use tiny_http::{Header, Method, Response, Server};
+
+const DEFAULT_EVENT_LIMIT: u32 = 100;
+
+/// Answers one request path, query string included, with a status and a JSON body.
+pub fn route(conn: &Connection, url: &str) -> (u16, Value) {
+    let (path, query) = url.split_once('?').unwrap_or((url, ""));
+    let param = |name: &str| {
+        query
+            .split('&')
+            .filter_map(|pair| pair.split_once('='))
+            .find(|(key, _)| *key == name)
+            .map(|(_, value)| value.to_string())
+    };
+    let segments: Vec<&str> = path.trim_matches('/').split('/').collect();
+
+    let result = match segments.as_slice() {
+        ["offers"] => match param("status") {
+            Some(status) => rows(
+                conn,
+                "SELECT * FROM offers WHERE status = ?1 ORDER BY created_slot",
+                [status],
+            ),
+            None => rows(conn, "SELECT * FROM offers ORDER BY created_slot", []),
+        },
+        ["offers", maker, offer_id] => offer(conn, maker, offer_id),
+        ["polls"] => rows(conn, "SELECT * FROM polls ORDER BY created_at", []),
+        ["polls", poll] => poll_history(conn, poll),
+        ["proposals"] => match param("status") {
+            Some(status) => rows(
+                conn,
+                "SELECT * FROM proposals WHERE status = ?1 ORDER BY created_slot",
+                [status],
+            ),
+            None => rows(conn, "SELECT * FROM proposals ORDER BY created_slot", []),
+        },
+        ["proposals", proposal] => proposal_votes(conn, proposal),
+        ["events"] => {
+            let limit = param("limit")
+                .and_then(|limit| limit.parse().ok())
+                .unwrap_or(DEFAULT_EVENT_LIMIT);
+            match param("name") {
+                Some(name) => rows(
+                    conn,
+                    "SELECT * FROM events WHERE name = ?1
+                     ORDER BY slot DESC, event_index DESC LIMIT ?2",
+                    (name, limit),
+                ),
+                None => rows(
+                    conn,
+                    "SELECT * FROM events ORDER BY slot DESC, event_index DESC LIMIT ?1",
+                    [limit],
+                ),
+            }
+        }
+        _ => return not_found(),
+    };
+
+    match result {
+        Ok(Value::Null) => not_found(),
+        Ok(body) => (200, body),
+        Err(err) => (500, json!({ "error": err.to_string() })),
+    }
+}
+
+/// Serves the API on `addr` until the process is stopped.
+pub fn serve(conn: &Connection, addr: &str) -> Result<()> {
+    let server = Server::http(addr).map_err(|err| anyhow!("binding {addr}: {err}"))?;
+    let content_type: Header = "Content-Type: application/json"
+        .parse()
+        .expect("static header");
+
+    for request in server.incoming_requests() {
+        let (status, body) = if *request.method() == Method::Get {
+            route(conn, request.url())
+        } else {
+            (405, json!({ "error": "method not allowed" }))
+        };
+        let response = Response::from_string(body.to_string())
+            .with_status_code(status)
+            .with_header(content_type.clone());
+        request.respond(response)?;
+    }
+    Ok(())
+}
+
+fn not_found() -> (u16, Value) {
+    (404, json!({ "error": "not found" }))
+}
+
+fn offer(conn: &Connection, maker: &str, offer_id: &str) -> rusqlite::Result<Value> {
+    let Ok(offer_id) = offer_id.parse::<u64>() else {
+        return Ok(Value::Null);
+    };
+    let Some(mut offer) = first(rows(
+        conn,
+        "SELECT * FROM offers WHERE maker = ?1 AND offer_id = ?2",
+        (maker, offer_id),
+    )?) else {
+        return Ok(Value::Null);
+    };
+    offer["fills"] = rows(
+        conn,
+        "SELECT signature, slot, taker, amount_taken, remaining_amount FROM offer_fills
+         WHERE maker = ?1 AND offer_id = ?2 ORDER BY slot",
+        (maker, offer_id),
+    )?;
+    Ok(offer)
+}
+
+fn poll_history(conn: &Connection, poll: &str) -> rusqlite::Result<Value> {
+    let Some(mut summary) = first(rows(conn, "SELECT * FROM polls WHERE poll = ?1", [poll])?)
+    else {
+        return Ok(Value::Null);
+    };
+    summary["votes"] = rows(
+        conn,
+        "SELECT signature, voter, weight, timestamp FROM poll_votes
+         WHERE poll = ?1 ORDER BY timestamp",
+        [poll],
+    )?;
+    let mut reveals = rows(
+        conn,
+        "SELECT signature, counts, consensus_price, revealed_at
+         FROM poll_reveals WHERE poll = ?1 ORDER BY revealed_at",
+        [poll],
+    )?;
+    // Counts are stored as a JSON array
+    for reveal in reveals.as_array_mut().into_iter().flatten() {
+        if let Some(counts) = reveal["counts"].as_str() {
+            reveal["counts"] = serde_json::from_str(counts).unwrap_or(Value::Null);
+        }
+    }
+    summary["reveals"] = reveals;
+    Ok(summary)
+}
+
+fn proposal_votes(conn: &Connection, proposal: &str) -> rusqlite::Result<Value> {
+    let Some(mut summary) = first(rows(
+        conn,
+        "SELECT * FROM proposals WHERE proposal = ?1",
+        [proposal],
+    )?) else {
+        return Ok(Value::Null);
+    };
+    summary["votes"] = rows(
+        conn,
+        "SELECT voter, score, conviction, locked_amount, unlock_time, retracted, updated_at
+         FROM conviction_votes WHERE proposal = ?1 ORDER BY updated_at",
+        [proposal],
+    )?;
+    Ok(summary)
+}
+
+fn first(rows: Value) -> Option<Value> {
+    match rows {
+        Value::Array(rows) => rows.into_iter().next(),
+        _ => None,
+    }
+}
+
+/// Runs `sql` and returns its rows as an array of objects keyed by column name.
+fn rows(conn: &Connection, sql: &str, params: impl Params) -> rusqlite::Result<Value> {
+    let mut stmt = conn.prepare(sql)?;
+    let columns: Vec<String> = stmt.column_names().into_iter().map(String::from).collect();
+    let rows = stmt
+        .query_map(params, |row| {
+            let mut object = Map::new();
+            for (index, column) in columns.iter().enumerate() {
+                object.insert(column.clone(), json_value(row.get_ref(index)?));
+            }
+            Ok(Value::Object(object))
+        })?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+    Ok(Value::Array(rows))
+}
+
+fn json_value(value: ValueRef) -> Value {
+    match value {
+        ValueRef::Null => Value::Null,
+        ValueRef::Integer(int) => int.into(),
+        ValueRef::Real(real) => real.into(),
+        ValueRef::Text(text) => String::from_utf8_lossy(text).into(),
+        ValueRef::Blob(blob) => STANDARD.encode(blob).into(),
+    }
+}
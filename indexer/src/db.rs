@@ -0,0 +1,126 @@
+//! SQLite schema the indexer materializes events into.
+
+use std::path::Path;
+
+use rusqlite::Connection;
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS transactions (
+    signature TEXT PRIMARY KEY,
+    slot INTEGER NOT NULL,
+    block_time INTEGER,
+    failed INTEGER NOT NULL
+);
+
+-- Every event the program logged, including ones this indexer cannot decode yet
+CREATE TABLE IF NOT EXISTS events (
+    signature TEXT NOT NULL,
+    event_index INTEGER NOT NULL,
+    slot INTEGER NOT NULL,
+    name TEXT,
+    version INTEGER,
+    data BLOB NOT NULL,
+    -- Why the event was left out of the tables below, e.g. an amount above i64::MAX
+    skipped TEXT,
+    PRIMARY KEY (signature, event_index)
+);
+CREATE INDEX IF NOT EXISTS events_by_name ON events (name, slot);
+
+CREATE TABLE IF NOT EXISTS offers (
+    maker TEXT NOT NULL,
+    offer_id INTEGER NOT NULL,
+    token_mint_a TEXT NOT NULL,
+    token_mint_b TEXT NOT NULL,
+    offered_amount INTEGER NOT NULL,
+    wanted_amount INTEGER NOT NULL,
+    remaining_amount INTEGER NOT NULL,
+    is_private INTEGER NOT NULL,
+    -- open, taken or cancelled
+    status TEXT NOT NULL,
+    created_slot INTEGER NOT NULL,
+    updated_slot INTEGER NOT NULL,
+    PRIMARY KEY (maker, offer_id)
+);
+
+CREATE TABLE IF NOT EXISTS offer_fills (
+    signature TEXT NOT NULL,
+    slot INTEGER NOT NULL,
+    maker TEXT NOT NULL,
+    offer_id INTEGER NOT NULL,
+    taker TEXT NOT NULL,
+    amount_taken INTEGER NOT NULL,
+    remaining_amount INTEGER NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS polls (
+    poll TEXT PRIMARY KEY,
+    poll_id INTEGER NOT NULL,
+    authority TEXT,
+    price INTEGER,
+    mint0 TEXT,
+    mint1 TEXT,
+    created_at INTEGER,
+    votes_cast INTEGER NOT NULL DEFAULT 0,
+    votes_counted INTEGER NOT NULL DEFAULT 0,
+    consensus_price INTEGER,
+    revealed_at INTEGER
+);
+
+CREATE TABLE IF NOT EXISTS poll_votes (
+    signature TEXT NOT NULL,
+    poll TEXT NOT NULL,
+    voter TEXT NOT NULL,
+    weight INTEGER NOT NULL,
+    timestamp INTEGER NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS poll_reveals (
+    signature TEXT NOT NULL,
+    poll TEXT NOT NULL,
+    poll_id INTEGER NOT NULL,
+    -- JSON array of the vote counts per option
+    counts TEXT NOT NULL,
+    consensus_price INTEGER NOT NULL,
+    revealed_at INTEGER NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS proposals (
+    proposal TEXT PRIMARY KEY,
+    author TEXT NOT NULL,
+    proposal_index INTEGER NOT NULL,
+    digest TEXT NOT NULL,
+    uri TEXT,
+    -- Active, Finalized or Failed
+    status TEXT NOT NULL,
+    vote_count INTEGER NOT NULL DEFAULT 0,
+    final_score INTEGER,
+    total_weight INTEGER,
+    created_slot INTEGER NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS conviction_votes (
+    proposal TEXT NOT NULL,
+    voter TEXT NOT NULL,
+    score INTEGER NOT NULL,
+    conviction INTEGER NOT NULL,
+    locked_amount INTEGER NOT NULL,
+    unlock_time INTEGER NOT NULL,
+    retracted INTEGER NOT NULL,
+    updated_at INTEGER NOT NULL,
+    PRIMARY KEY (proposal, voter)
+);
+";
+
+/// Opens the database at `path`, creating the schema if needed.
+pub fn open(path: &Path) -> rusqlite::Result<Connection> {
+    init(Connection::open(path)?)
+}
+
+pub fn open_in_memory() -> rusqlite::Result<Connection> {
+    init(Connection::open_in_memory()?)
+}
+
+fn init(conn: Connection) -> rusqlite::Result<Connection> {
+    conn.execute_batch(SCHEMA)?;
+    Ok(conn)
+}
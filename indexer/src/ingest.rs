@@ -0,0 +1,307 @@
+//! Applies the events of program transactions to the database.
+
+use anyhow::Result;
+use rusqlite::{params, Connection, OptionalExtension, Transaction};
+use serde::{Deserialize, Serialize};
+
+use rational_dex::state::conviction_state::ProposalStatus;
+use rational_dex_client::events::{program_data, DexEvent};
+
+/// A transaction of the program as stored in a dump, one JSON object per line.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TransactionRecord {
+    pub signature: String,
+    pub slot: u64,
+    #[serde(default)]
+    pub block_time: Option<i64>,
+    /// Failed transactions are recorded, but their events were rolled back
+    #[serde(default)]
+    pub failed: bool,
+    pub logs: Vec<String>,
+}
+
+/// Indexes `record` unless it already was. Returns whether it was new.
+pub fn ingest(conn: &mut Connection, record: &TransactionRecord) -> Result<bool> {
+    let mut tx = conn.transaction()?;
+    let inserted = tx.execute(
+        "INSERT OR IGNORE INTO transactions (signature, slot, block_time, failed)
+         VALUES (?1, ?2, ?3, ?4)",
+        params![
+            record.signature,
+            record.slot,
+            record.block_time,
+            record.failed
+        ],
+    )?;
+    if inserted == 0 {
+        return Ok(false);
+    }
+
+    if !record.failed {
+        for (index, data) in program_data(&record.logs).iter().enumerate() {
+            let event = DexEvent::decode(data);
+            let skipped = match &event {
+                Some(event) => apply_or_skip(&mut tx, record, event)?,
+                None => None,
+            };
+            tx.execute(
+                "INSERT INTO events (signature, event_index, slot, name, version, data, skipped)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                params![
+                    record.signature,
+                    index,
+                    record.slot,
+                    event.as_ref().map(DexEvent::name),
+                    event.as_ref().map(DexEvent::version),
+                    data,
+                    skipped,
+                ],
+            )?;
+        }
+    }
+
+    tx.commit()?;
+    Ok(true)
+}
+
+/// Slot of the newest indexed transaction and its signature.
+pub fn last_indexed(conn: &Connection) -> Result<Option<(u64, String)>> {
+    Ok(conn
+        .query_row(
+            "SELECT slot, signature FROM transactions ORDER BY slot DESC LIMIT 1",
+            [],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .optional()?)
+}
+
+/// Applies `event` inside a savepoint. SQLite integers are i64, so an event carrying a u64
+/// above `i64::MAX` cannot be stored; its changes are rolled back and the reason returned.
+/// Any other error fails the whole transaction.
+fn apply_or_skip(
+    tx: &mut Transaction,
+    record: &TransactionRecord,
+    event: &DexEvent,
+) -> Result<Option<String>> {
+    let savepoint = tx.savepoint()?;
+    match apply(&savepoint, record, event) {
+        Ok(()) => {
+            savepoint.commit()?;
+            Ok(None)
+        }
+        Err(err) => match err.downcast_ref::<rusqlite::Error>() {
+            Some(rusqlite::Error::ToSqlConversionFailure(reason)) => Ok(Some(reason.to_string())),
+            _ => Err(err),
+        },
+    }
+}
+
+fn apply(tx: &Connection, record: &TransactionRecord, event: &DexEvent) -> Result<()> {
+    let slot = record.slot;
+    match event {
+        DexEvent::OfferCreated(e) => {
+            // Offers are closed once taken or cancelled, so their id can come back
+            tx.execute(
+                "INSERT OR REPLACE INTO offers (maker, offer_id, token_mint_a, token_mint_b,
+                    offered_amount, wanted_amount, remaining_amount, is_private, status,
+                    created_slot, updated_slot)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?5, ?7, 'open', ?8, ?8)",
+                params![
+                    e.maker.to_string(),
+                    e.offer_id,
+                    e.token_mint_a.to_string(),
+                    e.token_mint_b.to_string(),
+                    e.offered_amount,
+                    e.wanted_amount,
+                    e.is_private,
+                    slot,
+                ],
+            )?;
+        }
+        DexEvent::OfferTaken(e) => {
+            let maker = e.maker.to_string();
+            let status = if e.remaining_amount == 0 {
+                "taken"
+            } else {
+                "open"
+            };
+            tx.execute(
+                "UPDATE offers SET remaining_amount = ?1, status = ?2, updated_slot = ?3
+                 WHERE maker = ?4 AND offer_id = ?5",
+                params![e.remaining_amount, status, slot, maker, e.offer_id],
+            )?;
+            tx.execute(
+                "INSERT INTO offer_fills (signature, slot, maker, offer_id, taker, amount_taken,
+                    remaining_amount)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                params![
+                    record.signature,
+                    slot,
+                    maker,
+                    e.offer_id,
+                    e.taker.to_string(),
+                    e.amount_taken,
+                    e.remaining_amount,
+                ],
+            )?;
+        }
+        DexEvent::OfferCancelled(e) => {
+            tx.execute(
+                "UPDATE offers SET remaining_amount = 0, status = 'cancelled', updated_slot = ?1
+                 WHERE maker = ?2 AND offer_id = ?3 AND status = 'open'",
+                params![slot, e.maker.to_string(), e.offer_id],
+            )?;
+        }
+        DexEvent::PollCreated(e) => {
+            tx.execute(
+                "INSERT INTO polls (poll, poll_id, authority, price, mint0, mint1, created_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
+                 ON CONFLICT (poll) DO UPDATE SET authority = ?3, price = ?4, mint0 = ?5,
+                    mint1 = ?6, created_at = ?7",
+                params![
+                    e.poll.to_string(),
+                    e.poll_id,
+                    e.authority.to_string(),
+                    e.price,
+                    e.mint0.to_string(),
+                    e.mint1.to_string(),
+                    e.timestamp,
+                ],
+            )?;
+        }
+        DexEvent::VoteCast(e) => {
+            tx.execute(
+                "INSERT INTO poll_votes (signature, poll, voter, weight, timestamp)
+                 VALUES (?1, ?2, ?3, ?4, ?5)",
+                params![
+                    record.signature,
+                    e.poll.to_string(),
+                    e.voter.to_string(),
+                    e.weight,
+                    e.timestamp,
+                ],
+            )?;
+            tx.execute(
+                "INSERT INTO polls (poll, poll_id, votes_cast) VALUES (?1, ?2, 1)
+                 ON CONFLICT (poll) DO UPDATE SET votes_cast = votes_cast + 1",
+                params![e.poll.to_string(), e.poll_id],
+            )?;
+        }
+        DexEvent::VoteEvent(e) => {
+            tx.execute(
+                "INSERT INTO polls (poll, poll_id, votes_counted) VALUES (?1, ?2, 1)
+                 ON CONFLICT (poll) DO UPDATE SET votes_counted = votes_counted + 1",
+                params![e.poll.to_string(), e.poll_id],
+            )?;
+        }
+        DexEvent::RevealResultEvent(e) => {
+            tx.execute(
+                "INSERT INTO poll_reveals (signature, poll, poll_id, counts, consensus_price,
+                    revealed_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                params![
+                    record.signature,
+                    e.poll.to_string(),
+                    e.poll_id,
+                    serde_json::to_string(&e.output)?,
+                    e.consensus_price,
+                    e.revealed_at,
+                ],
+            )?;
+            tx.execute(
+                "INSERT INTO polls (poll, poll_id, consensus_price, revealed_at)
+                 VALUES (?1, ?2, ?3, ?4)
+                 ON CONFLICT (poll) DO UPDATE SET consensus_price = ?3, revealed_at = ?4",
+                params![
+                    e.poll.to_string(),
+                    e.poll_id,
+                    e.consensus_price,
+                    e.revealed_at
+                ],
+            )?;
+        }
+        DexEvent::ProposalCreated(e) => {
+            tx.execute(
+                "INSERT OR REPLACE INTO proposals (proposal, author, proposal_index, digest, uri,
+                    status, created_slot)
+                 VALUES (?1, ?2, ?3, ?4, ?5, 'Active', ?6)",
+                params![
+                    e.proposal.to_string(),
+                    e.author.to_string(),
+                    e.proposal_index,
+                    hex(&e.evidence.digest),
+                    e.evidence.uri,
+                    slot,
+                ],
+            )?;
+        }
+        DexEvent::EvidenceUpdated(e) => {
+            tx.execute(
+                "UPDATE proposals SET digest = ?1, uri = ?2 WHERE proposal = ?3",
+                params![
+                    hex(&e.evidence.digest),
+                    e.evidence.uri,
+                    e.proposal.to_string()
+                ],
+            )?;
+        }
+        DexEvent::ConvictionVoteCast(e) => {
+            tx.execute(
+                "INSERT OR REPLACE INTO conviction_votes (proposal, voter, score, conviction,
+                    locked_amount, unlock_time, retracted, updated_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, 0, ?7)",
+                params![
+                    e.proposal.to_string(),
+                    e.voter.to_string(),
+                    e.score,
+                    e.conviction,
+                    e.locked_amount,
+                    e.unlock_time,
+                    e.timestamp,
+                ],
+            )?;
+            count_votes(tx, &e.proposal.to_string())?;
+        }
+        DexEvent::ConvictionVoteRetracted(e) => {
+            tx.execute(
+                "UPDATE conviction_votes SET retracted = 1, updated_at = ?1
+                 WHERE proposal = ?2 AND voter = ?3",
+                params![e.timestamp, e.proposal.to_string(), e.voter.to_string()],
+            )?;
+            count_votes(tx, &e.proposal.to_string())?;
+        }
+        DexEvent::ProposalFinalized(e) => {
+            let status = match e.status {
+                ProposalStatus::Active => "Active",
+                ProposalStatus::Finalized => "Finalized",
+                ProposalStatus::Failed => "Failed",
+            };
+            tx.execute(
+                "UPDATE proposals SET status = ?1, final_score = ?2, total_weight = ?3
+                 WHERE proposal = ?4",
+                params![
+                    status,
+                    e.final_score,
+                    e.total_weight,
+                    e.proposal.to_string()
+                ],
+            )?;
+        }
+        // Only kept in `events` for now
+        _ => {}
+    }
+    Ok(())
+}
+
+fn count_votes(tx: &Connection, proposal: &str) -> rusqlite::Result<usize> {
+    tx.execute(
+        "UPDATE proposals SET vote_count =
+            (SELECT COUNT(*) FROM conviction_votes WHERE proposal = ?1 AND retracted = 0)
+         WHERE proposal = ?1",
+        [proposal],
+    )
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
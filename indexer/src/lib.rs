@@ -0,0 +1,8 @@
+//! Indexer for the `rational_dex` program: decodes the events in its transaction logs into
+//! SQLite tables and serves them as JSON. Transactions come from an RPC node or from a
+//! saved dump, so the whole pipeline also runs offline.
+
+pub mod api;
+pub mod db;
+pub mod ingest;
+pub mod source;
@@ -0,0 +1,120 @@
+use std::fs::File;
+use std::io::{BufReader, BufWriter};
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use clap::{Parser, Subcommand};
+use rusqlite::Connection;
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::commitment_config::CommitmentConfig;
+
+use rational_dex_indexer::ingest::{ingest, last_indexed, TransactionRecord};
+use rational_dex_indexer::{api, db, source};
+
+#[derive(Parser)]
+#[command(
+    name = "rational-dex-indexer",
+    about = "Index rational_dex events into SQLite"
+)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Index the transactions of a dump, one JSON record per line
+    Ingest {
+        #[arg(long)]
+        db: PathBuf,
+        #[arg(long)]
+        dump: PathBuf,
+    },
+    /// Index the program transactions newer than the last indexed one
+    Sync {
+        #[arg(long)]
+        db: PathBuf,
+        /// RPC endpoint
+        #[arg(long, default_value = "http://127.0.0.1:8899")]
+        url: String,
+        /// Keep polling for new transactions
+        #[arg(long)]
+        follow: bool,
+        /// Seconds between polls with --follow
+        #[arg(long, default_value_t = 10)]
+        interval: u64,
+    },
+    /// Save the program transactions to a dump instead of indexing them
+    Fetch {
+        /// RPC endpoint
+        #[arg(long, default_value = "http://127.0.0.1:8899")]
+        url: String,
+        #[arg(long)]
+        out: PathBuf,
+    },
+    /// Serve the JSON query API
+    Serve {
+        #[arg(long)]
+        db: PathBuf,
+        #[arg(long, default_value = "127.0.0.1:8080")]
+        listen: String,
+    },
+}
+
+fn main() -> Result<()> {
+    match Cli::parse().command {
+        Command::Ingest { db, dump } => {
+            let mut conn = db::open(&db)?;
+            let file = File::open(&dump).with_context(|| format!("opening {}", dump.display()))?;
+            let records = source::read_dump(BufReader::new(file))?;
+            index(&mut conn, &records)?;
+        }
+        Command::Sync {
+            db,
+            url,
+            follow,
+            interval,
+        } => {
+            let mut conn = db::open(&db)?;
+            let client = RpcClient::new_with_commitment(url, CommitmentConfig::confirmed());
+            loop {
+                let until = last_indexed(&conn)?.map(|(_, signature)| signature);
+                let records = source::fetch_transactions(&client, until.as_deref())?;
+                index(&mut conn, &records)?;
+                if !follow {
+                    break;
+                }
+                thread::sleep(Duration::from_secs(interval));
+            }
+        }
+        Command::Fetch { url, out } => {
+            let client = RpcClient::new_with_commitment(url, CommitmentConfig::confirmed());
+            let records = source::fetch_transactions(&client, None)?;
+            source::write_dump(BufWriter::new(create(&out)?), &records)?;
+            println!("saved {} transactions", records.len());
+        }
+        Command::Serve { db, listen } => {
+            let conn = db::open(&db)?;
+            println!("listening on http://{listen}");
+            api::serve(&conn, &listen)?;
+        }
+    }
+    Ok(())
+}
+
+fn index(conn: &mut Connection, records: &[TransactionRecord]) -> Result<()> {
+    let mut new = 0;
+    for record in records {
+        if ingest(conn, record).with_context(|| format!("indexing {}", record.signature))? {
+            new += 1;
+        }
+    }
+    println!("indexed {new} new of {} transactions", records.len());
+    Ok(())
+}
+
+fn create(path: &Path) -> Result<File> {
+    File::create(path).with_context(|| format!("creating {}", path.display()))
+}
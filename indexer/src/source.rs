@@ -0,0 +1,94 @@
+//! Where transactions come from: a saved dump, or an RPC node.
+
+use std::io::{BufRead, Write};
+use std::str::FromStr;
+
+use anyhow::{Context, Result};
+use solana_client::rpc_client::{GetConfirmedSignaturesForAddress2Config, RpcClient};
+use solana_client::rpc_config::RpcTransactionConfig;
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::signature::Signature;
+use solana_transaction_status_client_types::UiTransactionEncoding;
+
+use crate::ingest::TransactionRecord;
+
+/// Most signatures `getSignaturesForAddress` returns per page.
+const SIGNATURE_PAGE: usize = 1000;
+
+/// Reads a dump of one JSON `TransactionRecord` per line. Blank lines are skipped.
+pub fn read_dump(reader: impl BufRead) -> Result<Vec<TransactionRecord>> {
+    reader
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| line.as_ref().map_or(true, |line| !line.trim().is_empty()))
+        .map(|(index, line)| {
+            serde_json::from_str(&line?).with_context(|| format!("dump line {}", index + 1))
+        })
+        .collect()
+}
+
+pub fn write_dump(mut writer: impl Write, records: &[TransactionRecord]) -> Result<()> {
+    for record in records {
+        serde_json::to_writer(&mut writer, record)?;
+        writeln!(writer)?;
+    }
+    Ok(())
+}
+
+/// Program transactions newer than `until`, oldest first.
+pub fn fetch_transactions(
+    client: &RpcClient,
+    until: Option<&str>,
+) -> Result<Vec<TransactionRecord>> {
+    let until = until.map(Signature::from_str).transpose()?;
+    let mut statuses = Vec::new();
+    let mut before = None;
+    loop {
+        let page = client.get_signatures_for_address_with_config(
+            &rational_dex::ID,
+            GetConfirmedSignaturesForAddress2Config {
+                before,
+                until,
+                limit: Some(SIGNATURE_PAGE),
+                commitment: Some(CommitmentConfig::confirmed()),
+            },
+        )?;
+        let Some(last) = page.last() else {
+            break;
+        };
+        before = Some(Signature::from_str(&last.signature)?);
+        let full = page.len() == SIGNATURE_PAGE;
+        statuses.extend(page);
+        if !full {
+            break;
+        }
+    }
+
+    statuses
+        .into_iter()
+        .rev()
+        .map(|status| {
+            let signature = Signature::from_str(&status.signature)?;
+            let tx = client.get_transaction_with_config(
+                &signature,
+                RpcTransactionConfig {
+                    encoding: Some(UiTransactionEncoding::Base64),
+                    commitment: Some(CommitmentConfig::confirmed()),
+                    max_supported_transaction_version: Some(0),
+                },
+            )?;
+            let logs = tx
+                .transaction
+                .meta
+                .and_then(|meta| Option::<Vec<String>>::from(meta.log_messages))
+                .unwrap_or_default();
+            Ok(TransactionRecord {
+                signature: status.signature,
+                slot: status.slot,
+                block_time: status.block_time,
+                failed: status.err.is_some(),
+                logs,
+            })
+        })
+        .collect()
+}
@@ -9,3 +9,51 @@ pub const ANCHOR_DISCRIMINATOR: usize = 8;
 pub const COMP_DEF_OFFSET_INIT_VOTE_STATS: u32 = comp_def_offset("init_vote_stats");
 pub const COMP_DEF_OFFSET_VOTE: u32 = comp_def_offset("vote");
 pub const COMP_DEF_OFFSET_REVEAL: u32 = comp_def_offset("reveal_result");
+pub const COMP_DEF_OFFSET_INIT_SEALED_ASK: u32 = comp_def_offset("init_sealed_ask");
+pub const COMP_DEF_OFFSET_MATCH_SEALED_BID: u32 = comp_def_offset("match_sealed_bid");
+
+pub const MAX_FEE_RECIPIENTS: usize = 4;
+
+// Bumped whenever a field is added to or removed from an event
+pub const EVENT_VERSION: u8 = 2;
+
+// Pools
+pub const BPS_DENOMINATOR: u64 = 10_000;
+pub const MAX_FEE_BPS: u64 = 1_000; // swap fee cap: 10%
+pub const PROTOCOL_FEE_SHARE_BPS: u64 = 2_000; // 20% of the swap fee goes to the protocol
+pub const PRICE_SCALE: u128 = 1_000_000_000; // prices are token B per token A, scaled by 1e9
+pub const OBSERVATION_CAPACITY: usize = 64;
+pub const DEFAULT_CONSENSUS_WEIGHT_BPS: u16 = 5_000;
+pub const DEFAULT_MAX_DEVIATION_BPS: u16 = 1_000;
+pub const DEFAULT_CIRCUIT_BREAKER_BPS: u16 = 2_000;
+pub const REFERENCE_TWAP_WINDOW: i64 = 30 * 60; // market side of the reference price
+pub const FLASH_LOAN_FEE_BPS: u64 = 9; // 0.09% of the borrowed amount, kept by LPs
+pub const MINIMUM_LIQUIDITY: u64 = 1_000; // LP tokens of the first deposit locked forever
+
+// Each step of a poll vote (-3..=3) moves the consensus price by 1%
+pub const POLL_VOTE_STEP_BPS: u64 = 100;
+
+// Concentrated liquidity
+pub const TICK_ARRAY_SIZE: usize = 60;
+pub const MIN_TICK: i32 = -221_818; // keeps the Q32.32 square root price within 2^-16..2^16
+pub const MAX_TICK: i32 = 221_818;
+
+// Order book
+pub const ORDER_BOOK_CAPACITY: usize = 128; // resting orders per side, see `SortedOrders`
+
+// Batch auction
+pub const EPOCH_CLEARING_TIMEOUT: i64 = 60 * 60 * 24 * 7; // uncleared epochs refund after this
+
+pub const MAX_EVIDENCE_URI_LEN: usize = 200;
+pub const MIN_VOTING_PERIOD: i64 = 60 * 60; // 1 hour
+pub const MAX_VOTING_PERIOD: i64 = 60 * 60 * 24 * 90; // 90 days
+
+// Reputation
+pub const REPUTATION_DECAY_PERIOD: i64 = 60 * 60 * 24 * 30; // 30 days
+pub const REPUTATION_DECAY_NUMERATOR: u64 = 9; // keep 90% per period
+pub const REPUTATION_DECAY_DENOMINATOR: u64 = 10;
+pub const REPUTATION_MAX_DECAY_PERIODS: i64 = 64; // score is ~0 after this many periods
+pub const REPUTATION_AUTHOR_POINTS: u64 = 10; // per point of final score
+pub const REPUTATION_VOTER_POINTS: u64 = 5; // per point of accuracy and of conviction
+pub const REPUTATION_POINTS_PER_WEIGHT: u64 = 100;
+pub const REPUTATION_MAX_WEIGHT: u64 = 10;
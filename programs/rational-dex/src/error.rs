@@ -63,6 +63,8 @@ pub enum ErrorCode {
     AbortedComputation,
     #[msg("Cluster not set")]
     ClusterNotSet,
+    #[msg("Pool does not trade the poll's pair")]
+    PollPoolMismatch,
 }
 
 #[error_code]
@@ -77,4 +79,109 @@ pub enum DexError {
     InvalidAmount,
     #[msg("Insufficient offer")]
     InsufficientOffer,
+    #[msg("Fee exceeds the maximum allowed")]
+    FeeTooHigh,
+    #[msg("Insufficient liquidity")]
+    InsufficientLiquidity,
+    #[msg("Not enough price history for the requested window")]
+    InsufficientObservations,
+    #[msg("Invalid blend policy")]
+    InvalidBlendPolicy,
+    #[msg("Order book side is full")]
+    OrderBookFull,
+    #[msg("Order not found")]
+    OrderNotFound,
+    #[msg("Open orders account missing from remaining accounts")]
+    MissingOpenOrders,
+    #[msg("Invalid market")]
+    InvalidMarket,
+    #[msg("Taker is not allowed to take this offer")]
+    TakerNotAllowed,
+    #[msg("Sealed offer is not open")]
+    OfferNotOpen,
+    #[msg("Sealed bid has not been matched yet")]
+    BidPending,
+    #[msg("Epoch is closed")]
+    EpochClosed,
+    #[msg("Epoch has not ended yet")]
+    EpochNotEnded,
+    #[msg("Epoch has not been closed yet")]
+    EpochNotClosed,
+    #[msg("Poll consensus has not been revealed during this epoch")]
+    ConsensusNotRevealed,
+    #[msg("Intent already exists on the other side")]
+    IntentSideMismatch,
+    #[msg("Pool tokens must be different mints")]
+    IdenticalMints,
+    #[msg("Route hop does not trade the previous hop's output token")]
+    InvalidRoute,
+    #[msg("Pool is locked by an outstanding flash loan")]
+    PoolLocked,
+    #[msg("No flash loan is outstanding")]
+    NoFlashLoan,
+    #[msg("Flash borrow must be followed by a flash repay to the same pool")]
+    MissingFlashRepay,
+    #[msg("Flash loans cannot be taken through CPI")]
+    FlashLoanCpi,
+    #[msg("Invalid tick or tick range")]
+    InvalidTick,
+    #[msg("Tick array for the requested tick is missing")]
+    MissingTickArray,
+    #[msg("Reward stake belongs to another owner or vote")]
+    InvalidRewardStake,
+    #[msg("Not enough LP tokens staked")]
+    InsufficientStake,
+    #[msg("No rewards to claim")]
+    NoRewards,
+    #[msg("Swap moves the market price too far from the reference price")]
+    OutsideReferenceBand,
+    #[msg("Pool does not trade the offer's pair")]
+    InvalidReferencePool,
+    #[msg("Open orders account passed more than once")]
+    DuplicateOpenOrders,
+    #[msg("Order is worth less than one quote atom")]
+    DustOrder,
+    #[msg("Epoch was not cleared in time and only refunds intents")]
+    EpochExpired,
+    #[msg("Pool mints must be passed in ascending order")]
+    UnorderedMints,
+    #[msg("The same tick array was passed more than once")]
+    DuplicateTickArray,
+    #[msg("Poll was not opened by the pool authority")]
+    UnauthorizedPoll,
+    #[msg("Poll was revealed before the pool's current consensus price")]
+    StalePollResult,
+}
+
+// Codes from 7000 on, clear of the enums above, which all start at 6000
+#[error_code(offset = 7000)]
+pub enum ConfigError {
+    #[msg("Only the admin can do this")]
+    NotAdmin,
+    #[msg("Signer is not the pending admin")]
+    NotPendingAdmin,
+    #[msg("Too many fee recipients")]
+    TooManyFeeRecipients,
+    #[msg("Signer is not a fee recipient")]
+    NotFeeRecipient,
+    #[msg("Swaps are paused")]
+    SwapsPaused,
+    #[msg("Offers are paused")]
+    OffersPaused,
+    #[msg("Polls are paused")]
+    PollsPaused,
+    #[msg("Conviction voting is paused")]
+    ConvictionPaused,
+    #[msg("Signer is not the program's upgrade authority")]
+    NotUpgradeAuthority,
+}
+
+#[error_code(offset = 7100)]
+pub enum TokenError {
+    #[msg("Non-transferable mints are not supported")]
+    NonTransferableMint,
+    #[msg("Mints with a permanent delegate are not supported")]
+    PermanentDelegateMint,
+    #[msg("Mints with a transfer hook are not supported")]
+    TransferHookMint,
 }
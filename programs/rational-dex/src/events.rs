@@ -1,18 +1,27 @@
 use anchor_lang::prelude::*;
 
+use crate::state::config_state::Subsystem;
+use crate::state::conviction_state::{EvidenceRef, ProposalStatus};
+use crate::state::order_book_state::Side;
+use crate::state::pool_state::BlendPolicy;
+
 #[event]
 pub struct OfferCreated {
+    pub version: u8,
     pub offer_id: u64,
     pub maker: Pubkey,
     pub token_mint_a: Pubkey,
     pub token_mint_b: Pubkey,
     pub offered_amount: u64,
     pub wanted_amount: u64,
+    pub is_private: bool,
 }
 
 #[event]
 pub struct OfferTaken {
+    pub version: u8,
     pub offer_id: u64,
+    pub maker: Pubkey,
     pub taker: Pubkey,
     pub amount_taken: u64,
     pub remaining_amount: u64,
@@ -20,7 +29,452 @@ pub struct OfferTaken {
 
 #[event]
 pub struct OfferCancelled {
+    pub version: u8,
     pub offer_id: u64,
     pub maker: Pubkey,
     pub refunded_amount: u64,
 }
+
+#[event]
+pub struct ProposalCreated {
+    pub version: u8,
+    pub proposal: Pubkey,
+    pub author: Pubkey,
+    pub proposal_index: u64,
+    pub evidence: EvidenceRef,
+}
+
+#[event]
+pub struct EvidenceUpdated {
+    pub version: u8,
+    pub proposal: Pubkey,
+    pub author: Pubkey,
+    pub evidence: EvidenceRef,
+}
+
+#[event]
+pub struct CircuitBreakerTriggered {
+    pub version: u8,
+    pub pool: Pubkey,
+    pub market_price: u64,
+    pub consensus_price: u64,
+    pub deviation_bps: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct OrderPlaced {
+    pub version: u8,
+    pub market: Pubkey,
+    pub order_id: u64,
+    pub owner: Pubkey,
+    pub side: Side,
+    pub price: u64,
+    pub quantity: u64,
+}
+
+#[event]
+pub struct OrderCancelled {
+    pub version: u8,
+    pub market: Pubkey,
+    pub order_id: u64,
+    pub owner: Pubkey,
+    pub remaining_quantity: u64,
+}
+
+#[event]
+pub struct OrderFilled {
+    pub version: u8,
+    pub market: Pubkey,
+    pub bid_order_id: u64,
+    pub ask_order_id: u64,
+    pub price: u64,
+    pub quantity: u64,
+}
+
+#[event]
+pub struct SealedOfferCreated {
+    pub version: u8,
+    pub offer: Pubkey,
+    pub maker: Pubkey,
+    pub token_mint_a: Pubkey,
+    pub token_mint_b: Pubkey,
+    pub offered_amount: u64,
+}
+
+#[event]
+pub struct SealedBidResolved {
+    pub version: u8,
+    pub offer: Pubkey,
+    pub bid: Pubkey,
+    pub taker: Pubkey,
+    pub matched: bool,
+    pub settlement_amount: u64,
+}
+
+#[event]
+pub struct EpochCleared {
+    pub version: u8,
+    pub epoch: Pubkey,
+    pub clearing_price: u64,
+    pub matched_base: u64,
+    pub matched_quote: u64,
+}
+
+#[event]
+pub struct FlashLoanRepaid {
+    pub version: u8,
+    pub pool: Pubkey,
+    pub borrower: Pubkey,
+    pub mint: Pubkey,
+    pub amount: u64,
+    pub fee: u64,
+}
+
+#[event]
+pub struct ActiveRangeRecentered {
+    pub version: u8,
+    pub cl_pool: Pubkey,
+    pub consensus_price: u64,
+    pub tick_lower: i32,
+    pub tick_upper: i32,
+}
+
+#[event]
+pub struct PositionUpdated {
+    pub version: u8,
+    pub pool: Pubkey,
+    pub owner: Pubkey,
+    /// True for a deposit, false for a withdrawal
+    pub deposit: bool,
+    pub lp_change: u64,
+    pub amount_a: u64,
+    pub amount_b: u64,
+    pub lp_amount: u64,
+    pub deposited_a: u64,
+    pub deposited_b: u64,
+    pub fees_earned_a: u64,
+    pub fees_earned_b: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct RewardsFunded {
+    pub version: u8,
+    pub reward_program: Pubkey,
+    pub amount: u64,
+    pub reward_rate: u64,
+    pub period_end: i64,
+}
+
+#[event]
+pub struct RewardsClaimed {
+    pub version: u8,
+    pub reward_program: Pubkey,
+    pub owner: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct PoolInitialized {
+    pub version: u8,
+    pub pool: Pubkey,
+    pub authority: Pubkey,
+    pub mint_a: Pubkey,
+    pub mint_b: Pubkey,
+    pub mint_lp: Pubkey,
+    pub vault_a: Pubkey,
+    pub vault_b: Pubkey,
+    pub fee_numerator: u64,
+    pub fee_denominator: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct LiquidityChanged {
+    pub version: u8,
+    pub pool: Pubkey,
+    pub user: Pubkey,
+    /// True for a deposit, false for a withdrawal
+    pub deposit: bool,
+    pub lp_amount: u64,
+    pub amount_a: u64,
+    pub amount_b: u64,
+    /// Reserves and LP supply after the change
+    pub reserve_a: u64,
+    pub reserve_b: u64,
+    pub total_lp_supply: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct Swapped {
+    pub version: u8,
+    pub pool: Pubkey,
+    pub user: Pubkey,
+    pub a_to_b: bool,
+    /// Input that reached the vault
+    pub amount_in: u64,
+    pub amount_out: u64,
+    pub protocol_fee: u64,
+    pub lp_fee: u64,
+    /// Reserves after the swap
+    pub reserve_a: u64,
+    pub reserve_b: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ConsensusPriceUpdated {
+    pub version: u8,
+    pub pool: Pubkey,
+    pub consensus_price: u64,
+    pub reference_price: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct PollCreated {
+    pub version: u8,
+    pub poll: Pubkey,
+    pub poll_id: u32,
+    pub authority: Pubkey,
+    pub price: u64,
+    pub mint0: Pubkey,
+    pub mint1: Pubkey,
+    pub timestamp: i64,
+}
+
+/// A vote was queued for encrypted tallying; `VoteEvent` follows once it is counted.
+#[event]
+pub struct VoteCast {
+    pub version: u8,
+    pub poll: Pubkey,
+    pub poll_id: u32,
+    pub voter: Pubkey,
+    pub weight: u64,
+    pub timestamp: i64,
+}
+
+/// Emitted for new votes and for changes to an existing vote.
+#[event]
+pub struct ConvictionVoteCast {
+    pub version: u8,
+    pub proposal: Pubkey,
+    pub voter: Pubkey,
+    pub score: u8,
+    pub conviction: u8,
+    pub locked_amount: u64,
+    pub unlock_time: i64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ConvictionVoteRetracted {
+    pub version: u8,
+    pub proposal: Pubkey,
+    pub voter: Pubkey,
+    pub pending_release: u64,
+    pub release_time: i64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct TokensUnlocked {
+    pub version: u8,
+    pub proposal: Pubkey,
+    pub voter: Pubkey,
+    pub amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ProposalFinalized {
+    pub version: u8,
+    pub proposal: Pubkey,
+    pub author: Pubkey,
+    pub status: ProposalStatus,
+    pub final_score: Option<u8>,
+    pub total_weight: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ProtocolFeesCollected {
+    pub version: u8,
+    pub pool: Pubkey,
+    pub recipient: Pubkey,
+    pub amount_a: u64,
+    pub amount_b: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct BlendPolicyUpdated {
+    pub version: u8,
+    pub pool: Pubkey,
+    pub policy: BlendPolicy,
+    /// Reference price recomputed under the new policy
+    pub reference_price: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct FlashLoanBorrowed {
+    pub version: u8,
+    pub pool: Pubkey,
+    pub borrower: Pubkey,
+    pub mint: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct VoterReputationSettled {
+    pub version: u8,
+    pub proposal: Pubkey,
+    pub voter: Pubkey,
+    pub points: u64,
+    /// Reputation score after the points were added
+    pub score: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct PausedChanged {
+    pub version: u8,
+    pub subsystem: Subsystem,
+    pub paused: bool,
+    pub timestamp: i64,
+}
+
+/// `pending_admin` is `None` when a pending transfer was cancelled.
+#[event]
+pub struct AdminProposed {
+    pub version: u8,
+    pub admin: Pubkey,
+    pub pending_admin: Option<Pubkey>,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct AdminAccepted {
+    pub version: u8,
+    pub previous_admin: Pubkey,
+    pub admin: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct FeeRecipientsUpdated {
+    pub version: u8,
+    pub fee_recipients: Vec<Pubkey>,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct EpochOpened {
+    pub version: u8,
+    pub epoch: Pubkey,
+    pub poll: Pubkey,
+    pub id: u64,
+    pub base_mint: Pubkey,
+    pub quote_mint: Pubkey,
+    pub ends_at: i64,
+}
+
+#[event]
+pub struct IntentSubmitted {
+    pub version: u8,
+    pub epoch: Pubkey,
+    pub owner: Pubkey,
+    pub side: Side,
+    /// Amount that reached the vault
+    pub amount: u64,
+    /// The owner's intent after this deposit
+    pub intent_amount: u64,
+}
+
+#[event]
+pub struct FillClaimed {
+    pub version: u8,
+    pub epoch: Pubkey,
+    pub owner: Pubkey,
+    pub side: Side,
+    pub base_amount: u64,
+    pub quote_amount: u64,
+}
+
+#[event]
+pub struct LpStakeChanged {
+    pub version: u8,
+    pub reward_program: Pubkey,
+    pub owner: Pubkey,
+    /// True for a stake, false for an unstake
+    pub stake: bool,
+    pub amount: u64,
+    pub lp_staked: u64,
+    pub lp_total_weight: u64,
+}
+
+#[event]
+pub struct VoteWeightSynced {
+    pub version: u8,
+    pub reward_program: Pubkey,
+    pub voter: Pubkey,
+    pub proposal: Pubkey,
+    pub vote_weight: u64,
+    pub vote_total_weight: u64,
+}
+
+#[event]
+pub struct ClPoolCreated {
+    pub version: u8,
+    pub cl_pool: Pubkey,
+    pub pool: Pubkey,
+    pub tick_spacing: u16,
+    pub fee_bps: u16,
+    pub sqrt_price_x32: u64,
+    pub tick_current: i32,
+}
+
+#[event]
+pub struct ClPositionOpened {
+    pub version: u8,
+    pub cl_pool: Pubkey,
+    pub position: Pubkey,
+    pub owner: Pubkey,
+    pub tick_lower: i32,
+    pub tick_upper: i32,
+    pub liquidity: u64,
+    pub amount_a: u64,
+    pub amount_b: u64,
+}
+
+#[event]
+pub struct ClPositionClosed {
+    pub version: u8,
+    pub cl_pool: Pubkey,
+    pub position: Pubkey,
+    pub owner: Pubkey,
+    pub liquidity: u64,
+    /// Withdrawn liquidity, fees not included
+    pub amount_a: u64,
+    pub amount_b: u64,
+    pub fees_a: u64,
+    pub fees_b: u64,
+}
+
+#[event]
+pub struct ClSwapped {
+    pub version: u8,
+    pub cl_pool: Pubkey,
+    pub user: Pubkey,
+    pub a_to_b: bool,
+    /// Input that reached the vault and was swapped, fees included
+    pub amount_in: u64,
+    pub amount_out: u64,
+    /// Pool state after the swap
+    pub sqrt_price_x32: u64,
+    pub tick_current: i32,
+    pub liquidity: u64,
+}
@@ -0,0 +1,164 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked},
+};
+
+use crate::error::DexError;
+use crate::events::FillClaimed;
+use crate::instructions::batch_auction::is_expired;
+use crate::instructions::pool::{mul_div_ceil, mul_div_floor};
+use crate::state::batch_auction_state::{Epoch, Intent};
+use crate::state::order_book_state::Side;
+use crate::EVENT_VERSION;
+
+/// Pays out the caller's share of the cleared volume and refunds the unfilled deposit, or the
+/// whole deposit once the epoch expired without being cleared.
+pub fn claim_fill_handler(ctx: Context<ClaimFill>) -> Result<()> {
+    let accounts = &ctx.accounts;
+    let epoch = &accounts.epoch;
+    let intent = &accounts.intent;
+    require!(
+        epoch.closed || is_expired(epoch, Clock::get()?.unix_timestamp)?,
+        DexError::EpochNotClosed
+    );
+
+    let (base_out, quote_out) = fill_amounts(epoch, intent.side, intent.amount)?;
+
+    let id_bytes = epoch.id.to_le_bytes();
+    let seeds = &[b"epoch", epoch.poll.as_ref(), &id_bytes[..], &[epoch.bump]];
+    let signer_seeds = [&seeds[..]];
+
+    for (amount, vault, to, mint) in [
+        (
+            base_out,
+            &accounts.base_vault,
+            &accounts.owner_base_account,
+            &accounts.base_mint,
+        ),
+        (
+            quote_out,
+            &accounts.quote_vault,
+            &accounts.owner_quote_account,
+            &accounts.quote_mint,
+        ),
+    ] {
+        if amount == 0 {
+            continue;
+        }
+        let transfer_accounts = TransferChecked {
+            from: vault.to_account_info(),
+            mint: mint.to_account_info(),
+            to: to.to_account_info(),
+            authority: epoch.to_account_info(),
+        };
+        let cpi_context = CpiContext::new_with_signer(
+            accounts.token_program.to_account_info(),
+            transfer_accounts,
+            &signer_seeds,
+        );
+        transfer_checked(cpi_context, amount, mint.decimals)?;
+    }
+
+    emit!(FillClaimed {
+        version: EVENT_VERSION,
+        epoch: epoch.key(),
+        owner: accounts.owner.key(),
+        side: intent.side,
+        base_amount: base_out,
+        quote_amount: quote_out,
+    });
+
+    Ok(())
+}
+
+/// Base and quote paid out for an intent of `amount` on `side`. What is received rounds down
+/// and what is spent rounds up, so the vaults stay solvent. An epoch that was never cleared
+/// matched nothing, so every intent gets its deposit back.
+pub fn fill_amounts(epoch: &Epoch, side: Side, amount: u64) -> Result<(u64, u64)> {
+    if !epoch.closed {
+        return Ok(match side {
+            Side::Bid => (0, amount),
+            Side::Ask => (amount, 0),
+        });
+    }
+    let unspent = |spent: u64| amount.checked_sub(spent).ok_or(DexError::Overflow);
+    Ok(match side {
+        Side::Bid => {
+            let received = mul_div_floor(amount, epoch.matched_base, epoch.total_buy_quote)?;
+            let spent = mul_div_ceil(amount, epoch.matched_quote, epoch.total_buy_quote)?;
+            (received, unspent(spent)?)
+        }
+        Side::Ask => {
+            let spent = mul_div_ceil(amount, epoch.matched_base, epoch.total_sell_base)?;
+            let received = mul_div_floor(amount, epoch.matched_quote, epoch.total_sell_base)?;
+            (unspent(spent)?, received)
+        }
+    })
+}
+
+#[derive(Accounts)]
+pub struct ClaimFill<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        seeds = [b"epoch", epoch.poll.as_ref(), epoch.id.to_le_bytes().as_ref()],
+        bump = epoch.bump,
+        has_one = base_mint,
+        has_one = quote_mint,
+    )]
+    pub epoch: Box<Account<'info, Epoch>>,
+
+    #[account(
+        mut,
+        close = owner,
+        seeds = [b"intent", epoch.key().as_ref(), owner.key().as_ref()],
+        bump = intent.bump,
+        has_one = epoch,
+        has_one = owner,
+    )]
+    pub intent: Box<Account<'info, Intent>>,
+
+    pub base_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    pub quote_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        associated_token::mint = base_mint,
+        associated_token::authority = epoch,
+        associated_token::token_program = token_program
+    )]
+    pub base_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        associated_token::mint = quote_mint,
+        associated_token::authority = epoch,
+        associated_token::token_program = token_program
+    )]
+    pub quote_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        init_if_needed,
+        payer = owner,
+        associated_token::mint = base_mint,
+        associated_token::authority = owner,
+        associated_token::token_program = token_program
+    )]
+    pub owner_base_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        init_if_needed,
+        payer = owner,
+        associated_token::mint = quote_mint,
+        associated_token::authority = owner,
+        associated_token::token_program = token_program
+    )]
+    pub owner_quote_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub system_program: Program<'info, System>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
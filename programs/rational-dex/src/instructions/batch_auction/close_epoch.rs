@@ -0,0 +1,75 @@
+use anchor_lang::prelude::*;
+
+use crate::error::DexError;
+use crate::events::EpochCleared;
+use crate::instructions::pool::mul_div_floor;
+use crate::state::batch_auction_state::Epoch;
+use crate::state::poll_state::PollAccount;
+use crate::{EPOCH_CLEARING_TIMEOUT, EVENT_VERSION, PRICE_SCALE};
+
+/// Permissionless. Clears every intent of the epoch at the poll's latest revealed consensus
+/// price, which must have been revealed after intents stopped, so no intent was placed
+/// knowing it. The lighter side fills completely and the heavier side fills pro rata in
+/// `claim_fill`. An epoch not cleared within `EPOCH_CLEARING_TIMEOUT` only refunds.
+pub fn close_epoch_handler(ctx: Context<CloseEpoch>) -> Result<()> {
+    let poll_acc = &ctx.accounts.poll_acc;
+    let epoch = &mut ctx.accounts.epoch;
+    require!(!epoch.closed, DexError::EpochClosed);
+    let now = Clock::get()?.unix_timestamp;
+    require!(now >= epoch.ends_at, DexError::EpochNotEnded);
+    require!(!is_expired(epoch, now)?, DexError::EpochExpired);
+    require!(
+        poll_acc.consensus_price > 0 && poll_acc.revealed_at >= epoch.ends_at,
+        DexError::ConsensusNotRevealed
+    );
+
+    let price = poll_acc.consensus_price;
+    let (matched_base, matched_quote) =
+        clear_epoch(epoch.total_buy_quote, epoch.total_sell_base, price)?;
+
+    epoch.clearing_price = price;
+    epoch.matched_base = matched_base;
+    epoch.matched_quote = matched_quote;
+    epoch.closed = true;
+
+    emit!(EpochCleared {
+        version: EVENT_VERSION,
+        epoch: epoch.key(),
+        clearing_price: price,
+        matched_base,
+        matched_quote,
+    });
+
+    Ok(())
+}
+
+/// Base and quote matched when buy intents worth `total_buy_quote` meet sell intents of
+/// `total_sell_base` at `price`, both rounded down.
+pub fn clear_epoch(total_buy_quote: u64, total_sell_base: u64, price: u64) -> Result<(u64, u64)> {
+    let demand_base = mul_div_floor(total_buy_quote, PRICE_SCALE as u64, price)?;
+    let matched_base = demand_base.min(total_sell_base);
+    let matched_quote = mul_div_floor(matched_base, price, PRICE_SCALE as u64)?;
+    Ok((matched_base, matched_quote))
+}
+
+/// Whether the epoch can no longer be cleared and its intents are refunded instead.
+pub fn is_expired(epoch: &Epoch, now: i64) -> Result<bool> {
+    let deadline = epoch
+        .ends_at
+        .checked_add(EPOCH_CLEARING_TIMEOUT)
+        .ok_or(DexError::Overflow)?;
+    Ok(!epoch.closed && now >= deadline)
+}
+
+#[derive(Accounts)]
+pub struct CloseEpoch<'info> {
+    #[account(
+        mut,
+        seeds = [b"epoch", epoch.poll.as_ref(), epoch.id.to_le_bytes().as_ref()],
+        bump = epoch.bump,
+    )]
+    pub epoch: Box<Account<'info, Epoch>>,
+
+    #[account(address = epoch.poll)]
+    pub poll_acc: Box<Account<'info, PollAccount>>,
+}
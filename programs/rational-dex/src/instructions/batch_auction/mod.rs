@@ -0,0 +1,9 @@
+pub mod claim_fill;
+pub mod close_epoch;
+pub mod open_epoch;
+pub mod submit_intent;
+
+pub use claim_fill::*;
+pub use close_epoch::*;
+pub use open_epoch::*;
+pub use submit_intent::*;
@@ -0,0 +1,93 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{Mint, TokenAccount, TokenInterface},
+};
+
+use crate::error::DexError;
+use crate::events::EpochOpened;
+use crate::state::batch_auction_state::Epoch;
+use crate::state::poll_state::PollAccount;
+use crate::{ANCHOR_DISCRIMINATOR, EVENT_VERSION};
+
+/// Opens a batch auction for the poll's pair (`mint0` base, `mint1` quote) that accepts
+/// intents for `duration` seconds.
+pub fn open_epoch_handler(ctx: Context<OpenEpoch>, id: u64, duration: i64) -> Result<()> {
+    require!(duration > 0, DexError::InvalidAmount);
+
+    let now = Clock::get()?.unix_timestamp;
+    ctx.accounts.epoch.set_inner(Epoch {
+        poll: ctx.accounts.poll_acc.key(),
+        id,
+        base_mint: ctx.accounts.base_mint.key(),
+        quote_mint: ctx.accounts.quote_mint.key(),
+        started_at: now,
+        ends_at: now.checked_add(duration).ok_or(DexError::Overflow)?,
+        total_buy_quote: 0,
+        total_sell_base: 0,
+        clearing_price: 0,
+        matched_base: 0,
+        matched_quote: 0,
+        closed: false,
+        bump: ctx.bumps.epoch,
+    });
+
+    let epoch = &ctx.accounts.epoch;
+    emit!(EpochOpened {
+        version: EVENT_VERSION,
+        epoch: epoch.key(),
+        poll: epoch.poll,
+        id,
+        base_mint: epoch.base_mint,
+        quote_mint: epoch.quote_mint,
+        ends_at: epoch.ends_at,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(id: u64)]
+pub struct OpenEpoch<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub poll_acc: Box<Account<'info, PollAccount>>,
+
+    #[account(address = poll_acc.mint0, mint::token_program = token_program)]
+    pub base_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(address = poll_acc.mint1, mint::token_program = token_program)]
+    pub quote_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = ANCHOR_DISCRIMINATOR + Epoch::INIT_SPACE,
+        seeds = [b"epoch", poll_acc.key().as_ref(), id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub epoch: Box<Account<'info, Epoch>>,
+
+    #[account(
+        init,
+        payer = payer,
+        associated_token::mint = base_mint,
+        associated_token::authority = epoch,
+        associated_token::token_program = token_program
+    )]
+    pub base_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        init,
+        payer = payer,
+        associated_token::mint = quote_mint,
+        associated_token::authority = epoch,
+        associated_token::token_program = token_program
+    )]
+    pub quote_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub system_program: Program<'info, System>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
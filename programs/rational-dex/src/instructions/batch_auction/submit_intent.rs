@@ -0,0 +1,153 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::error::DexError;
+use crate::events::IntentSubmitted;
+use crate::instructions::config::require_not_paused;
+use crate::instructions::swap::transfer_tokens;
+use crate::state::batch_auction_state::{Epoch, Intent};
+use crate::state::config_state::{GlobalConfig, Subsystem};
+use crate::state::order_book_state::Side;
+use crate::{ANCHOR_DISCRIMINATOR, EVENT_VERSION};
+
+/// Deposits `amount` into the epoch: quote tokens to buy, base tokens to sell.
+/// Repeated calls add to the caller's intent, which must stay on one side.
+pub fn submit_intent_handler(ctx: Context<SubmitIntent>, side: Side, amount: u64) -> Result<()> {
+    require_not_paused(&ctx.accounts.config, Subsystem::Offers)?;
+    require!(amount > 0, DexError::InvalidAmount);
+
+    let epoch = &ctx.accounts.epoch;
+    require!(!epoch.closed, DexError::EpochClosed);
+    require!(
+        Clock::get()?.unix_timestamp < epoch.ends_at,
+        DexError::EpochClosed
+    );
+
+    let (from, to, mint) = match side {
+        Side::Bid => (
+            &ctx.accounts.owner_quote_account,
+            &ctx.accounts.quote_vault,
+            &ctx.accounts.quote_mint,
+        ),
+        Side::Ask => (
+            &ctx.accounts.owner_base_account,
+            &ctx.accounts.base_vault,
+            &ctx.accounts.base_mint,
+        ),
+    };
+    // Credit only what reaches the vault after any transfer fee
+    let amount = transfer_tokens(
+        from,
+        to,
+        &amount,
+        mint,
+        &ctx.accounts.owner,
+        &ctx.accounts.token_program,
+    )?;
+
+    let epoch_key = ctx.accounts.epoch.key();
+    let intent = &mut ctx.accounts.intent;
+    if intent.owner == Pubkey::default() {
+        intent.epoch = epoch_key;
+        intent.owner = ctx.accounts.owner.key();
+        intent.side = side;
+        intent.bump = ctx.bumps.intent;
+    }
+    require!(intent.side == side, DexError::IntentSideMismatch);
+    intent.amount = intent
+        .amount
+        .checked_add(amount)
+        .ok_or(DexError::Overflow)?;
+    emit!(IntentSubmitted {
+        version: EVENT_VERSION,
+        epoch: epoch_key,
+        owner: intent.owner,
+        side,
+        amount,
+        intent_amount: intent.amount,
+    });
+
+    let epoch = &mut ctx.accounts.epoch;
+    match side {
+        Side::Bid => {
+            epoch.total_buy_quote = epoch
+                .total_buy_quote
+                .checked_add(amount)
+                .ok_or(DexError::Overflow)?
+        }
+        Side::Ask => {
+            epoch.total_sell_base = epoch
+                .total_sell_base
+                .checked_add(amount)
+                .ok_or(DexError::Overflow)?
+        }
+    }
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SubmitIntent<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Box<Account<'info, GlobalConfig>>,
+
+    #[account(
+        mut,
+        seeds = [b"epoch", epoch.poll.as_ref(), epoch.id.to_le_bytes().as_ref()],
+        bump = epoch.bump,
+        has_one = base_mint,
+        has_one = quote_mint,
+    )]
+    pub epoch: Box<Account<'info, Epoch>>,
+
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = ANCHOR_DISCRIMINATOR + Intent::INIT_SPACE,
+        seeds = [b"intent", epoch.key().as_ref(), owner.key().as_ref()],
+        bump
+    )]
+    pub intent: Box<Account<'info, Intent>>,
+
+    pub base_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    pub quote_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        associated_token::mint = base_mint,
+        associated_token::authority = epoch,
+        associated_token::token_program = token_program
+    )]
+    pub base_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        associated_token::mint = quote_mint,
+        associated_token::authority = epoch,
+        associated_token::token_program = token_program
+    )]
+    pub quote_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        token::mint = base_mint,
+        token::authority = owner,
+        token::token_program = token_program,
+    )]
+    pub owner_base_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        token::mint = quote_mint,
+        token::authority = owner,
+        token::token_program = token_program,
+    )]
+    pub owner_quote_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub system_program: Program<'info, System>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
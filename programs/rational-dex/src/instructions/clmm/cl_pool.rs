@@ -0,0 +1,171 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use super::{sqrt_price_at_tick, sqrt_price_from_price, tick_at_sqrt_price};
+use crate::error::{ConfigError, DexError};
+use crate::events::{ActiveRangeRecentered, ClPoolCreated};
+use crate::state::clmm_state::ClPool;
+use crate::state::config_state::GlobalConfig;
+use crate::state::pool_state::Pool;
+use crate::{ANCHOR_DISCRIMINATOR, EVENT_VERSION, MAX_FEE_BPS, MAX_TICK, MIN_TICK};
+
+/// Opens a concentrated-liquidity pool next to `pool`, starting at its consensus price.
+/// The active range spans `active_range_ticks` on each side of that price.
+pub fn create_cl_pool_handler(
+    ctx: Context<CreateClPool>,
+    tick_spacing: u16,
+    fee_bps: u16,
+    active_range_ticks: u32,
+) -> Result<()> {
+    require!(tick_spacing > 0, DexError::InvalidTick);
+    require!(fee_bps as u64 <= MAX_FEE_BPS, DexError::FeeTooHigh);
+    require!(
+        active_range_ticks >= tick_spacing as u32,
+        DexError::InvalidTick
+    );
+
+    let consensus_price = ctx.accounts.pool.consensus_price;
+    let sqrt_price_x32 = checked_sqrt_price(consensus_price)?;
+
+    let cl_pool = &mut ctx.accounts.cl_pool;
+    cl_pool.set_inner(ClPool {
+        pool: ctx.accounts.pool.key(),
+        mint_a: ctx.accounts.mint_a.key(),
+        mint_b: ctx.accounts.mint_b.key(),
+        vault_a: ctx.accounts.vault_a.key(),
+        vault_b: ctx.accounts.vault_b.key(),
+        tick_spacing,
+        fee_bps,
+        sqrt_price_x32,
+        tick_current: tick_at_sqrt_price(sqrt_price_x32)?,
+        liquidity: 0,
+        fee_growth_global_a_x32: 0,
+        fee_growth_global_b_x32: 0,
+        active_range_ticks,
+        active_tick_lower: 0,
+        active_tick_upper: 0,
+        bump: ctx.bumps.cl_pool,
+    });
+
+    emit!(ClPoolCreated {
+        version: EVENT_VERSION,
+        cl_pool: cl_pool.key(),
+        pool: cl_pool.pool,
+        tick_spacing,
+        fee_bps,
+        sqrt_price_x32,
+        tick_current: cl_pool.tick_current,
+    });
+
+    recenter(cl_pool, consensus_price)
+}
+
+/// Permissionless. Moves the active range to the latest consensus price of the paired pool.
+pub fn recenter_range_handler(ctx: Context<RecenterRange>) -> Result<()> {
+    recenter(&mut ctx.accounts.cl_pool, ctx.accounts.pool.consensus_price)
+}
+
+fn recenter(cl_pool: &mut Account<ClPool>, consensus_price: u64) -> Result<()> {
+    let center = tick_at_sqrt_price(checked_sqrt_price(consensus_price)?)?;
+    let spacing = cl_pool.tick_spacing as i32;
+    let width = cl_pool.active_range_ticks as i32;
+
+    // Widen to the nearest initializable ticks
+    cl_pool.active_tick_lower = ((center - width).div_euclid(spacing) * spacing).max(MIN_TICK);
+    cl_pool.active_tick_upper =
+        ((center + width + spacing - 1).div_euclid(spacing) * spacing).min(MAX_TICK);
+
+    emit!(ActiveRangeRecentered {
+        version: EVENT_VERSION,
+        cl_pool: cl_pool.key(),
+        consensus_price,
+        tick_lower: cl_pool.active_tick_lower,
+        tick_upper: cl_pool.active_tick_upper,
+    });
+
+    Ok(())
+}
+
+/// Square root of the consensus price, which must lie inside the supported tick range.
+fn checked_sqrt_price(consensus_price: u64) -> Result<u64> {
+    require!(consensus_price > 0, DexError::PriceNotSet);
+    let sqrt_price_x32 = sqrt_price_from_price(consensus_price)?;
+    require!(
+        sqrt_price_x32 >= sqrt_price_at_tick(MIN_TICK)?
+            && sqrt_price_x32 <= sqrt_price_at_tick(MAX_TICK)?,
+        DexError::InvalidTick
+    );
+    Ok(sqrt_price_x32)
+}
+
+#[derive(Accounts)]
+pub struct CreateClPool<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = config.admin == authority.key() @ ConfigError::NotAdmin
+    )]
+    pub config: Box<Account<'info, GlobalConfig>>,
+
+    #[account(
+        seeds = [b"pool", pool.mint_a.as_ref(), pool.mint_b.as_ref()],
+        bump = pool.bump,
+        has_one = mint_a,
+        has_one = mint_b,
+    )]
+    pub pool: Box<Account<'info, Pool>>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = ANCHOR_DISCRIMINATOR + ClPool::INIT_SPACE,
+        seeds = [b"cl_pool", pool.key().as_ref()],
+        bump
+    )]
+    pub cl_pool: Box<Account<'info, ClPool>>,
+
+    #[account(
+        init,
+        payer = authority,
+        token::mint = mint_a,
+        token::authority = vault_a,
+        token::token_program = token_program,
+        seeds = [b"cl_vault_a", cl_pool.key().as_ref()],
+        bump
+    )]
+    pub vault_a: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        init,
+        payer = authority,
+        token::mint = mint_b,
+        token::authority = vault_b,
+        token::token_program = token_program,
+        seeds = [b"cl_vault_b", cl_pool.key().as_ref()],
+        bump
+    )]
+    pub vault_b: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub mint_a: Box<InterfaceAccount<'info, Mint>>,
+
+    pub mint_b: Box<InterfaceAccount<'info, Mint>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RecenterRange<'info> {
+    #[account(
+        mut,
+        seeds = [b"cl_pool", pool.key().as_ref()],
+        bump = cl_pool.bump,
+        has_one = pool,
+    )]
+    pub cl_pool: Box<Account<'info, ClPool>>,
+
+    pub pool: Box<Account<'info, Pool>>,
+}
@@ -0,0 +1,406 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use super::{
+    amount_a_delta, amount_b_delta, next_sqrt_price_from_a, next_sqrt_price_from_b,
+    sqrt_price_at_tick, tick_at_sqrt_price,
+};
+use crate::error::{DexError, ErrorCode};
+use crate::events::ClSwapped;
+use crate::instructions::config::require_not_paused;
+use crate::instructions::pool::{mul_div_ceil, mul_div_floor, transfer_from_vault};
+use crate::instructions::swap::{amount_with_transfer_fee, transfer_fee, transfer_tokens};
+use crate::state::clmm_state::{ClPool, TickArray};
+use crate::state::config_state::{GlobalConfig, Subsystem};
+use crate::{BPS_DENOMINATOR, EVENT_VERSION, MAX_TICK, MIN_TICK, TICK_ARRAY_SIZE};
+
+/// One step of a swap, up to the next initialized tick or range bound.
+#[derive(Debug, PartialEq, Eq)]
+pub struct SwapStep {
+    pub sqrt_price_x32: u64,
+    pub amount_in: u64,
+    pub fee_amount: u64,
+    pub amount_out: u64,
+}
+
+/// Swaps up to `amount_in` through the in-range positions. The price moves tick by tick across
+/// the tick arrays passed as remaining accounts, starting with the one holding the current
+/// price. It stops early at the edge of the active range or of the arrays provided, and only
+/// the input actually used is taken from the user.
+pub fn cl_swap_handler<'info>(
+    ctx: Context<'_, '_, 'info, 'info, ClSwap<'info>>,
+    amount_in: u64,
+    min_amount_out: u64,
+    a_to_b: bool,
+) -> Result<()> {
+    require_not_paused(&ctx.accounts.config, Subsystem::Swaps)?;
+    require!(amount_in > 0, DexError::InvalidAmount);
+
+    let cl_pool_key = ctx.accounts.cl_pool.key();
+    let mut tick_arrays = ctx
+        .remaining_accounts
+        .iter()
+        .map(Account::<TickArray>::try_from)
+        .collect::<Result<Vec<_>>>()?;
+    for (index, tick_array) in tick_arrays.iter().enumerate() {
+        require_keys_eq!(tick_array.cl_pool, cl_pool_key, DexError::MissingTickArray);
+        // A second copy would be serialized last and undo the ticks crossed in the first
+        require!(
+            tick_arrays[..index]
+                .iter()
+                .all(|other| other.key() != tick_array.key()),
+            DexError::DuplicateTickArray
+        );
+    }
+
+    let accounts = &ctx.accounts;
+    let (mint_in, mint_out) = if a_to_b {
+        (&accounts.mint_a, &accounts.mint_b)
+    } else {
+        (&accounts.mint_b, &accounts.mint_a)
+    };
+    let cl_pool = &accounts.cl_pool;
+    let spacing = cl_pool.tick_spacing;
+    let bound = if a_to_b {
+        coverage_bound(&tick_arrays, cl_pool.tick_current, spacing, true)?
+            .max(cl_pool.active_tick_lower)
+    } else {
+        coverage_bound(&tick_arrays, cl_pool.tick_current, spacing, false)?
+            .min(cl_pool.active_tick_upper)
+    };
+
+    let mut sqrt_price = cl_pool.sqrt_price_x32;
+    let mut tick_current = cl_pool.tick_current;
+    let mut liquidity = cl_pool.liquidity;
+    let mut fee_growth_a = cl_pool.fee_growth_global_a_x32;
+    let mut fee_growth_b = cl_pool.fee_growth_global_b_x32;
+
+    // Only what reaches the vault after any Token-2022 transfer fee is swapped
+    let amount_in_net = amount_in - transfer_fee(mint_in, amount_in)?;
+    let mut remaining = amount_in_net;
+    let mut amount_out: u64 = 0;
+
+    while remaining > 0 {
+        let next = next_initialized_tick(&tick_arrays, tick_current, spacing, a_to_b, bound);
+        let (target_tick, cross) = match next {
+            Some(tick) => (tick, true),
+            None => (bound, false),
+        };
+        let target_sqrt_price = sqrt_price_at_tick(target_tick)?;
+        if (a_to_b && target_sqrt_price > sqrt_price) || (!a_to_b && target_sqrt_price < sqrt_price)
+        {
+            break;
+        }
+
+        let step = swap_step(
+            sqrt_price,
+            target_sqrt_price,
+            liquidity,
+            remaining,
+            cl_pool.fee_bps,
+            a_to_b,
+        )?;
+        remaining -= step.amount_in + step.fee_amount;
+        amount_out = amount_out
+            .checked_add(step.amount_out)
+            .ok_or(DexError::Overflow)?;
+        if liquidity > 0 {
+            let growth = fee_growth(step.fee_amount, liquidity)?;
+            if a_to_b {
+                fee_growth_a = fee_growth_a.wrapping_add(growth);
+            } else {
+                fee_growth_b = fee_growth_b.wrapping_add(growth);
+            }
+        }
+        sqrt_price = step.sqrt_price_x32;
+
+        if sqrt_price != target_sqrt_price {
+            tick_current = tick_at_sqrt_price(sqrt_price)?;
+            break;
+        }
+        if cross {
+            let tick_array = tick_arrays
+                .iter_mut()
+                .find(|tick_array| tick_array.offset(target_tick, spacing).is_some())
+                .ok_or(DexError::MissingTickArray)?;
+            let offset = tick_array
+                .offset(target_tick, spacing)
+                .ok_or(DexError::MissingTickArray)?;
+            let tick = &mut tick_array.ticks[offset];
+            tick.fee_growth_outside_a_x32 =
+                fee_growth_a.wrapping_sub(tick.fee_growth_outside_a_x32);
+            tick.fee_growth_outside_b_x32 =
+                fee_growth_b.wrapping_sub(tick.fee_growth_outside_b_x32);
+            let liquidity_net = if a_to_b {
+                -tick.liquidity_net
+            } else {
+                tick.liquidity_net
+            };
+            liquidity = liquidity
+                .checked_add_signed(liquidity_net)
+                .ok_or(DexError::Overflow)?;
+        }
+        tick_current = if a_to_b { target_tick - 1 } else { target_tick };
+        if !cross {
+            break;
+        }
+    }
+
+    let amount_in_used = amount_in_net - remaining;
+    require!(amount_out > 0, DexError::InsufficientLiquidity);
+    require!(
+        amount_out - transfer_fee(mint_out, amount_out)? >= min_amount_out,
+        ErrorCode::SlippageExceeded
+    );
+
+    let (user_token_in, user_token_out, vault_in, vault_out) = if a_to_b {
+        (
+            &accounts.user_token_a,
+            &accounts.user_token_b,
+            &accounts.vault_a,
+            &accounts.vault_b,
+        )
+    } else {
+        (
+            &accounts.user_token_b,
+            &accounts.user_token_a,
+            &accounts.vault_b,
+            &accounts.vault_a,
+        )
+    };
+    let (vault_out_seed, vault_out_bump): (&[u8], u8) = if a_to_b {
+        (b"cl_vault_b", ctx.bumps.vault_b)
+    } else {
+        (b"cl_vault_a", ctx.bumps.vault_a)
+    };
+    transfer_tokens(
+        user_token_in,
+        vault_in,
+        &amount_with_transfer_fee(mint_in, amount_in_used)?,
+        mint_in,
+        &accounts.user,
+        &accounts.token_program,
+    )?;
+    transfer_from_vault(
+        vault_out,
+        user_token_out,
+        mint_out,
+        amount_out,
+        vault_out_seed,
+        &cl_pool_key,
+        vault_out_bump,
+        &accounts.token_program,
+    )?;
+
+    let cl_pool = &mut ctx.accounts.cl_pool;
+    cl_pool.sqrt_price_x32 = sqrt_price;
+    cl_pool.tick_current = tick_current;
+    cl_pool.liquidity = liquidity;
+    cl_pool.fee_growth_global_a_x32 = fee_growth_a;
+    cl_pool.fee_growth_global_b_x32 = fee_growth_b;
+
+    for tick_array in &tick_arrays {
+        tick_array.exit(ctx.program_id)?;
+    }
+
+    emit!(ClSwapped {
+        version: EVENT_VERSION,
+        cl_pool: cl_pool_key,
+        user: ctx.accounts.user.key(),
+        a_to_b,
+        amount_in: amount_in_used,
+        amount_out,
+        sqrt_price_x32: sqrt_price,
+        tick_current,
+        liquidity,
+    });
+
+    Ok(())
+}
+
+/// Fee per unit of liquidity in Q32.32. Errors rather than truncating when a tiny liquidity
+/// earns a fee too large to record, as the excess would be owed to no position.
+pub fn fee_growth(fee_amount: u64, liquidity: u64) -> Result<u64> {
+    require!(liquidity > 0, DexError::DivisionByZero);
+    let growth = ((fee_amount as u128) << 32) / liquidity as u128;
+    u64::try_from(growth).map_err(|_| DexError::Overflow.into())
+}
+
+/// Moves the price from `sqrt_price` towards `target_sqrt_price` with at most `remaining`
+/// input. The fee is taken from the input; if the target is not reached it keeps the rest.
+pub fn swap_step(
+    sqrt_price: u64,
+    target_sqrt_price: u64,
+    liquidity: u64,
+    remaining: u64,
+    fee_bps: u16,
+    a_to_b: bool,
+) -> Result<SwapStep> {
+    if liquidity == 0 {
+        return Ok(SwapStep {
+            sqrt_price_x32: target_sqrt_price,
+            amount_in: 0,
+            fee_amount: 0,
+            amount_out: 0,
+        });
+    }
+
+    let fee_bps = fee_bps as u64;
+    let remaining_less_fee = mul_div_floor(remaining, BPS_DENOMINATOR - fee_bps, BPS_DENOMINATOR)?;
+    let amount_to_target = if a_to_b {
+        amount_a_delta(target_sqrt_price, sqrt_price, liquidity, true)?
+    } else {
+        amount_b_delta(sqrt_price, target_sqrt_price, liquidity, true)?
+    };
+
+    let (next_sqrt_price, amount_in) = if remaining_less_fee >= amount_to_target {
+        (target_sqrt_price, amount_to_target)
+    } else {
+        let next = if a_to_b {
+            next_sqrt_price_from_a(sqrt_price, liquidity, remaining_less_fee)?
+                .max(target_sqrt_price)
+        } else {
+            next_sqrt_price_from_b(sqrt_price, liquidity, remaining_less_fee)?
+                .min(target_sqrt_price)
+        };
+        let amount_in = if a_to_b {
+            amount_a_delta(next, sqrt_price, liquidity, true)?
+        } else {
+            amount_b_delta(sqrt_price, next, liquidity, true)?
+        };
+        (next, amount_in)
+    };
+
+    let amount_out = if a_to_b {
+        amount_b_delta(next_sqrt_price, sqrt_price, liquidity, false)?
+    } else {
+        amount_a_delta(sqrt_price, next_sqrt_price, liquidity, false)?
+    };
+    let fee_amount = if next_sqrt_price == target_sqrt_price {
+        mul_div_ceil(amount_in, fee_bps, BPS_DENOMINATOR - fee_bps)?
+    } else {
+        remaining - amount_in
+    };
+
+    Ok(SwapStep {
+        sqrt_price_x32: next_sqrt_price,
+        amount_in,
+        fee_amount,
+        amount_out,
+    })
+}
+
+/// Furthest tick the swap can reach with the tick arrays provided: the first tick of the
+/// lowest array below the price, or the last tick of the highest array above it.
+fn coverage_bound(
+    tick_arrays: &[Account<TickArray>],
+    tick_current: i32,
+    tick_spacing: u16,
+    a_to_b: bool,
+) -> Result<i32> {
+    let span = tick_spacing as i32 * TICK_ARRAY_SIZE as i32;
+    let has_array = |start: i32| {
+        tick_arrays
+            .iter()
+            .any(|tick_array| tick_array.start_tick_index == start)
+    };
+
+    let mut start = TickArray::start_index_for(tick_current, tick_spacing);
+    require!(has_array(start), DexError::MissingTickArray);
+    if a_to_b {
+        while has_array(start - span) {
+            start -= span;
+        }
+        Ok(start.max(MIN_TICK))
+    } else {
+        while has_array(start + span) {
+            start += span;
+        }
+        Ok((start + span - tick_spacing as i32).min(MAX_TICK))
+    }
+}
+
+/// Next initialized tick in the swap direction, not beyond `bound`: at or below the current
+/// tick when the price goes down, above it when the price goes up.
+fn next_initialized_tick(
+    tick_arrays: &[Account<TickArray>],
+    tick_current: i32,
+    tick_spacing: u16,
+    a_to_b: bool,
+    bound: i32,
+) -> Option<i32> {
+    let spacing = tick_spacing as i32;
+    let is_initialized = |tick: i32| {
+        tick_arrays.iter().any(|tick_array| {
+            tick_array
+                .offset(tick, tick_spacing)
+                .is_some_and(|offset| tick_array.ticks[offset].liquidity_gross > 0)
+        })
+    };
+
+    let mut tick = tick_current.div_euclid(spacing) * spacing;
+    if a_to_b {
+        while tick >= bound {
+            if is_initialized(tick) {
+                return Some(tick);
+            }
+            tick -= spacing;
+        }
+    } else {
+        tick += spacing;
+        while tick <= bound {
+            if is_initialized(tick) {
+                return Some(tick);
+            }
+            tick += spacing;
+        }
+    }
+    None
+}
+
+#[derive(Accounts)]
+pub struct ClSwap<'info> {
+    pub user: Signer<'info>,
+
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Box<Account<'info, GlobalConfig>>,
+
+    #[account(
+        mut,
+        seeds = [b"cl_pool", cl_pool.pool.as_ref()],
+        bump = cl_pool.bump,
+        has_one = vault_a,
+        has_one = vault_b,
+        has_one = mint_a,
+        has_one = mint_b,
+    )]
+    pub cl_pool: Box<Account<'info, ClPool>>,
+
+    #[account(mut, seeds = [b"cl_vault_a", cl_pool.key().as_ref()], bump)]
+    pub vault_a: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(mut, seeds = [b"cl_vault_b", cl_pool.key().as_ref()], bump)]
+    pub vault_b: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub mint_a: Box<InterfaceAccount<'info, Mint>>,
+
+    pub mint_b: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        token::mint = mint_a,
+        token::authority = user,
+        token::token_program = token_program,
+    )]
+    pub user_token_a: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        token::mint = mint_b,
+        token::authority = user,
+        token::token_program = token_program,
+    )]
+    pub user_token_b: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
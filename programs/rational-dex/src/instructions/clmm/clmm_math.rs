@@ -0,0 +1,168 @@
+use anchor_lang::prelude::*;
+
+use crate::error::DexError;
+use crate::instructions::pool::integer_sqrt;
+use crate::state::clmm_state::{ClPool, Tick};
+use crate::{MAX_TICK, MIN_TICK, PRICE_SCALE};
+
+/// sqrt(1.0001^(2^i)) in Q32.32, for every bit of a tick up to `MAX_TICK`.
+const SQRT_RATIOS_X32: [u64; 18] = [
+    4_295_182_039,
+    4_295_396_793,
+    4_295_826_332,
+    4_296_685_541,
+    4_298_404_473,
+    4_301_844_400,
+    4_308_732_516,
+    4_322_541_852,
+    4_350_293_443,
+    4_406_332_281,
+    4_520_584_868,
+    4_758_054_286,
+    5_271_071_706,
+    6_469_012_455,
+    9_743_525_213,
+    22_104_076_012,
+    113_758_765_237,
+    3_013_074_553_646,
+];
+
+/// Square root of `1.0001^tick` in Q32.32.
+pub fn sqrt_price_at_tick(tick: i32) -> Result<u64> {
+    require!((MIN_TICK..=MAX_TICK).contains(&tick), DexError::InvalidTick);
+
+    let abs_tick = tick.unsigned_abs();
+    let mut ratio: u128 = 1 << 32;
+    for (bit, sqrt_ratio) in SQRT_RATIOS_X32.iter().enumerate() {
+        if abs_tick & (1 << bit) != 0 {
+            ratio = (ratio * *sqrt_ratio as u128) >> 32;
+        }
+    }
+    if tick < 0 {
+        ratio = (1u128 << 64) / ratio;
+    }
+    Ok(ratio as u64)
+}
+
+/// Largest tick whose square root price does not exceed `sqrt_price_x32`.
+pub fn tick_at_sqrt_price(sqrt_price_x32: u64) -> Result<i32> {
+    let (mut low, mut high) = (MIN_TICK, MAX_TICK);
+    while low < high {
+        let mid = low + (high - low + 1) / 2;
+        if sqrt_price_at_tick(mid)? <= sqrt_price_x32 {
+            low = mid;
+        } else {
+            high = mid - 1;
+        }
+    }
+    Ok(low)
+}
+
+/// Square root of a `PRICE_SCALE`d price of token A in token B, in Q32.32.
+pub fn sqrt_price_from_price(price: u64) -> Result<u64> {
+    let price_x64 = ((price as u128) << 64) / PRICE_SCALE;
+    u64::try_from(integer_sqrt(price_x64)).map_err(|_| DexError::Overflow.into())
+}
+
+/// Token A backing `liquidity` between two square root prices: `L * (√b - √a) / (√a * √b)`.
+pub fn amount_a_delta(
+    sqrt_lower: u64,
+    sqrt_upper: u64,
+    liquidity: u64,
+    round_up: bool,
+) -> Result<u64> {
+    let numerator = (liquidity as u128) * ((sqrt_upper - sqrt_lower) as u128);
+    let amount = match numerator.checked_mul(1 << 32) {
+        Some(scaled) => div(
+            div(scaled, sqrt_upper as u128, round_up)?,
+            sqrt_lower as u128,
+            round_up,
+        )?,
+        // Very large liquidity: divide first and give up some precision instead of overflowing
+        None => div(
+            div(numerator, sqrt_upper as u128, round_up)?
+                .checked_mul(1 << 32)
+                .ok_or(DexError::Overflow)?,
+            sqrt_lower as u128,
+            round_up,
+        )?,
+    };
+    u64::try_from(amount).map_err(|_| DexError::Overflow.into())
+}
+
+/// Token B backing `liquidity` between two square root prices: `L * (√b - √a)`.
+pub fn amount_b_delta(
+    sqrt_lower: u64,
+    sqrt_upper: u64,
+    liquidity: u64,
+    round_up: bool,
+) -> Result<u64> {
+    let product = (liquidity as u128) * ((sqrt_upper - sqrt_lower) as u128);
+    let amount = div(product, 1 << 32, round_up)?;
+    u64::try_from(amount).map_err(|_| DexError::Overflow.into())
+}
+
+/// Price after adding `amount` token A, rounded up so the pool never gives out too much B.
+/// Computed as `L / (L / √p + amount)`, which stays within u128. Flooring `L / √p` can
+/// round the result above `√p` for small amounts, so it is capped there.
+pub fn next_sqrt_price_from_a(sqrt_price: u64, liquidity: u64, amount: u64) -> Result<u64> {
+    let liquidity_x32 = (liquidity as u128) << 32;
+    let denominator = div(liquidity_x32, sqrt_price as u128, false)? + amount as u128;
+    let next = div(liquidity_x32, denominator, true)?.min(sqrt_price as u128);
+    Ok(next as u64)
+}
+
+/// Price after adding `amount` token B, rounded down so the pool never gives out too much A.
+pub fn next_sqrt_price_from_b(sqrt_price: u64, liquidity: u64, amount: u64) -> Result<u64> {
+    let step = div((amount as u128) << 32, liquidity as u128, false)?;
+    let next = (sqrt_price as u128)
+        .checked_add(step)
+        .ok_or(DexError::Overflow)?;
+    u64::try_from(next).map_err(|_| DexError::Overflow.into())
+}
+
+/// Fee growth per unit of liquidity earned between two ticks, for tokens A and B.
+pub fn fee_growth_inside(
+    cl_pool: &ClPool,
+    lower: &Tick,
+    upper: &Tick,
+    tick_lower: i32,
+    tick_upper: i32,
+) -> (u64, u64) {
+    let inside = |global: u64, lower_outside: u64, upper_outside: u64| {
+        let below = if cl_pool.tick_current >= tick_lower {
+            lower_outside
+        } else {
+            global.wrapping_sub(lower_outside)
+        };
+        let above = if cl_pool.tick_current < tick_upper {
+            upper_outside
+        } else {
+            global.wrapping_sub(upper_outside)
+        };
+        global.wrapping_sub(below).wrapping_sub(above)
+    };
+
+    (
+        inside(
+            cl_pool.fee_growth_global_a_x32,
+            lower.fee_growth_outside_a_x32,
+            upper.fee_growth_outside_a_x32,
+        ),
+        inside(
+            cl_pool.fee_growth_global_b_x32,
+            lower.fee_growth_outside_b_x32,
+            upper.fee_growth_outside_b_x32,
+        ),
+    )
+}
+
+fn div(numerator: u128, denominator: u128, round_up: bool) -> Result<u128> {
+    require!(denominator > 0, DexError::DivisionByZero);
+    let quotient = numerator / denominator;
+    if round_up && numerator % denominator != 0 {
+        Ok(quotient + 1)
+    } else {
+        Ok(quotient)
+    }
+}
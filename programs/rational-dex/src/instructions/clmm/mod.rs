@@ -0,0 +1,11 @@
+pub mod cl_pool;
+pub mod cl_swap;
+pub mod clmm_math;
+pub mod position;
+pub mod tick_array;
+
+pub use cl_pool::*;
+pub use cl_swap::*;
+pub use clmm_math::*;
+pub use position::*;
+pub use tick_array::*;
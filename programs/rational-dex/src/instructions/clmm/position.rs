@@ -0,0 +1,468 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use super::{amount_a_delta, amount_b_delta, fee_growth_inside, sqrt_price_at_tick, tick_mut};
+use crate::error::{DexError, ErrorCode};
+use crate::events::{ClPositionClosed, ClPositionOpened};
+use crate::instructions::config::require_not_paused;
+use crate::instructions::pool::transfer_from_vault;
+use crate::instructions::swap::{amount_with_transfer_fee, transfer_tokens};
+use crate::state::clmm_state::{ClPool, ClPosition, Tick, TickArray};
+use crate::state::config_state::{GlobalConfig, Subsystem};
+use crate::{ANCHOR_DISCRIMINATOR, EVENT_VERSION, MAX_TICK, MIN_TICK};
+
+/// Provides `liquidity` between `tick_lower` and `tick_upper`. Deposits only token A while the
+/// range is above the price, only token B while it is below, and both while it is in range.
+pub fn open_position_handler(
+    ctx: Context<OpenPosition>,
+    tick_lower: i32,
+    tick_upper: i32,
+    liquidity: u64,
+    max_amount_a: u64,
+    max_amount_b: u64,
+) -> Result<()> {
+    require_not_paused(&ctx.accounts.config, Subsystem::Swaps)?;
+    let spacing = ctx.accounts.cl_pool.tick_spacing as i32;
+    require!(
+        tick_lower < tick_upper
+            && tick_lower >= MIN_TICK
+            && tick_upper <= MAX_TICK
+            && tick_lower % spacing == 0
+            && tick_upper % spacing == 0,
+        DexError::InvalidTick
+    );
+    require!(
+        liquidity > 0 && liquidity <= i64::MAX as u64,
+        DexError::InvalidAmount
+    );
+
+    let accounts = &mut *ctx.accounts;
+    let (inside_a, inside_b) = update_position_ticks(
+        &accounts.cl_pool,
+        &mut accounts.tick_array_lower,
+        &mut accounts.tick_array_upper,
+        tick_lower,
+        tick_upper,
+        liquidity as i64,
+    )?;
+    let (amount_a, amount_b) =
+        position_amounts(&accounts.cl_pool, tick_lower, tick_upper, liquidity, true)?;
+    require!(
+        amount_a <= max_amount_a && amount_b <= max_amount_b,
+        ErrorCode::SlippageExceeded
+    );
+
+    // The owner covers any transfer fee so the vaults hold the full position
+    for (amount, from, vault, mint) in [
+        (
+            amount_a,
+            &accounts.owner_token_a,
+            &accounts.vault_a,
+            &accounts.mint_a,
+        ),
+        (
+            amount_b,
+            &accounts.owner_token_b,
+            &accounts.vault_b,
+            &accounts.mint_b,
+        ),
+    ] {
+        if amount > 0 {
+            transfer_tokens(
+                from,
+                vault,
+                &amount_with_transfer_fee(mint, amount)?,
+                mint,
+                &accounts.owner,
+                &accounts.token_program,
+            )?;
+        }
+    }
+
+    let cl_pool = &mut accounts.cl_pool;
+    if in_range(cl_pool, tick_lower, tick_upper) {
+        cl_pool.liquidity = cl_pool
+            .liquidity
+            .checked_add(liquidity)
+            .ok_or(DexError::Overflow)?;
+    }
+
+    accounts.position.set_inner(ClPosition {
+        cl_pool: cl_pool.key(),
+        owner: accounts.owner.key(),
+        tick_lower,
+        tick_upper,
+        liquidity,
+        fee_growth_inside_a_last_x32: inside_a,
+        fee_growth_inside_b_last_x32: inside_b,
+        tokens_owed_a: 0,
+        tokens_owed_b: 0,
+        bump: ctx.bumps.position,
+    });
+
+    emit!(ClPositionOpened {
+        version: EVENT_VERSION,
+        cl_pool: accounts.cl_pool.key(),
+        position: accounts.position.key(),
+        owner: accounts.owner.key(),
+        tick_lower,
+        tick_upper,
+        liquidity,
+        amount_a,
+        amount_b,
+    });
+
+    Ok(())
+}
+
+/// Pays out the fees the position earned so far.
+pub fn collect_position_fees_handler(ctx: Context<ModifyPosition>) -> Result<()> {
+    let accounts = &mut *ctx.accounts;
+    settle_position(accounts, 0)?;
+    pay_position(accounts, 0, 0, ctx.bumps.vault_a, ctx.bumps.vault_b)
+}
+
+/// Withdraws all liquidity plus earned fees and closes the position.
+pub fn close_position_handler(ctx: Context<ModifyPosition>) -> Result<()> {
+    let accounts = &mut *ctx.accounts;
+    let liquidity = accounts.position.liquidity;
+    let (tick_lower, tick_upper) = (accounts.position.tick_lower, accounts.position.tick_upper);
+
+    settle_position(accounts, -(liquidity as i64))?;
+    let (amount_a, amount_b) =
+        position_amounts(&accounts.cl_pool, tick_lower, tick_upper, liquidity, false)?;
+    if in_range(&accounts.cl_pool, tick_lower, tick_upper) {
+        accounts.cl_pool.liquidity -= liquidity;
+    }
+
+    emit!(ClPositionClosed {
+        version: EVENT_VERSION,
+        cl_pool: accounts.cl_pool.key(),
+        position: accounts.position.key(),
+        owner: accounts.owner.key(),
+        liquidity,
+        amount_a,
+        amount_b,
+        fees_a: accounts.position.tokens_owed_a,
+        fees_b: accounts.position.tokens_owed_b,
+    });
+
+    pay_position(
+        accounts,
+        amount_a,
+        amount_b,
+        ctx.bumps.vault_a,
+        ctx.bumps.vault_b,
+    )?;
+
+    accounts.position.close(accounts.owner.to_account_info())
+}
+
+fn in_range(cl_pool: &ClPool, tick_lower: i32, tick_upper: i32) -> bool {
+    cl_pool.tick_current >= tick_lower && cl_pool.tick_current < tick_upper
+}
+
+/// Tokens backing `liquidity` in the range at the current price.
+fn position_amounts(
+    cl_pool: &ClPool,
+    tick_lower: i32,
+    tick_upper: i32,
+    liquidity: u64,
+    round_up: bool,
+) -> Result<(u64, u64)> {
+    let sqrt_lower = sqrt_price_at_tick(tick_lower)?;
+    let sqrt_upper = sqrt_price_at_tick(tick_upper)?;
+    let sqrt_price = cl_pool.sqrt_price_x32;
+
+    if cl_pool.tick_current < tick_lower {
+        Ok((
+            amount_a_delta(sqrt_lower, sqrt_upper, liquidity, round_up)?,
+            0,
+        ))
+    } else if cl_pool.tick_current < tick_upper {
+        Ok((
+            amount_a_delta(sqrt_price, sqrt_upper, liquidity, round_up)?,
+            amount_b_delta(sqrt_lower, sqrt_price, liquidity, round_up)?,
+        ))
+    } else {
+        Ok((
+            0,
+            amount_b_delta(sqrt_lower, sqrt_upper, liquidity, round_up)?,
+        ))
+    }
+}
+
+/// Applies `liquidity_delta` to both bounding ticks and returns the fee growth inside the range.
+/// When both ticks share an array only `upper_array` is written, as it is serialized last.
+fn update_position_ticks(
+    cl_pool: &ClPool,
+    lower_array: &mut Account<TickArray>,
+    upper_array: &mut Account<TickArray>,
+    tick_lower: i32,
+    tick_upper: i32,
+    liquidity_delta: i64,
+) -> Result<(u64, u64)> {
+    let spacing = cl_pool.tick_spacing;
+    let lower_array = if lower_array.key() == upper_array.key() {
+        &mut **upper_array
+    } else {
+        &mut **lower_array
+    };
+    let lower = tick_mut(lower_array, tick_lower, spacing)?;
+    update_tick(lower, tick_lower, cl_pool, liquidity_delta, false)?;
+    let lower = *lower;
+
+    let upper = tick_mut(upper_array, tick_upper, spacing)?;
+    update_tick(upper, tick_upper, cl_pool, liquidity_delta, true)?;
+
+    Ok(fee_growth_inside(
+        cl_pool, &lower, upper, tick_lower, tick_upper,
+    ))
+}
+
+fn update_tick(
+    tick: &mut Tick,
+    tick_index: i32,
+    cl_pool: &ClPool,
+    liquidity_delta: i64,
+    upper: bool,
+) -> Result<()> {
+    if tick.liquidity_gross == 0 {
+        // By convention all fee growth so far happened below a newly used tick under the price
+        let below = tick_index <= cl_pool.tick_current;
+        tick.fee_growth_outside_a_x32 = if below {
+            cl_pool.fee_growth_global_a_x32
+        } else {
+            0
+        };
+        tick.fee_growth_outside_b_x32 = if below {
+            cl_pool.fee_growth_global_b_x32
+        } else {
+            0
+        };
+    }
+    tick.liquidity_gross = tick
+        .liquidity_gross
+        .checked_add_signed(liquidity_delta)
+        .ok_or(DexError::Overflow)?;
+    let net_delta = if upper {
+        -liquidity_delta
+    } else {
+        liquidity_delta
+    };
+    tick.liquidity_net = tick
+        .liquidity_net
+        .checked_add(net_delta)
+        .ok_or(DexError::Overflow)?;
+    Ok(())
+}
+
+/// Credits the fees earned since the last checkpoint, then applies `liquidity_delta`.
+fn settle_position(accounts: &mut ModifyPosition, liquidity_delta: i64) -> Result<()> {
+    let position = &accounts.position;
+    let (inside_a, inside_b) = update_position_ticks(
+        &accounts.cl_pool,
+        &mut accounts.tick_array_lower,
+        &mut accounts.tick_array_upper,
+        position.tick_lower,
+        position.tick_upper,
+        liquidity_delta,
+    )?;
+
+    let earned = |inside: u64, last: u64| {
+        let amount = ((inside.wrapping_sub(last) as u128) * position.liquidity as u128) >> 32;
+        u64::try_from(amount).map_err(|_| DexError::Overflow)
+    };
+    let owed_a = earned(inside_a, position.fee_growth_inside_a_last_x32)?;
+    let owed_b = earned(inside_b, position.fee_growth_inside_b_last_x32)?;
+
+    let position = &mut accounts.position;
+    position.tokens_owed_a = position
+        .tokens_owed_a
+        .checked_add(owed_a)
+        .ok_or(DexError::Overflow)?;
+    position.tokens_owed_b = position
+        .tokens_owed_b
+        .checked_add(owed_b)
+        .ok_or(DexError::Overflow)?;
+    position.fee_growth_inside_a_last_x32 = inside_a;
+    position.fee_growth_inside_b_last_x32 = inside_b;
+    position.liquidity = position
+        .liquidity
+        .checked_add_signed(liquidity_delta)
+        .ok_or(DexError::Overflow)?;
+    Ok(())
+}
+
+/// Sends the withdrawn amounts plus all owed fees to the owner.
+fn pay_position(
+    accounts: &mut ModifyPosition,
+    amount_a: u64,
+    amount_b: u64,
+    vault_a_bump: u8,
+    vault_b_bump: u8,
+) -> Result<()> {
+    let cl_pool_key = accounts.cl_pool.key();
+    let position = &accounts.position;
+    let total_a = amount_a
+        .checked_add(position.tokens_owed_a)
+        .ok_or(DexError::Overflow)?;
+    let total_b = amount_b
+        .checked_add(position.tokens_owed_b)
+        .ok_or(DexError::Overflow)?;
+
+    if total_a > 0 {
+        transfer_from_vault(
+            &accounts.vault_a,
+            &accounts.owner_token_a,
+            &accounts.mint_a,
+            total_a,
+            b"cl_vault_a",
+            &cl_pool_key,
+            vault_a_bump,
+            &accounts.token_program,
+        )?;
+    }
+    if total_b > 0 {
+        transfer_from_vault(
+            &accounts.vault_b,
+            &accounts.owner_token_b,
+            &accounts.mint_b,
+            total_b,
+            b"cl_vault_b",
+            &cl_pool_key,
+            vault_b_bump,
+            &accounts.token_program,
+        )?;
+    }
+
+    let position = &mut accounts.position;
+    position.tokens_owed_a = 0;
+    position.tokens_owed_b = 0;
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(tick_lower: i32, tick_upper: i32)]
+pub struct OpenPosition<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Box<Account<'info, GlobalConfig>>,
+
+    #[account(
+        mut,
+        seeds = [b"cl_pool", cl_pool.pool.as_ref()],
+        bump = cl_pool.bump,
+        has_one = vault_a,
+        has_one = vault_b,
+        has_one = mint_a,
+        has_one = mint_b,
+    )]
+    pub cl_pool: Box<Account<'info, ClPool>>,
+
+    #[account(
+        init,
+        payer = owner,
+        space = ANCHOR_DISCRIMINATOR + ClPosition::INIT_SPACE,
+        seeds = [
+            b"cl_position",
+            cl_pool.key().as_ref(),
+            owner.key().as_ref(),
+            tick_lower.to_le_bytes().as_ref(),
+            tick_upper.to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub position: Box<Account<'info, ClPosition>>,
+
+    #[account(mut, has_one = cl_pool)]
+    pub tick_array_lower: Box<Account<'info, TickArray>>,
+
+    #[account(mut, has_one = cl_pool)]
+    pub tick_array_upper: Box<Account<'info, TickArray>>,
+
+    #[account(mut)]
+    pub vault_a: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(mut)]
+    pub vault_b: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub mint_a: Box<InterfaceAccount<'info, Mint>>,
+
+    pub mint_b: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        token::mint = mint_a,
+        token::authority = owner,
+        token::token_program = token_program,
+    )]
+    pub owner_token_a: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        token::mint = mint_b,
+        token::authority = owner,
+        token::token_program = token_program,
+    )]
+    pub owner_token_b: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ModifyPosition<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"cl_pool", cl_pool.pool.as_ref()],
+        bump = cl_pool.bump,
+        has_one = vault_a,
+        has_one = vault_b,
+        has_one = mint_a,
+        has_one = mint_b,
+    )]
+    pub cl_pool: Box<Account<'info, ClPool>>,
+
+    #[account(mut, has_one = cl_pool, has_one = owner)]
+    pub position: Box<Account<'info, ClPosition>>,
+
+    #[account(mut, has_one = cl_pool)]
+    pub tick_array_lower: Box<Account<'info, TickArray>>,
+
+    #[account(mut, has_one = cl_pool)]
+    pub tick_array_upper: Box<Account<'info, TickArray>>,
+
+    #[account(mut, seeds = [b"cl_vault_a", cl_pool.key().as_ref()], bump)]
+    pub vault_a: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(mut, seeds = [b"cl_vault_b", cl_pool.key().as_ref()], bump)]
+    pub vault_b: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub mint_a: Box<InterfaceAccount<'info, Mint>>,
+
+    pub mint_b: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        token::mint = mint_a,
+        token::authority = owner,
+        token::token_program = token_program,
+    )]
+    pub owner_token_a: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        token::mint = mint_b,
+        token::authority = owner,
+        token::token_program = token_program,
+    )]
+    pub owner_token_b: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
@@ -0,0 +1,57 @@
+use anchor_lang::prelude::*;
+
+use crate::error::DexError;
+use crate::state::clmm_state::{ClPool, Tick, TickArray};
+use crate::{ANCHOR_DISCRIMINATOR, MAX_TICK, MIN_TICK, TICK_ARRAY_SIZE};
+
+/// Permissionless. Creates the tick array starting at `start_tick_index`, which must be a
+/// multiple of `tick_spacing * TICK_ARRAY_SIZE`. Positions and swaps need the arrays they touch.
+pub fn init_tick_array_handler(ctx: Context<InitTickArray>, start_tick_index: i32) -> Result<()> {
+    let tick_spacing = ctx.accounts.cl_pool.tick_spacing;
+    require!(
+        TickArray::start_index_for(start_tick_index, tick_spacing) == start_tick_index,
+        DexError::InvalidTick
+    );
+    require!(
+        start_tick_index <= MAX_TICK
+            && start_tick_index >= TickArray::start_index_for(MIN_TICK, tick_spacing),
+        DexError::InvalidTick
+    );
+
+    ctx.accounts.tick_array.set_inner(TickArray {
+        cl_pool: ctx.accounts.cl_pool.key(),
+        start_tick_index,
+        ticks: [Tick::default(); TICK_ARRAY_SIZE],
+        bump: ctx.bumps.tick_array,
+    });
+
+    Ok(())
+}
+
+/// The tick at `tick` in `tick_array`, which must hold it.
+pub fn tick_mut(tick_array: &mut TickArray, tick: i32, tick_spacing: u16) -> Result<&mut Tick> {
+    let offset = tick_array
+        .offset(tick, tick_spacing)
+        .ok_or(DexError::MissingTickArray)?;
+    Ok(&mut tick_array.ticks[offset])
+}
+
+#[derive(Accounts)]
+#[instruction(start_tick_index: i32)]
+pub struct InitTickArray<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub cl_pool: Box<Account<'info, ClPool>>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = ANCHOR_DISCRIMINATOR + TickArray::INIT_SPACE,
+        seeds = [b"tick_array", cl_pool.key().as_ref(), start_tick_index.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub tick_array: Box<Account<'info, TickArray>>,
+
+    pub system_program: Program<'info, System>,
+}
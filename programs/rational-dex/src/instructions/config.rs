@@ -0,0 +1,163 @@
+/// Program-wide configuration: admin with two-step rotation, fee recipients and pause switches.
+use crate::error::ConfigError;
+use crate::events::{AdminAccepted, AdminProposed, FeeRecipientsUpdated, PausedChanged};
+use crate::state::config_state::{GlobalConfig, PauseFlags, Subsystem};
+use crate::{EVENT_VERSION, MAX_FEE_RECIPIENTS};
+use anchor_lang::prelude::*;
+
+pub fn initialize_config_handler(
+    ctx: Context<InitializeConfig>,
+    fee_recipients: Vec<Pubkey>,
+) -> Result<()> {
+    require!(
+        fee_recipients.len() <= MAX_FEE_RECIPIENTS,
+        ConfigError::TooManyFeeRecipients
+    );
+
+    let config = &mut ctx.accounts.config;
+    config.admin = ctx.accounts.admin.key();
+    config.pending_admin = None;
+    config.fee_recipients = fee_recipients;
+    config.paused = PauseFlags::default();
+    config.bump = ctx.bumps.config;
+    Ok(())
+}
+
+/// First step of the admin rotation. Passing `None` cancels a pending transfer.
+pub fn propose_admin_handler(ctx: Context<AdminOnly>, new_admin: Option<Pubkey>) -> Result<()> {
+    ctx.accounts.config.pending_admin = new_admin;
+
+    emit!(AdminProposed {
+        version: EVENT_VERSION,
+        admin: ctx.accounts.admin.key(),
+        pending_admin: new_admin,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+    Ok(())
+}
+
+pub fn accept_admin_handler(ctx: Context<AcceptAdmin>) -> Result<()> {
+    let config = &mut ctx.accounts.config;
+    require!(
+        config.pending_admin == Some(ctx.accounts.new_admin.key()),
+        ConfigError::NotPendingAdmin
+    );
+    let previous_admin = config.admin;
+    config.admin = ctx.accounts.new_admin.key();
+    config.pending_admin = None;
+
+    emit!(AdminAccepted {
+        version: EVENT_VERSION,
+        previous_admin,
+        admin: config.admin,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+    Ok(())
+}
+
+pub fn set_fee_recipients_handler(
+    ctx: Context<AdminOnly>,
+    fee_recipients: Vec<Pubkey>,
+) -> Result<()> {
+    require!(
+        fee_recipients.len() <= MAX_FEE_RECIPIENTS,
+        ConfigError::TooManyFeeRecipients
+    );
+    ctx.accounts.config.fee_recipients = fee_recipients.clone();
+
+    emit!(FeeRecipientsUpdated {
+        version: EVENT_VERSION,
+        fee_recipients,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+    Ok(())
+}
+
+pub fn set_paused_handler(
+    ctx: Context<AdminOnly>,
+    subsystem: Subsystem,
+    paused: bool,
+) -> Result<()> {
+    let flags = &mut ctx.accounts.config.paused;
+    match subsystem {
+        Subsystem::Swaps => flags.swaps = paused,
+        Subsystem::Offers => flags.offers = paused,
+        Subsystem::Polls => flags.polls = paused,
+        Subsystem::Conviction => flags.conviction = paused,
+    }
+
+    emit!(PausedChanged {
+        version: EVENT_VERSION,
+        subsystem,
+        paused,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+    Ok(())
+}
+
+pub fn require_not_paused(config: &GlobalConfig, subsystem: Subsystem) -> Result<()> {
+    let flags = &config.paused;
+    match subsystem {
+        Subsystem::Swaps => require!(!flags.swaps, ConfigError::SwapsPaused),
+        Subsystem::Offers => require!(!flags.offers, ConfigError::OffersPaused),
+        Subsystem::Polls => require!(!flags.polls, ConfigError::PollsPaused),
+        Subsystem::Conviction => require!(!flags.conviction, ConfigError::ConvictionPaused),
+    }
+    Ok(())
+}
+
+/// Only the program's upgrade authority can create the config, so nobody can front-run the
+/// deployment and install themselves as admin.
+#[derive(Accounts)]
+pub struct InitializeConfig<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        constraint = program.programdata_address()? == Some(program_data.key())
+            @ ConfigError::NotUpgradeAuthority
+    )]
+    pub program: Program<'info, crate::program::RationalDex>,
+
+    #[account(
+        constraint = program_data.upgrade_authority_address == Some(admin.key())
+            @ ConfigError::NotUpgradeAuthority
+    )]
+    pub program_data: Account<'info, ProgramData>,
+
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + GlobalConfig::INIT_SPACE,
+        seeds = [b"config"],
+        bump
+    )]
+    pub config: Account<'info, GlobalConfig>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct AdminOnly<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = admin @ ConfigError::NotAdmin,
+        seeds = [b"config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, GlobalConfig>,
+}
+
+#[derive(Accounts)]
+pub struct AcceptAdmin<'info> {
+    pub new_admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, GlobalConfig>,
+}
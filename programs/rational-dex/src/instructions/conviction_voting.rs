@@ -1,5 +1,22 @@
 /// Conviction voting is used to assign reputation scores to accounts. Each account can receive a score between 0 and 10.
-use crate::state::conviction_state::{AuthorState, Proposal, Scores, Voter};
+use crate::events::{
+    ConvictionVoteCast, ConvictionVoteRetracted, EvidenceUpdated, ProposalCreated,
+    ProposalFinalized, TokensUnlocked,
+};
+use crate::instructions::config::require_not_paused;
+use crate::instructions::reputation::credit_reputation;
+use crate::instructions::swap::{
+    amount_with_transfer_fee, transfer_tokens, validate_mint_extensions,
+};
+use crate::state::config_state::{GlobalConfig, Subsystem};
+use crate::state::conviction_state::{
+    AuthorState, EvidenceRef, Proposal, ProposalStatus, Scores, Voter,
+};
+use crate::state::reputation_state::Reputation;
+use crate::{
+    EVENT_VERSION, MAX_EVIDENCE_URI_LEN, MAX_VOTING_PERIOD, MIN_VOTING_PERIOD,
+    REPUTATION_AUTHOR_POINTS,
+};
 use anchor_lang::prelude::*;
 use anchor_spl::{
     associated_token::AssociatedToken,
@@ -10,13 +27,31 @@ use anchor_spl::{
 };
 pub fn initialize_proposal_handler(
     ctx: Context<InitializeProposal>,
-    evidence: String,
+    evidence: EvidenceRef,
+    voting_period: i64,
+    min_quorum_weight: u64,
 ) -> Result<()> {
+    require_not_paused(&ctx.accounts.config, Subsystem::Conviction)?;
+    validate_evidence(&evidence)?;
+    require!(
+        (MIN_VOTING_PERIOD..=MAX_VOTING_PERIOD).contains(&voting_period),
+        VotingError::InvalidVotingPeriod
+    );
+
     let proposal = &mut ctx.accounts.proposal;
     let author_state = &mut ctx.accounts.author_state;
+    let proposal_index = author_state.proposal_count;
     // ✅ Initialize proposal data
     proposal.author = ctx.accounts.author.key();
     proposal.evidence = evidence;
+    proposal.vote_count = 0;
+    proposal.voting_started = false;
+    proposal.voting_ends_at = Clock::get()?
+        .unix_timestamp
+        .checked_add(voting_period)
+        .ok_or(VotingError::OverflowError)?;
+    proposal.min_quorum_weight = min_quorum_weight;
+    proposal.status = ProposalStatus::Active;
     proposal.final_score = None;
     proposal.score_updated_at = None;
 
@@ -26,6 +61,51 @@ pub fn initialize_proposal_handler(
         .checked_add(1)
         .ok_or(VotingError::OverflowError)?; // return a custom error
 
+    emit!(ProposalCreated {
+        version: EVENT_VERSION,
+        proposal: proposal.key(),
+        author: proposal.author,
+        proposal_index,
+        evidence: proposal.evidence.clone(),
+    });
+
+    Ok(())
+}
+
+/// Replaces the evidence of a proposal. Only the author can do it, and only before the first vote.
+pub fn update_evidence_handler(
+    ctx: Context<UpdateEvidence>,
+    _proposal_count: u64,
+    evidence: EvidenceRef,
+) -> Result<()> {
+    validate_evidence(&evidence)?;
+
+    let proposal = &mut ctx.accounts.proposal;
+    require!(
+        proposal.status == ProposalStatus::Active,
+        VotingError::AlreadyFinalized
+    );
+    require!(!proposal.voting_started, VotingError::VotingStarted);
+
+    proposal.evidence = evidence;
+
+    emit!(EvidenceUpdated {
+        version: EVENT_VERSION,
+        proposal: proposal.key(),
+        author: proposal.author,
+        evidence: proposal.evidence.clone(),
+    });
+
+    Ok(())
+}
+
+fn validate_evidence(evidence: &EvidenceRef) -> Result<()> {
+    if let Some(uri) = &evidence.uri {
+        require!(
+            !uri.is_empty() && uri.len() <= MAX_EVIDENCE_URI_LEN,
+            VotingError::InvalidEvidenceUri
+        );
+    }
     Ok(())
 }
 
@@ -35,12 +115,21 @@ pub fn conviction_vote_handler(
     score: u8,
     conviction: u8,
 ) -> Result<()> {
+    require_not_paused(&ctx.accounts.config, Subsystem::Conviction)?;
+
     let scores = &mut ctx.accounts.scores;
     let voter_account = &mut ctx.accounts.voter_account;
+    let proposal = &mut ctx.accounts.proposal;
 
     // ✅ Validate score range
     require!(score <= 10, VotingError::InvalidScore);
 
+    // ✅ Reject votes after the voting window
+    require!(
+        Clock::get()?.unix_timestamp < proposal.voting_ends_at,
+        VotingError::VotingClosed
+    );
+
     // ✅ Get conviction weight
     let weight = conviction_weight(conviction)? as u64;
 
@@ -52,50 +141,321 @@ pub fn conviction_vote_handler(
         scores.counts = [0u64; 11]; // initialize all score counts
     }
 
-    // ✅ Multiply score by conviction weight
-    let effective_vote = (score as u64)
-        .checked_mul(weight)
-        .ok_or(VotingError::OverflowError)?;
-
     // ✅ Increment the weighted vote
-    scores.counts[score as usize] = scores.counts[score as usize]
-        .checked_add(effective_vote)
-        .ok_or(VotingError::OverflowError)?;
+    add_weighted_vote(scores, score, weight)?;
     // ✅ Mark voter as voted
     voter_account.voted = true;
+    voter_account.score = score;
+    voter_account.reputation_settled = false;
+    proposal.vote_count = proposal
+        .vote_count
+        .checked_add(1)
+        .ok_or(VotingError::OverflowError)?;
+    proposal.voting_started = true;
 
     // ✅ Lock tokens based on conviction
-    let lock_amount: u64 = 10_000_000 * weight; // example: conviction, 10 tokens per conviction weight
-    let decimals = ctx.accounts.mint.decimals;
-    let lock_duration = 60 * 60 * 24 * (weight as i64) * 10; // days = weight × 10 day
+    let (lock_amount, lock_duration) = conviction_lock(weight)?;
 
     voter_account.conviction = conviction;
-    voter_account.locked_amount = lock_amount;
     voter_account.unlock_time = Clock::get()?.unix_timestamp + lock_duration;
+    // The voter covers any transfer fee; what reached the vault is what gets released
+    voter_account.locked_amount = transfer_tokens(
+        &ctx.accounts.voter_token_account,
+        &ctx.accounts.vault_token_account,
+        &amount_with_transfer_fee(&ctx.accounts.mint, lock_amount)?,
+        &ctx.accounts.mint,
+        &ctx.accounts.voter,
+        &ctx.accounts.token_program,
+    )?;
+
+    emit_vote_cast(
+        ctx.accounts.proposal.key(),
+        ctx.accounts.voter.key(),
+        &ctx.accounts.voter_account,
+    )
+}
+
+/// Moves an existing vote to a new score and conviction. A higher conviction tops up the locked
+/// tokens, a lower one schedules the difference for release when the current lock ends.
+pub fn change_conviction_vote_handler(
+    ctx: Context<UpdateConvictionVote>,
+    _proposal_count: u64,
+    new_score: u8,
+    new_conviction: u8,
+) -> Result<()> {
+    require_not_paused(&ctx.accounts.config, Subsystem::Conviction)?;
+    require!(new_score <= 10, VotingError::InvalidScore);
+    let new_weight = conviction_weight(new_conviction)? as u64;
+
+    let now = Clock::get()?.unix_timestamp;
+    require!(
+        now < ctx.accounts.proposal.voting_ends_at,
+        VotingError::VotingClosed
+    );
+
+    let scores = &mut ctx.accounts.scores;
+    let voter_account = &mut ctx.accounts.voter_account;
+    require!(voter_account.voted, VotingError::NotVoted);
+
+    // Move the weight between score buckets
+    let old_weight = conviction_weight(voter_account.conviction)? as u64;
+    remove_weighted_vote(scores, voter_account.score, old_weight)?;
+    add_weighted_vote(scores, new_score, new_weight)?;
+
+    let (new_lock, lock_duration) = conviction_lock(new_weight)?;
+    let old_lock = voter_account.locked_amount;
+
+    voter_account.score = new_score;
+    voter_account.conviction = new_conviction;
+
+    if new_lock < old_lock {
+        voter_account.locked_amount = new_lock;
+        // Conviction lowered: the difference stays locked until the current lock ends
+        voter_account.pending_release = voter_account
+            .pending_release
+            .checked_add(old_lock - new_lock)
+            .ok_or(VotingError::OverflowError)?;
+        voter_account.release_time = voter_account.release_time.max(voter_account.unlock_time);
+    }
+    voter_account.unlock_time = voter_account.unlock_time.max(now + lock_duration);
+
+    if new_lock > old_lock {
+        let received = transfer_tokens(
+            &ctx.accounts.voter_token_account,
+            &ctx.accounts.vault_token_account,
+            &amount_with_transfer_fee(&ctx.accounts.mint, new_lock - old_lock)?,
+            &ctx.accounts.mint,
+            &ctx.accounts.voter,
+            &ctx.accounts.token_program,
+        )?;
+        let voter_account = &mut ctx.accounts.voter_account;
+        voter_account.locked_amount = old_lock
+            .checked_add(received)
+            .ok_or(VotingError::OverflowError)?;
+    }
+
+    emit_vote_cast(
+        ctx.accounts.proposal.key(),
+        ctx.accounts.voter.key(),
+        &ctx.accounts.voter_account,
+    )
+}
+
+/// Withdraws a vote entirely. The locked tokens are scheduled for release and returned right away
+/// if their lock has already expired. Not gated by the conviction pause, so voters can always
+/// get out of a proposal.
+pub fn retract_conviction_vote_handler(
+    ctx: Context<UpdateConvictionVote>,
+    _proposal_count: u64,
+) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    require!(
+        now < ctx.accounts.proposal.voting_ends_at,
+        VotingError::VotingClosed
+    );
+
+    let scores = &mut ctx.accounts.scores;
+    let voter_account = &mut ctx.accounts.voter_account;
+    require!(voter_account.voted, VotingError::NotVoted);
+
+    let weight = conviction_weight(voter_account.conviction)? as u64;
+    remove_weighted_vote(scores, voter_account.score, weight)?;
+
+    let proposal = &mut ctx.accounts.proposal;
+    proposal.vote_count = proposal
+        .vote_count
+        .checked_sub(1)
+        .ok_or(VotingError::OverflowError)?;
 
-    // SPL TransferChecked ensures mint & decimals consistency
+    voter_account.voted = false;
+    voter_account.conviction = 0;
+    voter_account.pending_release = voter_account
+        .pending_release
+        .checked_add(voter_account.locked_amount)
+        .ok_or(VotingError::OverflowError)?;
+    voter_account.release_time = voter_account.release_time.max(voter_account.unlock_time);
+    voter_account.locked_amount = 0;
+
+    emit!(ConvictionVoteRetracted {
+        version: EVENT_VERSION,
+        proposal: proposal.key(),
+        voter: ctx.accounts.voter.key(),
+        pending_release: voter_account.pending_release,
+        release_time: voter_account.release_time,
+        timestamp: now,
+    });
+
+    release_matured_tokens(ctx, now)
+}
+
+/// Returns every token whose lock has expired: scheduled releases, plus the vote's own lock once
+/// voting on the proposal has ended.
+pub fn unlock_conviction_tokens_handler(
+    ctx: Context<UpdateConvictionVote>,
+    _proposal_count: u64,
+) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let voting_ended = now >= ctx.accounts.proposal.voting_ends_at;
+
+    let voter_account = &mut ctx.accounts.voter_account;
+    if voting_ended && voter_account.locked_amount > 0 && now >= voter_account.unlock_time {
+        voter_account.pending_release = voter_account
+            .pending_release
+            .checked_add(voter_account.locked_amount)
+            .ok_or(VotingError::OverflowError)?;
+        voter_account.release_time = voter_account.release_time.max(voter_account.unlock_time);
+        voter_account.locked_amount = 0;
+    }
+
+    require!(
+        voter_account.pending_release > 0 && now >= voter_account.release_time,
+        VotingError::TokensLocked
+    );
+
+    release_matured_tokens(ctx, now)
+}
+
+fn release_matured_tokens(ctx: Context<UpdateConvictionVote>, now: i64) -> Result<()> {
+    let voter_account = &mut ctx.accounts.voter_account;
+    if voter_account.pending_release == 0 || now < voter_account.release_time {
+        return Ok(());
+    }
+    let amount = voter_account.pending_release;
+    voter_account.pending_release = 0;
+
+    // The vault is owned by the scores PDA
+    let proposal_key = ctx.accounts.proposal.key();
+    let seeds = &[b"scores", proposal_key.as_ref(), &[ctx.bumps.scores]];
+    let signer_seeds = [&seeds[..]];
+
+    validate_mint_extensions(&ctx.accounts.mint)?;
     transfer_checked(
-        CpiContext::new(
+        CpiContext::new_with_signer(
             ctx.accounts.token_program.to_account_info(),
             TransferChecked {
-                from: ctx.accounts.voter_token_account.to_account_info(),
+                from: ctx.accounts.vault_token_account.to_account_info(),
                 mint: ctx.accounts.mint.to_account_info(),
-                to: ctx.accounts.vault_token_account.to_account_info(),
-                authority: ctx.accounts.voter.to_account_info(),
+                to: ctx.accounts.voter_token_account.to_account_info(),
+                authority: ctx.accounts.scores.to_account_info(),
             },
+            &signer_seeds,
         ),
-        lock_amount,
-        decimals,
+        amount,
+        ctx.accounts.mint.decimals,
     )?;
 
+    emit!(TokensUnlocked {
+        version: EVENT_VERSION,
+        proposal: ctx.accounts.proposal.key(),
+        voter: ctx.accounts.voter.key(),
+        amount,
+        timestamp: now,
+    });
+
+    Ok(())
+}
+
+/// Closes voting once the window has ended. If the quorum was reached the proposal stores the
+/// weighted mean score and credits the author's reputation, otherwise it ends as `Failed`.
+pub fn finalize_proposal_handler(
+    ctx: Context<FinalizeProposal>,
+    _proposal_count: u64,
+) -> Result<()> {
+    let proposal = &mut ctx.accounts.proposal;
+    require!(
+        proposal.status == ProposalStatus::Active,
+        VotingError::AlreadyFinalized
+    );
+
+    let now = Clock::get()?.unix_timestamp;
+    require!(now >= proposal.voting_ends_at, VotingError::VotingStillOpen);
+
+    let scores = &ctx.accounts.scores;
+    if scores.total_weight == 0 || scores.total_weight < proposal.min_quorum_weight {
+        proposal.status = ProposalStatus::Failed;
+        proposal.score_updated_at = Some(now);
+        emit_proposal_finalized(proposal, scores.total_weight, now);
+        return Ok(());
+    }
+
+    let final_score = compute_final_score(scores)?;
+
+    proposal.status = ProposalStatus::Finalized;
+    proposal.final_score = Some(final_score);
+    proposal.score_updated_at = Some(now);
+    emit_proposal_finalized(proposal, scores.total_weight, now);
+
+    let author_state = &mut ctx.accounts.author_state;
+    author_state.last_final_score = Some(final_score);
+    author_state.score_updated_at = Some(now);
+
+    let reputation = &mut ctx.accounts.author_reputation;
+    if reputation.wallet == Pubkey::default() {
+        reputation.wallet = ctx.accounts.author.key();
+        reputation.bump = ctx.bumps.author_reputation;
+    }
+    let points = (final_score as u64)
+        .checked_mul(REPUTATION_AUTHOR_POINTS)
+        .ok_or(VotingError::OverflowError)?;
+    credit_reputation(reputation, points, now)?;
+    reputation.proposals_scored = reputation
+        .proposals_scored
+        .checked_add(1)
+        .ok_or(VotingError::OverflowError)?;
+
+    Ok(())
+}
+
+fn emit_vote_cast(proposal: Pubkey, voter: Pubkey, voter_account: &Voter) -> Result<()> {
+    emit!(ConvictionVoteCast {
+        version: EVENT_VERSION,
+        proposal,
+        voter,
+        score: voter_account.score,
+        conviction: voter_account.conviction,
+        locked_amount: voter_account.locked_amount,
+        unlock_time: voter_account.unlock_time,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
     Ok(())
 }
 
+fn emit_proposal_finalized(proposal: &Account<Proposal>, total_weight: u64, now: i64) {
+    emit!(ProposalFinalized {
+        version: EVENT_VERSION,
+        proposal: proposal.key(),
+        author: proposal.author,
+        status: proposal.status,
+        final_score: proposal.final_score,
+        total_weight,
+        timestamp: now,
+    });
+}
+
+/// Weighted mean of all votes, rounded to the nearest score.
+/// `counts[s]` already holds `s * weight` for every vote on score `s`.
+pub fn compute_final_score(scores: &Scores) -> Result<u8> {
+    require!(scores.total_weight > 0, VotingError::NoVotes);
+
+    let weighted_sum = scores
+        .counts
+        .iter()
+        .try_fold(0u128, |acc, count| acc.checked_add(*count as u128))
+        .ok_or(VotingError::OverflowError)?;
+    let total_weight = scores.total_weight as u128;
+    let rounded = (weighted_sum * 2 + total_weight) / (total_weight * 2);
+
+    Ok(rounded.min(10) as u8)
+}
+
 #[derive(Accounts)]
 pub struct InitializeProposal<'info> {
     #[account(mut)]
     pub author: Signer<'info>,
 
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, GlobalConfig>,
+
     // Stores author metadata and proposal counter
     #[account(
         init_if_needed,
@@ -120,16 +480,65 @@ pub struct InitializeProposal<'info> {
     )]
     pub proposal: Account<'info, Proposal>,
 
+    // Created with the proposal so it can be finalized without any vote
+    #[account(
+        init,
+        payer = author,
+        space = 8 + Scores::INIT_SPACE,
+        seeds = [
+            b"scores",
+            proposal.key().as_ref(),
+        ],
+        bump
+    )]
+    pub scores: Account<'info, Scores>,
+
     // Record that links the proposal and its related PDAs
     pub system_program: Program<'info, System>,
 }
 
+#[derive(Accounts)]
+#[instruction(proposal_count: u64)]
+pub struct UpdateEvidence<'info> {
+    pub author: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = author,
+        seeds = [
+            b"proposal",
+            author.key().as_ref(),
+            proposal_count.to_string().as_bytes()
+        ],
+        bump
+    )]
+    pub proposal: Account<'info, Proposal>,
+}
+
 #[derive(Accounts)]
 #[instruction(proposal_count: u64)]
 pub struct VoteProposal<'info> {
     #[account(mut)]
     pub voter: Signer<'info>,
 
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, GlobalConfig>,
+
+    /// CHECK: only used to derive the proposal PDA
+    pub author: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        has_one = author,
+        seeds = [
+            b"proposal",
+            author.key().as_ref(),
+            proposal_count.to_string().as_bytes()
+        ],
+        bump
+    )]
+    pub proposal: Account<'info, Proposal>,
+
     // ✅ SPL Token mint (your native token)
     #[account(mint::token_program = token_program)]
     pub mint: InterfaceAccount<'info, Mint>,
@@ -155,7 +564,7 @@ pub struct VoteProposal<'info> {
     #[account(init_if_needed, payer = voter, space = 8 + Scores::INIT_SPACE,
         seeds = [
             b"scores",
-            proposal_count.to_string().as_bytes(),
+            proposal.key().as_ref(),
         ],
         bump
     )]
@@ -167,8 +576,8 @@ pub struct VoteProposal<'info> {
         space = 8 + Voter::INIT_SPACE,
         seeds = [
             b"voter",
-           proposal_count.to_string().as_bytes(),
-           voter.key().as_ref()
+            proposal.key().as_ref(),
+            voter.key().as_ref()
         ],
         bump
     )]
@@ -179,6 +588,123 @@ pub struct VoteProposal<'info> {
     pub associated_token_program: Program<'info, AssociatedToken>,
 }
 
+#[derive(Accounts)]
+#[instruction(proposal_count: u64)]
+pub struct UpdateConvictionVote<'info> {
+    #[account(mut)]
+    pub voter: Signer<'info>,
+
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, GlobalConfig>,
+
+    /// CHECK: only used to derive the proposal PDA
+    pub author: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        has_one = author,
+        seeds = [
+            b"proposal",
+            author.key().as_ref(),
+            proposal_count.to_string().as_bytes()
+        ],
+        bump
+    )]
+    pub proposal: Account<'info, Proposal>,
+
+    #[account(mint::token_program = token_program)]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = voter,
+        associated_token::token_program = token_program,
+    )]
+    pub voter_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = scores,
+        associated_token::token_program = token_program,
+    )]
+    pub vault_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"scores",
+            proposal.key().as_ref(),
+        ],
+        bump
+    )]
+    pub scores: Account<'info, Scores>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"voter",
+            proposal.key().as_ref(),
+            voter.key().as_ref()
+        ],
+        bump
+    )]
+    pub voter_account: Account<'info, Voter>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+#[instruction(proposal_count: u64)]
+pub struct FinalizeProposal<'info> {
+    // Anyone can finalize once voting has ended
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// CHECK: only used to derive the proposal PDAs
+    pub author: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"author_state", author.key().as_ref()],
+        bump
+    )]
+    pub author_state: Account<'info, AuthorState>,
+
+    #[account(
+        mut,
+        has_one = author,
+        seeds = [
+            b"proposal",
+            author.key().as_ref(),
+            proposal_count.to_string().as_bytes()
+        ],
+        bump
+    )]
+    pub proposal: Account<'info, Proposal>,
+
+    #[account(
+        seeds = [
+            b"scores",
+            proposal.key().as_ref(),
+        ],
+        bump
+    )]
+    pub scores: Account<'info, Scores>,
+
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = 8 + Reputation::INIT_SPACE,
+        seeds = [b"reputation", author.key().as_ref()],
+        bump
+    )]
+    pub author_reputation: Account<'info, Reputation>,
+
+    pub system_program: Program<'info, System>,
+}
+
 #[error_code]
 pub enum VotingError {
     #[msg("Invalid score (must be between 0–10).")]
@@ -197,9 +723,71 @@ pub enum VotingError {
     InvalidAccountOwner,
     #[msg("Overflow error.")]
     OverflowError,
+    #[msg("Proposal already finalized.")]
+    AlreadyFinalized,
+    #[msg("Proposal not finalized yet.")]
+    NotFinalized,
+    #[msg("Voter has not voted.")]
+    NotVoted,
+    #[msg("Reputation already settled for this vote.")]
+    ReputationAlreadySettled,
+    #[msg("Voting has already started.")]
+    VotingStarted,
+    #[msg("Invalid evidence URI.")]
+    InvalidEvidenceUri,
+    #[msg("Invalid voting period.")]
+    InvalidVotingPeriod,
+    #[msg("Voting period has ended.")]
+    VotingClosed,
+    #[msg("Voting period has not ended yet.")]
+    VotingStillOpen,
+    #[msg("Tokens are still locked.")]
+    TokensLocked,
+    #[msg("Votes without conviction earn no reputation.")]
+    NoConviction,
 }
 
 pub fn conviction_weight(conviction: u8) -> Result<u8> {
     require!(conviction <= 6, VotingError::InvalidConviction);
     Ok(conviction)
 }
+
+/// Tokens to lock and lock duration in seconds for a conviction weight.
+pub fn conviction_lock(weight: u64) -> Result<(u64, i64)> {
+    let lock_amount = 10_000_000u64
+        .checked_mul(weight)
+        .ok_or(VotingError::OverflowError)?; // example: conviction, 10 tokens per conviction weight
+    let lock_duration = 60 * 60 * 24 * (weight as i64) * 10; // days = weight × 10 day
+    Ok((lock_amount, lock_duration))
+}
+
+fn add_weighted_vote(scores: &mut Scores, score: u8, weight: u64) -> Result<()> {
+    // ✅ Multiply score by conviction weight
+    let effective_vote = (score as u64)
+        .checked_mul(weight)
+        .ok_or(VotingError::OverflowError)?;
+
+    scores.counts[score as usize] = scores.counts[score as usize]
+        .checked_add(effective_vote)
+        .ok_or(VotingError::OverflowError)?;
+    scores.total_weight = scores
+        .total_weight
+        .checked_add(weight)
+        .ok_or(VotingError::OverflowError)?;
+    Ok(())
+}
+
+fn remove_weighted_vote(scores: &mut Scores, score: u8, weight: u64) -> Result<()> {
+    let effective_vote = (score as u64)
+        .checked_mul(weight)
+        .ok_or(VotingError::OverflowError)?;
+
+    scores.counts[score as usize] = scores.counts[score as usize]
+        .checked_sub(effective_vote)
+        .ok_or(VotingError::OverflowError)?;
+    scores.total_weight = scores
+        .total_weight
+        .checked_sub(weight)
+        .ok_or(VotingError::OverflowError)?;
+    Ok(())
+}
@@ -1,14 +1,29 @@
+pub mod batch_auction;
+pub mod clmm;
+pub mod config;
 pub mod conviction_voting;
 pub mod initialize;
+pub mod order_book;
 pub mod poll;
 pub mod pool;
+pub mod reputation;
 pub mod reveal;
+pub mod rewards;
+pub mod sealed_offer;
 pub mod swap;
 pub mod vote;
 
+pub use batch_auction::*;
+pub use clmm::*;
+pub use config::*;
 pub use conviction_voting::*;
 pub use initialize::*;
+pub use order_book::*;
 pub use poll::*;
 pub use pool::*;
+pub use reputation::*;
 pub use reveal::*;
+pub use rewards::*;
+pub use sealed_offer::*;
+pub use swap::*;
 pub use vote::*;
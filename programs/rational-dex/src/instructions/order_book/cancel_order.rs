@@ -0,0 +1,58 @@
+use anchor_lang::prelude::*;
+
+use crate::error::DexError;
+use crate::events::OrderCancelled;
+use crate::instructions::order_book::{release_base, release_quote};
+use crate::state::order_book_state::{Market, OpenOrders, OrderBook, Side};
+use crate::EVENT_VERSION;
+
+/// Removes one of the caller's resting orders and moves its locked funds to the free balance.
+pub fn cancel_order_handler(ctx: Context<CancelOrder>, side: Side, order_id: u64) -> Result<()> {
+    let open_orders_key = ctx.accounts.open_orders.key();
+    let mut book = ctx.accounts.order_book.load_mut()?;
+    let orders = match side {
+        Side::Bid => &mut book.bids,
+        Side::Ask => &mut book.asks,
+    };
+    let index = orders
+        .find(order_id)
+        .filter(|&index| orders.orders[index].open_orders == open_orders_key)
+        .ok_or(DexError::OrderNotFound)?;
+    let order = orders.remove(index);
+
+    let open_orders = &mut ctx.accounts.open_orders;
+    match side {
+        Side::Bid => release_quote(open_orders, order.locked)?,
+        Side::Ask => release_base(open_orders, order.locked)?,
+    }
+
+    emit!(OrderCancelled {
+        version: EVENT_VERSION,
+        market: ctx.accounts.market.key(),
+        order_id,
+        owner: ctx.accounts.owner.key(),
+        remaining_quantity: order.quantity,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct CancelOrder<'info> {
+    pub owner: Signer<'info>,
+
+    #[account(has_one = order_book)]
+    pub market: Box<Account<'info, Market>>,
+
+    #[account(mut)]
+    pub order_book: AccountLoader<'info, OrderBook>,
+
+    #[account(
+        mut,
+        seeds = [b"open_orders", market.key().as_ref(), owner.key().as_ref()],
+        bump = open_orders.bump,
+        has_one = owner,
+        has_one = market,
+    )]
+    pub open_orders: Box<Account<'info, OpenOrders>>,
+}
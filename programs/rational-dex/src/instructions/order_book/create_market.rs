@@ -0,0 +1,80 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::error::DexError;
+use crate::state::order_book_state::{Market, OrderBook};
+use crate::ANCHOR_DISCRIMINATOR;
+
+/// Creates a market for a base/quote pair. The order book account must already be
+/// allocated by the client with the program as owner; it is claimed here and never closed.
+pub fn create_market_handler(ctx: Context<CreateMarket>) -> Result<()> {
+    let market_key = ctx.accounts.market.key();
+    let mut book = ctx.accounts.order_book.load_init()?;
+    book.market = market_key;
+    book.next_order_id = 1;
+
+    ctx.accounts.market.set_inner(Market {
+        base_mint: ctx.accounts.base_mint.key(),
+        quote_mint: ctx.accounts.quote_mint.key(),
+        base_vault: ctx.accounts.base_vault.key(),
+        quote_vault: ctx.accounts.quote_vault.key(),
+        order_book: ctx.accounts.order_book.key(),
+        bump: ctx.bumps.market,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct CreateMarket<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(
+        mint::token_program = token_program,
+        constraint = base_mint.key() != quote_mint.key() @ DexError::InvalidMarket,
+    )]
+    pub base_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(mint::token_program = token_program)]
+    pub quote_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = ANCHOR_DISCRIMINATOR + Market::INIT_SPACE,
+        seeds = [b"market", base_mint.key().as_ref(), quote_mint.key().as_ref()],
+        bump
+    )]
+    pub market: Box<Account<'info, Market>>,
+
+    #[account(
+        init,
+        payer = payer,
+        token::mint = base_mint,
+        token::authority = market,
+        token::token_program = token_program,
+        seeds = [b"market_base", market.key().as_ref()],
+        bump
+    )]
+    pub base_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        init,
+        payer = payer,
+        token::mint = quote_mint,
+        token::authority = market,
+        token::token_program = token_program,
+        seeds = [b"market_quote", market.key().as_ref()],
+        bump
+    )]
+    pub quote_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    // `zero` checks the owner and that the discriminator is unset, so a book cannot be reused
+    #[account(zero)]
+    pub order_book: AccountLoader<'info, OrderBook>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+
+    pub system_program: Program<'info, System>,
+}
@@ -0,0 +1,195 @@
+use anchor_lang::prelude::*;
+
+use crate::error::DexError;
+use crate::events::{OrderCancelled, OrderFilled};
+use crate::instructions::config::require_not_paused;
+use crate::instructions::pool::mul_div_floor;
+use crate::state::config_state::{GlobalConfig, Subsystem};
+use crate::state::order_book_state::{Market, OpenOrders, OrderBook};
+use crate::{EVENT_VERSION, PRICE_SCALE};
+
+/// Permissionless crank that crosses the best bid and ask up to `limit` times.
+/// The older of the two orders sets the price. Fills are credited to the makers'
+/// open orders accounts, which must all be passed as writable remaining accounts.
+pub fn match_orders_handler<'info>(
+    ctx: Context<'_, '_, 'info, 'info, MatchOrders<'info>>,
+    limit: u16,
+) -> Result<()> {
+    require_not_paused(&ctx.accounts.config, Subsystem::Offers)?;
+    let market_key = ctx.accounts.market.key();
+    let mut open_orders = ctx
+        .remaining_accounts
+        .iter()
+        .map(Account::<OpenOrders>::try_from)
+        .collect::<Result<Vec<_>>>()?;
+    for (index, account) in open_orders.iter().enumerate() {
+        require_keys_eq!(account.market, market_key, DexError::InvalidMarket);
+        // A second copy would be written back over the first one's credits on exit
+        require!(
+            open_orders[..index]
+                .iter()
+                .all(|other| other.key() != account.key()),
+            DexError::DuplicateOpenOrders
+        );
+    }
+    let find = |open_orders: &[Account<OpenOrders>], key: Pubkey| {
+        open_orders
+            .iter()
+            .position(|account| account.key() == key)
+            .ok_or(DexError::MissingOpenOrders)
+    };
+
+    let mut book = ctx.accounts.order_book.load_mut()?;
+    let mut fills = 0;
+    while fills < limit {
+        let (Some(bid), Some(ask)) = (book.bids.best().copied(), book.asks.best().copied()) else {
+            break;
+        };
+        if bid.price < ask.price {
+            break;
+        }
+
+        let price = if bid.order_id < ask.order_id {
+            bid.price
+        } else {
+            ask.price
+        };
+        let quantity = bid.quantity.min(ask.quantity);
+        let quote = mul_div_floor(quantity, price, PRICE_SCALE as u64)?;
+        let bidder_index = find(&open_orders, bid.open_orders)?;
+        let asker_index = find(&open_orders, ask.open_orders)?;
+
+        if quote == 0 {
+            // Filling the smaller order would hand over base tokens for nothing, so it is
+            // cancelled instead and its funds freed, which keeps the book moving
+            let (order, owner) = if bid.quantity == quantity {
+                let bidder = &mut open_orders[bidder_index];
+                release_quote(bidder, bid.locked)?;
+                (book.bids.remove(0), bidder.owner)
+            } else {
+                let asker = &mut open_orders[asker_index];
+                release_base(asker, ask.locked)?;
+                (book.asks.remove(0), asker.owner)
+            };
+            emit!(OrderCancelled {
+                version: EVENT_VERSION,
+                market: market_key,
+                order_id: order.order_id,
+                owner,
+                remaining_quantity: order.quantity,
+            });
+            fills += 1;
+            continue;
+        }
+
+        // A partial fill releases the bid's reservation at its own limit price, rounded down,
+        // so the rest of the order stays fully funded. The last fill releases what is left.
+        let bid_remaining = bid.quantity - quantity;
+        let bid_released = if bid_remaining == 0 {
+            bid.locked
+        } else {
+            mul_div_floor(quantity, bid.price, PRICE_SCALE as u64)?
+        };
+
+        let bidder = &mut open_orders[bidder_index];
+        bidder.quote_locked = bidder
+            .quote_locked
+            .checked_sub(bid_released)
+            .ok_or(DexError::Overflow)?;
+        let refund = bid_released.checked_sub(quote).ok_or(DexError::Overflow)?;
+        bidder.quote_free = bidder
+            .quote_free
+            .checked_add(refund)
+            .ok_or(DexError::Overflow)?;
+        bidder.base_free = bidder
+            .base_free
+            .checked_add(quantity)
+            .ok_or(DexError::Overflow)?;
+
+        let asker = &mut open_orders[asker_index];
+        asker.base_locked = asker
+            .base_locked
+            .checked_sub(quantity)
+            .ok_or(DexError::Overflow)?;
+        asker.quote_free = asker
+            .quote_free
+            .checked_add(quote)
+            .ok_or(DexError::Overflow)?;
+
+        if bid_remaining == 0 {
+            book.bids.remove(0);
+        } else {
+            let order = &mut book.bids.orders[0];
+            order.quantity = bid_remaining;
+            order.locked = order
+                .locked
+                .checked_sub(bid_released)
+                .ok_or(DexError::Overflow)?;
+        }
+        if ask.quantity == quantity {
+            book.asks.remove(0);
+        } else {
+            let order = &mut book.asks.orders[0];
+            order.quantity -= quantity;
+            order.locked = order
+                .locked
+                .checked_sub(quantity)
+                .ok_or(DexError::Overflow)?;
+        }
+
+        emit!(OrderFilled {
+            version: EVENT_VERSION,
+            market: market_key,
+            bid_order_id: bid.order_id,
+            ask_order_id: ask.order_id,
+            price,
+            quantity,
+        });
+        fills += 1;
+    }
+    drop(book);
+
+    for account in &open_orders {
+        account.exit(ctx.program_id)?;
+    }
+
+    Ok(())
+}
+
+/// Moves `amount` of a bid's reservation to the free quote balance.
+pub fn release_quote(open_orders: &mut OpenOrders, amount: u64) -> Result<()> {
+    open_orders.quote_locked = open_orders
+        .quote_locked
+        .checked_sub(amount)
+        .ok_or(DexError::Overflow)?;
+    open_orders.quote_free = open_orders
+        .quote_free
+        .checked_add(amount)
+        .ok_or(DexError::Overflow)?;
+    Ok(())
+}
+
+/// Moves `amount` of an ask's reservation to the free base balance.
+pub fn release_base(open_orders: &mut OpenOrders, amount: u64) -> Result<()> {
+    open_orders.base_locked = open_orders
+        .base_locked
+        .checked_sub(amount)
+        .ok_or(DexError::Overflow)?;
+    open_orders.base_free = open_orders
+        .base_free
+        .checked_add(amount)
+        .ok_or(DexError::Overflow)?;
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct MatchOrders<'info> {
+    #[account(has_one = order_book)]
+    pub market: Box<Account<'info, Market>>,
+
+    #[account(mut)]
+    pub order_book: AccountLoader<'info, OrderBook>,
+
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Box<Account<'info, GlobalConfig>>,
+}
@@ -0,0 +1,11 @@
+pub mod cancel_order;
+pub mod create_market;
+pub mod match_orders;
+pub mod place_order;
+pub mod settle_funds;
+
+pub use cancel_order::*;
+pub use create_market::*;
+pub use match_orders::*;
+pub use place_order::*;
+pub use settle_funds::*;
@@ -0,0 +1,170 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::error::DexError;
+use crate::events::OrderPlaced;
+use crate::instructions::config::require_not_paused;
+use crate::instructions::pool::{mul_div_ceil, mul_div_floor};
+use crate::instructions::swap::{amount_with_transfer_fee, transfer_tokens};
+use crate::state::config_state::{GlobalConfig, Subsystem};
+use crate::state::order_book_state::{Market, OpenOrders, Order, OrderBook, Side};
+use crate::{ANCHOR_DISCRIMINATOR, EVENT_VERSION, PRICE_SCALE};
+
+/// Rests a limit order on the book. Bids lock `quantity * price` quote tokens (rounded up),
+/// asks lock `quantity` base tokens. Orders worth less than one quote atom are rejected.
+/// Nothing is filled here; see `match_orders`.
+pub fn place_order_handler(
+    ctx: Context<PlaceOrder>,
+    side: Side,
+    price: u64,
+    quantity: u64,
+) -> Result<()> {
+    require_not_paused(&ctx.accounts.config, Subsystem::Offers)?;
+    require!(price > 0 && quantity > 0, DexError::InvalidAmount);
+    require!(
+        mul_div_floor(quantity, price, PRICE_SCALE as u64)? > 0,
+        DexError::DustOrder
+    );
+
+    let accounts = &ctx.accounts;
+    let locked = match side {
+        Side::Bid => mul_div_ceil(quantity, price, PRICE_SCALE as u64)?,
+        Side::Ask => quantity,
+    };
+
+    let (from, to, mint) = match side {
+        Side::Bid => (
+            &accounts.owner_quote_account,
+            &accounts.quote_vault,
+            &accounts.quote_mint,
+        ),
+        Side::Ask => (
+            &accounts.owner_base_account,
+            &accounts.base_vault,
+            &accounts.base_mint,
+        ),
+    };
+    // The owner covers any transfer fee so the vault holds exactly `locked`
+    transfer_tokens(
+        from,
+        to,
+        &amount_with_transfer_fee(mint, locked)?,
+        mint,
+        &accounts.owner,
+        &accounts.token_program,
+    )?;
+
+    let market_key = ctx.accounts.market.key();
+    let open_orders_key = ctx.accounts.open_orders.key();
+    let open_orders = &mut ctx.accounts.open_orders;
+    if open_orders.owner == Pubkey::default() {
+        open_orders.market = market_key;
+        open_orders.owner = ctx.accounts.owner.key();
+        open_orders.bump = ctx.bumps.open_orders;
+    }
+    match side {
+        Side::Bid => {
+            open_orders.quote_locked = open_orders
+                .quote_locked
+                .checked_add(locked)
+                .ok_or(DexError::Overflow)?
+        }
+        Side::Ask => {
+            open_orders.base_locked = open_orders
+                .base_locked
+                .checked_add(locked)
+                .ok_or(DexError::Overflow)?
+        }
+    }
+
+    let mut book = ctx.accounts.order_book.load_mut()?;
+    let order_id = book.next_order_id;
+    book.next_order_id += 1;
+
+    let order = Order {
+        order_id,
+        open_orders: open_orders_key,
+        price,
+        quantity,
+        locked,
+    };
+    let inserted = match side {
+        Side::Bid => book.bids.insert(order, side),
+        Side::Ask => book.asks.insert(order, side),
+    };
+    require!(inserted, DexError::OrderBookFull);
+
+    emit!(OrderPlaced {
+        version: EVENT_VERSION,
+        market: market_key,
+        order_id,
+        owner: ctx.accounts.owner.key(),
+        side,
+        price,
+        quantity,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct PlaceOrder<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Box<Account<'info, GlobalConfig>>,
+
+    #[account(
+        seeds = [b"market", base_mint.key().as_ref(), quote_mint.key().as_ref()],
+        bump = market.bump,
+        has_one = base_mint,
+        has_one = quote_mint,
+        has_one = base_vault,
+        has_one = quote_vault,
+        has_one = order_book,
+    )]
+    pub market: Box<Account<'info, Market>>,
+
+    #[account(mut)]
+    pub order_book: AccountLoader<'info, OrderBook>,
+
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = ANCHOR_DISCRIMINATOR + OpenOrders::INIT_SPACE,
+        seeds = [b"open_orders", market.key().as_ref(), owner.key().as_ref()],
+        bump
+    )]
+    pub open_orders: Box<Account<'info, OpenOrders>>,
+
+    pub base_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    pub quote_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(mut)]
+    pub base_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(mut)]
+    pub quote_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        token::mint = base_mint,
+        token::authority = owner,
+        token::token_program = token_program,
+    )]
+    pub owner_base_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        token::mint = quote_mint,
+        token::authority = owner,
+        token::token_program = token_program,
+    )]
+    pub owner_quote_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+
+    pub system_program: Program<'info, System>,
+}
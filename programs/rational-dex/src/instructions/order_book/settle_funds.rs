@@ -0,0 +1,118 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{
+    transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked,
+};
+
+use crate::state::order_book_state::{Market, OpenOrders};
+
+/// Withdraws the free base and quote balances of the caller's open orders account.
+pub fn settle_funds_handler(ctx: Context<SettleFunds>) -> Result<()> {
+    let accounts = &ctx.accounts;
+    let base_amount = accounts.open_orders.base_free;
+    let quote_amount = accounts.open_orders.quote_free;
+
+    if base_amount > 0 {
+        transfer_from_market(
+            &accounts.market,
+            &accounts.base_vault,
+            &accounts.owner_base_account,
+            &accounts.base_mint,
+            base_amount,
+            &accounts.token_program,
+        )?;
+    }
+    if quote_amount > 0 {
+        transfer_from_market(
+            &accounts.market,
+            &accounts.quote_vault,
+            &accounts.owner_quote_account,
+            &accounts.quote_mint,
+            quote_amount,
+            &accounts.token_program,
+        )?;
+    }
+
+    let open_orders = &mut ctx.accounts.open_orders;
+    open_orders.base_free = 0;
+    open_orders.quote_free = 0;
+
+    Ok(())
+}
+
+fn transfer_from_market<'info>(
+    market: &Account<'info, Market>,
+    vault: &InterfaceAccount<'info, TokenAccount>,
+    to: &InterfaceAccount<'info, TokenAccount>,
+    mint: &InterfaceAccount<'info, Mint>,
+    amount: u64,
+    token_program: &Interface<'info, TokenInterface>,
+) -> Result<()> {
+    let seeds = &[
+        b"market".as_ref(),
+        market.base_mint.as_ref(),
+        market.quote_mint.as_ref(),
+        &[market.bump],
+    ];
+    let signer_seeds = [&seeds[..]];
+
+    let accounts = TransferChecked {
+        from: vault.to_account_info(),
+        mint: mint.to_account_info(),
+        to: to.to_account_info(),
+        authority: market.to_account_info(),
+    };
+    let cpi_context =
+        CpiContext::new_with_signer(token_program.to_account_info(), accounts, &signer_seeds);
+
+    transfer_checked(cpi_context, amount, mint.decimals)
+}
+
+#[derive(Accounts)]
+pub struct SettleFunds<'info> {
+    pub owner: Signer<'info>,
+
+    #[account(
+        has_one = base_mint,
+        has_one = quote_mint,
+        has_one = base_vault,
+        has_one = quote_vault,
+    )]
+    pub market: Box<Account<'info, Market>>,
+
+    #[account(
+        mut,
+        seeds = [b"open_orders", market.key().as_ref(), owner.key().as_ref()],
+        bump = open_orders.bump,
+        has_one = owner,
+        has_one = market,
+    )]
+    pub open_orders: Box<Account<'info, OpenOrders>>,
+
+    pub base_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    pub quote_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(mut)]
+    pub base_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(mut)]
+    pub quote_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        token::mint = base_mint,
+        token::authority = owner,
+        token::token_program = token_program,
+    )]
+    pub owner_base_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        token::mint = quote_mint,
+        token::authority = owner,
+        token::token_program = token_program,
+    )]
+    pub owner_quote_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
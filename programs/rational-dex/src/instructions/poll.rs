@@ -1,6 +1,10 @@
+use crate::events::PollCreated;
+use crate::instructions::config::require_not_paused;
+use crate::state::config_state::{GlobalConfig, Subsystem};
 use crate::state::poll_state::PollAccount;
 use crate::{
-    constants::COMP_DEF_OFFSET_INIT_VOTE_STATS, error::ErrorCode, SignerAccount, ID, ID_CONST,
+    constants::COMP_DEF_OFFSET_INIT_VOTE_STATS, error::ErrorCode, SignerAccount, EVENT_VERSION, ID,
+    ID_CONST,
 };
 use arcium_client::idl::arcium::types::CallbackAccount;
 
@@ -21,6 +25,7 @@ pub fn create_new_poll(
     mint1: Pubkey,
     nonce: u128,
 ) -> Result<()> {
+    require_not_paused(&ctx.accounts.config, Subsystem::Polls)?;
     msg!("Creating a new poll");
 
     // Initialize the poll account with the provided parameters
@@ -49,6 +54,18 @@ pub fn create_new_poll(
         }])],
     )?;
 
+    let poll_acc = &ctx.accounts.poll_acc;
+    emit!(PollCreated {
+        version: EVENT_VERSION,
+        poll: poll_acc.key(),
+        poll_id: id,
+        authority: poll_acc.authority,
+        price,
+        mint0,
+        mint1,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
     Ok(())
 }
 
@@ -135,6 +152,8 @@ pub struct CreateNewPoll<'info> {
         bump,
     )]
     pub poll_acc: Account<'info, PollAccount>,
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, GlobalConfig>,
 }
 
 #[callback_accounts("init_vote_stats")]
@@ -0,0 +1,129 @@
+use anchor_lang::prelude::*;
+
+use super::{accumulate_prices, refresh_reference_price, write_observation};
+use crate::error::{DexError, ErrorCode};
+use crate::events::ConsensusPriceUpdated;
+use crate::instructions::config::require_not_paused;
+use crate::state::config_state::{GlobalConfig, Subsystem};
+use crate::state::oracle_state::ObservationBuffer;
+use crate::state::poll_state::PollAccount;
+use crate::state::pool_state::Pool;
+use crate::EVENT_VERSION;
+
+/// Writes the consensus price agreed by stakeholders, scaled by `PRICE_SCALE`.
+pub fn update_consensus_price_handler(
+    ctx: Context<UpdateConsensusPrice>,
+    price: u64,
+) -> Result<()> {
+    require!(price > 0, DexError::InvalidAmount);
+    let now = Clock::get()?.unix_timestamp;
+    set_consensus_price(
+        &mut ctx.accounts.pool,
+        &mut ctx.accounts.observations,
+        price,
+        now,
+    )
+}
+
+/// Moves the pool to the consensus price of a revealed poll. Anyone can crank it, but only
+/// polls opened by the pool authority count, and each reveal is applied once.
+pub fn apply_poll_price_handler(ctx: Context<ApplyPollPrice>) -> Result<()> {
+    require_not_paused(&ctx.accounts.config, Subsystem::Polls)?;
+    let pool = &ctx.accounts.pool;
+    let poll = &ctx.accounts.poll;
+    require!(
+        pool.mint_a == poll.mint0 && pool.mint_b == poll.mint1,
+        ErrorCode::PollPoolMismatch
+    );
+    require_keys_eq!(poll.authority, pool.authority, DexError::UnauthorizedPoll);
+    require!(
+        poll.revealed_at > 0 && poll.consensus_price > 0,
+        DexError::ConsensusNotRevealed
+    );
+    // A reveal older than the current price would roll it back
+    require!(
+        poll.revealed_at > pool.last_price_timestamp as i64,
+        DexError::StalePollResult
+    );
+
+    let price = poll.consensus_price;
+    let now = Clock::get()?.unix_timestamp;
+    set_consensus_price(
+        &mut ctx.accounts.pool,
+        &mut ctx.accounts.observations,
+        price,
+        now,
+    )
+}
+
+/// Closes the accumulator period of the old consensus price, then moves the pool to `price`.
+/// Shared by the pool authority's updates and applied poll results.
+pub fn set_consensus_price(
+    pool: &mut Account<Pool>,
+    observations: &mut ObservationBuffer,
+    price: u64,
+    now: i64,
+) -> Result<()> {
+    let pool_key = pool.key();
+    accumulate_prices(pool, now)?;
+
+    pool.consensus_price = price;
+    pool.last_price_timestamp = now as u64;
+    refresh_reference_price(pool, observations, pool_key, now)?;
+
+    write_observation(observations, pool);
+
+    emit!(ConsensusPriceUpdated {
+        version: EVENT_VERSION,
+        pool: pool_key,
+        consensus_price: price,
+        reference_price: pool.reference_price,
+        timestamp: now,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct UpdateConsensusPrice<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = authority,
+        seeds = [b"pool", pool.mint_a.as_ref(), pool.mint_b.as_ref()],
+        bump = pool.bump,
+    )]
+    pub pool: Box<Account<'info, Pool>>,
+
+    #[account(
+        mut,
+        has_one = pool,
+        seeds = [b"observations", pool.key().as_ref()],
+        bump = observations.bump
+    )]
+    pub observations: Box<Account<'info, ObservationBuffer>>,
+}
+
+#[derive(Accounts)]
+pub struct ApplyPollPrice<'info> {
+    #[account(
+        mut,
+        seeds = [b"pool", pool.mint_a.as_ref(), pool.mint_b.as_ref()],
+        bump = pool.bump,
+    )]
+    pub pool: Box<Account<'info, Pool>>,
+
+    #[account(
+        mut,
+        has_one = pool,
+        seeds = [b"observations", pool.key().as_ref()],
+        bump = observations.bump
+    )]
+    pub observations: Box<Account<'info, ObservationBuffer>>,
+
+    pub poll: Box<Account<'info, PollAccount>>,
+
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Box<Account<'info, GlobalConfig>>,
+}
@@ -0,0 +1,194 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::sysvar::instructions::{
+    load_current_index_checked, load_instruction_at_checked,
+};
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use super::{accrue_lp_fees, mul_div_ceil, transfer_from_vault};
+use crate::error::DexError;
+use crate::events::{FlashLoanBorrowed, FlashLoanRepaid};
+use crate::instructions::config::require_not_paused;
+use crate::instructions::swap::{amount_with_transfer_fee, transfer_tokens};
+use crate::state::config_state::{GlobalConfig, Subsystem};
+use crate::state::pool_state::Pool;
+use crate::{BPS_DENOMINATOR, EVENT_VERSION, FLASH_LOAN_FEE_BPS};
+
+/// Position of `pool` in the `FlashLoan` accounts, used to match the repay instruction.
+const FLASH_LOAN_POOL_INDEX: usize = 2;
+
+/// Lends `amount` of token A (or B) out of the pool's vault. The same transaction must call
+/// `flash_repay` on this pool later on; the pool stays locked against swaps and liquidity
+/// changes until then.
+pub fn flash_borrow_handler(ctx: Context<FlashLoan>, amount: u64, token_a: bool) -> Result<()> {
+    require_not_paused(&ctx.accounts.config, Subsystem::Swaps)?;
+    require!(!ctx.accounts.pool.locked, DexError::PoolLocked);
+    require!(amount > 0, DexError::InvalidAmount);
+    require_flash_repay(&ctx.accounts.instructions_sysvar, ctx.accounts.pool.key())?;
+
+    let accounts = &ctx.accounts;
+    let pool_key = accounts.pool.key();
+    if token_a {
+        transfer_from_vault(
+            &accounts.vault_a,
+            &accounts.user_token_a,
+            &accounts.mint_a,
+            amount,
+            b"vault_a",
+            &pool_key,
+            ctx.bumps.vault_a,
+            &accounts.token_program,
+        )?;
+    } else {
+        transfer_from_vault(
+            &accounts.vault_b,
+            &accounts.user_token_b,
+            &accounts.mint_b,
+            amount,
+            b"vault_b",
+            &pool_key,
+            ctx.bumps.vault_b,
+            &accounts.token_program,
+        )?;
+    }
+
+    let mint = if token_a {
+        accounts.mint_a.key()
+    } else {
+        accounts.mint_b.key()
+    };
+    emit!(FlashLoanBorrowed {
+        version: EVENT_VERSION,
+        pool: pool_key,
+        borrower: accounts.user.key(),
+        mint,
+        amount,
+    });
+
+    let pool = &mut ctx.accounts.pool;
+    pool.locked = true;
+    pool.flash_loan_amount = amount;
+    pool.flash_loan_token_a = token_a;
+
+    Ok(())
+}
+
+/// Pays back the outstanding loan plus `FLASH_LOAN_FEE_BPS`. The fee is not counted as
+/// protocol fees, so it stays in the reserves and accrues to LPs.
+pub fn flash_repay_handler(ctx: Context<FlashLoan>) -> Result<()> {
+    let pool = &ctx.accounts.pool;
+    require!(pool.locked, DexError::NoFlashLoan);
+
+    let amount = pool.flash_loan_amount;
+    let fee = mul_div_ceil(amount, FLASH_LOAN_FEE_BPS, BPS_DENOMINATOR)?;
+    let owed = amount.checked_add(fee).ok_or(DexError::Overflow)?;
+
+    let accounts = &ctx.accounts;
+    let (user_token, vault, mint) = if pool.flash_loan_token_a {
+        (&accounts.user_token_a, &accounts.vault_a, &accounts.mint_a)
+    } else {
+        (&accounts.user_token_b, &accounts.vault_b, &accounts.mint_b)
+    };
+    // The borrower covers any transfer fee so the vault gets back at least what it lent
+    transfer_tokens(
+        user_token,
+        vault,
+        &amount_with_transfer_fee(mint, owed)?,
+        mint,
+        &accounts.user,
+        &accounts.token_program,
+    )?;
+
+    emit!(FlashLoanRepaid {
+        version: EVENT_VERSION,
+        pool: pool.key(),
+        borrower: accounts.user.key(),
+        mint: mint.key(),
+        amount,
+        fee,
+    });
+
+    let pool = &mut ctx.accounts.pool;
+    let token_a = pool.flash_loan_token_a;
+    accrue_lp_fees(pool, fee, token_a);
+    pool.locked = false;
+    pool.flash_loan_amount = 0;
+
+    Ok(())
+}
+
+/// Checks that the borrow is a top-level instruction of this program and that a
+/// `flash_repay` on the same pool follows it in the transaction.
+fn require_flash_repay(instructions_sysvar: &AccountInfo, pool: Pubkey) -> Result<()> {
+    let current_index = load_current_index_checked(instructions_sysvar)? as usize;
+    let current = load_instruction_at_checked(current_index, instructions_sysvar)?;
+    require_keys_eq!(current.program_id, crate::ID, DexError::FlashLoanCpi);
+
+    let mut index = current_index + 1;
+    while let Ok(instruction) = load_instruction_at_checked(index, instructions_sysvar) {
+        if instruction.program_id == crate::ID
+            && instruction
+                .data
+                .starts_with(crate::instruction::FlashRepay::DISCRIMINATOR)
+            && instruction
+                .accounts
+                .get(FLASH_LOAN_POOL_INDEX)
+                .is_some_and(|account| account.pubkey == pool)
+        {
+            return Ok(());
+        }
+        index += 1;
+    }
+
+    err!(DexError::MissingFlashRepay)
+}
+
+#[derive(Accounts)]
+pub struct FlashLoan<'info> {
+    pub user: Signer<'info>,
+
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Box<Account<'info, GlobalConfig>>,
+
+    #[account(
+        mut,
+        seeds = [b"pool", pool.mint_a.as_ref(), pool.mint_b.as_ref()],
+        bump = pool.bump,
+        has_one = vault_a,
+        has_one = vault_b,
+        has_one = mint_a,
+        has_one = mint_b,
+    )]
+    pub pool: Box<Account<'info, Pool>>,
+
+    #[account(mut, seeds = [b"vault_a", pool.key().as_ref()], bump)]
+    pub vault_a: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(mut, seeds = [b"vault_b", pool.key().as_ref()], bump)]
+    pub vault_b: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub mint_a: Box<InterfaceAccount<'info, Mint>>,
+
+    pub mint_b: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        token::mint = mint_a,
+        token::authority = user,
+        token::token_program = token_program,
+    )]
+    pub user_token_a: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        token::mint = mint_b,
+        token::authority = user,
+        token::token_program = token_program,
+    )]
+    pub user_token_b: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    /// CHECK: instructions_sysvar, checked by the account constraint
+    pub instructions_sysvar: AccountInfo<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
@@ -0,0 +1,150 @@
+use crate::error::{ConfigError, DexError};
+use crate::events::PoolInitialized;
+use crate::state::config_state::GlobalConfig;
+use crate::state::oracle_state::ObservationBuffer;
+use crate::state::pool_state::{BlendPolicy, Pool};
+use crate::{
+    BPS_DENOMINATOR, DEFAULT_CIRCUIT_BREAKER_BPS, DEFAULT_CONSENSUS_WEIGHT_BPS,
+    DEFAULT_MAX_DEVIATION_BPS, EVENT_VERSION, MAX_FEE_BPS,
+};
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use sha2::{Digest, Sha256};
+
+pub fn initialize_pool_handler(
+    ctx: Context<InitializePool>,
+    fee_numerator: u64,
+    fee_denominator: u64,
+) -> Result<()> {
+    validate_fee(fee_numerator, fee_denominator)?;
+    require_keys_neq!(
+        ctx.accounts.mint_a.key(),
+        ctx.accounts.mint_b.key(),
+        DexError::IdenticalMints
+    );
+    // One pool per pair: the canonical order keeps B/A from being created next to A/B
+    require!(
+        ctx.accounts.mint_a.key() < ctx.accounts.mint_b.key(),
+        DexError::UnorderedMints
+    );
+
+    let pool = &mut ctx.accounts.pool;
+    pool.authority = *ctx.accounts.authority.key;
+    pool.vault_a = *ctx.accounts.vault_a.to_account_info().key;
+    pool.vault_b = *ctx.accounts.vault_b.to_account_info().key;
+    pool.mint_lp = *ctx.accounts.mint_lp.to_account_info().key;
+    pool.mint_a = *ctx.accounts.mint_a.to_account_info().key;
+    pool.mint_b = *ctx.accounts.mint_b.to_account_info().key;
+    pool.fee_numerator = fee_numerator;
+    pool.fee_denominator = fee_denominator;
+    pool.bump = ctx.bumps.pool;
+    pool.total_lp_supply = 0;
+    pool.protocol_fees_a = 0;
+    pool.protocol_fees_b = 0;
+    pool.consensus_price = 0;
+    pool.last_price_timestamp = 0;
+    pool.last_market_price = 0;
+    pool.market_price_cumulative = 0;
+    pool.consensus_price_cumulative = 0;
+    pool.last_cumulative_update = Clock::get()?.unix_timestamp;
+    pool.blend_policy = BlendPolicy {
+        consensus_weight_bps: DEFAULT_CONSENSUS_WEIGHT_BPS,
+        max_deviation_bps: DEFAULT_MAX_DEVIATION_BPS,
+        circuit_breaker_bps: DEFAULT_CIRCUIT_BREAKER_BPS,
+        reject_outside_band: false,
+    };
+    pool.reference_price = 0;
+    pool.circuit_breaker_tripped = false;
+    pool.locked = false;
+    pool.flash_loan_amount = 0;
+    pool.flash_loan_token_a = false;
+    pool.lp_fee_growth_a_x64 = 0;
+    pool.lp_fee_growth_b_x64 = 0;
+
+    let observations = &mut ctx.accounts.observations;
+    observations.pool = pool.key();
+    observations.head = 0;
+    observations.len = 0;
+    observations.bump = ctx.bumps.observations;
+
+    let pool = &ctx.accounts.pool;
+    emit!(PoolInitialized {
+        version: EVENT_VERSION,
+        pool: pool.key(),
+        authority: pool.authority,
+        mint_a: pool.mint_a,
+        mint_b: pool.mint_b,
+        mint_lp: pool.mint_lp,
+        vault_a: pool.vault_a,
+        vault_b: pool.vault_b,
+        fee_numerator,
+        fee_denominator,
+        timestamp: pool.last_cumulative_update,
+    });
+
+    Ok(())
+}
+
+/// The swap fee must be a proper fraction no larger than `MAX_FEE_BPS`.
+pub fn validate_fee(fee_numerator: u64, fee_denominator: u64) -> Result<()> {
+    require!(fee_denominator > 0, DexError::DivisionByZero);
+
+    let fee_bps = (fee_numerator as u128) * (BPS_DENOMINATOR as u128);
+    let cap = (MAX_FEE_BPS as u128) * (fee_denominator as u128);
+    require!(fee_bps <= cap, DexError::FeeTooHigh);
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct InitializePool<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = config.admin == authority.key() @ ConfigError::NotAdmin
+    )]
+    pub config: Account<'info, GlobalConfig>,
+    pub mint_a: InterfaceAccount<'info, Mint>,
+    pub mint_b: InterfaceAccount<'info, Mint>,
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + Pool::INIT_SPACE,
+        seeds = [b"pool", mint_a.key().as_ref(), mint_b.key().as_ref()],
+        bump
+    )]
+    pub pool: Account<'info, Pool>,
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + ObservationBuffer::INIT_SPACE,
+        seeds = [b"observations", pool.key().as_ref()],
+        bump
+    )]
+    pub observations: Box<Account<'info, ObservationBuffer>>,
+    #[account(
+        init_if_needed,
+        payer = authority,
+        token::mint = mint_a,
+        token::authority = vault_a,
+        token::token_program = token_program,
+        seeds = [b"vault_a", pool.key().as_ref()],
+        bump
+    )]
+    pub vault_a: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        init_if_needed,
+        payer = authority,
+        token::mint = mint_b,
+        token::authority = vault_b,
+        token::token_program = token_program,
+        seeds = [b"vault_b", pool.key().as_ref()],
+        bump
+    )]
+    pub vault_b: InterfaceAccount<'info, TokenAccount>,
+    #[account(mut)]
+    pub mint_lp: InterfaceAccount<'info, Mint>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
@@ -0,0 +1,432 @@
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token_interface::{
+    burn, mint_to, Burn, Mint, MintTo, TokenAccount, TokenInterface,
+};
+
+use super::{
+    accumulate_prices, initial_lp, market_price, mul_div_ceil, mul_div_floor, open_lp_position,
+    pool_reserve, record_lp_change, transfer_from_vault,
+};
+use crate::error::{DexError, ErrorCode};
+use crate::events::LiquidityChanged;
+use crate::instructions::config::require_not_paused;
+use crate::instructions::swap::{
+    amount_with_transfer_fee, is_native, transfer_fee, transfer_tokens, unwrap_sol, wrap_sol,
+};
+use crate::state::config_state::{GlobalConfig, Subsystem};
+use crate::state::pool_state::{LpPosition, Pool};
+use crate::{ANCHOR_DISCRIMINATOR, EVENT_VERSION, MINIMUM_LIQUIDITY};
+
+/// Deposits up to `max_amount_a`/`max_amount_b` at the current reserve ratio and mints LP tokens.
+/// The first deposit sets the ratio and mints `sqrt(a * b)` LP tokens, of which
+/// `MINIMUM_LIQUIDITY` are locked in the pool. The deposit is added to the user's `LpPosition`.
+pub fn add_liquidity_handler(
+    ctx: Context<AddLiquidity>,
+    max_amount_a: u64,
+    max_amount_b: u64,
+    min_lp_out: u64,
+) -> Result<()> {
+    require_not_paused(&ctx.accounts.config, Subsystem::Swaps)?;
+    require!(!ctx.accounts.pool.locked, DexError::PoolLocked);
+    require!(
+        max_amount_a > 0 && max_amount_b > 0,
+        DexError::InvalidAmount
+    );
+
+    let accounts = &ctx.accounts;
+    let pool = &accounts.pool;
+    let reserve_a = pool_reserve(&accounts.vault_a, pool.protocol_fees_a)?;
+    let reserve_b = pool_reserve(&accounts.vault_b, pool.protocol_fees_b)?;
+    let supply = pool.total_lp_supply;
+
+    // Work in amounts that actually reach the vaults after Token-2022 transfer fees
+    let max_amount_a = max_amount_a - transfer_fee(&accounts.mint_a, max_amount_a)?;
+    let max_amount_b = max_amount_b - transfer_fee(&accounts.mint_b, max_amount_b)?;
+
+    let (lp_out, amount_a, amount_b) = if supply == 0 {
+        (
+            initial_lp(max_amount_a, max_amount_b)?,
+            max_amount_a,
+            max_amount_b,
+        )
+    } else {
+        let lp = mul_div_floor(max_amount_a, supply, reserve_a)?.min(mul_div_floor(
+            max_amount_b,
+            supply,
+            reserve_b,
+        )?);
+        // Round deposits up so existing LPs are never diluted
+        (
+            lp,
+            mul_div_ceil(lp, reserve_a, supply)?,
+            mul_div_ceil(lp, reserve_b, supply)?,
+        )
+    };
+    require!(lp_out > 0, ErrorCode::InsufficientLP);
+    require!(lp_out >= min_lp_out, ErrorCode::SlippageExceeded);
+
+    for (mint, user_token, vault, amount) in [
+        (
+            &accounts.mint_a,
+            &accounts.user_token_a,
+            &accounts.vault_a,
+            amount_a,
+        ),
+        (
+            &accounts.mint_b,
+            &accounts.user_token_b,
+            &accounts.vault_b,
+            amount_b,
+        ),
+    ] {
+        let gross_amount = amount_with_transfer_fee(mint, amount)?;
+        if is_native(mint) {
+            wrap_sol(
+                &accounts.user,
+                user_token,
+                gross_amount,
+                &accounts.system_program,
+                &accounts.token_program,
+            )?;
+        }
+        transfer_tokens(
+            user_token,
+            vault,
+            &gross_amount,
+            mint,
+            &accounts.user,
+            &accounts.token_program,
+        )?;
+    }
+
+    let seeds = &[
+        b"pool".as_ref(),
+        pool.mint_a.as_ref(),
+        pool.mint_b.as_ref(),
+        &[pool.bump],
+    ];
+    let signer_seeds = [&seeds[..]];
+    mint_to(
+        CpiContext::new_with_signer(
+            accounts.token_program.to_account_info(),
+            MintTo {
+                mint: accounts.mint_lp.to_account_info(),
+                to: accounts.user_token_lp.to_account_info(),
+                authority: pool.to_account_info(),
+            },
+            &signer_seeds,
+        ),
+        lp_out,
+    )?;
+
+    let new_reserve_a = reserve_a.checked_add(amount_a).ok_or(DexError::Overflow)?;
+    let new_reserve_b = reserve_b.checked_add(amount_b).ok_or(DexError::Overflow)?;
+    // The locked minimum is counted in the supply but never minted, so no one can redeem it
+    let locked = if supply == 0 { MINIMUM_LIQUIDITY } else { 0 };
+
+    let now = Clock::get()?.unix_timestamp;
+    let pool_key = ctx.accounts.pool.key();
+    let pool = &mut ctx.accounts.pool;
+    accumulate_prices(pool, now)?;
+    // Deposits keep the reserve ratio, so the reference price only follows swaps and
+    // consensus updates
+    pool.last_market_price = market_price(new_reserve_a, new_reserve_b);
+    pool.total_lp_supply = pool
+        .total_lp_supply
+        .checked_add(lp_out)
+        .and_then(|supply| supply.checked_add(locked))
+        .ok_or(DexError::Overflow)?;
+
+    emit!(LiquidityChanged {
+        version: EVENT_VERSION,
+        pool: pool_key,
+        user: ctx.accounts.user.key(),
+        deposit: true,
+        lp_amount: lp_out,
+        amount_a,
+        amount_b,
+        reserve_a: new_reserve_a,
+        reserve_b: new_reserve_b,
+        total_lp_supply: pool.total_lp_supply,
+        timestamp: now,
+    });
+
+    let position = &mut ctx.accounts.lp_position;
+    open_lp_position(
+        position,
+        pool,
+        ctx.accounts.user.key(),
+        ctx.bumps.lp_position,
+        now,
+    );
+    record_lp_change(position, pool, true, lp_out, amount_a, amount_b, now)?;
+
+    Ok(())
+}
+
+/// Burns `lp_amount` LP tokens and withdraws the matching share of both reserves.
+/// Accrued protocol fees are not part of the reserves and stay in the vaults. The withdrawal
+/// is recorded in the user's `LpPosition`. Not gated by the swaps pause, so liquidity can
+/// always be withdrawn.
+pub fn remove_liquidity_handler(
+    ctx: Context<RemoveLiquidity>,
+    lp_amount: u64,
+    min_amount_a: u64,
+    min_amount_b: u64,
+) -> Result<()> {
+    require!(!ctx.accounts.pool.locked, DexError::PoolLocked);
+    require!(lp_amount > 0, DexError::InvalidAmount);
+
+    let accounts = &ctx.accounts;
+    let pool = &accounts.pool;
+    let supply = pool.total_lp_supply;
+    require!(lp_amount <= supply, ErrorCode::InsufficientLP);
+
+    let reserve_a = pool_reserve(&accounts.vault_a, pool.protocol_fees_a)?;
+    let reserve_b = pool_reserve(&accounts.vault_b, pool.protocol_fees_b)?;
+    let amount_a = mul_div_floor(lp_amount, reserve_a, supply)?;
+    let amount_b = mul_div_floor(lp_amount, reserve_b, supply)?;
+    require!(
+        amount_a >= min_amount_a && amount_b >= min_amount_b,
+        ErrorCode::SlippageExceeded
+    );
+
+    burn(
+        CpiContext::new(
+            accounts.token_program.to_account_info(),
+            Burn {
+                mint: accounts.mint_lp.to_account_info(),
+                from: accounts.user_token_lp.to_account_info(),
+                authority: accounts.user.to_account_info(),
+            },
+        ),
+        lp_amount,
+    )?;
+
+    transfer_from_vault(
+        &accounts.vault_a,
+        &accounts.user_token_a,
+        &accounts.mint_a,
+        amount_a,
+        b"vault_a",
+        &ctx.accounts.pool.key(),
+        ctx.bumps.vault_a,
+        &accounts.token_program,
+    )?;
+    transfer_from_vault(
+        &accounts.vault_b,
+        &accounts.user_token_b,
+        &accounts.mint_b,
+        amount_b,
+        b"vault_b",
+        &ctx.accounts.pool.key(),
+        ctx.bumps.vault_b,
+        &accounts.token_program,
+    )?;
+    // Withdrawn native SOL is paid out to the user's wallet
+    for (mint, user_token, amount) in [
+        (&accounts.mint_a, &accounts.user_token_a, amount_a),
+        (&accounts.mint_b, &accounts.user_token_b, amount_b),
+    ] {
+        if is_native(mint) {
+            unwrap_sol(
+                &accounts.user,
+                user_token,
+                &accounts.unwrap_account,
+                ctx.bumps.unwrap_account,
+                mint,
+                amount,
+                &accounts.system_program,
+                &accounts.token_program,
+            )?;
+        }
+    }
+
+    let now = Clock::get()?.unix_timestamp;
+    let pool_key = ctx.accounts.pool.key();
+    let pool = &mut ctx.accounts.pool;
+    accumulate_prices(pool, now)?;
+    pool.last_market_price = market_price(reserve_a - amount_a, reserve_b - amount_b);
+    pool.total_lp_supply = pool
+        .total_lp_supply
+        .checked_sub(lp_amount)
+        .ok_or(DexError::Overflow)?;
+
+    emit!(LiquidityChanged {
+        version: EVENT_VERSION,
+        pool: pool_key,
+        user: ctx.accounts.user.key(),
+        deposit: false,
+        lp_amount,
+        amount_a,
+        amount_b,
+        reserve_a: reserve_a - amount_a,
+        reserve_b: reserve_b - amount_b,
+        total_lp_supply: pool.total_lp_supply,
+        timestamp: now,
+    });
+
+    let position = &mut ctx.accounts.lp_position;
+    open_lp_position(
+        position,
+        pool,
+        ctx.accounts.user.key(),
+        ctx.bumps.lp_position,
+        now,
+    );
+    record_lp_change(position, pool, false, lp_amount, amount_a, amount_b, now)?;
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct AddLiquidity<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Box<Account<'info, GlobalConfig>>,
+
+    #[account(
+        mut,
+        seeds = [b"pool", pool.mint_a.as_ref(), pool.mint_b.as_ref()],
+        bump = pool.bump,
+        has_one = vault_a,
+        has_one = vault_b,
+        has_one = mint_a,
+        has_one = mint_b,
+        has_one = mint_lp,
+    )]
+    pub pool: Box<Account<'info, Pool>>,
+
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = ANCHOR_DISCRIMINATOR + LpPosition::INIT_SPACE,
+        seeds = [b"lp_position", pool.key().as_ref(), user.key().as_ref()],
+        bump
+    )]
+    pub lp_position: Box<Account<'info, LpPosition>>,
+
+    #[account(mut, seeds = [b"vault_a", pool.key().as_ref()], bump)]
+    pub vault_a: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(mut, seeds = [b"vault_b", pool.key().as_ref()], bump)]
+    pub vault_b: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub mint_a: Box<InterfaceAccount<'info, Mint>>,
+
+    pub mint_b: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(mut, mint::authority = pool)]
+    pub mint_lp: Box<InterfaceAccount<'info, Mint>>,
+
+    // Associated token accounts, created when missing. For native SOL the wSOL account stays
+    // open; only the SOL moved by the instruction is wrapped or unwrapped.
+    #[account(
+        init_if_needed,
+        payer = user,
+        associated_token::mint = mint_a,
+        associated_token::authority = user,
+        associated_token::token_program = token_program,
+    )]
+    pub user_token_a: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        init_if_needed,
+        payer = user,
+        associated_token::mint = mint_b,
+        associated_token::authority = user,
+        associated_token::token_program = token_program,
+    )]
+    pub user_token_b: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        token::mint = mint_lp,
+        token::authority = user,
+        token::token_program = token_program,
+    )]
+    pub user_token_lp: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub system_program: Program<'info, System>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
+
+#[derive(Accounts)]
+pub struct RemoveLiquidity<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"pool", pool.mint_a.as_ref(), pool.mint_b.as_ref()],
+        bump = pool.bump,
+        has_one = vault_a,
+        has_one = vault_b,
+        has_one = mint_a,
+        has_one = mint_b,
+        has_one = mint_lp,
+    )]
+    pub pool: Box<Account<'info, Pool>>,
+
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = ANCHOR_DISCRIMINATOR + LpPosition::INIT_SPACE,
+        seeds = [b"lp_position", pool.key().as_ref(), user.key().as_ref()],
+        bump
+    )]
+    pub lp_position: Box<Account<'info, LpPosition>>,
+
+    #[account(mut, seeds = [b"vault_a", pool.key().as_ref()], bump)]
+    pub vault_a: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(mut, seeds = [b"vault_b", pool.key().as_ref()], bump)]
+    pub vault_b: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub mint_a: Box<InterfaceAccount<'info, Mint>>,
+
+    pub mint_b: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(mut)]
+    pub mint_lp: Box<InterfaceAccount<'info, Mint>>,
+
+    // Associated token accounts, created when missing. For native SOL the wSOL account stays
+    // open; only the SOL moved by the instruction is wrapped or unwrapped.
+    #[account(
+        init_if_needed,
+        payer = user,
+        associated_token::mint = mint_a,
+        associated_token::authority = user,
+        associated_token::token_program = token_program,
+    )]
+    pub user_token_a: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        init_if_needed,
+        payer = user,
+        associated_token::mint = mint_b,
+        associated_token::authority = user,
+        associated_token::token_program = token_program,
+    )]
+    pub user_token_b: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        token::mint = mint_lp,
+        token::authority = user,
+        token::token_program = token_program,
+    )]
+    pub user_token_lp: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// CHECK: created and closed within the instruction to pay out native SOL, see `unwrap_sol`
+    #[account(mut, seeds = [b"unwrap", user.key().as_ref()], bump)]
+    pub unwrap_account: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
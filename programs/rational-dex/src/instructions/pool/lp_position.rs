@@ -0,0 +1,174 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::TokenAccount;
+
+use super::{mul_div_floor, pool_reserve};
+use crate::error::DexError;
+use crate::events::PositionUpdated;
+use crate::state::pool_state::{LpPosition, Pool, PositionValue};
+use crate::EVENT_VERSION;
+
+/// Credits the LP share of a fee to every LP token in the pool. Fees earned while the pool
+/// has no LP tokens are not tracked.
+pub fn accrue_lp_fees(pool: &mut Pool, fee: u64, token_a: bool) {
+    if pool.total_lp_supply == 0 || fee == 0 {
+        return;
+    }
+    let growth = ((fee as u128) << 64) / pool.total_lp_supply as u128;
+    if token_a {
+        pool.lp_fee_growth_a_x64 = pool.lp_fee_growth_a_x64.wrapping_add(growth);
+    } else {
+        pool.lp_fee_growth_b_x64 = pool.lp_fee_growth_b_x64.wrapping_add(growth);
+    }
+}
+
+/// Sets up a position created by `init_if_needed`. It only earns fees from now on.
+pub fn open_lp_position(
+    position: &mut LpPosition,
+    pool: &Account<Pool>,
+    owner: Pubkey,
+    bump: u8,
+    now: i64,
+) {
+    if position.owner != Pubkey::default() {
+        return;
+    }
+    position.pool = pool.key();
+    position.owner = owner;
+    position.fee_growth_a_checkpoint_x64 = pool.lp_fee_growth_a_x64;
+    position.fee_growth_b_checkpoint_x64 = pool.lp_fee_growth_b_x64;
+    position.opened_at = now;
+    position.bump = bump;
+}
+
+/// Settles the fees earned since the last checkpoint, applies a deposit or withdrawal of
+/// `lp_change` LP tokens for `amount_a`/`amount_b` and emits `PositionUpdated`.
+pub fn record_lp_change(
+    position: &mut LpPosition,
+    pool: &Pool,
+    deposit: bool,
+    lp_change: u64,
+    amount_a: u64,
+    amount_b: u64,
+    now: i64,
+) -> Result<()> {
+    let (pending_a, pending_b) = pending_fees(position, pool)?;
+    position.fees_earned_a = position
+        .fees_earned_a
+        .checked_add(pending_a)
+        .ok_or(DexError::Overflow)?;
+    position.fees_earned_b = position
+        .fees_earned_b
+        .checked_add(pending_b)
+        .ok_or(DexError::Overflow)?;
+    position.fee_growth_a_checkpoint_x64 = pool.lp_fee_growth_a_x64;
+    position.fee_growth_b_checkpoint_x64 = pool.lp_fee_growth_b_x64;
+
+    if deposit {
+        position.lp_amount = position
+            .lp_amount
+            .checked_add(lp_change)
+            .ok_or(DexError::Overflow)?;
+        position.deposited_a = position
+            .deposited_a
+            .checked_add(amount_a)
+            .ok_or(DexError::Overflow)?;
+        position.deposited_b = position
+            .deposited_b
+            .checked_add(amount_b)
+            .ok_or(DexError::Overflow)?;
+    } else if position.lp_amount > 0 {
+        // LP tokens beyond the tracked amount came in by transfer and carry no basis
+        let removed = lp_change.min(position.lp_amount);
+        position.deposited_a -= mul_div_floor(position.deposited_a, removed, position.lp_amount)?;
+        position.deposited_b -= mul_div_floor(position.deposited_b, removed, position.lp_amount)?;
+        position.lp_amount -= removed;
+    }
+    position.updated_at = now;
+
+    emit!(PositionUpdated {
+        version: EVENT_VERSION,
+        pool: position.pool,
+        owner: position.owner,
+        deposit,
+        lp_change,
+        amount_a,
+        amount_b,
+        lp_amount: position.lp_amount,
+        deposited_a: position.deposited_a,
+        deposited_b: position.deposited_b,
+        fees_earned_a: position.fees_earned_a,
+        fees_earned_b: position.fees_earned_b,
+        timestamp: now,
+    });
+
+    Ok(())
+}
+
+/// Current value of a position, its deposit basis and fees earned, returned through
+/// return data.
+pub fn position_value_handler(ctx: Context<GetPositionValue>) -> Result<PositionValue> {
+    let pool = &ctx.accounts.pool;
+    let position = &ctx.accounts.lp_position;
+
+    let (amount_a, amount_b) = if pool.total_lp_supply == 0 {
+        (0, 0)
+    } else {
+        let reserve_a = pool_reserve(&ctx.accounts.vault_a, pool.protocol_fees_a)?;
+        let reserve_b = pool_reserve(&ctx.accounts.vault_b, pool.protocol_fees_b)?;
+        (
+            mul_div_floor(position.lp_amount, reserve_a, pool.total_lp_supply)?,
+            mul_div_floor(position.lp_amount, reserve_b, pool.total_lp_supply)?,
+        )
+    };
+    let (pending_a, pending_b) = pending_fees(position, pool)?;
+
+    Ok(PositionValue {
+        lp_amount: position.lp_amount,
+        amount_a,
+        amount_b,
+        deposited_a: position.deposited_a,
+        deposited_b: position.deposited_b,
+        fees_earned_a: position.fees_earned_a.saturating_add(pending_a),
+        fees_earned_b: position.fees_earned_b.saturating_add(pending_b),
+        timestamp: Clock::get()?.unix_timestamp,
+    })
+}
+
+/// Fees earned by the position's LP tokens since its last checkpoint.
+fn pending_fees(position: &LpPosition, pool: &Pool) -> Result<(u64, u64)> {
+    let pending = |growth: u128, checkpoint: u128| {
+        let amount = growth
+            .wrapping_sub(checkpoint)
+            .checked_mul(position.lp_amount as u128)
+            .ok_or(DexError::Overflow)?
+            >> 64;
+        u64::try_from(amount).map_err(|_| DexError::Overflow)
+    };
+    Ok((
+        pending(
+            pool.lp_fee_growth_a_x64,
+            position.fee_growth_a_checkpoint_x64,
+        )?,
+        pending(
+            pool.lp_fee_growth_b_x64,
+            position.fee_growth_b_checkpoint_x64,
+        )?,
+    ))
+}
+
+#[derive(Accounts)]
+pub struct GetPositionValue<'info> {
+    #[account(has_one = vault_a, has_one = vault_b)]
+    pub pool: Box<Account<'info, Pool>>,
+
+    #[account(
+        has_one = pool,
+        seeds = [b"lp_position", pool.key().as_ref(), lp_position.owner.as_ref()],
+        bump = lp_position.bump
+    )]
+    pub lp_position: Box<Account<'info, LpPosition>>,
+
+    pub vault_a: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub vault_b: Box<InterfaceAccount<'info, TokenAccount>>,
+}
@@ -0,0 +1,23 @@
+pub mod consensus_price;
+pub mod flash_loan;
+pub mod initialize_pool;
+pub mod liquidity;
+pub mod lp_position;
+pub mod pool_math;
+pub mod pool_swap;
+pub mod protocol_fees;
+pub mod reference_price;
+pub mod route_swap;
+pub mod twap;
+
+pub use consensus_price::*;
+pub use flash_loan::*;
+pub use initialize_pool::*;
+pub use liquidity::*;
+pub use lp_position::*;
+pub use pool_math::*;
+pub use pool_swap::*;
+pub use protocol_fees::*;
+pub use reference_price::*;
+pub use route_swap::*;
+pub use twap::*;
@@ -0,0 +1,115 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{
+    transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked,
+};
+
+use crate::error::{DexError, ErrorCode};
+use crate::{BPS_DENOMINATOR, MINIMUM_LIQUIDITY, PROTOCOL_FEE_SHARE_BPS};
+
+/// Total swap fee charged on `amount_in`.
+pub fn swap_fee(amount_in: u64, fee_numerator: u64, fee_denominator: u64) -> Result<u64> {
+    require!(fee_denominator > 0, DexError::DivisionByZero);
+    let fee = (amount_in as u128)
+        .checked_mul(fee_numerator as u128)
+        .ok_or(DexError::Overflow)?
+        / fee_denominator as u128;
+    u64::try_from(fee).map_err(|_| DexError::Overflow.into())
+}
+
+/// Part of the swap fee kept by the protocol. The rest stays in the reserves for LPs.
+pub fn protocol_fee_share(fee: u64) -> u64 {
+    ((fee as u128) * (PROTOCOL_FEE_SHARE_BPS as u128) / (BPS_DENOMINATOR as u128)) as u64
+}
+
+/// Constant product output: `reserve_out * amount_in / (reserve_in + amount_in)`.
+pub fn constant_product_out(amount_in: u64, reserve_in: u64, reserve_out: u64) -> Result<u64> {
+    require!(
+        reserve_in > 0 && reserve_out > 0,
+        DexError::InsufficientLiquidity
+    );
+    let numerator = (reserve_out as u128)
+        .checked_mul(amount_in as u128)
+        .ok_or(DexError::Overflow)?;
+    let denominator = (reserve_in as u128)
+        .checked_add(amount_in as u128)
+        .ok_or(DexError::Overflow)?;
+    Ok((numerator / denominator) as u64)
+}
+
+/// LP tokens minted to the first depositor: `sqrt(a * b)` less `MINIMUM_LIQUIDITY`, which
+/// stays in the supply without an owner. Without it the first LP could mint a single LP token,
+/// donate to the vaults and round every later deposit down to nothing.
+pub fn initial_lp(amount_a: u64, amount_b: u64) -> Result<u64> {
+    let lp = integer_sqrt((amount_a as u128) * (amount_b as u128)) as u64;
+    require!(lp > MINIMUM_LIQUIDITY, ErrorCode::InsufficientLP);
+    Ok(lp - MINIMUM_LIQUIDITY)
+}
+
+/// Vault balance available to LPs, i.e. without the accrued protocol fees.
+pub fn pool_reserve(vault: &InterfaceAccount<TokenAccount>, protocol_fees: u64) -> Result<u64> {
+    let reserve = vault
+        .amount
+        .checked_sub(protocol_fees)
+        .ok_or(DexError::Overflow)?;
+    Ok(reserve)
+}
+
+/// `a * b / c` in u128, rounded up.
+pub fn mul_div_ceil(a: u64, b: u64, c: u64) -> Result<u64> {
+    require!(c > 0, DexError::DivisionByZero);
+    let product = (a as u128)
+        .checked_mul(b as u128)
+        .ok_or(DexError::Overflow)?;
+    let result = product.div_ceil(c as u128);
+    u64::try_from(result).map_err(|_| DexError::Overflow.into())
+}
+
+/// `a * b / c` in u128, rounded down.
+pub fn mul_div_floor(a: u64, b: u64, c: u64) -> Result<u64> {
+    require!(c > 0, DexError::DivisionByZero);
+    let product = (a as u128)
+        .checked_mul(b as u128)
+        .ok_or(DexError::Overflow)?;
+    u64::try_from(product / c as u128).map_err(|_| DexError::Overflow.into())
+}
+
+pub fn integer_sqrt(value: u128) -> u128 {
+    if value < 2 {
+        return value;
+    }
+    // Newton's method
+    let mut x = value;
+    let mut y = (x + 1) / 2;
+    while y < x {
+        x = y;
+        y = (x + value / x) / 2;
+    }
+    x
+}
+
+/// Moves tokens out of a self-owned vault PDA (`token::authority = vault`) of `pool`.
+#[allow(clippy::too_many_arguments)]
+pub fn transfer_from_vault<'info>(
+    vault: &InterfaceAccount<'info, TokenAccount>,
+    to: &InterfaceAccount<'info, TokenAccount>,
+    mint: &InterfaceAccount<'info, Mint>,
+    amount: u64,
+    vault_seed: &[u8],
+    pool: &Pubkey,
+    vault_bump: u8,
+    token_program: &Interface<'info, TokenInterface>,
+) -> Result<()> {
+    let seeds = &[vault_seed, pool.as_ref(), &[vault_bump]];
+    let signer_seeds = [&seeds[..]];
+
+    let accounts = TransferChecked {
+        from: vault.to_account_info(),
+        mint: mint.to_account_info(),
+        to: to.to_account_info(),
+        authority: vault.to_account_info(),
+    };
+    let cpi_context =
+        CpiContext::new_with_signer(token_program.to_account_info(), accounts, &signer_seeds);
+
+    transfer_checked(cpi_context, amount, mint.decimals)
+}
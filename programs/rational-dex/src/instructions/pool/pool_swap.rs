@@ -0,0 +1,305 @@
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use super::{
+    accrue_lp_fees, accumulate_prices, check_reference_band, constant_product_out, market_price,
+    pool_reserve, protocol_fee_share, refresh_reference_price, swap_fee, transfer_from_vault,
+    write_observation,
+};
+use crate::error::{DexError, ErrorCode};
+use crate::events::Swapped;
+use crate::instructions::config::require_not_paused;
+use crate::instructions::swap::{is_native, transfer_fee, transfer_tokens, unwrap_sol, wrap_sol};
+use crate::state::config_state::{GlobalConfig, Subsystem};
+use crate::state::oracle_state::ObservationBuffer;
+use crate::state::pool_state::Pool;
+use crate::EVENT_VERSION;
+
+/// A swap priced against the pool's reserves before it.
+pub struct SwapQuote {
+    /// Input that reaches the vault after any Token-2022 transfer fee
+    pub amount_in_net: u64,
+    pub protocol_fee: u64,
+    /// Part of the swap fee left in the reserves for LPs
+    pub lp_fee: u64,
+    pub amount_out: u64,
+    pub reserve_in: u64,
+    pub reserve_out: u64,
+}
+
+/// Prices sending `amount_in` of the input token to the pool, after its swap fee.
+pub fn quote_swap(
+    pool: &Pool,
+    vault_a: &InterfaceAccount<TokenAccount>,
+    vault_b: &InterfaceAccount<TokenAccount>,
+    mint_in: &InterfaceAccount<Mint>,
+    amount_in: u64,
+    a_to_b: bool,
+) -> Result<SwapQuote> {
+    let reserve_a = pool_reserve(vault_a, pool.protocol_fees_a)?;
+    let reserve_b = pool_reserve(vault_b, pool.protocol_fees_b)?;
+    let (reserve_in, reserve_out) = if a_to_b {
+        (reserve_a, reserve_b)
+    } else {
+        (reserve_b, reserve_a)
+    };
+
+    // Only what reaches the vault after any Token-2022 transfer fee is swapped
+    let amount_in_net = amount_in - transfer_fee(mint_in, amount_in)?;
+    let fee = swap_fee(amount_in_net, pool.fee_numerator, pool.fee_denominator)?;
+    let amount_out = constant_product_out(amount_in_net - fee, reserve_in, reserve_out)?;
+    require!(amount_out < reserve_out, DexError::InsufficientLiquidity);
+
+    let protocol_fee = protocol_fee_share(fee);
+
+    Ok(SwapQuote {
+        amount_in_net,
+        protocol_fee,
+        lp_fee: fee - protocol_fee,
+        amount_out,
+        reserve_in,
+        reserve_out,
+    })
+}
+
+/// Updates the price accumulators, reference price, protocol fees and observations
+/// once the tokens of `quote` have moved, and emits `Swapped`.
+pub fn record_swap(
+    pool: &mut Account<Pool>,
+    observations: &mut ObservationBuffer,
+    user: Pubkey,
+    quote: &SwapQuote,
+    a_to_b: bool,
+) -> Result<()> {
+    // Reserves after the swap: the input side keeps the LP part of the fee
+    let new_reserve_in = quote
+        .reserve_in
+        .checked_add(quote.amount_in_net - quote.protocol_fee)
+        .ok_or(DexError::Overflow)?;
+    let new_reserve_out = quote
+        .reserve_out
+        .checked_sub(quote.amount_out)
+        .ok_or(DexError::Overflow)?;
+    let (new_reserve_a, new_reserve_b) = if a_to_b {
+        (new_reserve_in, new_reserve_out)
+    } else {
+        (new_reserve_out, new_reserve_in)
+    };
+
+    let now = Clock::get()?.unix_timestamp;
+    let pool_key = pool.key();
+    accumulate_prices(pool, now)?;
+    let new_market_price = market_price(new_reserve_a, new_reserve_b);
+    check_reference_band(pool, pool.last_market_price, new_market_price)?;
+    pool.last_market_price = new_market_price;
+    refresh_reference_price(pool, observations, pool_key, now)?;
+    if a_to_b {
+        pool.protocol_fees_a = pool
+            .protocol_fees_a
+            .checked_add(quote.protocol_fee)
+            .ok_or(DexError::Overflow)?;
+    } else {
+        pool.protocol_fees_b = pool
+            .protocol_fees_b
+            .checked_add(quote.protocol_fee)
+            .ok_or(DexError::Overflow)?;
+    }
+    accrue_lp_fees(pool, quote.lp_fee, a_to_b);
+
+    write_observation(observations, pool);
+
+    emit!(Swapped {
+        version: EVENT_VERSION,
+        pool: pool_key,
+        user,
+        a_to_b,
+        amount_in: quote.amount_in_net,
+        amount_out: quote.amount_out,
+        protocol_fee: quote.protocol_fee,
+        lp_fee: quote.lp_fee,
+        reserve_a: new_reserve_a,
+        reserve_b: new_reserve_b,
+        timestamp: now,
+    });
+
+    Ok(())
+}
+
+/// Swaps `amount_in` of one pool token for the other. `a_to_b` selects the direction.
+pub fn swap_handler(
+    ctx: Context<Swap>,
+    amount_in: u64,
+    min_amount_out: u64,
+    a_to_b: bool,
+) -> Result<()> {
+    require_not_paused(&ctx.accounts.config, Subsystem::Swaps)?;
+    require!(!ctx.accounts.pool.locked, DexError::PoolLocked);
+    require!(amount_in > 0, DexError::InvalidAmount);
+
+    let accounts = &ctx.accounts;
+    let pool_key = accounts.pool.key();
+    let (mint_in, mint_out) = if a_to_b {
+        (&accounts.mint_a, &accounts.mint_b)
+    } else {
+        (&accounts.mint_b, &accounts.mint_a)
+    };
+
+    let quote = quote_swap(
+        &accounts.pool,
+        &accounts.vault_a,
+        &accounts.vault_b,
+        mint_in,
+        amount_in,
+        a_to_b,
+    )?;
+    let amount_out = quote.amount_out;
+    require!(
+        amount_out - transfer_fee(mint_out, amount_out)? >= min_amount_out,
+        ErrorCode::SlippageExceeded
+    );
+
+    let (user_token_in, user_token_out) = if a_to_b {
+        (&accounts.user_token_a, &accounts.user_token_b)
+    } else {
+        (&accounts.user_token_b, &accounts.user_token_a)
+    };
+    if is_native(mint_in) {
+        wrap_sol(
+            &accounts.user,
+            user_token_in,
+            amount_in,
+            &accounts.system_program,
+            &accounts.token_program,
+        )?;
+    }
+
+    if a_to_b {
+        transfer_tokens(
+            &accounts.user_token_a,
+            &accounts.vault_a,
+            &amount_in,
+            &accounts.mint_a,
+            &accounts.user,
+            &accounts.token_program,
+        )?;
+        transfer_from_vault(
+            &accounts.vault_b,
+            &accounts.user_token_b,
+            &accounts.mint_b,
+            amount_out,
+            b"vault_b",
+            &pool_key,
+            ctx.bumps.vault_b,
+            &accounts.token_program,
+        )?;
+    } else {
+        transfer_tokens(
+            &accounts.user_token_b,
+            &accounts.vault_b,
+            &amount_in,
+            &accounts.mint_b,
+            &accounts.user,
+            &accounts.token_program,
+        )?;
+        transfer_from_vault(
+            &accounts.vault_a,
+            &accounts.user_token_a,
+            &accounts.mint_a,
+            amount_out,
+            b"vault_a",
+            &pool_key,
+            ctx.bumps.vault_a,
+            &accounts.token_program,
+        )?;
+    }
+
+    // Native SOL bought ends up in the user's wallet
+    if is_native(mint_out) {
+        unwrap_sol(
+            &accounts.user,
+            user_token_out,
+            &accounts.unwrap_account,
+            ctx.bumps.unwrap_account,
+            mint_out,
+            amount_out,
+            &accounts.system_program,
+            &accounts.token_program,
+        )?;
+    }
+
+    let accounts = &mut ctx.accounts;
+    record_swap(
+        &mut accounts.pool,
+        &mut accounts.observations,
+        accounts.user.key(),
+        &quote,
+        a_to_b,
+    )
+}
+
+#[derive(Accounts)]
+pub struct Swap<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Box<Account<'info, GlobalConfig>>,
+
+    #[account(
+        mut,
+        seeds = [b"pool", pool.mint_a.as_ref(), pool.mint_b.as_ref()],
+        bump = pool.bump,
+        has_one = vault_a,
+        has_one = vault_b,
+        has_one = mint_a,
+        has_one = mint_b,
+    )]
+    pub pool: Box<Account<'info, Pool>>,
+
+    #[account(
+        mut,
+        has_one = pool,
+        seeds = [b"observations", pool.key().as_ref()],
+        bump = observations.bump
+    )]
+    pub observations: Box<Account<'info, ObservationBuffer>>,
+
+    #[account(mut, seeds = [b"vault_a", pool.key().as_ref()], bump)]
+    pub vault_a: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(mut, seeds = [b"vault_b", pool.key().as_ref()], bump)]
+    pub vault_b: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub mint_a: Box<InterfaceAccount<'info, Mint>>,
+
+    pub mint_b: Box<InterfaceAccount<'info, Mint>>,
+
+    // Associated token accounts, created when missing. For native SOL the wSOL account stays
+    // open; only the SOL moved by the instruction is wrapped or unwrapped.
+    #[account(
+        init_if_needed,
+        payer = user,
+        associated_token::mint = mint_a,
+        associated_token::authority = user,
+        associated_token::token_program = token_program,
+    )]
+    pub user_token_a: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        init_if_needed,
+        payer = user,
+        associated_token::mint = mint_b,
+        associated_token::authority = user,
+        associated_token::token_program = token_program,
+    )]
+    pub user_token_b: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// CHECK: created and closed within the instruction to pay out native SOL, see `unwrap_sol`
+    #[account(mut, seeds = [b"unwrap", user.key().as_ref()], bump)]
+    pub unwrap_account: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
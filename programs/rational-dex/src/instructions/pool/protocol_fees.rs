@@ -0,0 +1,109 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use super::transfer_from_vault;
+use crate::error::{ConfigError, DexError};
+use crate::events::ProtocolFeesCollected;
+use crate::state::config_state::GlobalConfig;
+use crate::state::pool_state::Pool;
+use crate::EVENT_VERSION;
+
+/// Withdraws the accrued protocol fees of both pool tokens to a configured fee recipient.
+pub fn collect_protocol_fees_handler(ctx: Context<CollectProtocolFees>) -> Result<()> {
+    require!(!ctx.accounts.pool.locked, DexError::PoolLocked);
+
+    let accounts = &ctx.accounts;
+    let fees_a = accounts.pool.protocol_fees_a;
+    let fees_b = accounts.pool.protocol_fees_b;
+
+    if fees_a > 0 {
+        transfer_from_vault(
+            &accounts.vault_a,
+            &accounts.recipient_token_a,
+            &accounts.mint_a,
+            fees_a,
+            b"vault_a",
+            &ctx.accounts.pool.key(),
+            ctx.bumps.vault_a,
+            &accounts.token_program,
+        )?;
+    }
+    if fees_b > 0 {
+        transfer_from_vault(
+            &accounts.vault_b,
+            &accounts.recipient_token_b,
+            &accounts.mint_b,
+            fees_b,
+            b"vault_b",
+            &ctx.accounts.pool.key(),
+            ctx.bumps.vault_b,
+            &accounts.token_program,
+        )?;
+    }
+
+    let pool = &mut ctx.accounts.pool;
+    pool.protocol_fees_a = 0;
+    pool.protocol_fees_b = 0;
+
+    emit!(ProtocolFeesCollected {
+        version: EVENT_VERSION,
+        pool: pool.key(),
+        recipient: ctx.accounts.fee_recipient.key(),
+        amount_a: fees_a,
+        amount_b: fees_b,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct CollectProtocolFees<'info> {
+    pub fee_recipient: Signer<'info>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = config.fee_recipients.contains(&fee_recipient.key()) @ ConfigError::NotFeeRecipient
+    )]
+    pub config: Box<Account<'info, GlobalConfig>>,
+
+    #[account(
+        mut,
+        seeds = [b"pool", pool.mint_a.as_ref(), pool.mint_b.as_ref()],
+        bump = pool.bump,
+        has_one = vault_a,
+        has_one = vault_b,
+        has_one = mint_a,
+        has_one = mint_b,
+    )]
+    pub pool: Box<Account<'info, Pool>>,
+
+    #[account(mut, seeds = [b"vault_a", pool.key().as_ref()], bump)]
+    pub vault_a: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(mut, seeds = [b"vault_b", pool.key().as_ref()], bump)]
+    pub vault_b: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub mint_a: Box<InterfaceAccount<'info, Mint>>,
+
+    pub mint_b: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        token::mint = mint_a,
+        token::authority = fee_recipient,
+        token::token_program = token_program,
+    )]
+    pub recipient_token_a: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        token::mint = mint_b,
+        token::authority = fee_recipient,
+        token::token_program = token_program,
+    )]
+    pub recipient_token_b: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
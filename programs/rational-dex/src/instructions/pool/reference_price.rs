@@ -0,0 +1,169 @@
+use anchor_lang::prelude::*;
+
+use super::{average_price, observation_before, oldest_observation};
+use crate::error::DexError;
+use crate::events::{BlendPolicyUpdated, CircuitBreakerTriggered};
+use crate::state::oracle_state::ObservationBuffer;
+use crate::state::pool_state::{BlendPolicy, Pool};
+use crate::{BPS_DENOMINATOR, EVENT_VERSION, REFERENCE_TWAP_WINDOW};
+
+/// Blends the market TWAP and the consensus price according to the pool's `BlendPolicy` and
+/// stores the result as `reference_price`. Emits `CircuitBreakerTriggered` when the spot market
+/// price starts to differ from the consensus price by more than the policy threshold.
+/// The pool's accumulators must be up to date.
+pub fn refresh_reference_price(
+    pool: &mut Pool,
+    observations: &ObservationBuffer,
+    pool_key: Pubkey,
+    now: i64,
+) -> Result<()> {
+    let market = pool.last_market_price;
+    let consensus = pool.consensus_price;
+    let policy = pool.blend_policy;
+
+    let twap = market_twap(pool, observations)?;
+    pool.reference_price = blend_prices(twap, consensus, &policy)?;
+
+    if market == 0 || consensus == 0 {
+        pool.circuit_breaker_tripped = false;
+        return Ok(());
+    }
+
+    let deviation_bps =
+        (market.abs_diff(consensus) as u128) * (BPS_DENOMINATOR as u128) / consensus as u128;
+    let tripped = deviation_bps > policy.circuit_breaker_bps as u128;
+    if tripped && !pool.circuit_breaker_tripped {
+        emit!(CircuitBreakerTriggered {
+            version: EVENT_VERSION,
+            pool: pool_key,
+            market_price: market,
+            consensus_price: consensus,
+            deviation_bps: deviation_bps.min(u64::MAX as u128) as u64,
+            timestamp: now,
+        });
+    }
+    pool.circuit_breaker_tripped = tripped;
+
+    Ok(())
+}
+
+/// Market price averaged over the last `REFERENCE_TWAP_WINDOW`, or over the whole history
+/// while the pool is younger than that. The spot price until there is any history, so a
+/// single transaction cannot move the reference price far.
+pub fn market_twap(pool: &Pool, observations: &ObservationBuffer) -> Result<u64> {
+    let now = pool.last_cumulative_update;
+    let start = observation_before(observations, now - REFERENCE_TWAP_WINDOW)
+        .or_else(|| oldest_observation(observations));
+    match start {
+        Some(start) if start.timestamp < now => average_price(
+            pool.market_price_cumulative,
+            start.market_price_cumulative,
+            now - start.timestamp,
+        ),
+        _ => Ok(pool.last_market_price),
+    }
+}
+
+/// Rejects a swap that leaves the market price more than `circuit_breaker_bps` away from the
+/// reference price, unless it moves the price back towards it. Only pools whose policy sets
+/// `reject_outside_band` are bounded, the others just emit `CircuitBreakerTriggered`; pools
+/// without a reference price yet are never bounded.
+pub fn check_reference_band(pool: &Pool, price_before: u64, price_after: u64) -> Result<()> {
+    let reference = pool.reference_price;
+    if !pool.blend_policy.reject_outside_band || reference == 0 {
+        return Ok(());
+    }
+    let band = (reference as u128) * (pool.blend_policy.circuit_breaker_bps as u128)
+        / BPS_DENOMINATOR as u128;
+    let distance = price_after.abs_diff(reference);
+    require!(
+        distance as u128 <= band || distance <= price_before.abs_diff(reference),
+        DexError::OutsideReferenceBand
+    );
+    Ok(())
+}
+
+/// Weighted mean of both prices, clamped to `max_deviation_bps` around the consensus price.
+/// Falls back to whichever price is set when the other one is missing.
+pub fn blend_prices(market: u64, consensus: u64, policy: &BlendPolicy) -> Result<u64> {
+    if consensus == 0 {
+        return Ok(market);
+    }
+    if market == 0 {
+        return Ok(consensus);
+    }
+
+    let bps = BPS_DENOMINATOR as u128;
+    let weight = policy.consensus_weight_bps as u128;
+    let blended = ((consensus as u128) * weight + (market as u128) * (bps - weight)) / bps;
+
+    let band = (consensus as u128) * (policy.max_deviation_bps as u128) / bps;
+    let lower = (consensus as u128).saturating_sub(band);
+    let upper = (consensus as u128) + band;
+
+    u64::try_from(blended.clamp(lower, upper)).map_err(|_| DexError::Overflow.into())
+}
+
+pub fn set_blend_policy_handler(ctx: Context<SetBlendPolicy>, policy: BlendPolicy) -> Result<()> {
+    validate_blend_policy(&policy)?;
+
+    let pool_key = ctx.accounts.pool.key();
+    let now = Clock::get()?.unix_timestamp;
+    let pool = &mut ctx.accounts.pool;
+    pool.blend_policy = policy;
+    refresh_reference_price(pool, &ctx.accounts.observations, pool_key, now)?;
+
+    emit!(BlendPolicyUpdated {
+        version: EVENT_VERSION,
+        pool: pool_key,
+        policy,
+        reference_price: pool.reference_price,
+        timestamp: now,
+    });
+
+    Ok(())
+}
+
+/// Weights and bands are shares of the price. A circuit breaker at 0 would bound swaps to the
+/// reference price itself.
+pub fn validate_blend_policy(policy: &BlendPolicy) -> Result<()> {
+    let bps = BPS_DENOMINATOR as u16;
+    require!(
+        policy.consensus_weight_bps <= bps
+            && policy.max_deviation_bps <= bps
+            && policy.circuit_breaker_bps > 0
+            && policy.circuit_breaker_bps <= bps,
+        DexError::InvalidBlendPolicy
+    );
+    Ok(())
+}
+
+/// Returns the pool's current reference price through return data.
+pub fn get_reference_price_handler(ctx: Context<GetReferencePrice>) -> Result<u64> {
+    Ok(ctx.accounts.pool.reference_price)
+}
+
+#[derive(Accounts)]
+pub struct SetBlendPolicy<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = authority,
+        seeds = [b"pool", pool.mint_a.as_ref(), pool.mint_b.as_ref()],
+        bump = pool.bump,
+    )]
+    pub pool: Box<Account<'info, Pool>>,
+
+    #[account(
+        has_one = pool,
+        seeds = [b"observations", pool.key().as_ref()],
+        bump = observations.bump
+    )]
+    pub observations: Box<Account<'info, ObservationBuffer>>,
+}
+
+#[derive(Accounts)]
+pub struct GetReferencePrice<'info> {
+    pub pool: Account<'info, Pool>,
+}
@@ -0,0 +1,272 @@
+use anchor_lang::error::ErrorCode::ConstraintHasOne;
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use super::{quote_swap, record_swap, transfer_from_vault};
+use crate::error::{DexError, ErrorCode};
+use crate::instructions::config::require_not_paused;
+use crate::instructions::swap::{is_native, transfer_fee, transfer_tokens, unwrap_sol, wrap_sol};
+use crate::state::config_state::{GlobalConfig, Subsystem};
+use crate::state::oracle_state::ObservationBuffer;
+use crate::state::pool_state::Pool;
+
+/// Remaining accounts per hop: pool, observations, vault_a, vault_b, mint_a, mint_b and the
+/// token program of the pool's mints.
+pub const ROUTE_HOP_ACCOUNTS: usize = 7;
+
+/// One pool of a route, loaded from the remaining accounts.
+struct Hop<'info> {
+    pool: Account<'info, Pool>,
+    observations: Account<'info, ObservationBuffer>,
+    vault_a: InterfaceAccount<'info, TokenAccount>,
+    vault_b: InterfaceAccount<'info, TokenAccount>,
+    mint_a: InterfaceAccount<'info, Mint>,
+    mint_b: InterfaceAccount<'info, Mint>,
+    token_program: Interface<'info, TokenInterface>,
+    a_to_b: bool,
+}
+
+impl<'info> Hop<'info> {
+    fn load(accounts: &'info [AccountInfo<'info>], mint_in: Pubkey) -> Result<Self> {
+        let pool = Account::<Pool>::try_from(&accounts[0])?;
+        let observations = Account::<ObservationBuffer>::try_from(&accounts[1])?;
+        let vault_a = InterfaceAccount::<TokenAccount>::try_from(&accounts[2])?;
+        let vault_b = InterfaceAccount::<TokenAccount>::try_from(&accounts[3])?;
+        let mint_a = InterfaceAccount::<Mint>::try_from(&accounts[4])?;
+        let mint_b = InterfaceAccount::<Mint>::try_from(&accounts[5])?;
+        let token_program = Interface::<TokenInterface>::try_from(&accounts[6])?;
+
+        require_keys_eq!(observations.pool, pool.key(), ConstraintHasOne);
+        require_keys_eq!(vault_a.key(), pool.vault_a, ConstraintHasOne);
+        require_keys_eq!(vault_b.key(), pool.vault_b, ConstraintHasOne);
+        require_keys_eq!(mint_a.key(), pool.mint_a, DexError::InvalidRoute);
+        require_keys_eq!(mint_b.key(), pool.mint_b, DexError::InvalidRoute);
+        for vault in [&vault_a, &vault_b] {
+            require_keys_eq!(
+                *vault.to_account_info().owner,
+                token_program.key(),
+                DexError::InvalidRoute
+            );
+        }
+        require!(!pool.locked, DexError::PoolLocked);
+
+        let a_to_b = if mint_in == pool.mint_a {
+            true
+        } else {
+            require_keys_eq!(mint_in, pool.mint_b, DexError::InvalidRoute);
+            false
+        };
+
+        Ok(Self {
+            pool,
+            observations,
+            vault_a,
+            vault_b,
+            mint_a,
+            mint_b,
+            token_program,
+            a_to_b,
+        })
+    }
+
+    fn vault_in(&self) -> &InterfaceAccount<'info, TokenAccount> {
+        if self.a_to_b {
+            &self.vault_a
+        } else {
+            &self.vault_b
+        }
+    }
+
+    fn mint_in(&self) -> &InterfaceAccount<'info, Mint> {
+        if self.a_to_b {
+            &self.mint_a
+        } else {
+            &self.mint_b
+        }
+    }
+
+    fn mint_out(&self) -> &InterfaceAccount<'info, Mint> {
+        if self.a_to_b {
+            &self.mint_b
+        } else {
+            &self.mint_a
+        }
+    }
+
+    /// Pays `amount` out of the hop's output vault, signed by the vault PDA.
+    fn pay_out(
+        &self,
+        to: &InterfaceAccount<'info, TokenAccount>,
+        amount: u64,
+        program_id: &Pubkey,
+    ) -> Result<()> {
+        let (vault, vault_seed): (_, &[u8]) = if self.a_to_b {
+            (&self.vault_b, b"vault_b")
+        } else {
+            (&self.vault_a, b"vault_a")
+        };
+        let pool_key = self.pool.key();
+        let (_, vault_bump) =
+            Pubkey::find_program_address(&[vault_seed, pool_key.as_ref()], program_id);
+
+        transfer_from_vault(
+            vault,
+            to,
+            self.mint_out(),
+            amount,
+            vault_seed,
+            &pool_key,
+            vault_bump,
+            &self.token_program,
+        )
+    }
+}
+
+/// Swaps `amount_in` through a sequence of pools passed as remaining accounts, each hop's
+/// output feeding the next hop's vault directly. Every pool charges its own fee and only the
+/// final output is checked against `min_amount_out`, so the route fills in full or not at all.
+pub fn route_swap_handler<'info>(
+    ctx: Context<'_, '_, 'info, 'info, RouteSwap<'info>>,
+    amount_in: u64,
+    min_amount_out: u64,
+) -> Result<()> {
+    require_not_paused(&ctx.accounts.config, Subsystem::Swaps)?;
+    require!(amount_in > 0, DexError::InvalidAmount);
+    require!(
+        !ctx.remaining_accounts.is_empty()
+            && ctx.remaining_accounts.len() % ROUTE_HOP_ACCOUNTS == 0,
+        DexError::InvalidRoute
+    );
+
+    let mut hops: Vec<Hop> = Vec::new();
+    let mut mint = ctx.accounts.mint_in.key();
+    for accounts in ctx.remaining_accounts.chunks(ROUTE_HOP_ACCOUNTS) {
+        let hop = Hop::load(accounts, mint)?;
+        // Reserves are read once up front, so a pool may only appear once
+        require!(
+            hops.iter().all(|other| other.pool.key() != hop.pool.key()),
+            DexError::InvalidRoute
+        );
+        mint = hop.mint_out().key();
+        hops.push(hop);
+    }
+    require_keys_eq!(mint, ctx.accounts.mint_out.key(), DexError::InvalidRoute);
+
+    // Price every hop before moving anything; each one receives what the previous paid out
+    let mut quotes = Vec::with_capacity(hops.len());
+    let mut amount = amount_in;
+    for hop in &hops {
+        let quote = quote_swap(
+            &hop.pool,
+            &hop.vault_a,
+            &hop.vault_b,
+            hop.mint_in(),
+            amount,
+            hop.a_to_b,
+        )?;
+        amount = quote.amount_out;
+        quotes.push(quote);
+    }
+    require!(
+        amount - transfer_fee(&ctx.accounts.mint_out, amount)? >= min_amount_out,
+        ErrorCode::SlippageExceeded
+    );
+
+    let accounts = &ctx.accounts;
+    if is_native(&accounts.mint_in) {
+        wrap_sol(
+            &accounts.user,
+            &accounts.user_token_in,
+            amount_in,
+            &accounts.system_program,
+            &accounts.token_program,
+        )?;
+    }
+    transfer_tokens(
+        &accounts.user_token_in,
+        hops[0].vault_in(),
+        &amount_in,
+        &accounts.mint_in,
+        &accounts.user,
+        &accounts.token_program,
+    )?;
+    for (index, (hop, quote)) in hops.iter().zip(&quotes).enumerate() {
+        let to = match hops.get(index + 1) {
+            Some(next) => next.vault_in(),
+            None => &*accounts.user_token_out,
+        };
+        hop.pay_out(to, quote.amount_out, ctx.program_id)?;
+    }
+
+    if is_native(&accounts.mint_out) {
+        unwrap_sol(
+            &accounts.user,
+            &accounts.user_token_out,
+            &accounts.unwrap_account,
+            ctx.bumps.unwrap_account,
+            &accounts.mint_out,
+            amount,
+            &accounts.system_program,
+            &accounts.token_program_out,
+        )?;
+    }
+
+    let user = ctx.accounts.user.key();
+    for (hop, quote) in hops.iter_mut().zip(&quotes) {
+        record_swap(
+            &mut hop.pool,
+            &mut hop.observations,
+            user,
+            quote,
+            hop.a_to_b,
+        )?;
+        hop.pool.exit(ctx.program_id)?;
+        hop.observations.exit(ctx.program_id)?;
+    }
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct RouteSwap<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Box<Account<'info, GlobalConfig>>,
+
+    pub mint_in: Box<InterfaceAccount<'info, Mint>>,
+
+    pub mint_out: Box<InterfaceAccount<'info, Mint>>,
+
+    // Associated token accounts, created when missing. For native SOL the wSOL account stays
+    // open; only the SOL moved by the instruction is wrapped or unwrapped.
+    #[account(
+        init_if_needed,
+        payer = user,
+        associated_token::mint = mint_in,
+        associated_token::authority = user,
+        associated_token::token_program = token_program,
+    )]
+    pub user_token_in: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        init_if_needed,
+        payer = user,
+        associated_token::mint = mint_out,
+        associated_token::authority = user,
+        associated_token::token_program = token_program_out,
+    )]
+    pub user_token_out: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// CHECK: created and closed within the instruction to pay out native SOL, see `unwrap_sol`
+    #[account(mut, seeds = [b"unwrap", user.key().as_ref()], bump)]
+    pub unwrap_account: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+    // Program of `mint_in`; each hop passes its own and `mint_out` may use another
+    pub token_program: Interface<'info, TokenInterface>,
+    pub token_program_out: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
@@ -0,0 +1,136 @@
+use anchor_lang::prelude::*;
+
+use crate::error::DexError;
+use crate::state::oracle_state::{Observation, ObservationBuffer, TwapResult};
+use crate::state::pool_state::Pool;
+use crate::{OBSERVATION_CAPACITY, PRICE_SCALE};
+
+/// Reserve price of token A in token B, scaled by `PRICE_SCALE`. Zero while the pool is empty.
+/// Saturates at `u64::MAX` for pools too lopsided to price, so swaps out of them still work.
+pub fn market_price(reserve_a: u64, reserve_b: u64) -> u64 {
+    if reserve_a == 0 {
+        return 0;
+    }
+    // Cannot overflow: both factors fit in 64 bits
+    let price = reserve_b as u128 * PRICE_SCALE / reserve_a as u128;
+    price.min(u64::MAX as u128) as u64
+}
+
+/// Adds the prices that held since the last update, multiplied by the elapsed seconds.
+/// Must run before anything that changes the market or consensus price.
+pub fn accumulate_prices(pool: &mut Pool, now: i64) -> Result<()> {
+    let elapsed = now.saturating_sub(pool.last_cumulative_update);
+    if elapsed > 0 && pool.last_cumulative_update > 0 {
+        let elapsed = elapsed as u128;
+        pool.market_price_cumulative = pool
+            .market_price_cumulative
+            .wrapping_add((pool.last_market_price as u128) * elapsed);
+        pool.consensus_price_cumulative = pool
+            .consensus_price_cumulative
+            .wrapping_add((pool.consensus_price as u128) * elapsed);
+    }
+    pool.last_cumulative_update = now;
+    Ok(())
+}
+
+/// Stores the current accumulators in the ring buffer. At most one observation per second.
+pub fn write_observation(buffer: &mut ObservationBuffer, pool: &Pool) {
+    let now = pool.last_cumulative_update;
+    if buffer.len > 0 && buffer.observations[buffer.head as usize].timestamp == now {
+        return;
+    }
+
+    let next = if buffer.len == 0 {
+        0
+    } else {
+        (buffer.head as usize + 1) % OBSERVATION_CAPACITY
+    };
+    buffer.observations[next] = Observation {
+        timestamp: now,
+        market_price_cumulative: pool.market_price_cumulative,
+        consensus_price_cumulative: pool.consensus_price_cumulative,
+    };
+    buffer.head = next as u16;
+    buffer.len = (buffer.len + 1).min(OBSERVATION_CAPACITY as u16);
+}
+
+/// Time-weighted average market and consensus prices over the last `window` seconds,
+/// returned through return data.
+pub fn get_twap_handler(ctx: Context<GetTwap>, window: u32) -> Result<TwapResult> {
+    let pool = &ctx.accounts.pool;
+    let buffer = &ctx.accounts.observations;
+    let now = Clock::get()?.unix_timestamp;
+
+    // Accumulators as of now, without writing to the pool
+    let mut current = (**pool).clone();
+    accumulate_prices(&mut current, now)?;
+
+    if window == 0 {
+        return Ok(TwapResult {
+            market_price: current.last_market_price,
+            consensus_price: current.consensus_price,
+            window: 0,
+            timestamp: now,
+        });
+    }
+
+    let start = observation_before(buffer, now - window as i64)
+        .ok_or(DexError::InsufficientObservations)?;
+    let elapsed = now - start.timestamp;
+    require!(elapsed > 0, DexError::InsufficientObservations);
+
+    Ok(TwapResult {
+        market_price: average_price(
+            current.market_price_cumulative,
+            start.market_price_cumulative,
+            elapsed,
+        )?,
+        consensus_price: average_price(
+            current.consensus_price_cumulative,
+            start.consensus_price_cumulative,
+            elapsed,
+        )?,
+        window: elapsed,
+        timestamp: now,
+    })
+}
+
+/// Newest observation taken at or before `target`.
+pub fn observation_before(buffer: &ObservationBuffer, target: i64) -> Option<Observation> {
+    (0..buffer.len as usize)
+        .map(|i| {
+            let slot = (buffer.head as usize + OBSERVATION_CAPACITY - i) % OBSERVATION_CAPACITY;
+            buffer.observations[slot]
+        })
+        .find(|observation| observation.timestamp <= target)
+}
+
+/// Oldest observation still in the buffer.
+pub fn oldest_observation(buffer: &ObservationBuffer) -> Option<Observation> {
+    match buffer.len as usize {
+        0 => None,
+        OBSERVATION_CAPACITY => {
+            Some(buffer.observations[(buffer.head as usize + 1) % OBSERVATION_CAPACITY])
+        }
+        _ => Some(buffer.observations[0]),
+    }
+}
+
+/// Mean price over `elapsed` seconds from two readings of a wrapping accumulator.
+pub fn average_price(cumulative_now: u128, cumulative_then: u128, elapsed: i64) -> Result<u64> {
+    require!(elapsed > 0, DexError::InsufficientObservations);
+    let average = cumulative_now.wrapping_sub(cumulative_then) / elapsed as u128;
+    u64::try_from(average).map_err(|_| DexError::Overflow.into())
+}
+
+#[derive(Accounts)]
+pub struct GetTwap<'info> {
+    pub pool: Account<'info, Pool>,
+
+    #[account(
+        has_one = pool,
+        seeds = [b"observations", pool.key().as_ref()],
+        bump = observations.bump
+    )]
+    pub observations: Box<Account<'info, ObservationBuffer>>,
+}
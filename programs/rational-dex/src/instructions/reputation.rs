@@ -0,0 +1,142 @@
+/// Reputation is earned from conviction outcomes and decays over time. Authors earn it from the
+/// final score of their proposals, voters from how close their score was to the final result.
+use crate::events::VoterReputationSettled;
+use crate::instructions::conviction_voting::{conviction_weight, VotingError};
+use crate::state::conviction_state::{Proposal, Voter};
+use crate::state::reputation_state::Reputation;
+use crate::{
+    EVENT_VERSION, REPUTATION_DECAY_DENOMINATOR, REPUTATION_DECAY_NUMERATOR,
+    REPUTATION_DECAY_PERIOD, REPUTATION_MAX_DECAY_PERIODS, REPUTATION_MAX_WEIGHT,
+    REPUTATION_POINTS_PER_WEIGHT, REPUTATION_VOTER_POINTS,
+};
+use anchor_lang::prelude::*;
+
+pub fn settle_voter_reputation_handler(
+    ctx: Context<SettleVoterReputation>,
+    _proposal_count: u64,
+) -> Result<()> {
+    let final_score = ctx
+        .accounts
+        .proposal
+        .final_score
+        .ok_or(VotingError::NotFinalized)?;
+
+    let voter_account = &mut ctx.accounts.voter_account;
+    require!(voter_account.voted, VotingError::NotVoted);
+    require!(
+        !voter_account.reputation_settled,
+        VotingError::ReputationAlreadySettled
+    );
+    // A conviction-0 vote locks nothing, so it has nothing at stake to earn from
+    let weight = conviction_weight(voter_account.conviction)? as u64;
+    require!(weight > 0, VotingError::NoConviction);
+    voter_account.reputation_settled = true;
+
+    let reputation = &mut ctx.accounts.reputation;
+    if reputation.wallet == Pubkey::default() {
+        reputation.wallet = ctx.accounts.voter.key();
+        reputation.bump = ctx.bumps.reputation;
+    }
+
+    // 10 points of accuracy for an exact match, 0 for the opposite end of the scale, scaled by
+    // the conviction the voter locked tokens for
+    let accuracy = 10 - voter_account.score.abs_diff(final_score).min(10) as u64;
+    let points = accuracy
+        .checked_mul(REPUTATION_VOTER_POINTS)
+        .and_then(|points| points.checked_mul(weight))
+        .ok_or(VotingError::OverflowError)?;
+
+    let now = Clock::get()?.unix_timestamp;
+    credit_reputation(reputation, points, now)?;
+    reputation.votes_scored = reputation
+        .votes_scored
+        .checked_add(1)
+        .ok_or(VotingError::OverflowError)?;
+
+    emit!(VoterReputationSettled {
+        version: EVENT_VERSION,
+        proposal: ctx.accounts.proposal.key(),
+        voter: reputation.wallet,
+        points,
+        score: reputation.score,
+        timestamp: now,
+    });
+
+    Ok(())
+}
+
+/// Applies the pending decay and adds `points` to the reputation score.
+pub fn credit_reputation(reputation: &mut Reputation, points: u64, now: i64) -> Result<()> {
+    reputation.score = decayed_score(reputation, now)
+        .checked_add(points)
+        .ok_or(VotingError::OverflowError)?;
+    reputation.last_updated = now;
+    Ok(())
+}
+
+/// Score after applying `REPUTATION_DECAY_NUMERATOR / REPUTATION_DECAY_DENOMINATOR`
+/// once for every full decay period elapsed since the last update.
+pub fn decayed_score(reputation: &Reputation, now: i64) -> u64 {
+    let elapsed = now.saturating_sub(reputation.last_updated).max(0);
+    let periods = (elapsed / REPUTATION_DECAY_PERIOD).min(REPUTATION_MAX_DECAY_PERIODS);
+
+    let mut score = reputation.score;
+    for _ in 0..periods {
+        if score == 0 {
+            break;
+        }
+        score = score * REPUTATION_DECAY_NUMERATOR / REPUTATION_DECAY_DENOMINATOR;
+    }
+    score
+}
+
+/// Vote weight multiplier for price polls. Wallets without reputation vote with weight 1.
+pub fn reputation_weight(reputation: Option<&Reputation>, now: i64) -> u64 {
+    match reputation {
+        Some(reputation) => {
+            let bonus = decayed_score(reputation, now) / REPUTATION_POINTS_PER_WEIGHT;
+            (1 + bonus).min(REPUTATION_MAX_WEIGHT)
+        }
+        None => 1,
+    }
+}
+
+#[derive(Accounts)]
+#[instruction(proposal_count: u64)]
+pub struct SettleVoterReputation<'info> {
+    #[account(mut)]
+    pub voter: Signer<'info>,
+
+    /// CHECK: only used to derive the proposal PDA
+    pub author: UncheckedAccount<'info>,
+
+    #[account(
+        has_one = author,
+        seeds = [
+            b"proposal",
+            author.key().as_ref(),
+            proposal_count.to_string().as_bytes()
+        ],
+        bump
+    )]
+    pub proposal: Account<'info, Proposal>,
+
+    // Keyed by the proposal, so a vote only settles against the proposal it was cast on
+    #[account(
+        mut,
+        seeds = [b"voter", proposal.key().as_ref(), voter.key().as_ref()],
+        bump
+    )]
+    pub voter_account: Account<'info, Voter>,
+
+    #[account(
+        init_if_needed,
+        payer = voter,
+        space = 8 + Reputation::INIT_SPACE,
+        seeds = [b"reputation", voter.key().as_ref()],
+        bump
+    )]
+    pub reputation: Account<'info, Reputation>,
+
+    pub system_program: Program<'info, System>,
+}
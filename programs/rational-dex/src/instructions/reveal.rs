@@ -1,5 +1,9 @@
 use crate::state::poll_state::PollAccount;
-use crate::{constants::COMP_DEF_OFFSET_REVEAL, error::ErrorCode, SignerAccount, ID, ID_CONST};
+use crate::{
+    constants::COMP_DEF_OFFSET_REVEAL, error::ErrorCode, SignerAccount, BPS_DENOMINATOR,
+    EVENT_VERSION, ID, ID_CONST, POLL_VOTE_STEP_BPS,
+};
+use arcium_client::idl::arcium::types::CallbackAccount;
 
 use anchor_lang::prelude::*;
 use arcium_anchor::prelude::*;
@@ -12,6 +16,9 @@ pub fn init_reveal_result_comp_def_handler(ctx: Context<InitRevealResultCompDef>
 /// Reveals the final result of the poll.
 /// # Arguments
 /// * `id` - The poll ID to reveal results for
+///
+/// Not gated by the polls pause: a reveal only publishes votes already cast. Pools take the
+/// result through `apply_poll_price`, which is gated.
 pub fn reveal_result_handler(
     ctx: Context<RevealVotingResult>,
     computation_offset: u64,
@@ -41,7 +48,10 @@ pub fn reveal_result_handler(
         computation_offset,
         args,
         None,
-        vec![RevealResultCallback::callback_ix(&[])],
+        vec![RevealResultCallback::callback_ix(&[CallbackAccount {
+            pubkey: ctx.accounts.poll_acc.key(),
+            is_writable: true,
+        }])],
     )?;
     Ok(())
 }
@@ -55,11 +65,45 @@ pub fn reveal_result_callback_handler(
         _ => return Err(ErrorCode::AbortedComputation.into()),
     };
 
-    emit!(RevealResultEvent { output: o });
+    // Pools of the poll's pair pick the price up through `apply_poll_price`
+    let poll_acc = &mut ctx.accounts.poll_acc;
+    if let Some(consensus_price) = consensus_price(poll_acc.price, &o) {
+        poll_acc.consensus_price = consensus_price;
+        poll_acc.revealed_at = Clock::get()?.unix_timestamp;
+    }
+
+    emit!(RevealResultEvent {
+        version: EVENT_VERSION,
+        poll: poll_acc.key(),
+        poll_id: poll_acc.id,
+        output: o,
+        consensus_price: poll_acc.consensus_price,
+        revealed_at: poll_acc.revealed_at,
+    });
 
     Ok(())
 }
 
+/// `price` moved by `POLL_VOTE_STEP_BPS` per step of the weighted mean vote.
+/// `None` if nobody has voted.
+pub fn consensus_price(price: u64, counts: &[u64; 7]) -> Option<u64> {
+    let total: u128 = counts.iter().map(|&count| count as u128).sum();
+    if total == 0 {
+        return None;
+    }
+    // Bucket i holds votes for i - 3
+    let steps: i128 = counts
+        .iter()
+        .enumerate()
+        .map(|(i, &count)| (i as i128 - 3) * count as i128)
+        .sum();
+
+    let scale = total as i128 * BPS_DENOMINATOR as i128;
+    let adjusted = scale + steps * POLL_VOTE_STEP_BPS as i128;
+    let consensus = price as i128 * adjusted / scale;
+    u64::try_from(consensus).ok()
+}
+
 #[queue_computation_accounts("reveal_result", payer)]
 #[derive(Accounts)]
 #[instruction(computation_offset: u64, id: u32)]
@@ -135,6 +179,8 @@ pub struct RevealResultCallback<'info> {
     #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
     /// CHECK: instructions_sysvar, checked by the account constraint
     pub instructions_sysvar: AccountInfo<'info>,
+    #[account(mut)]
+    pub poll_acc: Account<'info, PollAccount>,
 }
 
 #[init_computation_definition_accounts("reveal_result", payer)]
@@ -157,5 +203,11 @@ pub struct InitRevealResultCompDef<'info> {
 
 #[event]
 pub struct RevealResultEvent {
+    pub version: u8,
+    pub poll: Pubkey,
+    pub poll_id: u32,
     pub output: [u64; 7],
+    /// Consensus price of the poll after this reveal; unchanged if nobody voted
+    pub consensus_price: u64,
+    pub revealed_at: i64,
 }
@@ -0,0 +1,85 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use super::{accrue_rewards, settle_stake};
+use crate::error::DexError;
+use crate::events::RewardsClaimed;
+use crate::instructions::pool::transfer_from_vault;
+use crate::state::reward_state::{RewardProgram, RewardStake};
+use crate::EVENT_VERSION;
+
+/// Pays out everything the owner's stake has earned so far.
+pub fn claim_rewards_handler(ctx: Context<ClaimRewards>) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let program = &mut ctx.accounts.reward_program;
+    let stake = &mut ctx.accounts.reward_stake;
+    accrue_rewards(program, now)?;
+    settle_stake(stake, program, now)?;
+
+    let amount = stake.rewards_owed;
+    require!(amount > 0, DexError::NoRewards);
+    stake.rewards_owed = 0;
+
+    let accounts = &ctx.accounts;
+    transfer_from_vault(
+        &accounts.reward_vault,
+        &accounts.owner_reward_account,
+        &accounts.reward_mint,
+        amount,
+        b"reward_vault",
+        &accounts.reward_program.key(),
+        ctx.bumps.reward_vault,
+        &accounts.token_program,
+    )?;
+
+    emit!(RewardsClaimed {
+        version: EVENT_VERSION,
+        reward_program: accounts.reward_program.key(),
+        owner: accounts.owner.key(),
+        amount,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ClaimRewards<'info> {
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"reward_program",
+            reward_program.pool.as_ref(),
+            reward_program.reward_mint.as_ref()
+        ],
+        bump = reward_program.bump,
+        has_one = reward_vault,
+        has_one = reward_mint,
+    )]
+    pub reward_program: Box<Account<'info, RewardProgram>>,
+
+    #[account(
+        mut,
+        has_one = reward_program,
+        has_one = owner,
+        seeds = [b"reward_stake", reward_program.key().as_ref(), owner.key().as_ref()],
+        bump = reward_stake.bump
+    )]
+    pub reward_stake: Box<Account<'info, RewardStake>>,
+
+    #[account(mut, seeds = [b"reward_vault", reward_program.key().as_ref()], bump)]
+    pub reward_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub reward_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        token::mint = reward_mint,
+        token::authority = owner,
+        token::token_program = token_program,
+    )]
+    pub owner_reward_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
@@ -0,0 +1,100 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::error::{ConfigError, DexError};
+use crate::state::config_state::GlobalConfig;
+use crate::state::pool_state::Pool;
+use crate::state::reward_state::RewardProgram;
+use crate::{ANCHOR_DISCRIMINATOR, BPS_DENOMINATOR};
+
+/// Creates a reward program for `pool` paying out `reward_mint`. `lp_share_bps` of the stream
+/// goes to staked LP tokens, the rest to registered conviction votes. Nothing streams until
+/// the first `fund_rewards`.
+pub fn create_reward_program_handler(
+    ctx: Context<CreateRewardProgram>,
+    lp_share_bps: u16,
+) -> Result<()> {
+    require!(
+        lp_share_bps as u64 <= BPS_DENOMINATOR,
+        DexError::InvalidAmount
+    );
+
+    ctx.accounts.reward_program.set_inner(RewardProgram {
+        pool: ctx.accounts.pool.key(),
+        reward_mint: ctx.accounts.reward_mint.key(),
+        reward_vault: ctx.accounts.reward_vault.key(),
+        lp_vault: ctx.accounts.lp_vault.key(),
+        lp_share_bps,
+        reward_rate: 0,
+        period_end: 0,
+        last_update: Clock::get()?.unix_timestamp,
+        lp_total_weight: 0,
+        vote_total_weight: 0,
+        reward_per_lp_x64: 0,
+        reward_per_vote_x64: 0,
+        bump: ctx.bumps.reward_program,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct CreateRewardProgram<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = config.admin == authority.key() @ ConfigError::NotAdmin
+    )]
+    pub config: Box<Account<'info, GlobalConfig>>,
+
+    #[account(
+        seeds = [b"pool", pool.mint_a.as_ref(), pool.mint_b.as_ref()],
+        bump = pool.bump,
+        has_one = mint_lp,
+    )]
+    pub pool: Box<Account<'info, Pool>>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = ANCHOR_DISCRIMINATOR + RewardProgram::INIT_SPACE,
+        seeds = [b"reward_program", pool.key().as_ref(), reward_mint.key().as_ref()],
+        bump
+    )]
+    pub reward_program: Box<Account<'info, RewardProgram>>,
+
+    #[account(
+        init,
+        payer = authority,
+        token::mint = reward_mint,
+        token::authority = reward_vault,
+        token::token_program = reward_token_program,
+        seeds = [b"reward_vault", reward_program.key().as_ref()],
+        bump
+    )]
+    pub reward_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        init,
+        payer = authority,
+        token::mint = mint_lp,
+        token::authority = lp_vault,
+        token::token_program = token_program,
+        seeds = [b"reward_lp_vault", reward_program.key().as_ref()],
+        bump
+    )]
+    pub lp_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(mint::token_program = reward_token_program)]
+    pub reward_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(mint::token_program = token_program)]
+    pub mint_lp: Box<InterfaceAccount<'info, Mint>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub reward_token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
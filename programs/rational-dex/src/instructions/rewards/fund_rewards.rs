@@ -0,0 +1,92 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use super::accrue_rewards;
+use crate::error::{ConfigError, DexError};
+use crate::events::RewardsFunded;
+use crate::instructions::swap::transfer_tokens;
+use crate::state::config_state::GlobalConfig;
+use crate::state::reward_state::RewardProgram;
+use crate::EVENT_VERSION;
+
+/// Adds `amount` reward tokens and streams them, together with whatever is left of the current
+/// period, evenly over the next `duration` seconds.
+pub fn fund_rewards_handler(ctx: Context<FundRewards>, amount: u64, duration: i64) -> Result<()> {
+    require!(amount > 0 && duration > 0, DexError::InvalidAmount);
+
+    // Stream only what actually reaches the vault after any Token-2022 transfer fee
+    let received = transfer_tokens(
+        &ctx.accounts.authority_token_account,
+        &ctx.accounts.reward_vault,
+        &amount,
+        &ctx.accounts.reward_mint,
+        &ctx.accounts.authority,
+        &ctx.accounts.token_program,
+    )?;
+
+    let now = Clock::get()?.unix_timestamp;
+    let program = &mut ctx.accounts.reward_program;
+    accrue_rewards(program, now)?;
+
+    let leftover = if now < program.period_end {
+        (program.reward_rate as u128) * (program.period_end - now) as u128
+    } else {
+        0
+    };
+    let reward_rate = (received as u128 + leftover) / duration as u128;
+    require!(reward_rate > 0, DexError::InvalidAmount);
+
+    program.reward_rate = u64::try_from(reward_rate).map_err(|_| DexError::Overflow)?;
+    program.period_end = now.checked_add(duration).ok_or(DexError::Overflow)?;
+    program.last_update = now;
+
+    emit!(RewardsFunded {
+        version: EVENT_VERSION,
+        reward_program: program.key(),
+        amount: received,
+        reward_rate: program.reward_rate,
+        period_end: program.period_end,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct FundRewards<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = config.admin == authority.key() @ ConfigError::NotAdmin
+    )]
+    pub config: Box<Account<'info, GlobalConfig>>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"reward_program",
+            reward_program.pool.as_ref(),
+            reward_program.reward_mint.as_ref()
+        ],
+        bump = reward_program.bump,
+        has_one = reward_vault,
+        has_one = reward_mint,
+    )]
+    pub reward_program: Box<Account<'info, RewardProgram>>,
+
+    #[account(mut)]
+    pub reward_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub reward_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        token::mint = reward_mint,
+        token::authority = authority,
+        token::token_program = token_program,
+    )]
+    pub authority_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
@@ -0,0 +1,13 @@
+pub mod claim_rewards;
+pub mod create_reward_program;
+pub mod fund_rewards;
+pub mod reward_math;
+pub mod stake_lp;
+pub mod sync_vote_weight;
+
+pub use claim_rewards::*;
+pub use create_reward_program::*;
+pub use fund_rewards::*;
+pub use reward_math::*;
+pub use stake_lp::*;
+pub use sync_vote_weight::*;
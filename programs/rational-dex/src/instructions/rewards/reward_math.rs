@@ -0,0 +1,115 @@
+use anchor_lang::prelude::*;
+
+use crate::error::DexError;
+use crate::instructions::pool::mul_div_floor;
+use crate::state::reward_state::{RewardProgram, RewardStake};
+use crate::BPS_DENOMINATOR;
+
+/// Streams the rewards since the last update to the current weights. A side with no weight
+/// earns nothing; its share stays in the vault.
+pub fn accrue_rewards(program: &mut RewardProgram, now: i64) -> Result<()> {
+    let until = now.min(program.period_end);
+    if until > program.last_update {
+        let emitted = (program.reward_rate as u128)
+            .checked_mul((until - program.last_update) as u128)
+            .ok_or(DexError::Overflow)?;
+        let lp_part = emitted * program.lp_share_bps as u128 / BPS_DENOMINATOR as u128;
+        let vote_part = emitted - lp_part;
+
+        let per_weight = |part: u128, total_weight: u64| {
+            part.checked_mul(1 << 64)
+                .map(|scaled| scaled / total_weight as u128)
+                .ok_or(DexError::Overflow)
+        };
+        if program.lp_total_weight > 0 {
+            program.reward_per_lp_x64 = program
+                .reward_per_lp_x64
+                .wrapping_add(per_weight(lp_part, program.lp_total_weight)?);
+        }
+        if program.vote_total_weight > 0 {
+            program.reward_per_vote_x64 = program
+                .reward_per_vote_x64
+                .wrapping_add(per_weight(vote_part, program.vote_total_weight)?);
+        }
+    }
+    program.last_update = program.last_update.max(now);
+    Ok(())
+}
+
+/// Credits the stake with what its weights earned since its last checkpoint. Must run after
+/// `accrue_rewards` and before either weight changes. A vote that expired since then only
+/// earns for the part of the interval before its expiry, and its weight is removed.
+pub fn settle_stake(stake: &mut RewardStake, program: &mut RewardProgram, now: i64) -> Result<()> {
+    let earned = |reward_per_weight: u128, checkpoint: u128, weight: u64| {
+        let amount = reward_per_weight
+            .wrapping_sub(checkpoint)
+            .checked_mul(weight as u128)
+            .ok_or(DexError::Overflow)?
+            >> 64;
+        u64::try_from(amount).map_err(|_| DexError::Overflow)
+    };
+    let lp_earned = earned(
+        program.reward_per_lp_x64,
+        stake.reward_per_lp_checkpoint_x64,
+        stake.lp_staked,
+    )?;
+    let mut vote_earned = earned(
+        program.reward_per_vote_x64,
+        stake.reward_per_vote_checkpoint_x64,
+        stake.vote_weight,
+    )?;
+    if stake.vote_weight > 0 && now > stake.vote_expires_at {
+        vote_earned =
+            vote_earned_before_expiry(vote_earned, stake.settled_at, stake.vote_expires_at, now)?;
+        program.vote_total_weight -= stake.vote_weight;
+        stake.vote_weight = 0;
+    }
+
+    stake.rewards_owed = stake
+        .rewards_owed
+        .checked_add(lp_earned)
+        .and_then(|owed| owed.checked_add(vote_earned))
+        .ok_or(DexError::Overflow)?;
+    stake.reward_per_lp_checkpoint_x64 = program.reward_per_lp_x64;
+    stake.reward_per_vote_checkpoint_x64 = program.reward_per_vote_x64;
+    stake.settled_at = now;
+    Ok(())
+}
+
+/// Share of `earned`, streamed evenly from `settled_at` to `now`, that falls before
+/// `expires_at`.
+pub fn vote_earned_before_expiry(
+    earned: u64,
+    settled_at: i64,
+    expires_at: i64,
+    now: i64,
+) -> Result<u64> {
+    if expires_at <= settled_at {
+        return Ok(0);
+    }
+    if now <= expires_at {
+        return Ok(earned);
+    }
+    mul_div_floor(
+        earned,
+        (expires_at - settled_at) as u64,
+        (now - settled_at) as u64,
+    )
+}
+
+/// Sets up a stake created by `init_if_needed`.
+pub fn open_reward_stake(
+    stake: &mut RewardStake,
+    program: &Account<RewardProgram>,
+    owner: Pubkey,
+    bump: u8,
+) {
+    if stake.owner != Pubkey::default() {
+        return;
+    }
+    stake.reward_program = program.key();
+    stake.owner = owner;
+    stake.reward_per_lp_checkpoint_x64 = program.reward_per_lp_x64;
+    stake.reward_per_vote_checkpoint_x64 = program.reward_per_vote_x64;
+    stake.bump = bump;
+}
@@ -0,0 +1,154 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use super::{accrue_rewards, open_reward_stake, settle_stake};
+use crate::error::DexError;
+use crate::events::LpStakeChanged;
+use crate::instructions::pool::transfer_from_vault;
+use crate::instructions::swap::transfer_tokens;
+use crate::state::pool_state::{LpPosition, Pool};
+use crate::state::reward_state::{RewardProgram, RewardStake};
+use crate::{ANCHOR_DISCRIMINATOR, EVENT_VERSION};
+
+/// Stakes `amount` LP tokens of the program's pool. Only owners of an `LpPosition` in that
+/// pool can stake.
+pub fn stake_lp_handler(ctx: Context<StakeLp>, amount: u64) -> Result<()> {
+    require!(amount > 0, DexError::InvalidAmount);
+
+    let received = transfer_tokens(
+        &ctx.accounts.owner_token_lp,
+        &ctx.accounts.lp_vault,
+        &amount,
+        &ctx.accounts.mint_lp,
+        &ctx.accounts.owner,
+        &ctx.accounts.token_program,
+    )?;
+
+    let now = Clock::get()?.unix_timestamp;
+    let program = &mut ctx.accounts.reward_program;
+    let stake = &mut ctx.accounts.reward_stake;
+    accrue_rewards(program, now)?;
+    open_reward_stake(
+        stake,
+        program,
+        ctx.accounts.owner.key(),
+        ctx.bumps.reward_stake,
+    );
+    settle_stake(stake, program, now)?;
+
+    stake.lp_staked = stake
+        .lp_staked
+        .checked_add(received)
+        .ok_or(DexError::Overflow)?;
+    program.lp_total_weight = program
+        .lp_total_weight
+        .checked_add(received)
+        .ok_or(DexError::Overflow)?;
+
+    emit!(LpStakeChanged {
+        version: EVENT_VERSION,
+        reward_program: program.key(),
+        owner: stake.owner,
+        stake: true,
+        amount: received,
+        lp_staked: stake.lp_staked,
+        lp_total_weight: program.lp_total_weight,
+    });
+
+    Ok(())
+}
+
+/// Returns `amount` staked LP tokens to the owner. Rewards earned so far stay claimable.
+pub fn unstake_lp_handler(ctx: Context<StakeLp>, amount: u64) -> Result<()> {
+    require!(amount > 0, DexError::InvalidAmount);
+    require!(
+        amount <= ctx.accounts.reward_stake.lp_staked,
+        DexError::InsufficientStake
+    );
+
+    let now = Clock::get()?.unix_timestamp;
+    let program = &mut ctx.accounts.reward_program;
+    let stake = &mut ctx.accounts.reward_stake;
+    accrue_rewards(program, now)?;
+    settle_stake(stake, program, now)?;
+
+    stake.lp_staked -= amount;
+    program.lp_total_weight -= amount;
+
+    emit!(LpStakeChanged {
+        version: EVENT_VERSION,
+        reward_program: program.key(),
+        owner: stake.owner,
+        stake: false,
+        amount,
+        lp_staked: stake.lp_staked,
+        lp_total_weight: program.lp_total_weight,
+    });
+
+    let accounts = &ctx.accounts;
+    transfer_from_vault(
+        &accounts.lp_vault,
+        &accounts.owner_token_lp,
+        &accounts.mint_lp,
+        amount,
+        b"reward_lp_vault",
+        &accounts.reward_program.key(),
+        ctx.bumps.lp_vault,
+        &accounts.token_program,
+    )
+}
+
+#[derive(Accounts)]
+pub struct StakeLp<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"reward_program",
+            reward_program.pool.as_ref(),
+            reward_program.reward_mint.as_ref()
+        ],
+        bump = reward_program.bump,
+        has_one = pool,
+        has_one = lp_vault,
+    )]
+    pub reward_program: Box<Account<'info, RewardProgram>>,
+
+    #[account(has_one = mint_lp)]
+    pub pool: Box<Account<'info, Pool>>,
+
+    #[account(
+        has_one = pool,
+        has_one = owner,
+        seeds = [b"lp_position", pool.key().as_ref(), owner.key().as_ref()],
+        bump = lp_position.bump
+    )]
+    pub lp_position: Box<Account<'info, LpPosition>>,
+
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = ANCHOR_DISCRIMINATOR + RewardStake::INIT_SPACE,
+        seeds = [b"reward_stake", reward_program.key().as_ref(), owner.key().as_ref()],
+        bump
+    )]
+    pub reward_stake: Box<Account<'info, RewardStake>>,
+
+    #[account(mut, seeds = [b"reward_lp_vault", reward_program.key().as_ref()], bump)]
+    pub lp_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub mint_lp: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        token::mint = mint_lp,
+        token::authority = owner,
+        token::token_program = token_program,
+    )]
+    pub owner_token_lp: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
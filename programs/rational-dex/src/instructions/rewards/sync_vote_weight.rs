@@ -0,0 +1,95 @@
+use anchor_lang::prelude::*;
+
+use super::{accrue_rewards, open_reward_stake, settle_stake};
+use crate::error::DexError;
+use crate::events::VoteWeightSynced;
+use crate::instructions::conviction_voting::conviction_weight;
+use crate::state::conviction_state::Voter;
+use crate::state::reward_state::{RewardProgram, RewardStake};
+use crate::{ANCHOR_DISCRIMINATOR, EVENT_VERSION};
+
+/// Sets the vote weight of `voter`'s stake to the conviction of their vote on `proposal`, or to
+/// zero once that vote is retracted or its lock has ended. The voter can register any of their
+/// votes; anyone else can only refresh the one already registered, so expired weights can be
+/// removed without the voter's help.
+pub fn sync_vote_weight_handler(ctx: Context<SyncVoteWeight>) -> Result<()> {
+    let voter = ctx.accounts.voter.key();
+    let proposal = ctx.accounts.proposal.key();
+    let stake = &mut ctx.accounts.reward_stake;
+    if ctx.accounts.payer.key() != voter {
+        require!(
+            stake.owner == voter && stake.vote_proposal == proposal,
+            DexError::InvalidRewardStake
+        );
+    }
+
+    let now = Clock::get()?.unix_timestamp;
+    let program = &mut ctx.accounts.reward_program;
+    accrue_rewards(program, now)?;
+    open_reward_stake(stake, program, voter, ctx.bumps.reward_stake);
+    settle_stake(stake, program, now)?;
+
+    let voter_account = &ctx.accounts.voter_account;
+    let weight = if voter_account.voted && now < voter_account.unlock_time {
+        conviction_weight(voter_account.conviction)? as u64
+    } else {
+        0
+    };
+    program.vote_total_weight = (program.vote_total_weight - stake.vote_weight)
+        .checked_add(weight)
+        .ok_or(DexError::Overflow)?;
+    stake.vote_proposal = proposal;
+    stake.vote_weight = weight;
+    stake.vote_expires_at = voter_account.unlock_time;
+
+    emit!(VoteWeightSynced {
+        version: EVENT_VERSION,
+        reward_program: program.key(),
+        voter,
+        proposal,
+        vote_weight: weight,
+        vote_total_weight: program.vote_total_weight,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SyncVoteWeight<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// CHECK: only used to derive the stake and voter PDAs
+    pub voter: UncheckedAccount<'info>,
+
+    /// CHECK: only used to derive the voter PDA, which only exists for real proposals
+    pub proposal: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"reward_program",
+            reward_program.pool.as_ref(),
+            reward_program.reward_mint.as_ref()
+        ],
+        bump = reward_program.bump,
+    )]
+    pub reward_program: Box<Account<'info, RewardProgram>>,
+
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = ANCHOR_DISCRIMINATOR + RewardStake::INIT_SPACE,
+        seeds = [b"reward_stake", reward_program.key().as_ref(), voter.key().as_ref()],
+        bump
+    )]
+    pub reward_stake: Box<Account<'info, RewardStake>>,
+
+    #[account(
+        seeds = [b"voter", proposal.key().as_ref(), voter.key().as_ref()],
+        bump
+    )]
+    pub voter_account: Box<Account<'info, Voter>>,
+
+    pub system_program: Program<'info, System>,
+}
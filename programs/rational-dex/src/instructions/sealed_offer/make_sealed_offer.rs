@@ -0,0 +1,236 @@
+use crate::error::{DexError, ErrorCode};
+use crate::events::SealedOfferCreated;
+use crate::instructions::config::require_not_paused;
+use crate::instructions::swap::transfer_tokens;
+use crate::state::config_state::{GlobalConfig, Subsystem};
+use crate::state::sealed_offer_state::{SealedOffer, SealedOfferStatus};
+use crate::{
+    constants::COMP_DEF_OFFSET_INIT_SEALED_ASK, SignerAccount, ANCHOR_DISCRIMINATOR, EVENT_VERSION,
+    ID, ID_CONST,
+};
+use arcium_client::idl::arcium::types::CallbackAccount;
+
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{Mint, TokenAccount, TokenInterface},
+};
+use arcium_anchor::prelude::*;
+
+pub fn init_sealed_ask_comp_def_handler(ctx: Context<InitSealedAskCompDef>) -> Result<()> {
+    init_comp_def(ctx.accounts, true, 0, None, None)?;
+    Ok(())
+}
+
+/// Escrows `offered_amount` token A and queues the maker's encrypted ask.
+/// The offer opens for bids once MPC has re-encrypted the ask for the MXE.
+#[allow(clippy::too_many_arguments)]
+pub fn make_sealed_offer_handler(
+    ctx: Context<MakeSealedOffer>,
+    computation_offset: u64,
+    id: u64,
+    offered_amount: u64,
+    ask: [u8; 32],
+    ask_encryption_pubkey: [u8; 32],
+    ask_nonce: u128,
+    mxe_nonce: u128,
+) -> Result<()> {
+    require_not_paused(&ctx.accounts.config, Subsystem::Offers)?;
+    require!(offered_amount > 0, DexError::InvalidAmount);
+
+    // The offer is for what reaches the vault after any transfer fee
+    let offered_amount = transfer_tokens(
+        &ctx.accounts.maker_token_account_a,
+        &ctx.accounts.vault,
+        &offered_amount,
+        &ctx.accounts.token_mint_a,
+        &ctx.accounts.payer,
+        &ctx.accounts.token_program,
+    )?;
+
+    ctx.accounts.sealed_offer.set_inner(SealedOffer {
+        bump: ctx.bumps.sealed_offer,
+        ask_state: [[0; 32]; 1],
+        nonce: 0,
+        id,
+        maker: ctx.accounts.payer.key(),
+        token_mint_a: ctx.accounts.token_mint_a.key(),
+        token_mint_b: ctx.accounts.token_mint_b.key(),
+        offered_amount,
+        status: SealedOfferStatus::Pending,
+    });
+
+    let args = vec![
+        Argument::ArcisPubkey(ask_encryption_pubkey),
+        Argument::PlaintextU128(ask_nonce),
+        Argument::EncryptedU64(ask),
+        Argument::PlaintextU128(mxe_nonce),
+    ];
+
+    ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+
+    queue_computation(
+        ctx.accounts,
+        computation_offset,
+        args,
+        None,
+        vec![InitSealedAskCallback::callback_ix(&[CallbackAccount {
+            pubkey: ctx.accounts.sealed_offer.key(),
+            is_writable: true,
+        }])],
+    )?;
+
+    emit!(SealedOfferCreated {
+        version: EVENT_VERSION,
+        offer: ctx.accounts.sealed_offer.key(),
+        maker: ctx.accounts.payer.key(),
+        token_mint_a: ctx.accounts.token_mint_a.key(),
+        token_mint_b: ctx.accounts.token_mint_b.key(),
+        offered_amount,
+    });
+
+    Ok(())
+}
+
+pub fn init_sealed_ask_callback_handler(
+    ctx: Context<InitSealedAskCallback>,
+    output: ComputationOutputs<InitSealedAskOutput>,
+) -> Result<()> {
+    let o = match output {
+        ComputationOutputs::Success(InitSealedAskOutput { field_0 }) => field_0,
+        _ => return Err(ErrorCode::AbortedComputation.into()),
+    };
+
+    let sealed_offer = &mut ctx.accounts.sealed_offer;
+    sealed_offer.ask_state = o.ciphertexts;
+    sealed_offer.nonce = o.nonce;
+    // The maker may have cancelled while the computation was queued
+    if sealed_offer.status == SealedOfferStatus::Pending {
+        sealed_offer.status = SealedOfferStatus::Open;
+    }
+
+    Ok(())
+}
+
+#[init_computation_definition_accounts("init_sealed_ask", payer)]
+#[derive(Accounts)]
+pub struct InitSealedAskCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        mut,
+        address = derive_mxe_pda!()
+    )]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    /// CHECK: comp_def_account, checked by arcium program.
+    /// Can't check it here as it's not initialized yet.
+    pub comp_def_account: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
+
+#[queue_computation_accounts("init_sealed_ask", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64, id: u64)]
+pub struct MakeSealedOffer<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        init_if_needed,
+        space = 9,
+        payer = payer,
+        seeds = [&SIGN_PDA_SEED],
+        bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, SignerAccount>,
+    #[account(
+        address = derive_mxe_pda!()
+    )]
+    pub mxe_account: Account<'info, MXEAccount>,
+    #[account(
+        mut,
+        address = derive_mempool_pda!()
+    )]
+    /// CHECK: mempool_account, checked by the arcium program
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        address = derive_execpool_pda!()
+    )]
+    /// CHECK: executing_pool, checked by the arcium program
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        address = derive_comp_pda!(computation_offset)
+    )]
+    /// CHECK: computation_account, checked by the arcium program.
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(
+        address = derive_comp_def_pda!(COMP_DEF_OFFSET_INIT_SEALED_ASK)
+    )]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(
+        mut,
+        address = derive_cluster_pda!(mxe_account)
+    )]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(
+        mut,
+        address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS,
+    )]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(
+        address = ARCIUM_CLOCK_ACCOUNT_ADDRESS,
+    )]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Box<Account<'info, GlobalConfig>>,
+    #[account(mint::token_program = token_program)]
+    pub token_mint_a: Box<InterfaceAccount<'info, Mint>>,
+    #[account(mint::token_program = token_program)]
+    pub token_mint_b: Box<InterfaceAccount<'info, Mint>>,
+    #[account(
+        mut,
+        associated_token::mint = token_mint_a,
+        associated_token::authority = payer,
+        associated_token::token_program = token_program
+    )]
+    pub maker_token_account_a: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(
+        init,
+        payer = payer,
+        space = ANCHOR_DISCRIMINATOR + SealedOffer::INIT_SPACE,
+        seeds = [b"sealed_offer", payer.key().as_ref(), id.to_le_bytes().as_ref()],
+        bump,
+    )]
+    pub sealed_offer: Box<Account<'info, SealedOffer>>,
+    #[account(
+        init,
+        payer = payer,
+        associated_token::mint = token_mint_a,
+        associated_token::authority = sealed_offer,
+        associated_token::token_program = token_program
+    )]
+    pub vault: Box<InterfaceAccount<'info, TokenAccount>>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
+
+#[callback_accounts("init_sealed_ask")]
+#[derive(Accounts)]
+pub struct InitSealedAskCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(
+        address = derive_comp_def_pda!(COMP_DEF_OFFSET_INIT_SEALED_ASK)
+    )]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    /// CHECK: instructions_sysvar, checked by the account constraint
+    pub instructions_sysvar: AccountInfo<'info>,
+    #[account(mut)]
+    pub sealed_offer: Account<'info, SealedOffer>,
+}
@@ -0,0 +1,7 @@
+pub mod make_sealed_offer;
+pub mod settle_sealed_bid;
+pub mod submit_sealed_bid;
+
+pub use make_sealed_offer::*;
+pub use settle_sealed_bid::*;
+pub use submit_sealed_bid::*;
@@ -0,0 +1,304 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{
+        close_account, transfer_checked, CloseAccount, Mint, TokenAccount, TokenInterface,
+        TransferChecked,
+    },
+};
+
+use crate::error::DexError;
+use crate::state::sealed_offer_state::{
+    SealedBid, SealedBidStatus, SealedOffer, SealedOfferStatus,
+};
+
+/// Permissionless. A matched bid pays the settlement amount to the maker, receives the
+/// escrowed token A and gets the rest of its deposit back. Any other resolved bid is refunded.
+pub fn settle_sealed_bid_handler(ctx: Context<SettleSealedBid>) -> Result<()> {
+    let accounts = &ctx.accounts;
+    let sealed_offer = &accounts.sealed_offer;
+    let sealed_bid = &accounts.sealed_bid;
+
+    // A bid still waiting on MPC can only be refunded once the offer can no longer match it
+    require!(
+        sealed_bid.status != SealedBidStatus::Pending
+            || sealed_offer.status != SealedOfferStatus::Open,
+        DexError::BidPending
+    );
+
+    let offer_key = sealed_offer.key();
+    let bid_seeds = &[
+        b"sealed_bid",
+        offer_key.as_ref(),
+        sealed_bid.taker.as_ref(),
+        &[sealed_bid.bump],
+    ];
+    let id_bytes = sealed_offer.id.to_le_bytes();
+    let offer_seeds = &[
+        b"sealed_offer",
+        sealed_offer.maker.as_ref(),
+        &id_bytes[..],
+        &[sealed_offer.bump],
+    ];
+
+    let settlement_amount = if sealed_bid.status == SealedBidStatus::Matched {
+        sealed_bid.settlement_amount
+    } else {
+        0
+    };
+    let refund = sealed_bid
+        .deposit
+        .checked_sub(settlement_amount)
+        .ok_or(DexError::Overflow)?;
+
+    if settlement_amount > 0 {
+        let (Some(vault), Some(taker_token_account_a)) =
+            (&accounts.vault, &accounts.taker_token_account_a)
+        else {
+            return Err(anchor_lang::error::ErrorCode::AccountNotEnoughKeys.into());
+        };
+        transfer_signed(
+            &accounts.bid_vault,
+            &accounts.maker_token_account_b,
+            &accounts.token_mint_b,
+            sealed_bid.to_account_info(),
+            settlement_amount,
+            &[&bid_seeds[..]],
+            &accounts.token_program,
+        )?;
+        transfer_signed(
+            vault,
+            taker_token_account_a,
+            &accounts.token_mint_a,
+            sealed_offer.to_account_info(),
+            sealed_offer.offered_amount,
+            &[&offer_seeds[..]],
+            &accounts.token_program,
+        )?;
+        close_signed(
+            vault,
+            accounts.maker.to_account_info(),
+            sealed_offer.to_account_info(),
+            &[&offer_seeds[..]],
+            &accounts.token_program,
+        )?;
+    }
+    if refund > 0 {
+        transfer_signed(
+            &accounts.bid_vault,
+            &accounts.taker_token_account_b,
+            &accounts.token_mint_b,
+            sealed_bid.to_account_info(),
+            refund,
+            &[&bid_seeds[..]],
+            &accounts.token_program,
+        )?;
+    }
+    close_signed(
+        &accounts.bid_vault,
+        accounts.taker.to_account_info(),
+        sealed_bid.to_account_info(),
+        &[&bid_seeds[..]],
+        &accounts.token_program,
+    )?;
+
+    Ok(())
+}
+
+/// Refunds the escrowed token A while no bid has matched. The offer account is kept,
+/// marked cancelled, so bids still in flight resolve as rejected and can be refunded.
+pub fn cancel_sealed_offer_handler(ctx: Context<CancelSealedOffer>) -> Result<()> {
+    let sealed_offer = &ctx.accounts.sealed_offer;
+    require!(
+        matches!(
+            sealed_offer.status,
+            SealedOfferStatus::Pending | SealedOfferStatus::Open
+        ),
+        DexError::OfferNotOpen
+    );
+
+    let id_bytes = sealed_offer.id.to_le_bytes();
+    let offer_seeds = &[
+        b"sealed_offer",
+        sealed_offer.maker.as_ref(),
+        &id_bytes[..],
+        &[sealed_offer.bump],
+    ];
+
+    transfer_signed(
+        &ctx.accounts.vault,
+        &ctx.accounts.maker_token_account_a,
+        &ctx.accounts.token_mint_a,
+        sealed_offer.to_account_info(),
+        ctx.accounts.vault.amount,
+        &[&offer_seeds[..]],
+        &ctx.accounts.token_program,
+    )?;
+    close_signed(
+        &ctx.accounts.vault,
+        ctx.accounts.maker.to_account_info(),
+        sealed_offer.to_account_info(),
+        &[&offer_seeds[..]],
+        &ctx.accounts.token_program,
+    )?;
+
+    ctx.accounts.sealed_offer.status = SealedOfferStatus::Cancelled;
+
+    Ok(())
+}
+
+fn transfer_signed<'info>(
+    from: &InterfaceAccount<'info, TokenAccount>,
+    to: &InterfaceAccount<'info, TokenAccount>,
+    mint: &InterfaceAccount<'info, Mint>,
+    authority: AccountInfo<'info>,
+    amount: u64,
+    signer_seeds: &[&[&[u8]]],
+    token_program: &Interface<'info, TokenInterface>,
+) -> Result<()> {
+    let accounts = TransferChecked {
+        from: from.to_account_info(),
+        mint: mint.to_account_info(),
+        to: to.to_account_info(),
+        authority,
+    };
+    let cpi_context =
+        CpiContext::new_with_signer(token_program.to_account_info(), accounts, signer_seeds);
+
+    transfer_checked(cpi_context, amount, mint.decimals)
+}
+
+fn close_signed<'info>(
+    vault: &InterfaceAccount<'info, TokenAccount>,
+    destination: AccountInfo<'info>,
+    authority: AccountInfo<'info>,
+    signer_seeds: &[&[&[u8]]],
+    token_program: &Interface<'info, TokenInterface>,
+) -> Result<()> {
+    let accounts = CloseAccount {
+        account: vault.to_account_info(),
+        destination,
+        authority,
+    };
+    let cpi_context =
+        CpiContext::new_with_signer(token_program.to_account_info(), accounts, signer_seeds);
+
+    close_account(cpi_context)
+}
+
+#[derive(Accounts)]
+pub struct SettleSealedBid<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(mut)]
+    pub maker: SystemAccount<'info>,
+
+    #[account(mut)]
+    pub taker: SystemAccount<'info>,
+
+    #[account(
+        seeds = [b"sealed_offer", maker.key().as_ref(), sealed_offer.id.to_le_bytes().as_ref()],
+        bump = sealed_offer.bump,
+        has_one = maker,
+        has_one = token_mint_a,
+        has_one = token_mint_b,
+    )]
+    pub sealed_offer: Box<Account<'info, SealedOffer>>,
+
+    #[account(
+        mut,
+        close = taker,
+        seeds = [b"sealed_bid", sealed_offer.key().as_ref(), taker.key().as_ref()],
+        bump = sealed_bid.bump,
+        has_one = sealed_offer,
+        has_one = taker,
+    )]
+    pub sealed_bid: Box<Account<'info, SealedBid>>,
+
+    pub token_mint_a: Box<InterfaceAccount<'info, Mint>>,
+
+    pub token_mint_b: Box<InterfaceAccount<'info, Mint>>,
+
+    // Only needed to settle a matched bid; closed once the offer is filled or cancelled
+    #[account(
+        mut,
+        associated_token::mint = token_mint_a,
+        associated_token::authority = sealed_offer,
+        associated_token::token_program = token_program,
+    )]
+    pub vault: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    #[account(
+        mut,
+        associated_token::mint = token_mint_b,
+        associated_token::authority = sealed_bid,
+        associated_token::token_program = token_program,
+    )]
+    pub bid_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        associated_token::mint = token_mint_a,
+        associated_token::authority = taker,
+        associated_token::token_program = token_program,
+    )]
+    pub taker_token_account_a: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    #[account(
+        init_if_needed,
+        payer = payer,
+        associated_token::mint = token_mint_b,
+        associated_token::authority = taker,
+        associated_token::token_program = token_program,
+    )]
+    pub taker_token_account_b: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        init_if_needed,
+        payer = payer,
+        associated_token::mint = token_mint_b,
+        associated_token::authority = maker,
+        associated_token::token_program = token_program,
+    )]
+    pub maker_token_account_b: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub system_program: Program<'info, System>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
+
+#[derive(Accounts)]
+pub struct CancelSealedOffer<'info> {
+    #[account(mut)]
+    pub maker: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"sealed_offer", maker.key().as_ref(), sealed_offer.id.to_le_bytes().as_ref()],
+        bump = sealed_offer.bump,
+        has_one = maker,
+        has_one = token_mint_a,
+    )]
+    pub sealed_offer: Box<Account<'info, SealedOffer>>,
+
+    pub token_mint_a: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        associated_token::mint = token_mint_a,
+        associated_token::authority = maker,
+        associated_token::token_program = token_program,
+    )]
+    pub maker_token_account_a: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        associated_token::mint = token_mint_a,
+        associated_token::authority = sealed_offer,
+        associated_token::token_program = token_program,
+    )]
+    pub vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
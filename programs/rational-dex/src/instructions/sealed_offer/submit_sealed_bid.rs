@@ -0,0 +1,266 @@
+use crate::error::{DexError, ErrorCode};
+use crate::events::SealedBidResolved;
+use crate::instructions::config::require_not_paused;
+use crate::instructions::swap::transfer_tokens;
+use crate::state::config_state::{GlobalConfig, Subsystem};
+use crate::state::sealed_offer_state::{
+    SealedBid, SealedBidStatus, SealedOffer, SealedOfferStatus,
+};
+use crate::{
+    constants::COMP_DEF_OFFSET_MATCH_SEALED_BID, SignerAccount, ANCHOR_DISCRIMINATOR,
+    EVENT_VERSION, ID, ID_CONST,
+};
+use arcium_client::idl::arcium::types::CallbackAccount;
+
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{Mint, TokenAccount, TokenInterface},
+};
+use arcium_anchor::prelude::*;
+
+pub fn init_match_sealed_bid_comp_def_handler(
+    ctx: Context<InitMatchSealedBidCompDef>,
+) -> Result<()> {
+    init_comp_def(ctx.accounts, true, 0, None, None)?;
+    Ok(())
+}
+
+/// Escrows `deposit` token B and queues the encrypted bid against the offer's ask.
+/// The deposit is public, so takers should escrow more than they bid.
+pub fn submit_sealed_bid_handler(
+    ctx: Context<SubmitSealedBid>,
+    computation_offset: u64,
+    deposit: u64,
+    bid: [u8; 32],
+    bid_encryption_pubkey: [u8; 32],
+    bid_nonce: u128,
+) -> Result<()> {
+    require_not_paused(&ctx.accounts.config, Subsystem::Offers)?;
+    require!(
+        ctx.accounts.sealed_offer.status == SealedOfferStatus::Open,
+        DexError::OfferNotOpen
+    );
+    require!(deposit > 0, DexError::InvalidAmount);
+
+    // Only what reaches the vault after any transfer fee can be settled
+    let deposit = transfer_tokens(
+        &ctx.accounts.taker_token_account_b,
+        &ctx.accounts.bid_vault,
+        &deposit,
+        &ctx.accounts.token_mint_b,
+        &ctx.accounts.payer,
+        &ctx.accounts.token_program,
+    )?;
+
+    ctx.accounts.sealed_bid.set_inner(SealedBid {
+        sealed_offer: ctx.accounts.sealed_offer.key(),
+        taker: ctx.accounts.payer.key(),
+        deposit,
+        status: SealedBidStatus::Pending,
+        settlement_amount: 0,
+        bump: ctx.bumps.sealed_bid,
+    });
+
+    let args = vec![
+        Argument::ArcisPubkey(bid_encryption_pubkey),
+        Argument::PlaintextU128(bid_nonce),
+        Argument::EncryptedU64(bid),
+        Argument::PlaintextU64(deposit),
+        Argument::PlaintextU128(ctx.accounts.sealed_offer.nonce),
+        Argument::Account(
+            ctx.accounts.sealed_offer.key(),
+            // Offset calculation: 8 bytes (discriminator) + 1 byte (bump)
+            8 + 1,
+            32, // encrypted ask, one 32-byte ciphertext
+        ),
+    ];
+
+    ctx.accounts.sign_pda_account.bump = ctx.bumps.sign_pda_account;
+
+    queue_computation(
+        ctx.accounts,
+        computation_offset,
+        args,
+        None,
+        vec![MatchSealedBidCallback::callback_ix(&[
+            CallbackAccount {
+                pubkey: ctx.accounts.sealed_offer.key(),
+                is_writable: true,
+            },
+            CallbackAccount {
+                pubkey: ctx.accounts.sealed_bid.key(),
+                is_writable: true,
+            },
+        ])],
+    )?;
+
+    Ok(())
+}
+
+/// Records the revealed settlement amount. Only the first matching bid fills the offer;
+/// later matches are rejected and refunded like any other losing bid.
+pub fn match_sealed_bid_callback_handler(
+    ctx: Context<MatchSealedBidCallback>,
+    output: ComputationOutputs<MatchSealedBidOutput>,
+) -> Result<()> {
+    let settlement_amount = match output {
+        ComputationOutputs::Success(MatchSealedBidOutput { field_0 }) => field_0,
+        _ => return Err(ErrorCode::AbortedComputation.into()),
+    };
+
+    let sealed_offer = &mut ctx.accounts.sealed_offer;
+    let sealed_bid = &mut ctx.accounts.sealed_bid;
+    let matched = settlement_amount > 0 && sealed_offer.status == SealedOfferStatus::Open;
+    if matched {
+        sealed_offer.status = SealedOfferStatus::Filled;
+        sealed_bid.status = SealedBidStatus::Matched;
+        sealed_bid.settlement_amount = settlement_amount;
+    } else {
+        sealed_bid.status = SealedBidStatus::Rejected;
+    }
+
+    emit!(SealedBidResolved {
+        version: EVENT_VERSION,
+        offer: sealed_offer.key(),
+        bid: sealed_bid.key(),
+        taker: sealed_bid.taker,
+        matched,
+        settlement_amount: sealed_bid.settlement_amount,
+    });
+
+    Ok(())
+}
+
+#[init_computation_definition_accounts("match_sealed_bid", payer)]
+#[derive(Accounts)]
+pub struct InitMatchSealedBidCompDef<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        mut,
+        address = derive_mxe_pda!()
+    )]
+    pub mxe_account: Box<Account<'info, MXEAccount>>,
+    #[account(mut)]
+    /// CHECK: comp_def_account, checked by arcium program.
+    /// Can't check it here as it's not initialized yet.
+    pub comp_def_account: UncheckedAccount<'info>,
+    pub arcium_program: Program<'info, Arcium>,
+    pub system_program: Program<'info, System>,
+}
+
+#[queue_computation_accounts("match_sealed_bid", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64)]
+pub struct SubmitSealedBid<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        init_if_needed,
+        space = 9,
+        payer = payer,
+        seeds = [&SIGN_PDA_SEED],
+        bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, SignerAccount>,
+    #[account(
+        address = derive_mxe_pda!()
+    )]
+    pub mxe_account: Account<'info, MXEAccount>,
+    #[account(
+        mut,
+        address = derive_mempool_pda!()
+    )]
+    /// CHECK: mempool_account, checked by the arcium program
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        address = derive_execpool_pda!()
+    )]
+    /// CHECK: executing_pool, checked by the arcium program
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        address = derive_comp_pda!(computation_offset)
+    )]
+    /// CHECK: computation_account, checked by the arcium program.
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(
+        address = derive_comp_def_pda!(COMP_DEF_OFFSET_MATCH_SEALED_BID)
+    )]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(
+        mut,
+        address = derive_cluster_pda!(mxe_account)
+    )]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(
+        mut,
+        address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS,
+    )]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(
+        address = ARCIUM_CLOCK_ACCOUNT_ADDRESS,
+    )]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Box<Account<'info, GlobalConfig>>,
+    #[account(
+        seeds = [
+            b"sealed_offer",
+            sealed_offer.maker.as_ref(),
+            sealed_offer.id.to_le_bytes().as_ref()
+        ],
+        bump = sealed_offer.bump,
+        has_one = token_mint_b,
+    )]
+    pub sealed_offer: Box<Account<'info, SealedOffer>>,
+    #[account(mint::token_program = token_program)]
+    pub token_mint_b: Box<InterfaceAccount<'info, Mint>>,
+    #[account(
+        mut,
+        associated_token::mint = token_mint_b,
+        associated_token::authority = payer,
+        associated_token::token_program = token_program
+    )]
+    pub taker_token_account_b: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(
+        init,
+        payer = payer,
+        space = ANCHOR_DISCRIMINATOR + SealedBid::INIT_SPACE,
+        seeds = [b"sealed_bid", sealed_offer.key().as_ref(), payer.key().as_ref()],
+        bump,
+    )]
+    pub sealed_bid: Box<Account<'info, SealedBid>>,
+    #[account(
+        init,
+        payer = payer,
+        associated_token::mint = token_mint_b,
+        associated_token::authority = sealed_bid,
+        associated_token::token_program = token_program
+    )]
+    pub bid_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
+
+#[callback_accounts("match_sealed_bid")]
+#[derive(Accounts)]
+pub struct MatchSealedBidCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(
+        address = derive_comp_def_pda!(COMP_DEF_OFFSET_MATCH_SEALED_BID)
+    )]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    /// CHECK: instructions_sysvar, checked by the account constraint
+    pub instructions_sysvar: AccountInfo<'info>,
+    #[account(mut)]
+    pub sealed_offer: Account<'info, SealedOffer>,
+    #[account(mut, has_one = sealed_offer)]
+    pub sealed_bid: Account<'info, SealedBid>,
+}
@@ -1,10 +1,15 @@
 use crate::error::DexError;
 use crate::events::OfferCancelled;
+use crate::instructions::swap::{is_native, unwrap_sol};
 use crate::state::swap_state::Offer;
+use crate::EVENT_VERSION;
 use anchor_lang::prelude::*;
-use anchor_spl::token_interface::{
-    close_account, transfer_checked, CloseAccount, Mint, TokenAccount, TokenInterface,
-    TransferChecked,
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{
+        close_account, transfer_checked, CloseAccount, Mint, TokenAccount, TokenInterface,
+        TransferChecked,
+    },
 };
 
 #[derive(Accounts)]
@@ -14,8 +19,10 @@ pub struct CancelOffer<'info> {
 
     pub token_mint_a: InterfaceAccount<'info, Mint>,
 
+    // Created when missing, e.g. if the maker closed their wSOL account after making the offer
     #[account(
-        mut,
+        init_if_needed,
+        payer = maker,
         associated_token::mint = token_mint_a,
         associated_token::authority = maker,
         associated_token::token_program = token_program
@@ -40,7 +47,13 @@ pub struct CancelOffer<'info> {
     )]
     pub vault: InterfaceAccount<'info, TokenAccount>,
 
+    /// CHECK: created and closed within the instruction to pay out native SOL, see `unwrap_sol`
+    #[account(mut, seeds = [b"unwrap", maker.key().as_ref()], bump)]
+    pub unwrap_account: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
     pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
 }
 
 pub fn cancel_offer(context: Context<CancelOffer>) -> Result<()> {
@@ -89,7 +102,22 @@ pub fn cancel_offer(context: Context<CancelOffer>) -> Result<()> {
     );
     close_account(cpi_context)?;
 
+    // Refund native SOL straight to the maker's system account
+    if is_native(&context.accounts.token_mint_a) {
+        unwrap_sol(
+            &context.accounts.maker,
+            &context.accounts.maker_token_account_a,
+            &context.accounts.unwrap_account,
+            context.bumps.unwrap_account,
+            &context.accounts.token_mint_a,
+            remaining,
+            &context.accounts.system_program,
+            &context.accounts.token_program,
+        )?;
+    }
+
     emit!(OfferCancelled {
+        version: EVENT_VERSION,
         offer_id: offer.id,
         maker: context.accounts.maker.key(),
         refunded_amount: remaining,
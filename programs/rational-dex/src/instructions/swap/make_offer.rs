@@ -1,13 +1,17 @@
-use crate::state::swap_state::{Offer, Price};
+use crate::error::DexError;
+use crate::instructions::config::require_not_paused;
+use crate::state::config_state::{GlobalConfig, Subsystem};
+use crate::state::pool_state::Pool;
+use crate::state::swap_state::{Offer, OfferAccess, Price, PricingMode};
 use anchor_lang::prelude::*;
 use anchor_spl::{
     associated_token::AssociatedToken,
     token_interface::{Mint, TokenAccount, TokenInterface},
 };
 
-use crate::ANCHOR_DISCRIMINATOR;
+use crate::{ANCHOR_DISCRIMINATOR, EVENT_VERSION};
 
-use super::{compute_token_b_wanted, transfer_tokens};
+use super::{compute_token_b_wanted, is_native, offer_prices, transfer_tokens, wrap_sol};
 use crate::events::OfferCreated;
 
 #[derive(Accounts)]
@@ -16,14 +20,19 @@ pub struct MakeOffer<'info> {
     #[account(mut)]
     pub maker: Signer<'info>,
 
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, GlobalConfig>,
+
     #[account(mint::token_program = token_program)]
     pub token_mint_a: InterfaceAccount<'info, Mint>,
 
     #[account(mint::token_program = token_program)]
     pub token_mint_b: InterfaceAccount<'info, Mint>,
 
+    // Created on the fly when the maker offers native SOL
     #[account(
-        mut,
+        init_if_needed,
+        payer = maker,
         associated_token::mint = token_mint_a,
         associated_token::authority = maker,
         associated_token::token_program = token_program
@@ -39,17 +48,21 @@ pub struct MakeOffer<'info> {
     )]
     pub offer: Account<'info, Offer>,
 
+    // Only needed for oracle-relative pricing
     #[account(
         seeds = [b"price", token_mint_a.key().as_ref()],
         bump
     )]
-    pub price_of_token_a: Account<'info, Price>,
+    pub price_of_token_a: Option<Account<'info, Price>>,
 
     #[account(
         seeds = [b"price", token_mint_b.key().as_ref()],
         bump
     )]
-    pub price_of_token_b: Account<'info, Price>,
+    pub price_of_token_b: Option<Account<'info, Price>>,
+
+    // Pool of the pair named by oracle-relative pricing, instead of the `Price` PDAs
+    pub reference_pool: Option<Box<Account<'info, Pool>>>,
 
     #[account(
         init, // No init_if_needed as we don't want someone to reuse vault
@@ -65,43 +78,96 @@ pub struct MakeOffer<'info> {
     pub associated_token_program: Program<'info, AssociatedToken>,
 }
 
+pub fn make_offer_handler(
+    context: Context<MakeOffer>,
+    id: u64,
+    token_a_offered_amount: u64,
+    pricing: PricingMode,
+    access: OfferAccess,
+) -> Result<()> {
+    require_not_paused(&context.accounts.config, Subsystem::Offers)?;
+    require!(token_a_offered_amount > 0, DexError::InvalidAmount);
+    // The offer is for what reached the vault, net of any transfer fee
+    let received_amount = send_offered_tokens_to_vault(&context, token_a_offered_amount)?;
+    save_offer(context, id, received_amount, pricing, access)
+}
+
 pub fn send_offered_tokens_to_vault(
     context: &Context<MakeOffer>,
     token_a_offered_amount: u64,
-) -> Result<()> {
-    transfer_tokens(
+) -> Result<u64> {
+    if is_native(&context.accounts.token_mint_a) {
+        wrap_sol(
+            &context.accounts.maker,
+            &context.accounts.maker_token_account_a,
+            token_a_offered_amount,
+            &context.accounts.system_program,
+            &context.accounts.token_program,
+        )?;
+    }
+
+    let received_amount = transfer_tokens(
         &context.accounts.maker_token_account_a, //from
         &context.accounts.vault,                 //to
         &token_a_offered_amount,                 //amount
         &context.accounts.token_mint_a,          //mint
         &context.accounts.maker,                 //authority
         &context.accounts.token_program,         //token program
-    )
+    )?;
+
+    Ok(received_amount)
 }
 
-pub fn save_offer(context: Context<MakeOffer>, id: u64, token_a_offered_amount: u64) -> Result<()> {
-    let token_b_wanted_amount = compute_token_b_wanted(
-        token_a_offered_amount,
-        context.accounts.price_of_token_a.price,
-        context.accounts.price_of_token_b.price,
-    )?;
+pub fn save_offer(
+    context: Context<MakeOffer>,
+    id: u64,
+    token_a_offered_amount: u64,
+    pricing: PricingMode,
+    access: OfferAccess,
+) -> Result<()> {
+    let token_b_wanted_amount = match pricing {
+        PricingMode::Fixed { wanted_amount } => {
+            require!(wanted_amount > 0, DexError::InvalidAmount);
+            wanted_amount
+        }
+        PricingMode::OracleRelative {
+            premium_bps,
+            reference_pool,
+        } => {
+            let accounts = &context.accounts;
+            let (price_a, price_b) = offer_prices(
+                reference_pool,
+                &accounts.reference_pool,
+                &accounts.price_of_token_a,
+                &accounts.price_of_token_b,
+                &accounts.token_mint_a.key(),
+                &accounts.token_mint_b.key(),
+            )?;
+            compute_token_b_wanted(token_a_offered_amount, price_a, price_b, premium_bps)?
+        }
+    };
     context.accounts.offer.set_inner(Offer {
         id,
         maker: context.accounts.maker.key(),
         token_mint_a: context.accounts.token_mint_a.key(),
         token_mint_b: context.accounts.token_mint_b.key(),
+        offered_amount: token_a_offered_amount,
         token_b_wanted_amount,
         remaining_amount: token_a_offered_amount,
+        pricing,
+        access,
         bump: context.bumps.offer,
     });
 
     emit!(OfferCreated {
+        version: EVENT_VERSION,
         offer_id: id,
         maker: context.accounts.maker.key(),
         token_mint_a: context.accounts.token_mint_a.key(),
         token_mint_b: context.accounts.token_mint_b.key(),
         offered_amount: token_a_offered_amount,
         wanted_amount: token_b_wanted_amount,
+        is_private: access.is_private(),
     });
     Ok(())
 }
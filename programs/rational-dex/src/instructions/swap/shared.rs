@@ -1,10 +1,29 @@
 use anchor_lang::prelude::*;
+use anchor_lang::solana_program::program_pack::Pack;
+use anchor_lang::system_program;
+use anchor_spl::token::spl_token::{native_mint, state::Account as TokenAccountState};
+use anchor_spl::token_2022::spl_token_2022::{
+    self,
+    extension::{
+        transfer_fee::TransferFeeConfig, BaseStateWithExtensions, ExtensionType,
+        StateWithExtensions,
+    },
+    state::Mint as MintState,
+};
 use anchor_spl::token_interface::{
-    transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked,
+    close_account, initialize_account3, sync_native, transfer_checked, CloseAccount,
+    InitializeAccount3, Mint, SyncNative, TokenAccount, TokenInterface, TransferChecked,
 };
+use sha2::{Digest, Sha256};
 
-use crate::error::DexError;
+use crate::error::{DexError, TokenError};
+use crate::instructions::pool::mul_div_ceil;
+use crate::state::pool_state::Pool;
+use crate::state::swap_state::{Offer, OfferAccess, Price, PricingMode};
+use crate::{BPS_DENOMINATOR, PRICE_SCALE};
 
+/// Moves `amount` from a user account and returns what `to` actually receives,
+/// i.e. `amount` minus any Token-2022 transfer fee.
 pub fn transfer_tokens<'info>(
     from: &InterfaceAccount<'info, TokenAccount>,
     to: &InterfaceAccount<'info, TokenAccount>,
@@ -12,7 +31,10 @@ pub fn transfer_tokens<'info>(
     mint: &InterfaceAccount<'info, Mint>,
     authority: &Signer<'info>,
     token_program: &Interface<'info, TokenInterface>,
-) -> Result<()> {
+) -> Result<u64> {
+    validate_mint_extensions(mint)?;
+    let fee = transfer_fee(mint, *amount)?;
+
     let transfer_accounts_options = TransferChecked {
         from: from.to_account_info(),
         mint: mint.to_account_info(),
@@ -22,30 +44,306 @@ pub fn transfer_tokens<'info>(
 
     let cpi_context = CpiContext::new(token_program.to_account_info(), transfer_accounts_options);
 
-    transfer_checked(cpi_context, *amount, mint.decimals)
+    transfer_checked(cpi_context, *amount, mint.decimals)?;
+    Ok(*amount - fee)
+}
+
+/// Rejects Token-2022 mints whose extensions break escrow accounting: non-transferable
+/// tokens cannot leave the vault, a permanent delegate can drain it, and a transfer hook
+/// runs arbitrary code on every move.
+pub fn validate_mint_extensions(mint: &InterfaceAccount<Mint>) -> Result<()> {
+    let mint_info = mint.to_account_info();
+    if *mint_info.owner != spl_token_2022::ID {
+        return Ok(());
+    }
+    let data = mint_info.try_borrow_data()?;
+    let state = StateWithExtensions::<MintState>::unpack(&data)?;
+    for extension in state.get_extension_types()? {
+        match extension {
+            ExtensionType::NonTransferable => return err!(TokenError::NonTransferableMint),
+            ExtensionType::PermanentDelegate => return err!(TokenError::PermanentDelegateMint),
+            ExtensionType::TransferHook => return err!(TokenError::TransferHookMint),
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+/// Token-2022 transfer fee withheld when sending `amount`. Zero for mints without one.
+pub fn transfer_fee(mint: &InterfaceAccount<Mint>, amount: u64) -> Result<u64> {
+    let mint_info = mint.to_account_info();
+    if *mint_info.owner != spl_token_2022::ID {
+        return Ok(0);
+    }
+    let data = mint_info.try_borrow_data()?;
+    let state = StateWithExtensions::<MintState>::unpack(&data)?;
+    let Ok(config) = state.get_extension::<TransferFeeConfig>() else {
+        return Ok(0);
+    };
+    let fee = config
+        .calculate_epoch_fee(Clock::get()?.epoch, amount)
+        .ok_or(DexError::Overflow)?;
+    Ok(fee)
+}
+
+/// Amount to send so that `to` receives exactly `net_amount` after the transfer fee.
+pub fn amount_with_transfer_fee(mint: &InterfaceAccount<Mint>, net_amount: u64) -> Result<u64> {
+    let mint_info = mint.to_account_info();
+    if *mint_info.owner != spl_token_2022::ID || net_amount == 0 {
+        return Ok(net_amount);
+    }
+    let data = mint_info.try_borrow_data()?;
+    let state = StateWithExtensions::<MintState>::unpack(&data)?;
+    let Ok(config) = state.get_extension::<TransferFeeConfig>() else {
+        return Ok(net_amount);
+    };
+    let epoch = Clock::get()?.epoch;
+    let fee = config
+        .calculate_inverse_epoch_fee(epoch, net_amount)
+        .ok_or(DexError::Overflow)?;
+    Ok(net_amount.checked_add(fee).ok_or(DexError::Overflow)?)
+}
+
+/// Whether `mint` is wrapped SOL, which is wrapped and unwrapped around the instruction.
+pub fn is_native(mint: &InterfaceAccount<Mint>) -> bool {
+    mint.key() == native_mint::ID
+}
+
+/// Wraps `amount` lamports from the owner's system account into their wSOL account, so
+/// spending `amount` leaves it with the wSOL it held before.
+pub fn wrap_sol<'info>(
+    owner: &Signer<'info>,
+    wsol_account: &InterfaceAccount<'info, TokenAccount>,
+    amount: u64,
+    system_program: &Program<'info, System>,
+    token_program: &Interface<'info, TokenInterface>,
+) -> Result<()> {
+    if amount == 0 {
+        return Ok(());
+    }
+
+    system_program::transfer(
+        CpiContext::new(
+            system_program.to_account_info(),
+            system_program::Transfer {
+                from: owner.to_account_info(),
+                to: wsol_account.to_account_info(),
+            },
+        ),
+        amount,
+    )?;
+    sync_native(CpiContext::new(
+        token_program.to_account_info(),
+        SyncNative {
+            account: wsol_account.to_account_info(),
+        },
+    ))
+}
+
+/// Pays `amount` of the owner's wSOL out to their system account. The wSOL passes through
+/// `unwrap_account`, a token account at `[b"unwrap", owner]` that is created and closed here,
+/// so the owner's own wSOL account stays open with the rest of its balance.
+#[allow(clippy::too_many_arguments)]
+pub fn unwrap_sol<'info>(
+    owner: &Signer<'info>,
+    wsol_account: &InterfaceAccount<'info, TokenAccount>,
+    unwrap_account: &UncheckedAccount<'info>,
+    unwrap_bump: u8,
+    mint: &InterfaceAccount<'info, Mint>,
+    amount: u64,
+    system_program: &Program<'info, System>,
+    token_program: &Interface<'info, TokenInterface>,
+) -> Result<()> {
+    if amount == 0 {
+        return Ok(());
+    }
+    let owner_key = owner.key();
+    let seeds = &[b"unwrap".as_ref(), owner_key.as_ref(), &[unwrap_bump]];
+    let signer_seeds = [&seeds[..]];
+
+    // Topped up rather than created, so lamports sent to the address can't block it
+    let space = TokenAccountState::LEN;
+    let shortfall = Rent::get()?
+        .minimum_balance(space)
+        .saturating_sub(unwrap_account.lamports());
+    if shortfall > 0 {
+        system_program::transfer(
+            CpiContext::new(
+                system_program.to_account_info(),
+                system_program::Transfer {
+                    from: owner.to_account_info(),
+                    to: unwrap_account.to_account_info(),
+                },
+            ),
+            shortfall,
+        )?;
+    }
+    system_program::allocate(
+        CpiContext::new_with_signer(
+            system_program.to_account_info(),
+            system_program::Allocate {
+                account_to_allocate: unwrap_account.to_account_info(),
+            },
+            &signer_seeds,
+        ),
+        space as u64,
+    )?;
+    system_program::assign(
+        CpiContext::new_with_signer(
+            system_program.to_account_info(),
+            system_program::Assign {
+                account_to_assign: unwrap_account.to_account_info(),
+            },
+            &signer_seeds,
+        ),
+        &token_program.key(),
+    )?;
+    initialize_account3(CpiContext::new(
+        token_program.to_account_info(),
+        InitializeAccount3 {
+            account: unwrap_account.to_account_info(),
+            mint: mint.to_account_info(),
+            authority: owner.to_account_info(),
+        },
+    ))?;
+
+    transfer_checked(
+        CpiContext::new(
+            token_program.to_account_info(),
+            TransferChecked {
+                from: wsol_account.to_account_info(),
+                mint: mint.to_account_info(),
+                to: unwrap_account.to_account_info(),
+                authority: owner.to_account_info(),
+            },
+        ),
+        amount,
+        mint.decimals,
+    )?;
+    close_account(CpiContext::new(
+        token_program.to_account_info(),
+        CloseAccount {
+            account: unwrap_account.to_account_info(),
+            destination: owner.to_account_info(),
+            authority: owner.to_account_info(),
+        },
+    ))
+}
+
+/// Token B owed for `token_a_amount` of `offer`, rounded up in the maker's favour.
+/// `prices` are the token A and token B oracle prices, only needed for oracle pricing.
+pub fn compute_token_b_owed(
+    offer: &Offer,
+    token_a_amount: u64,
+    prices: Option<(u64, u64)>,
+) -> Result<u64> {
+    match offer.pricing {
+        PricingMode::Fixed { wanted_amount } => {
+            // Price the cumulative fill so partial fills add up to exactly `wanted_amount`
+            let filled_before = offer.offered_amount - offer.remaining_amount;
+            let filled_after = filled_before
+                .checked_add(token_a_amount)
+                .ok_or(DexError::Overflow)?;
+            let owed_before = mul_div_ceil(filled_before, wanted_amount, offer.offered_amount)?;
+            let owed_after = mul_div_ceil(filled_after, wanted_amount, offer.offered_amount)?;
+            Ok(owed_after - owed_before)
+        }
+        PricingMode::OracleRelative { premium_bps, .. } => {
+            let (price_a, price_b) = prices.ok_or(DexError::PriceNotSet)?;
+            compute_token_b_wanted(token_a_amount, price_a, price_b, premium_bps)
+        }
+    }
 }
 
+/// `token_a_amount * price_a / price_b`, plus `premium_bps`, rounded up.
 pub fn compute_token_b_wanted(
-    token_a_offered_account: u64,
+    token_a_amount: u64,
     price_a: u64,
     price_b: u64,
+    premium_bps: u16,
 ) -> Result<u64> {
-    // widen to u128 for safe intermediate multiplication
-    let offered = token_a_offered_account;
-
-    if price_b == 0 {
+    if price_a == 0 || price_b == 0 {
         return Err(DexError::PriceNotSet.into());
     }
 
-    if price_a == 0 {
-        return Err(DexError::PriceNotSet.into());
+    // widen to u128 for safe intermediate multiplication
+    let numerator = (token_a_amount as u128)
+        .checked_mul(price_a as u128)
+        .and_then(|product| product.checked_mul((BPS_DENOMINATOR + premium_bps as u64) as u128))
+        .ok_or(DexError::Overflow)?;
+    let denominator = (price_b as u128) * (BPS_DENOMINATOR as u128);
+
+    u64::try_from(numerator.div_ceil(denominator)).map_err(|_| DexError::Overflow.into())
+}
+
+/// Token A and token B prices for oracle-relative pricing from `source`, the offer's
+/// reference pool. That pool must be the one passed; without one both `Price` accounts are
+/// needed.
+pub fn offer_prices(
+    source: Option<Pubkey>,
+    reference_pool: &Option<Box<Account<Pool>>>,
+    price_of_token_a: &Option<Account<Price>>,
+    price_of_token_b: &Option<Account<Price>>,
+    token_mint_a: &Pubkey,
+    token_mint_b: &Pubkey,
+) -> Result<(u64, u64)> {
+    match source {
+        Some(source) => {
+            let pool = reference_pool
+                .as_ref()
+                .filter(|pool| pool.key() == source)
+                .ok_or(DexError::InvalidReferencePool)?;
+            reference_prices(pool, token_mint_a, token_mint_b)
+        }
+        None => match (price_of_token_a, price_of_token_b) {
+            (Some(price_a), Some(price_b)) => Ok((price_a.price, price_b.price)),
+            _ => err!(DexError::PriceNotSet),
+        },
     }
+}
 
-    // multiply, checking overflow
-    let product = offered.checked_mul(price_a).ok_or(DexError::Overflow)?;
+/// The pool's reference price, token B per token A scaled by `PRICE_SCALE`, as a pair of
+/// prices for `compute_token_b_wanted`. The offer may trade the pool's pair either way round.
+pub fn reference_prices(
+    pool: &Pool,
+    token_mint_a: &Pubkey,
+    token_mint_b: &Pubkey,
+) -> Result<(u64, u64)> {
+    let scale = PRICE_SCALE as u64;
+    if pool.mint_a == *token_mint_a && pool.mint_b == *token_mint_b {
+        Ok((pool.reference_price, scale))
+    } else if pool.mint_a == *token_mint_b && pool.mint_b == *token_mint_a {
+        Ok((scale, pool.reference_price))
+    } else {
+        err!(DexError::InvalidReferencePool)
+    }
+}
 
-    // divide, checking overflow (division itself won't overflow, but keep pattern)
-    let token_b_wanted = product.checked_div(price_b).ok_or(DexError::Overflow)?;
+/// Checks `taker` against the offer's access rule. `proof` is only used for merkle roots.
+pub fn verify_taker(access: &OfferAccess, taker: &Pubkey, proof: &[[u8; 32]]) -> Result<()> {
+    let allowed = match access {
+        OfferAccess::Public => true,
+        OfferAccess::Taker(allowed_taker) => allowed_taker == taker,
+        OfferAccess::MerkleRoot(root) => {
+            // Pairs are hashed in sorted order, so proofs carry no left/right flags
+            let computed = proof.iter().fold(hash(&[taker.as_ref()]), |node, sibling| {
+                if node <= *sibling {
+                    hash(&[&node, sibling])
+                } else {
+                    hash(&[sibling, &node])
+                }
+            });
+            computed == *root
+        }
+    };
+    require!(allowed, DexError::TakerNotAllowed);
+    Ok(())
+}
 
-    Ok(token_b_wanted)
+fn hash(parts: &[&[u8]]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    for part in parts {
+        hasher.update(part);
+    }
+    hasher.finalize().into()
 }
@@ -1,4 +1,5 @@
 use anchor_lang::prelude::*;
+use anchor_lang::system_program;
 use anchor_spl::{
     associated_token::AssociatedToken,
     token_interface::{
@@ -9,10 +10,17 @@ use anchor_spl::{
 
 use crate::events::OfferTaken;
 
-use crate::error::DexError;
-use crate::state::swap_state::{Offer, Price};
+use crate::error::{DexError, ErrorCode};
+use crate::instructions::config::require_not_paused;
+use crate::state::config_state::{GlobalConfig, Subsystem};
+use crate::state::pool_state::Pool;
+use crate::state::swap_state::{Offer, Price, PricingMode};
+use crate::EVENT_VERSION;
 
-use super::{compute_token_b_wanted, transfer_tokens};
+use super::{
+    amount_with_transfer_fee, compute_token_b_owed, is_native, offer_prices, transfer_tokens,
+    unwrap_sol, verify_taker,
+};
 
 #[derive(Accounts)]
 pub struct TakeOffer<'info> {
@@ -22,6 +30,9 @@ pub struct TakeOffer<'info> {
     #[account(mut)]
     pub maker: SystemAccount<'info>,
 
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Box<Account<'info, GlobalConfig>>,
+
     pub token_mint_a: InterfaceAccount<'info, Mint>,
 
     pub token_mint_b: InterfaceAccount<'info, Mint>,
@@ -35,13 +46,14 @@ pub struct TakeOffer<'info> {
     )]
     pub taker_token_account_a: Box<InterfaceAccount<'info, TokenAccount>>,
 
+    // Not needed when token B is native SOL, which is paid in lamports
     #[account(
         mut,
         associated_token::mint = token_mint_b,
         associated_token::authority = taker,
         associated_token::token_program = token_program,
     )]
-    pub taker_token_account_b: Box<InterfaceAccount<'info, TokenAccount>>,
+    pub taker_token_account_b: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
 
     #[account(
         init_if_needed,
@@ -50,19 +62,23 @@ pub struct TakeOffer<'info> {
         associated_token::authority = maker,
         associated_token::token_program = token_program,
     )]
-    pub maker_token_account_b: Box<InterfaceAccount<'info, TokenAccount>>,
+    pub maker_token_account_b: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
 
+    // Only needed for oracle-relative pricing
     #[account(
         seeds = [b"price", token_mint_a.key().as_ref()],
         bump
     )]
-    pub price_of_token_a: Account<'info, Price>,
+    pub price_of_token_a: Option<Account<'info, Price>>,
 
     #[account(
         seeds = [b"price", token_mint_b.key().as_ref()],
         bump
     )]
-    pub price_of_token_b: Account<'info, Price>,
+    pub price_of_token_b: Option<Account<'info, Price>>,
+
+    // Pool of the pair named by oracle-relative pricing, instead of the `Price` PDAs
+    pub reference_pool: Option<Box<Account<'info, Pool>>>,
 
     #[account(
         mut, // Why mut because we will close the account when offer is taken
@@ -83,12 +99,39 @@ pub struct TakeOffer<'info> {
     )]
     vault: InterfaceAccount<'info, TokenAccount>,
 
+    /// CHECK: created and closed within the instruction to pay out native SOL, see `unwrap_sol`
+    #[account(mut, seeds = [b"unwrap", taker.key().as_ref()], bump)]
+    pub unwrap_account: UncheckedAccount<'info>,
+
     pub system_program: Program<'info, System>,
     pub token_program: Interface<'info, TokenInterface>,
     pub associated_token_program: Program<'info, AssociatedToken>,
 }
 
-pub fn send_wanted_tokens_to_maker(context: Context<TakeOffer>, taker_amount: u64) -> Result<()> {
+pub fn take_offer_handler(
+    mut context: Context<TakeOffer>,
+    taker_amount: u64,
+    max_token_b: u64,
+    proof: Vec<[u8; 32]>,
+) -> Result<()> {
+    require_not_paused(&context.accounts.config, Subsystem::Offers)?;
+    verify_taker(
+        &context.accounts.offer.access,
+        &context.accounts.taker.key(),
+        &proof,
+    )?;
+    send_wanted_tokens_to_maker(&mut context, taker_amount, max_token_b)?;
+    withdraw_and_close_vault(&mut context, taker_amount)
+}
+
+/// Sends the token B owed for `taker_amount` to the maker. Fails if the taker would pay more
+/// than `max_token_b`, transfer fee included, which bounds oracle-relative offers whose price
+/// moves between signing and execution.
+pub fn send_wanted_tokens_to_maker(
+    context: &mut Context<TakeOffer>,
+    taker_amount: u64,
+    max_token_b: u64,
+) -> Result<()> {
     let offer = &mut context.accounts.offer;
 
     // Basic checks
@@ -99,20 +142,56 @@ pub fn send_wanted_tokens_to_maker(context: Context<TakeOffer>, taker_amount: u6
     );
 
     // Compute how many token B the taker must send
-    let token_b_required = compute_token_b_wanted(
-        taker_amount,
-        context.accounts.price_of_token_a.price, // you'll need to pass these price accounts into TakeOffer if required
-        context.accounts.price_of_token_b.price,
-    )?;
+    let prices = match offer.pricing {
+        PricingMode::OracleRelative { reference_pool, .. } => Some(offer_prices(
+            reference_pool,
+            &context.accounts.reference_pool,
+            &context.accounts.price_of_token_a,
+            &context.accounts.price_of_token_b,
+            &offer.token_mint_a,
+            &offer.token_mint_b,
+        )?),
+        PricingMode::Fixed { .. } => None,
+    };
+    let token_b_required = compute_token_b_owed(offer, taker_amount, prices)?;
+    let native = is_native(&context.accounts.token_mint_b);
+    // The taker covers any transfer fee so the maker receives the full amount
+    let token_b_sent = if native {
+        token_b_required
+    } else {
+        amount_with_transfer_fee(&context.accounts.token_mint_b, token_b_required)?
+    };
+    require!(token_b_sent <= max_token_b, ErrorCode::SlippageExceeded);
 
-    let result = transfer_tokens(
-        &context.accounts.taker_token_account_b, // from
-        &context.accounts.maker_token_account_b, // to
-        &token_b_required,                       // amount
-        &context.accounts.token_mint_b,
-        &context.accounts.taker,
-        &context.accounts.token_program,
-    );
+    let result = if native {
+        // Native SOL goes straight from the taker's system account to the maker's
+        system_program::transfer(
+            CpiContext::new(
+                context.accounts.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: context.accounts.taker.to_account_info(),
+                    to: context.accounts.maker.to_account_info(),
+                },
+            ),
+            token_b_required,
+        )
+    } else {
+        let (Some(taker_token_account_b), Some(maker_token_account_b)) = (
+            &context.accounts.taker_token_account_b,
+            &context.accounts.maker_token_account_b,
+        ) else {
+            return Err(anchor_lang::error::ErrorCode::AccountNotEnoughKeys.into());
+        };
+        transfer_tokens(
+            taker_token_account_b, // from
+            maker_token_account_b, // to
+            &token_b_sent,         // amount
+            &context.accounts.token_mint_b,
+            &context.accounts.taker,
+            &context.accounts.token_program,
+        )
+        .map(|_| ())
+    };
 
     //  Update remaining_amount
     offer.remaining_amount = offer
@@ -121,7 +200,9 @@ pub fn send_wanted_tokens_to_maker(context: Context<TakeOffer>, taker_amount: u6
         .ok_or(DexError::Overflow)?;
 
     emit!(OfferTaken {
+        version: EVENT_VERSION,
         offer_id: offer.id,
+        maker: offer.maker,
         taker: context.accounts.taker.key(),
         amount_taken: taker_amount,
         remaining_amount: offer.remaining_amount,
@@ -130,7 +211,7 @@ pub fn send_wanted_tokens_to_maker(context: Context<TakeOffer>, taker_amount: u6
     result
 }
 
-pub fn withdraw_and_close_vault(context: Context<TakeOffer>, taker_amount: u64) -> Result<()> {
+pub fn withdraw_and_close_vault(context: &mut Context<TakeOffer>, taker_amount: u64) -> Result<()> {
     let offer = &mut context.accounts.offer;
 
     let seeds = &[
@@ -178,5 +259,19 @@ pub fn withdraw_and_close_vault(context: Context<TakeOffer>, taker_amount: u64)
         close_account(cpi_context)?;
     }
 
+    // Pay native SOL out to the taker's system account
+    if is_native(&context.accounts.token_mint_a) {
+        unwrap_sol(
+            &context.accounts.taker,
+            &context.accounts.taker_token_account_a,
+            &context.accounts.unwrap_account,
+            context.bumps.unwrap_account,
+            &context.accounts.token_mint_a,
+            taker_amount,
+            &context.accounts.system_program,
+            &context.accounts.token_program,
+        )?;
+    }
+
     Ok(())
 }
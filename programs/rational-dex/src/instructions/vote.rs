@@ -1,5 +1,12 @@
+use crate::events::VoteCast;
+use crate::instructions::config::require_not_paused;
+use crate::instructions::reputation::reputation_weight;
+use crate::state::config_state::{GlobalConfig, Subsystem};
 use crate::state::poll_state::PollAccount;
-use crate::{constants::COMP_DEF_OFFSET_VOTE, error::ErrorCode, SignerAccount, ID, ID_CONST};
+use crate::state::reputation_state::Reputation;
+use crate::{
+    constants::COMP_DEF_OFFSET_VOTE, error::ErrorCode, SignerAccount, EVENT_VERSION, ID, ID_CONST,
+};
 use arcium_client::idl::arcium::types::CallbackAccount;
 
 use anchor_lang::prelude::*;
@@ -18,10 +25,17 @@ pub fn vote_handler(
     vote_encryption_pubkey: [u8; 32],
     vote_nonce: u128,
 ) -> Result<()> {
+    require_not_paused(&ctx.accounts.config, Subsystem::Polls)?;
+
+    // Voters with reputation count more than once in the tally
+    let now = Clock::get()?.unix_timestamp;
+    let weight = reputation_weight(ctx.accounts.reputation.as_deref(), now);
+
     let args = vec![
         Argument::ArcisPubkey(vote_encryption_pubkey),
         Argument::PlaintextU128(vote_nonce),
         Argument::EncryptedBool(vote),
+        Argument::PlaintextU64(weight),
         Argument::PlaintextU128(ctx.accounts.poll_acc.nonce),
         Argument::Account(
             ctx.accounts.poll_acc.key(),
@@ -43,6 +57,16 @@ pub fn vote_handler(
             is_writable: true,
         }])],
     )?;
+
+    emit!(VoteCast {
+        version: EVENT_VERSION,
+        poll: ctx.accounts.poll_acc.key(),
+        poll_id: ctx.accounts.poll_acc.id,
+        voter: ctx.accounts.payer.key(),
+        weight,
+        timestamp: now,
+    });
+
     Ok(())
 }
 
@@ -62,6 +86,11 @@ pub fn vote_callback_handler(
     let current_timestamp = clock.unix_timestamp;
 
     emit!(VoteEvent {
+        version: EVENT_VERSION,
+        poll: ctx.accounts.poll_acc.key(),
+        poll_id: ctx.accounts.poll_acc.id,
+        vote_state: o.ciphertexts,
+        nonce: o.nonce,
         timestamp: current_timestamp,
     });
 
@@ -136,6 +165,13 @@ pub struct Vote<'info> {
         has_one = authority
     )]
     pub poll_acc: Account<'info, PollAccount>,
+    #[account(
+        seeds = [b"reputation", payer.key().as_ref()],
+        bump = reputation.bump,
+    )]
+    pub reputation: Option<Account<'info, Reputation>>,
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, GlobalConfig>,
 }
 
 #[init_computation_definition_accounts("vote", payer)]
@@ -173,5 +209,11 @@ pub struct VoteCallback<'info> {
 
 #[event]
 pub struct VoteEvent {
+    pub version: u8,
+    pub poll: Pubkey,
+    pub poll_id: u32,
+    /// Encrypted tally after the vote, as stored in the poll
+    pub vote_state: [[u8; 32]; 7],
+    pub nonce: u128,
     pub timestamp: i64,
 }
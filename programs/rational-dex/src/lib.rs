@@ -11,6 +11,13 @@ pub use instructions::*;
 #[allow(unused_imports)]
 pub use state::*;
 
+use state::config_state::Subsystem;
+use state::conviction_state::EvidenceRef;
+use state::oracle_state::TwapResult;
+use state::order_book_state::Side;
+use state::pool_state::{BlendPolicy, PositionValue};
+use state::swap_state::{OfferAccess, PricingMode};
+
 declare_id!("EEL1Q3J9MjPxTWagTKE39jpUVBjUg7q283ztTVzbveDz");
 
 #[arcium_program]
@@ -20,10 +27,337 @@ pub mod rational_dex {
     pub fn initialize(ctx: Context<Initialize>) -> Result<()> {
         initialize::handler(ctx)
     }
+    pub fn initialize_config(
+        ctx: Context<InitializeConfig>,
+        fee_recipients: Vec<Pubkey>,
+    ) -> Result<()> {
+        initialize_config_handler(ctx, fee_recipients)
+    }
+
+    pub fn propose_admin(ctx: Context<AdminOnly>, new_admin: Option<Pubkey>) -> Result<()> {
+        propose_admin_handler(ctx, new_admin)
+    }
+
+    pub fn accept_admin(ctx: Context<AcceptAdmin>) -> Result<()> {
+        accept_admin_handler(ctx)
+    }
+
+    pub fn set_fee_recipients(ctx: Context<AdminOnly>, fee_recipients: Vec<Pubkey>) -> Result<()> {
+        set_fee_recipients_handler(ctx, fee_recipients)
+    }
+
+    pub fn set_paused(ctx: Context<AdminOnly>, subsystem: Subsystem, paused: bool) -> Result<()> {
+        set_paused_handler(ctx, subsystem, paused)
+    }
+
     pub fn initialize_pool(ctx: Context<InitializePool>, fee_num: u64, fee_den: u64) -> Result<()> {
         pool::initialize_pool_handler(ctx, fee_num, fee_den)
     }
 
+    pub fn add_liquidity(
+        ctx: Context<AddLiquidity>,
+        max_amount_a: u64,
+        max_amount_b: u64,
+        min_lp_out: u64,
+    ) -> Result<()> {
+        add_liquidity_handler(ctx, max_amount_a, max_amount_b, min_lp_out)
+    }
+
+    pub fn remove_liquidity(
+        ctx: Context<RemoveLiquidity>,
+        lp_amount: u64,
+        min_amount_a: u64,
+        min_amount_b: u64,
+    ) -> Result<()> {
+        remove_liquidity_handler(ctx, lp_amount, min_amount_a, min_amount_b)
+    }
+
+    pub fn swap(
+        ctx: Context<Swap>,
+        amount_in: u64,
+        min_amount_out: u64,
+        a_to_b: bool,
+    ) -> Result<()> {
+        swap_handler(ctx, amount_in, min_amount_out, a_to_b)
+    }
+
+    pub fn route_swap<'info>(
+        ctx: Context<'_, '_, 'info, 'info, RouteSwap<'info>>,
+        amount_in: u64,
+        min_amount_out: u64,
+    ) -> Result<()> {
+        route_swap_handler(ctx, amount_in, min_amount_out)
+    }
+
+    pub fn flash_borrow(ctx: Context<FlashLoan>, amount: u64, token_a: bool) -> Result<()> {
+        flash_borrow_handler(ctx, amount, token_a)
+    }
+
+    pub fn flash_repay(ctx: Context<FlashLoan>) -> Result<()> {
+        flash_repay_handler(ctx)
+    }
+
+    pub fn update_consensus_price(ctx: Context<UpdateConsensusPrice>, price: u64) -> Result<()> {
+        update_consensus_price_handler(ctx, price)
+    }
+
+    pub fn apply_poll_price(ctx: Context<ApplyPollPrice>) -> Result<()> {
+        apply_poll_price_handler(ctx)
+    }
+
+    pub fn get_twap(ctx: Context<GetTwap>, window: u32) -> Result<TwapResult> {
+        get_twap_handler(ctx, window)
+    }
+
+    pub fn set_blend_policy(ctx: Context<SetBlendPolicy>, policy: BlendPolicy) -> Result<()> {
+        set_blend_policy_handler(ctx, policy)
+    }
+
+    pub fn get_reference_price(ctx: Context<GetReferencePrice>) -> Result<u64> {
+        get_reference_price_handler(ctx)
+    }
+
+    pub fn collect_protocol_fees(ctx: Context<CollectProtocolFees>) -> Result<()> {
+        collect_protocol_fees_handler(ctx)
+    }
+
+    pub fn position_value(ctx: Context<GetPositionValue>) -> Result<PositionValue> {
+        position_value_handler(ctx)
+    }
+
+    // Concentrated Liquidity Instructions
+
+    pub fn create_cl_pool(
+        ctx: Context<CreateClPool>,
+        tick_spacing: u16,
+        fee_bps: u16,
+        active_range_ticks: u32,
+    ) -> Result<()> {
+        create_cl_pool_handler(ctx, tick_spacing, fee_bps, active_range_ticks)
+    }
+
+    pub fn recenter_range(ctx: Context<RecenterRange>) -> Result<()> {
+        recenter_range_handler(ctx)
+    }
+
+    pub fn init_tick_array(ctx: Context<InitTickArray>, start_tick_index: i32) -> Result<()> {
+        init_tick_array_handler(ctx, start_tick_index)
+    }
+
+    pub fn open_position(
+        ctx: Context<OpenPosition>,
+        tick_lower: i32,
+        tick_upper: i32,
+        liquidity: u64,
+        max_amount_a: u64,
+        max_amount_b: u64,
+    ) -> Result<()> {
+        open_position_handler(
+            ctx,
+            tick_lower,
+            tick_upper,
+            liquidity,
+            max_amount_a,
+            max_amount_b,
+        )
+    }
+
+    pub fn collect_position_fees(ctx: Context<ModifyPosition>) -> Result<()> {
+        collect_position_fees_handler(ctx)
+    }
+
+    pub fn close_position(ctx: Context<ModifyPosition>) -> Result<()> {
+        close_position_handler(ctx)
+    }
+
+    pub fn cl_swap<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ClSwap<'info>>,
+        amount_in: u64,
+        min_amount_out: u64,
+        a_to_b: bool,
+    ) -> Result<()> {
+        cl_swap_handler(ctx, amount_in, min_amount_out, a_to_b)
+    }
+
+    // Reward Instructions
+
+    pub fn create_reward_program(
+        ctx: Context<CreateRewardProgram>,
+        lp_share_bps: u16,
+    ) -> Result<()> {
+        create_reward_program_handler(ctx, lp_share_bps)
+    }
+
+    pub fn fund_rewards(ctx: Context<FundRewards>, amount: u64, duration: i64) -> Result<()> {
+        fund_rewards_handler(ctx, amount, duration)
+    }
+
+    pub fn stake_lp(ctx: Context<StakeLp>, amount: u64) -> Result<()> {
+        stake_lp_handler(ctx, amount)
+    }
+
+    pub fn unstake_lp(ctx: Context<StakeLp>, amount: u64) -> Result<()> {
+        unstake_lp_handler(ctx, amount)
+    }
+
+    pub fn sync_vote_weight(ctx: Context<SyncVoteWeight>) -> Result<()> {
+        sync_vote_weight_handler(ctx)
+    }
+
+    pub fn claim_rewards(ctx: Context<ClaimRewards>) -> Result<()> {
+        claim_rewards_handler(ctx)
+    }
+
+    // Offer Instructions
+
+    pub fn make_offer(
+        ctx: Context<MakeOffer>,
+        id: u64,
+        token_a_offered_amount: u64,
+        pricing: PricingMode,
+        access: OfferAccess,
+    ) -> Result<()> {
+        make_offer_handler(ctx, id, token_a_offered_amount, pricing, access)
+    }
+
+    pub fn take_offer(
+        ctx: Context<TakeOffer>,
+        taker_amount: u64,
+        max_token_b: u64,
+        proof: Vec<[u8; 32]>,
+    ) -> Result<()> {
+        take_offer_handler(ctx, taker_amount, max_token_b, proof)
+    }
+
+    pub fn cancel_offer(ctx: Context<CancelOffer>) -> Result<()> {
+        swap::cancel_offer(ctx)
+    }
+
+    // Sealed-Bid Offer Instructions
+
+    pub fn init_sealed_ask_comp_def(ctx: Context<InitSealedAskCompDef>) -> Result<()> {
+        init_sealed_ask_comp_def_handler(ctx)
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn make_sealed_offer(
+        ctx: Context<MakeSealedOffer>,
+        computation_offset: u64,
+        id: u64,
+        offered_amount: u64,
+        ask: [u8; 32],
+        ask_encryption_pubkey: [u8; 32],
+        ask_nonce: u128,
+        mxe_nonce: u128,
+    ) -> Result<()> {
+        make_sealed_offer_handler(
+            ctx,
+            computation_offset,
+            id,
+            offered_amount,
+            ask,
+            ask_encryption_pubkey,
+            ask_nonce,
+            mxe_nonce,
+        )
+    }
+
+    #[arcium_callback(encrypted_ix = "init_sealed_ask")]
+    pub fn init_sealed_ask_callback(
+        ctx: Context<InitSealedAskCallback>,
+        output: ComputationOutputs<InitSealedAskOutput>,
+    ) -> Result<()> {
+        init_sealed_ask_callback_handler(ctx, output)
+    }
+
+    pub fn init_match_sealed_bid_comp_def(ctx: Context<InitMatchSealedBidCompDef>) -> Result<()> {
+        init_match_sealed_bid_comp_def_handler(ctx)
+    }
+
+    pub fn submit_sealed_bid(
+        ctx: Context<SubmitSealedBid>,
+        computation_offset: u64,
+        deposit: u64,
+        bid: [u8; 32],
+        bid_encryption_pubkey: [u8; 32],
+        bid_nonce: u128,
+    ) -> Result<()> {
+        submit_sealed_bid_handler(
+            ctx,
+            computation_offset,
+            deposit,
+            bid,
+            bid_encryption_pubkey,
+            bid_nonce,
+        )
+    }
+
+    #[arcium_callback(encrypted_ix = "match_sealed_bid")]
+    pub fn match_sealed_bid_callback(
+        ctx: Context<MatchSealedBidCallback>,
+        output: ComputationOutputs<MatchSealedBidOutput>,
+    ) -> Result<()> {
+        match_sealed_bid_callback_handler(ctx, output)
+    }
+
+    pub fn settle_sealed_bid(ctx: Context<SettleSealedBid>) -> Result<()> {
+        settle_sealed_bid_handler(ctx)
+    }
+
+    pub fn cancel_sealed_offer(ctx: Context<CancelSealedOffer>) -> Result<()> {
+        cancel_sealed_offer_handler(ctx)
+    }
+
+    // Batch Auction Instructions
+
+    pub fn open_epoch(ctx: Context<OpenEpoch>, id: u64, duration: i64) -> Result<()> {
+        open_epoch_handler(ctx, id, duration)
+    }
+
+    pub fn submit_intent(ctx: Context<SubmitIntent>, side: Side, amount: u64) -> Result<()> {
+        submit_intent_handler(ctx, side, amount)
+    }
+
+    pub fn close_epoch(ctx: Context<CloseEpoch>) -> Result<()> {
+        close_epoch_handler(ctx)
+    }
+
+    pub fn claim_fill(ctx: Context<ClaimFill>) -> Result<()> {
+        claim_fill_handler(ctx)
+    }
+
+    // Order Book Instructions
+
+    pub fn create_market(ctx: Context<CreateMarket>) -> Result<()> {
+        create_market_handler(ctx)
+    }
+
+    pub fn place_order(
+        ctx: Context<PlaceOrder>,
+        side: Side,
+        price: u64,
+        quantity: u64,
+    ) -> Result<()> {
+        place_order_handler(ctx, side, price, quantity)
+    }
+
+    pub fn cancel_order(ctx: Context<CancelOrder>, side: Side, order_id: u64) -> Result<()> {
+        cancel_order_handler(ctx, side, order_id)
+    }
+
+    pub fn match_orders<'info>(
+        ctx: Context<'_, '_, 'info, 'info, MatchOrders<'info>>,
+        limit: u16,
+    ) -> Result<()> {
+        match_orders_handler(ctx, limit)
+    }
+
+    pub fn settle_funds(ctx: Context<SettleFunds>) -> Result<()> {
+        settle_funds_handler(ctx)
+    }
+
+    // Poll Instructions
+
     pub fn init_vote_stats_comp_def(ctx: Context<InitVoteStatsCompDef>) -> Result<()> {
         init_vote_stats_comp_def_handle(ctx)
     }
@@ -36,6 +370,18 @@ pub mod rational_dex {
         init_vote_stats_callback_handler(ctx, output)
     }
 
+    pub fn create_new_poll(
+        ctx: Context<CreateNewPoll>,
+        computation_offset: u64,
+        id: u32,
+        price: u64,
+        mint0: Pubkey,
+        mint1: Pubkey,
+        nonce: u128,
+    ) -> Result<()> {
+        poll::create_new_poll(ctx, computation_offset, id, price, mint0, mint1, nonce)
+    }
+
     pub fn init_vote_comp_def(ctx: Context<InitVoteCompDef>) -> Result<()> {
         init_vote_comp_def_handler(ctx)
     }
@@ -86,8 +432,21 @@ pub mod rational_dex {
 
     // Conviction Voting Instructions
 
-    pub fn initialize_proposal(ctx: Context<InitializeProposal>, evidence: String) -> Result<()> {
-        initialize_proposal_handler(ctx, evidence)
+    pub fn initialize_proposal(
+        ctx: Context<InitializeProposal>,
+        evidence: EvidenceRef,
+        voting_period: i64,
+        min_quorum_weight: u64,
+    ) -> Result<()> {
+        initialize_proposal_handler(ctx, evidence, voting_period, min_quorum_weight)
+    }
+
+    pub fn update_evidence(
+        ctx: Context<UpdateEvidence>,
+        _proposal_count: u64,
+        evidence: EvidenceRef,
+    ) -> Result<()> {
+        update_evidence_handler(ctx, _proposal_count, evidence)
     }
 
     pub fn conviction_vote(
@@ -98,4 +457,38 @@ pub mod rational_dex {
     ) -> Result<()> {
         conviction_vote_handler(ctx, _proposal_count, score, conviction)
     }
+
+    pub fn change_conviction_vote(
+        ctx: Context<UpdateConvictionVote>,
+        _proposal_count: u64,
+        new_score: u8,
+        new_conviction: u8,
+    ) -> Result<()> {
+        change_conviction_vote_handler(ctx, _proposal_count, new_score, new_conviction)
+    }
+
+    pub fn retract_conviction_vote(
+        ctx: Context<UpdateConvictionVote>,
+        proposal_count: u64,
+    ) -> Result<()> {
+        retract_conviction_vote_handler(ctx, proposal_count)
+    }
+
+    pub fn unlock_conviction_tokens(
+        ctx: Context<UpdateConvictionVote>,
+        proposal_count: u64,
+    ) -> Result<()> {
+        unlock_conviction_tokens_handler(ctx, proposal_count)
+    }
+
+    pub fn finalize_proposal(ctx: Context<FinalizeProposal>, _proposal_count: u64) -> Result<()> {
+        finalize_proposal_handler(ctx, _proposal_count)
+    }
+
+    pub fn settle_voter_reputation(
+        ctx: Context<SettleVoterReputation>,
+        _proposal_count: u64,
+    ) -> Result<()> {
+        settle_voter_reputation_handler(ctx, _proposal_count)
+    }
 }
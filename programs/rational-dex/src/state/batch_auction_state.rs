@@ -0,0 +1,36 @@
+use anchor_lang::prelude::*;
+
+use crate::state::order_book_state::Side;
+
+#[account]
+#[derive(InitSpace)]
+pub struct Epoch {
+    /// Poll whose revealed consensus sets the clearing price
+    pub poll: Pubkey,
+    pub id: u64,
+    pub base_mint: Pubkey,
+    pub quote_mint: Pubkey,
+    pub started_at: i64,
+    pub ends_at: i64,
+    /// Quote tokens deposited by buy intents
+    pub total_buy_quote: u64,
+    /// Base tokens deposited by sell intents
+    pub total_sell_base: u64,
+    /// Quote per base, scaled by PRICE_SCALE. Set when the epoch closes.
+    pub clearing_price: u64,
+    pub matched_base: u64,
+    pub matched_quote: u64,
+    pub closed: bool,
+    pub bump: u8,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Intent {
+    pub epoch: Pubkey,
+    pub owner: Pubkey,
+    pub side: Side,
+    /// Quote deposited for buys, base deposited for sells
+    pub amount: u64,
+    pub bump: u8,
+}
@@ -0,0 +1,87 @@
+use anchor_lang::prelude::*;
+
+use crate::TICK_ARRAY_SIZE;
+
+/// Concentrated-liquidity pool for the pair of a constant-product `Pool`. Swaps can only move
+/// the price inside the active range, which is re-centred on that pool's consensus price.
+#[account]
+#[derive(InitSpace)]
+pub struct ClPool {
+    pub pool: Pubkey,
+    pub mint_a: Pubkey,
+    pub mint_b: Pubkey,
+    pub vault_a: Pubkey,
+    pub vault_b: Pubkey,
+    pub tick_spacing: u16,
+    /// Swap fee on the input token, all of it paid to in-range positions
+    pub fee_bps: u16,
+    /// Square root of the price of token A in token B, Q32.32
+    pub sqrt_price_x32: u64,
+    /// Tick of the current price, rounded down
+    pub tick_current: i32,
+    /// Liquidity of the positions whose range contains the current price
+    pub liquidity: u64,
+    /// Fees earned per unit of liquidity since creation, Q32.32 and wrapping
+    pub fee_growth_global_a_x32: u64,
+    pub fee_growth_global_b_x32: u64,
+    /// Half width of the active range, in ticks
+    pub active_range_ticks: u32,
+    pub active_tick_lower: i32,
+    pub active_tick_upper: i32,
+    pub bump: u8,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, InitSpace)]
+pub struct Tick {
+    /// Liquidity added to the pool when the price crosses this tick upwards
+    pub liquidity_net: i64,
+    /// Liquidity of all positions bounded by this tick; zero means uninitialized
+    pub liquidity_gross: u64,
+    /// Fee growth on the side of this tick away from the current price
+    pub fee_growth_outside_a_x32: u64,
+    pub fee_growth_outside_b_x32: u64,
+}
+
+/// `TICK_ARRAY_SIZE` consecutive initializable ticks, starting at `start_tick_index`.
+#[account]
+#[derive(InitSpace)]
+pub struct TickArray {
+    pub cl_pool: Pubkey,
+    pub start_tick_index: i32,
+    pub ticks: [Tick; TICK_ARRAY_SIZE],
+    pub bump: u8,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct ClPosition {
+    pub cl_pool: Pubkey,
+    pub owner: Pubkey,
+    pub tick_lower: i32,
+    pub tick_upper: i32,
+    pub liquidity: u64,
+    /// Fee growth inside the range when the position last settled its fees
+    pub fee_growth_inside_a_last_x32: u64,
+    pub fee_growth_inside_b_last_x32: u64,
+    pub tokens_owed_a: u64,
+    pub tokens_owed_b: u64,
+    pub bump: u8,
+}
+
+impl TickArray {
+    /// First tick of the array holding `tick`.
+    pub fn start_index_for(tick: i32, tick_spacing: u16) -> i32 {
+        let span = tick_spacing as i32 * TICK_ARRAY_SIZE as i32;
+        tick.div_euclid(span) * span
+    }
+
+    /// Slot of an initializable `tick`, if it falls in this array.
+    pub fn offset(&self, tick: i32, tick_spacing: u16) -> Option<usize> {
+        let spacing = tick_spacing as i32;
+        if tick.rem_euclid(spacing) != 0 || tick < self.start_tick_index {
+            return None;
+        }
+        let offset = ((tick - self.start_tick_index) / spacing) as usize;
+        (offset < TICK_ARRAY_SIZE).then_some(offset)
+    }
+}
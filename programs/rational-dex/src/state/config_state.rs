@@ -0,0 +1,31 @@
+use anchor_lang::prelude::*;
+
+use crate::MAX_FEE_RECIPIENTS;
+
+#[account]
+#[derive(InitSpace)]
+pub struct GlobalConfig {
+    pub admin: Pubkey,
+    /// Set by the admin, becomes admin once it accepts
+    pub pending_admin: Option<Pubkey>,
+    #[max_len(MAX_FEE_RECIPIENTS)]
+    pub fee_recipients: Vec<Pubkey>,
+    pub paused: PauseFlags,
+    pub bump: u8,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, PartialEq, Eq, InitSpace)]
+pub struct PauseFlags {
+    pub swaps: bool,
+    pub offers: bool,
+    pub polls: bool,
+    pub conviction: bool,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum Subsystem {
+    Swaps,
+    Offers,
+    Polls,
+    Conviction,
+}
@@ -1,15 +1,45 @@
 use anchor_lang::prelude::*;
 
+use crate::MAX_EVIDENCE_URI_LEN;
+
 #[account]
 #[derive(InitSpace)]
 pub struct Proposal {
     pub author: Pubkey,
-    #[max_len(32)]
-    pub evidence: String,
+    pub evidence: EvidenceRef,
+    pub vote_count: u64,
+    /// Set by the first vote and kept when votes are retracted, freezing the evidence
+    pub voting_started: bool,
+    pub voting_ends_at: i64,
+    pub min_quorum_weight: u64, // minimum total conviction weight for a valid result
+    pub status: ProposalStatus,
     pub final_score: Option<u8>,
     pub score_updated_at: Option<i64>,
 }
 
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub enum ProposalStatus {
+    Active,
+    Finalized,
+    Failed, // voting ended without reaching quorum
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub enum HashAlgorithm {
+    Sha256,
+    Keccak256,
+    Blake3,
+}
+
+/// Content-addressed pointer to the evidence backing a proposal
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, InitSpace)]
+pub struct EvidenceRef {
+    pub algorithm: HashAlgorithm,
+    pub digest: [u8; 32],
+    #[max_len(MAX_EVIDENCE_URI_LEN)]
+    pub uri: Option<String>, // e.g. ipfs://<cid> or https://...
+}
+
 #[account]
 #[derive(InitSpace)]
 pub struct AuthorState {
@@ -23,13 +53,18 @@ pub struct AuthorState {
 #[derive(InitSpace)]
 pub struct Scores {
     pub counts: [u64; 11], // 0–10 scores
+    pub total_weight: u64, // sum of conviction weights of all voters
 }
 
 #[account]
 #[derive(InitSpace)]
 pub struct Voter {
     pub voted: bool,
+    pub score: u8,
     pub conviction: u8,
     pub locked_amount: u64,
-    pub unlock_time: i64, // Unix timestamp when tokens can be unlocked
+    pub unlock_time: i64,     // Unix timestamp when tokens can be unlocked
+    pub pending_release: u64, // tokens freed by lowering or retracting the vote
+    pub release_time: i64,    // Unix timestamp when pending_release can be withdrawn
+    pub reputation_settled: bool,
 }
@@ -1,5 +1,13 @@
+pub mod batch_auction_state;
+pub mod clmm_state;
+pub mod config_state;
 pub mod conviction_state;
+pub mod oracle_state;
+pub mod order_book_state;
 pub mod poll_state;
 pub mod pool_state;
+pub mod reputation_state;
+pub mod reward_state;
+pub mod sealed_offer_state;
 pub mod swap_state;
 pub mod vote_states;
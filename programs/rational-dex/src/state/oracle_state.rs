@@ -0,0 +1,34 @@
+use anchor_lang::prelude::*;
+
+use crate::OBSERVATION_CAPACITY;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, InitSpace)]
+pub struct Observation {
+    pub timestamp: i64,
+    /// Sum of market price × seconds since pool creation
+    pub market_price_cumulative: u128,
+    /// Sum of consensus price × seconds since pool creation
+    pub consensus_price_cumulative: u128,
+}
+
+/// Ring buffer of accumulator snapshots used to compute TWAPs.
+#[account]
+#[derive(InitSpace)]
+pub struct ObservationBuffer {
+    pub pool: Pubkey,
+    pub observations: [Observation; OBSERVATION_CAPACITY],
+    /// Slot of the most recent observation
+    pub head: u16,
+    /// Number of slots written so far, saturates at OBSERVATION_CAPACITY
+    pub len: u16,
+    pub bump: u8,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+pub struct TwapResult {
+    pub market_price: u64,
+    pub consensus_price: u64,
+    /// Actual window covered, in seconds
+    pub window: i64,
+    pub timestamp: i64,
+}
@@ -0,0 +1,116 @@
+use anchor_lang::prelude::*;
+
+use crate::ORDER_BOOK_CAPACITY;
+
+#[account]
+#[derive(InitSpace)]
+pub struct Market {
+    pub base_mint: Pubkey,
+    pub quote_mint: Pubkey,
+    pub base_vault: Pubkey,
+    pub quote_vault: Pubkey,
+    pub order_book: Pubkey,
+    pub bump: u8,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct OpenOrders {
+    pub market: Pubkey,
+    pub owner: Pubkey,
+    /// Balances that can be withdrawn with `settle_funds`
+    pub base_free: u64,
+    pub quote_free: u64,
+    /// Balances reserved by resting orders
+    pub base_locked: u64,
+    pub quote_locked: u64,
+    pub bump: u8,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub enum Side {
+    Bid,
+    Ask,
+}
+
+/// Bids and asks of one market. Too large to be created by the program (see
+/// `docs/zero-copy-risk.md`), so the client allocates it and `create_market` claims it.
+#[account(zero_copy)]
+pub struct OrderBook {
+    pub market: Pubkey,
+    pub next_order_id: u64,
+    pub bids: SortedOrders,
+    pub asks: SortedOrders,
+}
+
+/// One side of the book: up to `ORDER_BOOK_CAPACITY` orders in a flat array kept sorted by
+/// price-time priority, best order first. Not a slab or critbit tree, so inserting and removing
+/// shift the orders behind the position and finding an order scans the side, all O(n).
+/// `place_order` fails with `OrderBookFull` once a side holds `ORDER_BOOK_CAPACITY` orders.
+#[zero_copy]
+pub struct SortedOrders {
+    pub len: u64,
+    pub orders: [Order; ORDER_BOOK_CAPACITY],
+}
+
+#[zero_copy]
+pub struct Order {
+    pub order_id: u64,
+    /// `OpenOrders` account of the maker
+    pub open_orders: Pubkey,
+    /// Quote atoms per base atom, scaled by PRICE_SCALE
+    pub price: u64,
+    /// Remaining base quantity
+    pub quantity: u64,
+    /// Funds still reserved by this order: quote atoms for bids, base atoms for asks
+    pub locked: u64,
+}
+
+impl SortedOrders {
+    pub fn orders(&self) -> &[Order] {
+        &self.orders[..self.len as usize]
+    }
+
+    pub fn best(&self) -> Option<&Order> {
+        self.orders().first()
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.len as usize == ORDER_BOOK_CAPACITY
+    }
+
+    /// Inserts after every order with the same or a better price, so earlier orders fill first.
+    pub fn insert(&mut self, order: Order, side: Side) -> bool {
+        if self.is_full() {
+            return false;
+        }
+        let position = self
+            .orders()
+            .iter()
+            .position(|resting| match side {
+                Side::Bid => order.price > resting.price,
+                Side::Ask => order.price < resting.price,
+            })
+            .unwrap_or(self.len as usize);
+
+        let len = self.len as usize;
+        self.orders.copy_within(position..len, position + 1);
+        self.orders[position] = order;
+        self.len += 1;
+        true
+    }
+
+    pub fn remove(&mut self, index: usize) -> Order {
+        let len = self.len as usize;
+        let order = self.orders[index];
+        self.orders.copy_within(index + 1..len, index);
+        self.len -= 1;
+        order
+    }
+
+    pub fn find(&self, order_id: u64) -> Option<usize> {
+        self.orders()
+            .iter()
+            .position(|order| order.order_id == order_id)
+    }
+}
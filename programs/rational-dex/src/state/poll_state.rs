@@ -18,4 +18,8 @@ pub struct PollAccount {
 
     pub mint0: Pubkey,
     pub mint1: Pubkey,
+    /// `price` adjusted by the weighted mean vote, as of the last reveal
+    pub consensus_price: u64,
+    /// When the result was last revealed, 0 if never
+    pub revealed_at: i64,
 }
@@ -13,6 +13,76 @@ pub struct Pool {
     pub fee_numerator: u64,
     pub fee_denominator: u64,
     pub total_lp_supply: u64,
+    /// Protocol share of swap fees, held in the vaults but excluded from the reserves
+    pub protocol_fees_a: u64,
+    pub protocol_fees_b: u64,
     pub consensus_price: u64,
     pub last_price_timestamp: u64,
+    /// Reserve price after the last swap or liquidity change, scaled by PRICE_SCALE
+    pub last_market_price: u64,
+    pub market_price_cumulative: u128,
+    pub consensus_price_cumulative: u128,
+    pub last_cumulative_update: i64,
+    pub blend_policy: BlendPolicy,
+    /// Blend of market and consensus price that offers and pools can quote from
+    pub reference_price: u64,
+    pub circuit_breaker_tripped: bool,
+    /// Reentrancy guard, held from `flash_borrow` until the matching `flash_repay`
+    pub locked: bool,
+    /// Outstanding flash loan, owed back together with its fee
+    pub flash_loan_amount: u64,
+    pub flash_loan_token_a: bool,
+    /// LP share of fees earned per LP token since creation, Q64.64 and wrapping
+    pub lp_fee_growth_a_x64: u128,
+    pub lp_fee_growth_b_x64: u128,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub struct BlendPolicy {
+    /// Share of the consensus price in the reference price, the rest is market price
+    pub consensus_weight_bps: u16,
+    /// Maximum distance of the reference price from the consensus price
+    pub max_deviation_bps: u16,
+    /// Market/consensus divergence that triggers the circuit breaker event
+    pub circuit_breaker_bps: u16,
+    /// Also reject swaps that leave `circuit_breaker_bps` around the reference price. Off by
+    /// default: a stale consensus price would otherwise let the pool trade one way only.
+    pub reject_outside_band: bool,
+}
+
+/// An owner's liquidity in a pool, tracked for reporting. LP tokens received by transfer
+/// have no deposit basis until they are withdrawn.
+#[account]
+#[derive(InitSpace)]
+pub struct LpPosition {
+    pub pool: Pubkey,
+    pub owner: Pubkey,
+    pub lp_amount: u64,
+    /// Tokens deposited for the LP tokens still held; withdrawals reduce it pro rata
+    pub deposited_a: u64,
+    pub deposited_b: u64,
+    /// Pool fee growth when the position last settled its fees
+    pub fee_growth_a_checkpoint_x64: u128,
+    pub fee_growth_b_checkpoint_x64: u128,
+    /// Fees earned up to the last checkpoint. They stay in the reserves and are paid out
+    /// through withdrawals.
+    pub fees_earned_a: u64,
+    pub fees_earned_b: u64,
+    pub opened_at: i64,
+    pub updated_at: i64,
+    pub bump: u8,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+pub struct PositionValue {
+    pub lp_amount: u64,
+    /// Tokens the position would withdraw at the current reserves
+    pub amount_a: u64,
+    pub amount_b: u64,
+    pub deposited_a: u64,
+    pub deposited_b: u64,
+    /// Fees earned so far, including those since the last checkpoint
+    pub fees_earned_a: u64,
+    pub fees_earned_b: u64,
+    pub timestamp: i64,
 }
@@ -0,0 +1,15 @@
+use anchor_lang::prelude::*;
+
+#[account]
+#[derive(InitSpace)]
+pub struct Reputation {
+    pub wallet: Pubkey,
+    /// Reputation points, decayed lazily on every update
+    pub score: u64,
+    /// Number of finalized proposals authored by this wallet
+    pub proposals_scored: u64,
+    /// Number of conviction votes settled against a final score
+    pub votes_scored: u64,
+    pub last_updated: i64,
+    pub bump: u8,
+}
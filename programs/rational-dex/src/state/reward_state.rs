@@ -0,0 +1,47 @@
+use anchor_lang::prelude::*;
+
+/// Streams `reward_mint` to the LP tokens staked for `pool` and to conviction voters. Each side
+/// receives its share of the stream pro rata by weight.
+#[account]
+#[derive(InitSpace)]
+pub struct RewardProgram {
+    pub pool: Pubkey,
+    pub reward_mint: Pubkey,
+    pub reward_vault: Pubkey,
+    /// Holds the staked LP tokens
+    pub lp_vault: Pubkey,
+    /// Share of the stream going to LP stakers, the rest goes to voters
+    pub lp_share_bps: u16,
+    /// Reward tokens streamed per second until `period_end`
+    pub reward_rate: u64,
+    pub period_end: i64,
+    pub last_update: i64,
+    /// Staked LP tokens
+    pub lp_total_weight: u64,
+    /// Conviction weight of the registered votes
+    pub vote_total_weight: u64,
+    /// Rewards per unit of weight since creation, Q64.64
+    pub reward_per_lp_x64: u128,
+    pub reward_per_vote_x64: u128,
+    pub bump: u8,
+}
+
+/// An owner's weight in a reward program and the rewards it earned.
+#[account]
+#[derive(InitSpace)]
+pub struct RewardStake {
+    pub reward_program: Pubkey,
+    pub owner: Pubkey,
+    pub lp_staked: u64,
+    /// Proposal of the registered conviction vote
+    pub vote_proposal: Pubkey,
+    pub vote_weight: u64,
+    /// Unlock time of the registered vote, after which its weight earns nothing
+    pub vote_expires_at: i64,
+    /// Program rewards per unit of weight when the stake last settled
+    pub reward_per_lp_checkpoint_x64: u128,
+    pub reward_per_vote_checkpoint_x64: u128,
+    pub rewards_owed: u64,
+    pub settled_at: i64,
+    pub bump: u8,
+}
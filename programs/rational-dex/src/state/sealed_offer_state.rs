@@ -0,0 +1,47 @@
+use anchor_lang::prelude::*;
+
+#[account]
+#[derive(InitSpace)]
+pub struct SealedOffer {
+    /// PDA bump seed
+    pub bump: u8,
+    /// Maker's ask encrypted for the MXE. Kept right after the bump so MPC can read it.
+    pub ask_state: [[u8; 32]; 1],
+    /// Nonce of `ask_state`
+    pub nonce: u128,
+    pub id: u64,
+    pub maker: Pubkey,
+    pub token_mint_a: Pubkey,
+    pub token_mint_b: Pubkey,
+    /// Token A escrowed for the whole offer
+    pub offered_amount: u64,
+    pub status: SealedOfferStatus,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub enum SealedOfferStatus {
+    /// Waiting for the ask to be encrypted for the MXE
+    Pending,
+    Open,
+    Filled,
+    Cancelled,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct SealedBid {
+    pub sealed_offer: Pubkey,
+    pub taker: Pubkey,
+    /// Token B escrowed by the taker. An upper bound on the bid, not the bid itself.
+    pub deposit: u64,
+    pub status: SealedBidStatus,
+    pub settlement_amount: u64,
+    pub bump: u8,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub enum SealedBidStatus {
+    Pending,
+    Matched,
+    Rejected,
+}
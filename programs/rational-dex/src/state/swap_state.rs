@@ -8,11 +8,42 @@ pub struct Offer {
     pub maker: Pubkey,
     pub token_mint_a: Pubkey,
     pub token_mint_b: Pubkey,
-    pub token_b_wanted_amount: u64, // Its calculated automatically from the token A amount and the swap rate
+    pub offered_amount: u64,
+    pub token_b_wanted_amount: u64, // For oracle pricing this is only the quote at creation time
     pub remaining_amount: u64,
+    pub pricing: PricingMode,
+    pub access: OfferAccess,
     pub bump: u8,
 }
 
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub enum PricingMode {
+    /// The maker asks `wanted_amount` token B for the whole offer
+    Fixed { wanted_amount: u64 },
+    /// Token B owed follows a price at take time, plus `premium_bps`. The price is the
+    /// reference price of `reference_pool`, a pool of the pair, or that of the `Price` PDAs
+    /// when `None`; it is fixed by the maker so the taker cannot pick the cheaper source.
+    OracleRelative {
+        premium_bps: u16,
+        reference_pool: Option<Pubkey>,
+    },
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub enum OfferAccess {
+    Public,
+    /// Only this wallet can take the offer
+    Taker(Pubkey),
+    /// Takers prove membership with a merkle proof of `sha256(taker)`
+    MerkleRoot([u8; 32]),
+}
+
+impl OfferAccess {
+    pub fn is_private(&self) -> bool {
+        *self != OfferAccess::Public
+    }
+}
+
 #[account]
 #[derive(InitSpace)]
 pub struct Price {
@@ -0,0 +1,334 @@
+use rational_dex::state::oracle_state::{Observation, ObservationBuffer};
+use rational_dex::state::pool_state::{BlendPolicy, Pool};
+use rational_dex::OBSERVATION_CAPACITY;
+use rational_dex_client::{instructions, PROGRAM_ID};
+
+use anchor_spl::token_2022::spl_token_2022::{
+    self,
+    extension::{
+        transfer_fee::{TransferFee, TransferFeeAmount, TransferFeeConfig},
+        BaseStateWithExtensionsMut, ExtensionType, StateWithExtensionsMut,
+    },
+    state::{Account as Token2022Account, AccountState as Token2022State, Mint as Token2022Mint},
+};
+
+use {
+    litesvm::LiteSVM,
+    solana_account::Account,
+    solana_instruction::error::InstructionError,
+    solana_keypair::Keypair,
+    solana_message::{Message, VersionedMessage},
+    solana_program::{bpf_loader_upgradeable, clock::Clock},
+    solana_program_option::COption,
+    solana_program_pack::Pack,
+    solana_pubkey::Pubkey,
+    solana_sdk::transaction::TransactionError,
+    solana_signer::Signer,
+    solana_transaction::versioned::VersionedTransaction,
+    spl_token::{
+        native_mint,
+        state::{Account as TokenAccount, AccountState, Mint},
+        ID as TOKEN_PROGRAM_ID,
+    },
+};
+
+const PROGRAM_BYTES: &[u8] = include_bytes!("../../target/deploy/rational_dex.so");
+
+/// Deploys the program through the upgradeable loader with `upgrade_authority`, which
+/// `initialize_config` requires to sign.
+pub fn deploy(svm: &mut LiteSVM, upgrade_authority: Pubkey) {
+    let program_data = rational_dex_client::pda::program_data();
+
+    // Bincode layout of `UpgradeableLoaderState::ProgramData { slot: 0, upgrade_authority }`,
+    // followed by the ELF
+    let mut data = 3u32.to_le_bytes().to_vec();
+    data.extend_from_slice(&0u64.to_le_bytes());
+    data.push(1);
+    data.extend_from_slice(upgrade_authority.as_ref());
+    data.extend_from_slice(PROGRAM_BYTES);
+    set_loader_account(svm, program_data, data, false);
+
+    // `UpgradeableLoaderState::Program { programdata_address }`. The ProgramData account has
+    // to exist first, LiteSVM loads the ELF from it when the program account is written.
+    let mut data = 2u32.to_le_bytes().to_vec();
+    data.extend_from_slice(program_data.as_ref());
+    set_loader_account(svm, PROGRAM_ID, data, true);
+}
+
+fn set_loader_account(svm: &mut LiteSVM, address: Pubkey, data: Vec<u8>, executable: bool) {
+    svm.set_account(
+        address,
+        Account {
+            lamports: svm.minimum_balance_for_rent_exemption(data.len()),
+            data,
+            owner: bpf_loader_upgradeable::ID,
+            executable,
+            rent_epoch: 0,
+        },
+    )
+    .unwrap();
+}
+
+/// Signs `ixs` with `signers`, the first of which pays, and sends them.
+pub fn send(
+    svm: &mut LiteSVM,
+    ixs: &[solana_instruction::Instruction],
+    signers: &[&Keypair],
+) -> litesvm::types::TransactionResult {
+    let blockhash = svm.latest_blockhash();
+    let msg = Message::new_with_blockhash(ixs, Some(&signers[0].pubkey()), &blockhash);
+    let tx = VersionedTransaction::try_new(VersionedMessage::Legacy(msg), signers).unwrap();
+    svm.send_transaction(tx)
+}
+
+/// The custom program error `result` failed with, if it failed with one.
+pub fn custom_error(result: litesvm::types::TransactionResult) -> Option<u32> {
+    match result.err()?.err {
+        TransactionError::InstructionError(_, InstructionError::Custom(code)) => Some(code),
+        _ => None,
+    }
+}
+
+/// Creates the `GlobalConfig` PDA with `admin`, the program's upgrade authority, as admin and
+/// returns its address.
+pub fn initialize_config(
+    svm: &mut LiteSVM,
+    admin: &Keypair,
+    fee_recipients: Vec<Pubkey>,
+) -> Pubkey {
+    let ix = instructions::initialize_config(admin.pubkey(), fee_recipients);
+    send(svm, &[ix], &[admin]).unwrap();
+
+    rational_dex_client::pda::config()
+}
+
+/// Moves the clock forward by `seconds`, under a fresh blockhash so a transaction that failed
+/// earlier can be sent again.
+pub fn warp(svm: &mut LiteSVM, seconds: i64) {
+    let mut clock = svm.get_sysvar::<Clock>();
+    clock.unix_timestamp += seconds;
+    svm.set_sysvar(&clock);
+    svm.expire_blockhash();
+}
+
+/// Writes an initialized SPL token mint with 9 decimals at `mint`.
+pub fn set_mint(svm: &mut LiteSVM, mint: Pubkey, authority: Pubkey) {
+    let state = Mint {
+        mint_authority: COption::Some(authority),
+        supply: 0,
+        decimals: 9,
+        is_initialized: true,
+        freeze_authority: COption::None,
+    };
+    let mut data = [0u8; Mint::LEN];
+    Mint::pack(state, &mut data).unwrap();
+    set_token_program_account(svm, mint, data.to_vec());
+}
+
+/// Writes a token account of `owner` holding `amount` of `mint` at `address`.
+pub fn set_token_account(
+    svm: &mut LiteSVM,
+    address: Pubkey,
+    mint: Pubkey,
+    owner: Pubkey,
+    amount: u64,
+) {
+    let state = TokenAccount {
+        mint,
+        owner,
+        amount,
+        delegate: COption::None,
+        state: AccountState::Initialized,
+        is_native: COption::None,
+        delegated_amount: 0,
+        close_authority: COption::None,
+    };
+    let mut data = [0u8; TokenAccount::LEN];
+    TokenAccount::pack(state, &mut data).unwrap();
+    set_token_program_account(svm, address, data.to_vec());
+}
+
+/// Writes a wSOL account of `owner` holding `amount` at `address`, backed by lamports as the
+/// token program expects of native accounts.
+pub fn set_wsol_account(svm: &mut LiteSVM, address: Pubkey, owner: Pubkey, amount: u64) {
+    let rent = svm.minimum_balance_for_rent_exemption(TokenAccount::LEN);
+    let state = TokenAccount {
+        mint: native_mint::ID,
+        owner,
+        amount,
+        delegate: COption::None,
+        state: AccountState::Initialized,
+        is_native: COption::Some(rent),
+        delegated_amount: 0,
+        close_authority: COption::None,
+    };
+    let mut data = [0u8; TokenAccount::LEN];
+    TokenAccount::pack(state, &mut data).unwrap();
+    svm.set_account(
+        address,
+        Account {
+            lamports: rent + amount,
+            data: data.to_vec(),
+            owner: TOKEN_PROGRAM_ID,
+            executable: false,
+            rent_epoch: 0,
+        },
+    )
+    .unwrap();
+}
+
+/// Writes a Token-2022 mint with 9 decimals at `mint` that withholds `fee_bps` of every
+/// transfer, without a maximum.
+pub fn set_fee_mint(svm: &mut LiteSVM, mint: Pubkey, authority: Pubkey, fee_bps: u16) {
+    let len = ExtensionType::try_calculate_account_len::<Token2022Mint>(&[
+        ExtensionType::TransferFeeConfig,
+    ])
+    .unwrap();
+    let mut data = vec![0; len];
+    let mut state =
+        StateWithExtensionsMut::<Token2022Mint>::unpack_uninitialized(&mut data).unwrap();
+    let config = state.init_extension::<TransferFeeConfig>(true).unwrap();
+    let fee = TransferFee {
+        epoch: 0.into(),
+        maximum_fee: u64::MAX.into(),
+        transfer_fee_basis_points: fee_bps.into(),
+    };
+    config.older_transfer_fee = fee;
+    config.newer_transfer_fee = fee;
+    state.base = Token2022Mint {
+        mint_authority: COption::Some(authority),
+        supply: 0,
+        decimals: 9,
+        is_initialized: true,
+        freeze_authority: COption::None,
+    };
+    state.pack_base();
+    state.init_account_type().unwrap();
+    set_owned_account(svm, mint, data, spl_token_2022::ID);
+}
+
+/// Writes a Token-2022 account of `owner` holding `amount` of the fee mint `mint`.
+pub fn set_fee_token_account(
+    svm: &mut LiteSVM,
+    address: Pubkey,
+    mint: Pubkey,
+    owner: Pubkey,
+    amount: u64,
+) {
+    let len = ExtensionType::try_calculate_account_len::<Token2022Account>(&[
+        ExtensionType::TransferFeeAmount,
+    ])
+    .unwrap();
+    let mut data = vec![0; len];
+    let mut state =
+        StateWithExtensionsMut::<Token2022Account>::unpack_uninitialized(&mut data).unwrap();
+    state.init_extension::<TransferFeeAmount>(true).unwrap();
+    state.base = Token2022Account {
+        mint,
+        owner,
+        amount,
+        delegate: COption::None,
+        state: Token2022State::Initialized,
+        is_native: COption::None,
+        delegated_amount: 0,
+        close_authority: COption::None,
+    };
+    state.pack_base();
+    state.init_account_type().unwrap();
+    set_owned_account(svm, address, data, spl_token_2022::ID);
+}
+
+/// Token balance held by the token account at `address`, under either token program.
+pub fn token_balance(svm: &LiteSVM, address: &Pubkey) -> u64 {
+    let account = svm.get_account(address).unwrap();
+    TokenAccount::unpack(&account.data[..TokenAccount::LEN])
+        .unwrap()
+        .amount
+}
+
+fn set_token_program_account(svm: &mut LiteSVM, address: Pubkey, data: Vec<u8>) {
+    set_owned_account(svm, address, data, TOKEN_PROGRAM_ID);
+}
+
+fn set_owned_account(svm: &mut LiteSVM, address: Pubkey, data: Vec<u8>, owner: Pubkey) {
+    svm.set_account(
+        address,
+        Account {
+            lamports: 1_000_000_000,
+            data,
+            owner,
+            executable: false,
+            rent_epoch: 0,
+        },
+    )
+    .unwrap();
+}
+
+/// Writes `state`, discriminator included, as a program-owned account at `address`.
+pub fn set_program_account<T: anchor_lang::AccountSerialize>(
+    svm: &mut LiteSVM,
+    address: Pubkey,
+    state: &T,
+) {
+    let mut data = Vec::new();
+    state.try_serialize(&mut data).unwrap();
+    svm.set_account(
+        address,
+        Account {
+            lamports: svm.minimum_balance_for_rent_exemption(data.len()),
+            data,
+            owner: PROGRAM_ID,
+            executable: false,
+            rent_epoch: 0,
+        },
+    )
+    .unwrap();
+}
+
+/// A pool last priced at `market` and `consensus`, with accumulators started at `start`.
+pub fn pool_state(market: u64, consensus: u64, start: i64) -> Pool {
+    Pool {
+        authority: Pubkey::default(),
+        vault_a: Pubkey::default(),
+        vault_b: Pubkey::default(),
+        mint_lp: Pubkey::default(),
+        mint_a: Pubkey::default(),
+        mint_b: Pubkey::default(),
+        bump: 0,
+        fee_numerator: 0,
+        fee_denominator: 1,
+        total_lp_supply: 0,
+        protocol_fees_a: 0,
+        protocol_fees_b: 0,
+        consensus_price: consensus,
+        last_price_timestamp: 0,
+        last_market_price: market,
+        market_price_cumulative: 0,
+        consensus_price_cumulative: 0,
+        last_cumulative_update: start,
+        blend_policy: BlendPolicy {
+            consensus_weight_bps: 0,
+            max_deviation_bps: 0,
+            circuit_breaker_bps: 0,
+            reject_outside_band: false,
+        },
+        reference_price: 0,
+        circuit_breaker_tripped: false,
+        locked: false,
+        flash_loan_amount: 0,
+        flash_loan_token_a: false,
+        lp_fee_growth_a_x64: 0,
+        lp_fee_growth_b_x64: 0,
+    }
+}
+
+/// An observation buffer nothing has been written to.
+pub fn observation_buffer() -> ObservationBuffer {
+    ObservationBuffer {
+        pool: Pubkey::default(),
+        observations: [Observation::default(); OBSERVATION_CAPACITY],
+        head: 0,
+        len: 0,
+        bump: 0,
+    }
+}
@@ -1,4 +1,40 @@
 #[cfg(test)]
+mod common;
+#[cfg(test)]
+mod test_batch_auction;
+#[cfg(test)]
+mod test_circuits;
+#[cfg(test)]
+mod test_cli;
+#[cfg(test)]
+mod test_clmm_math;
+#[cfg(test)]
+mod test_config;
+#[cfg(test)]
 mod test_conviction;
 #[cfg(test)]
+mod test_flash_loan;
+#[cfg(test)]
+mod test_indexer;
+#[cfg(test)]
 mod test_initialize;
+#[cfg(test)]
+mod test_lp_position;
+#[cfg(test)]
+mod test_offer_access;
+#[cfg(test)]
+mod test_offer_pricing;
+#[cfg(test)]
+mod test_order_book;
+#[cfg(test)]
+mod test_pool;
+#[cfg(test)]
+mod test_pool_math;
+#[cfg(test)]
+mod test_reference_price;
+#[cfg(test)]
+mod test_reward_math;
+#[cfg(test)]
+mod test_sealed_offer;
+#[cfg(test)]
+mod test_twap;
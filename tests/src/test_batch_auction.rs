@@ -0,0 +1,207 @@
+use rational_dex::instructions::batch_auction::{clear_epoch, fill_amounts, is_expired};
+use rational_dex::state::batch_auction_state::Epoch;
+use rational_dex::state::order_book_state::Side;
+use rational_dex::state::poll_state::PollAccount;
+use rational_dex::{EPOCH_CLEARING_TIMEOUT, PRICE_SCALE};
+use rational_dex_client::instructions::{self, EpochKeys};
+use rational_dex_client::pda;
+
+use {
+    litesvm::LiteSVM, solana_keypair::Keypair, solana_program::clock::Clock, solana_pubkey::Pubkey,
+    solana_signer::Signer, spl_token::ID as TOKEN_PROGRAM_ID,
+};
+
+use crate::common::{
+    deploy, initialize_config, send, set_mint, set_program_account, set_token_account,
+    token_balance, warp,
+};
+
+const SCALE: u64 = PRICE_SCALE as u64;
+const WALLET_TOKENS: u64 = 1_000_000;
+const DURATION: i64 = 100;
+
+fn closed_epoch(total_buy_quote: u64, total_sell_base: u64, price: u64) -> Epoch {
+    let (matched_base, matched_quote) =
+        clear_epoch(total_buy_quote, total_sell_base, price).unwrap();
+    Epoch {
+        poll: Pubkey::default(),
+        id: 0,
+        base_mint: Pubkey::default(),
+        quote_mint: Pubkey::default(),
+        started_at: 0,
+        ends_at: DURATION,
+        total_buy_quote,
+        total_sell_base,
+        clearing_price: price,
+        matched_base,
+        matched_quote,
+        closed: true,
+        bump: 0,
+    }
+}
+
+#[test]
+fn test_clearing_fills_the_lighter_side() {
+    // Buyers want 1_500 base at a price of 2, sellers only have 1_000
+    assert_eq!(
+        clear_epoch(3_000, 1_000, 2 * SCALE).unwrap(),
+        (1_000, 2_000)
+    );
+    // Buyers only want 500 base
+    assert_eq!(clear_epoch(1_000, 1_000, 2 * SCALE).unwrap(), (500, 1_000));
+    assert_eq!(clear_epoch(0, 1_000, 2 * SCALE).unwrap(), (0, 0));
+}
+
+#[test]
+fn test_pro_rata_fills_never_pay_out_more_than_matched() {
+    let epoch = closed_epoch(3_000, 1_000, 2 * SCALE);
+
+    let mut received_base = 0;
+    let mut refunded_quote = 0;
+    for amount in [1_000, 2_000] {
+        let (base, quote) = fill_amounts(&epoch, Side::Bid, amount).unwrap();
+        received_base += base;
+        refunded_quote += quote;
+    }
+    // 333 + 666 base; 2_000 quote spent rounds up to 667 + 1_334
+    assert_eq!(received_base, 999);
+    assert_eq!(refunded_quote, 999);
+    assert!(received_base <= epoch.matched_base);
+    assert!(3_000 - refunded_quote >= epoch.matched_quote);
+
+    // The only seller is filled completely
+    assert_eq!(fill_amounts(&epoch, Side::Ask, 1_000).unwrap(), (0, 2_000));
+}
+
+#[test]
+fn test_uncleared_epoch_refunds_deposits() {
+    let mut epoch = closed_epoch(3_000, 1_000, 2 * SCALE);
+    epoch.closed = false;
+    assert_eq!(fill_amounts(&epoch, Side::Bid, 1_000).unwrap(), (0, 1_000));
+    assert_eq!(fill_amounts(&epoch, Side::Ask, 1_000).unwrap(), (1_000, 0));
+}
+
+#[test]
+fn test_epoch_expires_after_the_clearing_timeout() {
+    let mut epoch = closed_epoch(0, 0, SCALE);
+    epoch.closed = false;
+    let deadline = DURATION + EPOCH_CLEARING_TIMEOUT;
+    assert!(!is_expired(&epoch, deadline - 1).unwrap());
+    assert!(is_expired(&epoch, deadline).unwrap());
+
+    epoch.closed = true;
+    assert!(!is_expired(&epoch, deadline).unwrap());
+}
+
+/// Writes the poll an epoch clears against, priced at 2 and revealed at `revealed_at`.
+fn set_poll(svm: &mut LiteSVM, keys: &EpochKeys, revealed_at: i64) {
+    let poll = PollAccount {
+        bump: 0,
+        vote_state: [[0; 32]; 7],
+        id: 0,
+        authority: Pubkey::default(),
+        nonce: 0,
+        price: 2 * SCALE,
+        mint0: keys.base_mint,
+        mint1: keys.quote_mint,
+        consensus_price: 2 * SCALE,
+        revealed_at,
+    };
+    set_program_account(svm, keys.poll, &poll);
+}
+
+/// An open epoch with a buyer who deposited 3_000 quote and a seller who deposited 1_000 base.
+fn setup() -> (LiteSVM, EpochKeys, Keypair, Keypair) {
+    let admin = Keypair::new();
+    let mut svm = LiteSVM::new();
+    deploy(&mut svm, admin.pubkey());
+    svm.airdrop(&admin.pubkey(), 10_000_000_000).unwrap();
+    initialize_config(&mut svm, &admin, vec![]);
+
+    let keys = EpochKeys {
+        poll: Pubkey::new_unique(),
+        id: 1,
+        base_mint: Pubkey::new_unique(),
+        quote_mint: Pubkey::new_unique(),
+        token_program: TOKEN_PROGRAM_ID,
+    };
+    set_mint(&mut svm, keys.base_mint, admin.pubkey());
+    set_mint(&mut svm, keys.quote_mint, admin.pubkey());
+    set_poll(&mut svm, &keys, 0);
+    let ix = instructions::open_epoch(admin.pubkey(), &keys, DURATION);
+    send(&mut svm, &[ix], &[&admin]).unwrap();
+
+    let [buyer, seller] = [Keypair::new(), Keypair::new()];
+    for trader in [&buyer, &seller] {
+        svm.airdrop(&trader.pubkey(), 10_000_000_000).unwrap();
+        for mint in [keys.base_mint, keys.quote_mint] {
+            let address = pda::ata(&trader.pubkey(), &mint, &keys.token_program);
+            set_token_account(&mut svm, address, mint, trader.pubkey(), WALLET_TOKENS);
+        }
+    }
+    let ix = instructions::submit_intent(buyer.pubkey(), &keys, Side::Bid, 3_000);
+    send(&mut svm, &[ix], &[&buyer]).unwrap();
+    let ix = instructions::submit_intent(seller.pubkey(), &keys, Side::Ask, 1_000);
+    send(&mut svm, &[ix], &[&seller]).unwrap();
+
+    (svm, keys, buyer, seller)
+}
+
+fn balance(svm: &LiteSVM, trader: &Keypair, mint: &Pubkey) -> u64 {
+    token_balance(svm, &pda::ata(&trader.pubkey(), mint, &TOKEN_PROGRAM_ID))
+}
+
+#[test]
+fn test_epoch_clears_only_on_a_consensus_revealed_after_it_ended() {
+    let (mut svm, keys, buyer, seller) = setup();
+    let ends_at = svm.get_sysvar::<Clock>().unix_timestamp + DURATION;
+    warp(&mut svm, DURATION);
+
+    // Revealed while intents were still accepted, so it could have been traded on
+    set_poll(&mut svm, &keys, ends_at - 1);
+    assert!(send(&mut svm, &[instructions::close_epoch(&keys)], &[&buyer]).is_err());
+
+    set_poll(&mut svm, &keys, ends_at);
+    svm.expire_blockhash();
+    send(&mut svm, &[instructions::close_epoch(&keys)], &[&buyer]).unwrap();
+
+    for trader in [&buyer, &seller] {
+        let ix = instructions::claim_fill(trader.pubkey(), &keys);
+        send(&mut svm, &[ix], &[trader]).unwrap();
+    }
+    assert_eq!(
+        balance(&svm, &buyer, &keys.base_mint),
+        WALLET_TOKENS + 1_000
+    );
+    assert_eq!(
+        balance(&svm, &buyer, &keys.quote_mint),
+        WALLET_TOKENS - 2_000
+    );
+    assert_eq!(
+        balance(&svm, &seller, &keys.quote_mint),
+        WALLET_TOKENS + 2_000
+    );
+}
+
+#[test]
+fn test_epoch_never_cleared_refunds_after_the_timeout() {
+    let (mut svm, keys, buyer, seller) = setup();
+
+    let claim = instructions::claim_fill(buyer.pubkey(), &keys);
+    warp(&mut svm, DURATION);
+    assert!(send(&mut svm, &[claim.clone()], &[&buyer]).is_err());
+
+    warp(&mut svm, EPOCH_CLEARING_TIMEOUT);
+    let now = svm.get_sysvar::<Clock>().unix_timestamp;
+    set_poll(&mut svm, &keys, now);
+    // Too late to clear, even with a fresh consensus
+    assert!(send(&mut svm, &[instructions::close_epoch(&keys)], &[&seller]).is_err());
+
+    send(&mut svm, &[claim], &[&buyer]).unwrap();
+    let ix = instructions::claim_fill(seller.pubkey(), &keys);
+    send(&mut svm, &[ix], &[&seller]).unwrap();
+    for trader in [&buyer, &seller] {
+        assert_eq!(balance(&svm, trader, &keys.base_mint), WALLET_TOKENS);
+        assert_eq!(balance(&svm, trader, &keys.quote_mint), WALLET_TOKENS);
+    }
+}
@@ -0,0 +1,164 @@
+use std::cmp::Ordering;
+
+use encrypted_ixs::reference::{self, VOTE_OPTIONS};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use rational_dex::instructions::reveal::consensus_price;
+
+/// The voting circuits of `encrypted-ixs/src/lib.rs` with `Enc` taken away, since Arcis
+/// circuits only run inside an MXE. Keep the bodies in step with the circuits; the tests
+/// check them against the separately written `reference`.
+mod circuits {
+    #[derive(Clone)]
+    pub struct VoteStats {
+        minus_three: u64,
+        minus_two: u64,
+        minus_one: u64,
+        zero: u64,
+        one: u64,
+        two: u64,
+        three: u64,
+    }
+
+    pub struct UserVote {
+        pub vote_idx: u8,
+    }
+
+    pub fn init_vote_stats() -> VoteStats {
+        VoteStats {
+            minus_three: 0,
+            minus_two: 0,
+            minus_one: 0,
+            zero: 0,
+            one: 0,
+            two: 0,
+            three: 0,
+        }
+    }
+
+    /// `None` where the circuit's index is out of bounds and the computation aborts.
+    pub fn vote(user_vote: UserVote, weight: u64, mut vote_stats: VoteStats) -> Option<VoteStats> {
+        let mut counts = [
+            vote_stats.minus_three,
+            vote_stats.minus_two,
+            vote_stats.minus_one,
+            vote_stats.zero,
+            vote_stats.one,
+            vote_stats.two,
+            vote_stats.three,
+        ];
+
+        *counts.get_mut(user_vote.vote_idx as usize)? += weight;
+
+        vote_stats.minus_three = counts[0];
+        vote_stats.minus_two = counts[1];
+        vote_stats.minus_one = counts[2];
+        vote_stats.zero = counts[3];
+        vote_stats.one = counts[4];
+        vote_stats.two = counts[5];
+        vote_stats.three = counts[6];
+
+        Some(vote_stats)
+    }
+
+    pub fn reveal_result(vote_stats: VoteStats) -> [u64; 7] {
+        [
+            vote_stats.minus_three,
+            vote_stats.minus_two,
+            vote_stats.minus_one,
+            vote_stats.zero,
+            vote_stats.one,
+            vote_stats.two,
+            vote_stats.three,
+        ]
+    }
+}
+
+const SEED: u64 = 0x5eed;
+
+/// A vote index, out of range about one time in four.
+fn vote_idx(rng: &mut StdRng) -> u8 {
+    if rng.gen_ratio(1, 4) {
+        rng.gen_range(VOTE_OPTIONS as u8..=u8::MAX)
+    } else {
+        rng.gen_range(0..VOTE_OPTIONS as u8)
+    }
+}
+
+#[test]
+fn test_circuits_match_reference() {
+    let mut rng = StdRng::seed_from_u64(SEED);
+    for _ in 0..200 {
+        let mut stats = circuits::init_vote_stats();
+        let mut expected = reference::init_vote_stats();
+        for _ in 0..rng.gen_range(0..50) {
+            let vote_idx = vote_idx(&mut rng);
+            // Reputation weights stay far below what a poll could overflow
+            let weight = rng.gen_range(0..=u32::MAX as u64);
+            let voted = circuits::vote(circuits::UserVote { vote_idx }, weight, stats.clone());
+            let reference = reference::vote(vote_idx, weight, expected);
+            assert_eq!(voted.is_some(), reference.is_some());
+            // An aborted computation leaves the poll's tally as it was
+            if let (Some(voted), Some(reference)) = (voted, reference) {
+                stats = voted;
+                expected = reference;
+            }
+        }
+        assert_eq!(
+            circuits::reveal_result(stats),
+            reference::reveal_result(expected)
+        );
+    }
+}
+
+#[test]
+fn test_circuits_abort_on_out_of_range_vote() {
+    let vote = |vote_idx, stats| circuits::vote(circuits::UserVote { vote_idx }, 100, stats);
+    for vote_idx in [7, 8, u8::MAX] {
+        assert!(vote(vote_idx, circuits::init_vote_stats()).is_none());
+        assert_eq!(
+            reference::vote(vote_idx, 100, reference::init_vote_stats()),
+            None
+        );
+    }
+    let stats = vote(6, circuits::init_vote_stats()).unwrap();
+    assert_eq!(circuits::reveal_result(stats), [0, 0, 0, 0, 0, 0, 100]);
+}
+
+#[test]
+fn test_reference_consensus_price() {
+    let mut rng = StdRng::seed_from_u64(SEED);
+    let price = 1_000_000;
+    for _ in 0..200 {
+        let mut counts = reference::init_vote_stats();
+        let mut steps: i128 = 0;
+        let mut total: u128 = 0;
+        for _ in 0..rng.gen_range(0..20) {
+            let vote_idx = vote_idx(&mut rng);
+            let weight = rng.gen_range(1..=1_000);
+            counts = reference::vote(vote_idx, weight, counts).unwrap_or(counts);
+            if (vote_idx as usize) < VOTE_OPTIONS {
+                steps += (vote_idx as i128 - 3) * weight as i128;
+                total += weight as u128;
+            }
+        }
+
+        let consensus = consensus_price(price, &reference::reveal_result(counts));
+        match consensus {
+            None => assert_eq!(total, 0),
+            // Aborted votes neither count towards nor move the price
+            Some(consensus) => {
+                assert_eq!(
+                    counts.iter().map(|&count| count as u128).sum::<u128>(),
+                    total
+                );
+                match steps.cmp(&0) {
+                    Ordering::Less => assert!(consensus < price),
+                    Ordering::Equal => assert_eq!(consensus, price),
+                    // A slightly positive mean can round back down to the price
+                    Ordering::Greater => assert!(consensus >= price),
+                }
+            }
+        }
+    }
+}
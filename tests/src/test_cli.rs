@@ -0,0 +1,85 @@
+use litesvm::LiteSVM;
+use rational_dex_cli::backend::LiteSvmBackend;
+use rational_dex_cli::Session;
+use rational_dex_client::instructions::{self, PoolKeys};
+use solana_keypair::Keypair;
+use solana_pubkey::Pubkey;
+use solana_signer::Signer;
+use spl_token::ID as TOKEN_PROGRAM_ID;
+
+use crate::common::{deploy, set_mint};
+
+fn session(dry_run: bool) -> Session<LiteSvmBackend> {
+    let payer = Keypair::new();
+    let mut svm = LiteSVM::new();
+    deploy(&mut svm, payer.pubkey());
+    svm.airdrop(&payer.pubkey(), 10_000_000_000).unwrap();
+
+    let mut session = Session::new(LiteSvmBackend::new(svm), payer, false);
+    let ix = instructions::initialize_config(session.payer.pubkey(), vec![]);
+    session.submit(&[ix]).unwrap();
+    session.dry_run = dry_run;
+    session
+}
+
+#[test]
+fn test_cli_pools() {
+    let mut session = session(false);
+    let keys = PoolKeys {
+        mint_a: Pubkey::new_unique(),
+        mint_b: Pubkey::new_unique(),
+        mint_lp: Pubkey::new_unique(),
+        token_program: TOKEN_PROGRAM_ID,
+    };
+    for mint in [keys.mint_a, keys.mint_b, keys.mint_lp] {
+        set_mint(&mut session.backend.svm, mint, Pubkey::new_unique());
+    }
+
+    session.init_pool(&keys, 3, 1000).unwrap();
+    session.set_price(keys.pool(), 1_000_000).unwrap();
+
+    let pools = session.pools().unwrap();
+    assert_eq!(pools.len(), 1);
+    assert_eq!(pools[0].0, keys.pool());
+    assert_eq!(pools[0].1.consensus_price, 1_000_000);
+}
+
+#[test]
+fn test_cli_proposals() {
+    let mut session = session(false);
+
+    let (first, _) = session
+        .create_proposal(b"evidence", None, 60 * 60, 1)
+        .unwrap();
+    let (second, _) = session
+        .create_proposal(b"more evidence", Some("ipfs://cid".to_string()), 60 * 60, 1)
+        .unwrap();
+    assert_eq!((first, second), (0, 1));
+    assert_eq!(session.proposals().unwrap().len(), 2);
+    assert!(session.offers().unwrap().is_empty());
+}
+
+#[test]
+fn test_cli_dry_run() {
+    let mut session = session(true);
+
+    let outcome = session
+        .create_proposal(b"evidence", None, 60 * 60, 1)
+        .unwrap()
+        .1;
+    assert!(outcome.error.is_none());
+    assert!(session.proposals().unwrap().is_empty());
+
+    // Simulation failures are reported, not raised
+    let outcome = session.vote(
+        &instructions::ProposalKeys {
+            author: session.payer.pubkey(),
+            proposal_count: 0,
+            mint: Pubkey::new_unique(),
+            token_program: TOKEN_PROGRAM_ID,
+        },
+        5,
+        1,
+    );
+    assert!(outcome.unwrap().error.is_some());
+}
@@ -0,0 +1,175 @@
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use rational_dex::instructions::clmm::{
+    amount_a_delta, amount_b_delta, fee_growth, next_sqrt_price_from_a, next_sqrt_price_from_b,
+    sqrt_price_at_tick, swap_step, tick_at_sqrt_price, SwapStep,
+};
+use rational_dex::{MAX_TICK, MIN_TICK};
+use rational_dex_client::instructions::tick_array_start;
+
+const SEED: u64 = 0xc1a0;
+const ONE_X32: u64 = 1 << 32;
+
+#[test]
+fn test_sqrt_price_at_tick_known_values() {
+    assert_eq!(sqrt_price_at_tick(0).unwrap(), ONE_X32);
+    assert_eq!(sqrt_price_at_tick(1).unwrap(), 4_295_182_039);
+    assert_eq!(sqrt_price_at_tick(-1).unwrap(), 4_294_752_563);
+    // The tick range keeps the price within 2^-16..2^16
+    assert_eq!(sqrt_price_at_tick(MIN_TICK).unwrap(), 1 << 16);
+    assert!(sqrt_price_at_tick(MAX_TICK).unwrap() < u64::MAX);
+    assert!(sqrt_price_at_tick(MIN_TICK - 1).is_err());
+    assert!(sqrt_price_at_tick(MAX_TICK + 1).is_err());
+}
+
+#[test]
+fn test_sqrt_price_at_tick_matches_floating_point() {
+    for tick in [-200_000, -50_000, -100, 100, 50_000, 200_000] {
+        let expected = 1.0001f64.powf(tick as f64 / 2.0) * ONE_X32 as f64;
+        let actual = sqrt_price_at_tick(tick).unwrap() as f64;
+        assert!(
+            (actual - expected).abs() <= expected * 1e-8 + 1.0,
+            "tick {tick}: {actual} vs {expected}"
+        );
+    }
+}
+
+#[test]
+fn test_tick_at_sqrt_price_inverts_sqrt_price_at_tick() {
+    let mut rng = StdRng::seed_from_u64(SEED);
+    for _ in 0..200 {
+        let tick = rng.gen_range(MIN_TICK..MAX_TICK);
+        let sqrt_price = sqrt_price_at_tick(tick).unwrap();
+        assert!(sqrt_price < sqrt_price_at_tick(tick + 1).unwrap());
+        assert_eq!(tick_at_sqrt_price(sqrt_price).unwrap(), tick);
+    }
+    // Between two ticks the lower one is returned
+    let sqrt_price = sqrt_price_at_tick(100).unwrap();
+    assert_eq!(tick_at_sqrt_price(sqrt_price + 1).unwrap(), 100);
+    assert_eq!(tick_at_sqrt_price(sqrt_price - 1).unwrap(), 99);
+}
+
+#[test]
+fn test_amount_deltas_between_prices_one_and_four() {
+    // √p goes from 1 to 2: A = L * (2 - 1) / (1 * 2), B = L * (2 - 1)
+    let (lower, upper) = (ONE_X32, 2 * ONE_X32);
+    assert_eq!(amount_a_delta(lower, upper, 1_000, false).unwrap(), 500);
+    assert_eq!(amount_b_delta(lower, upper, 1_000, false).unwrap(), 1_000);
+    assert_eq!(amount_a_delta(lower, lower, 1_000, true).unwrap(), 0);
+}
+
+#[test]
+fn test_amount_deltas_round_in_the_requested_direction() {
+    let (lower, upper) = (ONE_X32, ONE_X32 + 1);
+    assert_eq!(amount_b_delta(lower, upper, 1, false).unwrap(), 0);
+    assert_eq!(amount_b_delta(lower, upper, 1, true).unwrap(), 1);
+    assert_eq!(amount_a_delta(lower, upper, 1, false).unwrap(), 0);
+    assert_eq!(amount_a_delta(lower, upper, 1, true).unwrap(), 1);
+    assert!(amount_b_delta(0, u64::MAX, u64::MAX, false).is_err());
+}
+
+#[test]
+fn test_next_sqrt_price_inverts_the_amount_deltas() {
+    assert_eq!(
+        next_sqrt_price_from_a(2 * ONE_X32, 1_000, 500).unwrap(),
+        ONE_X32
+    );
+    assert_eq!(
+        next_sqrt_price_from_b(ONE_X32, 1_000, 1_000).unwrap(),
+        2 * ONE_X32
+    );
+}
+
+#[test]
+fn test_adding_token_a_never_raises_the_price() {
+    // 3 / 2 floors to 1, which alone would put the next price at 3 * 2^32
+    let sqrt_price = 2 * ONE_X32 + 1;
+    assert_eq!(
+        next_sqrt_price_from_a(sqrt_price, 3, 0).unwrap(),
+        sqrt_price
+    );
+}
+
+#[test]
+fn test_swap_step_reaches_the_target_and_charges_the_fee_on_top() {
+    let step = swap_step(2 * ONE_X32, ONE_X32, 1_000, 1_000, 30, true).unwrap();
+    assert_eq!(
+        step,
+        SwapStep {
+            sqrt_price_x32: ONE_X32,
+            amount_in: 500,
+            fee_amount: 2,
+            amount_out: 1_000,
+        }
+    );
+}
+
+#[test]
+fn test_swap_step_short_of_the_target_keeps_the_rest_as_fee() {
+    let step = swap_step(2 * ONE_X32, ONE_X32, 1_000, 100, 30, true).unwrap();
+    assert_eq!(
+        step,
+        SwapStep {
+            sqrt_price_x32: 7_170_229_209,
+            amount_in: 99,
+            fee_amount: 1,
+            amount_out: 330,
+        }
+    );
+}
+
+#[test]
+fn test_swap_step_without_liquidity_jumps_to_the_target() {
+    let step = swap_step(2 * ONE_X32, ONE_X32, 0, 1_000, 30, true).unwrap();
+    assert_eq!(step.sqrt_price_x32, ONE_X32);
+    assert_eq!(
+        (step.amount_in, step.fee_amount, step.amount_out),
+        (0, 0, 0)
+    );
+}
+
+#[test]
+fn test_swap_step_never_uses_more_than_the_remaining_input() {
+    let mut rng = StdRng::seed_from_u64(SEED);
+    for _ in 0..10_000 {
+        let sqrt_price = rng.gen_range(1 << 16..1u64 << 48);
+        let a_to_b = rng.gen_bool(0.5);
+        let target = if a_to_b {
+            rng.gen_range(1 << 16..=sqrt_price)
+        } else {
+            rng.gen_range(sqrt_price..1u64 << 48)
+        };
+        let liquidity = rng.gen_range(1..=1u64 << rng.gen_range(1..40));
+        let remaining = rng.gen_range(1..=1u64 << rng.gen_range(1..40));
+        let fee_bps = [0, 1, 30, 100, 1_000][rng.gen_range(0..5)];
+
+        // Amounts too large for u64 are errors; everything else must fit the input
+        let Ok(step) = swap_step(sqrt_price, target, liquidity, remaining, fee_bps, a_to_b) else {
+            continue;
+        };
+        assert!(step.amount_in + step.fee_amount <= remaining);
+        if a_to_b {
+            assert!((target..=sqrt_price).contains(&step.sqrt_price_x32));
+        } else {
+            assert!((sqrt_price..=target).contains(&step.sqrt_price_x32));
+        }
+    }
+}
+
+#[test]
+fn test_fee_growth_is_q32_and_rejects_truncation() {
+    assert_eq!(fee_growth(1, 1).unwrap(), ONE_X32);
+    assert_eq!(fee_growth(3, 2).unwrap(), 3 * ONE_X32 / 2);
+    assert!(fee_growth(ONE_X32, 1).is_err());
+    assert!(fee_growth(1, 0).is_err());
+}
+
+#[test]
+fn test_tick_array_start_rounds_towards_negative_infinity() {
+    // 60 ticks of spacing 10 per array
+    assert_eq!(tick_array_start(0, 10), 0);
+    assert_eq!(tick_array_start(599, 10), 0);
+    assert_eq!(tick_array_start(600, 10), 600);
+    assert_eq!(tick_array_start(-1, 10), -600);
+    assert_eq!(tick_array_start(-600, 10), -600);
+}
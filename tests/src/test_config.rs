@@ -0,0 +1,145 @@
+use rational_dex::error::ConfigError;
+use rational_dex::state::config_state::{GlobalConfig, Subsystem};
+use rational_dex::state::conviction_state::{EvidenceRef, HashAlgorithm};
+use rational_dex_client::accounts::decode;
+use rational_dex_client::instructions::{self, ProposalKeys};
+use rational_dex_client::pda;
+
+use {
+    litesvm::LiteSVM, solana_keypair::Keypair, solana_pubkey::Pubkey, solana_signer::Signer,
+    spl_token::ID as TOKEN_PROGRAM_ID,
+};
+
+use crate::common::{custom_error, deploy, initialize_config, send, set_mint, set_token_account};
+
+fn setup() -> (LiteSVM, Keypair) {
+    let admin = Keypair::new();
+    let mut svm = LiteSVM::new();
+    deploy(&mut svm, admin.pubkey());
+    svm.airdrop(&admin.pubkey(), 10_000_000_000).unwrap();
+    (svm, admin)
+}
+
+fn funded(svm: &mut LiteSVM) -> Keypair {
+    let wallet = Keypair::new();
+    svm.airdrop(&wallet.pubkey(), 10_000_000_000).unwrap();
+    wallet
+}
+
+fn config(svm: &LiteSVM) -> GlobalConfig {
+    let account = svm.get_account(&pda::config()).unwrap();
+    decode(&account.data).unwrap()
+}
+
+#[test]
+fn test_only_upgrade_authority_initializes_config() {
+    let (mut svm, admin) = setup();
+    let squatter = funded(&mut svm);
+
+    let ix = instructions::initialize_config(squatter.pubkey(), vec![]);
+    assert!(send(&mut svm, &[ix], &[&squatter]).is_err());
+    assert!(svm.get_account(&pda::config()).is_none());
+
+    initialize_config(&mut svm, &admin, vec![]);
+    assert_eq!(config(&svm).admin, admin.pubkey());
+}
+
+#[test]
+fn test_paused_subsystem_rejects_instructions() {
+    let (mut svm, admin) = setup();
+    initialize_config(&mut svm, &admin, vec![]);
+
+    let keys = ProposalKeys {
+        author: admin.pubkey(),
+        proposal_count: 0,
+        mint: Pubkey::new_unique(),
+        token_program: TOKEN_PROGRAM_ID,
+    };
+    set_mint(&mut svm, keys.mint, admin.pubkey());
+    let evidence = EvidenceRef {
+        algorithm: HashAlgorithm::Sha256,
+        digest: [1; 32],
+        uri: None,
+    };
+    let ix = instructions::initialize_proposal(admin.pubkey(), 0, evidence, 60 * 60, 1);
+    send(&mut svm, &[ix], &[&admin]).unwrap();
+
+    let voter = funded(&mut svm);
+    set_token_account(
+        &mut svm,
+        pda::ata(&voter.pubkey(), &keys.mint, &keys.token_program),
+        keys.mint,
+        voter.pubkey(),
+        1_000_000_000_000,
+    );
+
+    // Only the admin can flip the switch
+    let ix = instructions::set_paused(voter.pubkey(), Subsystem::Conviction, true);
+    assert!(send(&mut svm, &[ix], &[&voter]).is_err());
+
+    let ix = instructions::set_paused(admin.pubkey(), Subsystem::Conviction, true);
+    send(&mut svm, &[ix], &[&admin]).unwrap();
+    assert!(config(&svm).paused.conviction);
+    let vote_ix = instructions::conviction_vote(voter.pubkey(), &keys, 7, 3);
+    // In its own code range, so clients can tell it from the other enums' errors
+    assert_eq!(
+        custom_error(send(&mut svm, &[vote_ix.clone()], &[&voter])),
+        Some(ConfigError::ConvictionPaused.into())
+    );
+
+    let ix = instructions::set_paused(admin.pubkey(), Subsystem::Conviction, false);
+    send(&mut svm, &[ix], &[&admin]).unwrap();
+    // The rejected vote is in the history under the current blockhash
+    svm.expire_blockhash();
+    send(&mut svm, &[vote_ix], &[&voter]).unwrap();
+}
+
+#[test]
+fn test_admin_rotation_takes_two_steps() {
+    let (mut svm, admin) = setup();
+    initialize_config(&mut svm, &admin, vec![]);
+    let new_admin = funded(&mut svm);
+    let stranger = funded(&mut svm);
+
+    // Nothing to accept before a proposal
+    let ix = instructions::accept_admin(new_admin.pubkey());
+    assert!(send(&mut svm, &[ix], &[&new_admin]).is_err());
+
+    let ix = instructions::propose_admin(stranger.pubkey(), Some(stranger.pubkey()));
+    assert!(send(&mut svm, &[ix], &[&stranger]).is_err());
+
+    let ix = instructions::propose_admin(admin.pubkey(), Some(new_admin.pubkey()));
+    send(&mut svm, &[ix], &[&admin]).unwrap();
+    // Proposing alone changes nothing
+    assert_eq!(config(&svm).admin, admin.pubkey());
+    assert_eq!(config(&svm).pending_admin, Some(new_admin.pubkey()));
+
+    let ix = instructions::accept_admin(stranger.pubkey());
+    assert!(send(&mut svm, &[ix], &[&stranger]).is_err());
+
+    let ix = instructions::accept_admin(new_admin.pubkey());
+    send(&mut svm, &[ix], &[&new_admin]).unwrap();
+    assert_eq!(config(&svm).admin, new_admin.pubkey());
+    assert_eq!(config(&svm).pending_admin, None);
+
+    // The old admin has lost its rights, the new one has them
+    let ix = instructions::set_paused(admin.pubkey(), Subsystem::Swaps, true);
+    assert!(send(&mut svm, &[ix], &[&admin]).is_err());
+    let ix = instructions::set_paused(new_admin.pubkey(), Subsystem::Swaps, true);
+    send(&mut svm, &[ix], &[&new_admin]).unwrap();
+}
+
+#[test]
+fn test_cancelled_admin_proposal_cannot_be_accepted() {
+    let (mut svm, admin) = setup();
+    initialize_config(&mut svm, &admin, vec![]);
+    let new_admin = funded(&mut svm);
+
+    let propose_ix = instructions::propose_admin(admin.pubkey(), Some(new_admin.pubkey()));
+    let cancel_ix = instructions::propose_admin(admin.pubkey(), None);
+    send(&mut svm, &[propose_ix, cancel_ix], &[&admin]).unwrap();
+
+    let ix = instructions::accept_admin(new_admin.pubkey());
+    assert!(send(&mut svm, &[ix], &[&new_admin]).is_err());
+    assert_eq!(config(&svm).admin, admin.pubkey());
+}
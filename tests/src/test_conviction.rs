@@ -1,220 +1,478 @@
-use partial_idl_parser::AnchorIdlPartialData;
-use partial_idl_parser::{get_idl, idl_custom_path};
+use rational_dex::instructions::conviction_voting::VotingError;
+use rational_dex::state::conviction_state::{
+    EvidenceRef, HashAlgorithm, Proposal, ProposalStatus, Scores, Voter,
+};
+use rational_dex::state::reputation_state::Reputation;
+use rational_dex_client::accounts::decode;
+use rational_dex_client::instructions::{self, ProposalKeys};
+use rational_dex_client::pda;
 
-use std::env;
-use std::path::PathBuf;
-use std::{error::Error, path::Path, rc::Rc};
+use anchor_spl::token_2022::ID as TOKEN_2022_PROGRAM_ID;
 
-use spl_token::state::Mint;
 use {
-    borsh::{BorshDeserialize, BorshSerialize},
-    litesvm::LiteSVM,
-    solana_account::Account,
-    solana_instruction::{account_meta::AccountMeta, Instruction},
-    solana_keypair::Keypair,
-    solana_message::{Message, VersionedMessage},
-    solana_program_option::COption,
-    solana_program_pack::Pack,
-    solana_pubkey::{pubkey, Pubkey},
-    solana_signer::Signer,
-    solana_transaction::versioned::VersionedTransaction,
-    spl_token::{
-        state::{Account as TokenAccount, AccountState},
-        ID as TOKEN_PROGRAM_ID,
-    },
+    litesvm::LiteSVM, solana_keypair::Keypair, solana_pubkey::Pubkey, solana_signer::Signer,
+    spl_token::ID as TOKEN_PROGRAM_ID,
 };
 
-use solana_program::rent::Rent;
-
-use solana_program::system_instruction::create_account;
-const IDL_RAW_DATA: &str = idl_custom_path!(concat!(
-    env!("CARGO_WORKSPACE_DIR"),
-    "/target/idl/",
-    "rational_dex.json"
-));
+use crate::common::{
+    custom_error, deploy, initialize_config, send, set_fee_mint, set_fee_token_account, set_mint,
+    set_token_account, token_balance, warp,
+};
 
-/// Replace with your program id (you provided this earlier).
-const PROGRAM_ID: Pubkey = pubkey!("EEL1Q3J9MjPxTWagTKE39jpUVBjUg7q283ztTVzbveDz");
+const VOTING_PERIOD: i64 = 60 * 60 * 24 * 7;
 
-const PROGRAM_BYTES: &[u8] = include_bytes!("../../target/deploy/rational_dex.so");
+const DAY: i64 = 60 * 60 * 24;
 
-#[test]
-fn test_create_proposal() {
-    let program_id = PROGRAM_ID;
+const VOTER_TOKENS: u64 = 1_000_000_000_000;
 
-    let mut svm = LiteSVM::new();
-    svm.add_program(program_id, PROGRAM_BYTES).unwrap();
+fn evidence(digest: u8, uri: Option<&str>) -> EvidenceRef {
+    EvidenceRef {
+        algorithm: HashAlgorithm::Sha256,
+        digest: [digest; 32],
+        uri: uri.map(str::to_string),
+    }
+}
 
+fn setup() -> (LiteSVM, Keypair) {
     let author = Keypair::new();
+    let mut svm = LiteSVM::new();
+    deploy(&mut svm, author.pubkey());
     svm.airdrop(&author.pubkey(), 10_000_000_000).unwrap();
+    initialize_config(&mut svm, &author, vec![]);
+    (svm, author)
+}
 
-    // --- 1. Compute PDAs ---
-    let (author_state_pda, _) =
-        Pubkey::find_program_address(&[b"author_state", author.pubkey().as_ref()], &PROGRAM_ID);
-
-    // initial proposal_count = 0 => first proposal PDA
-    let (proposal_pda, _) = Pubkey::find_program_address(
-        &[
-            b"proposal",
-            author.pubkey().as_ref(),
-            &0u64.to_string().as_bytes(),
-        ],
-        &PROGRAM_ID,
-    );
-
-    // --- 3. Prepare instruction args ---
-    #[derive(BorshSerialize)]
-    struct CreateProposalArgs {
-        evidence: String,
-    }
-
-    let args = CreateProposalArgs {
-        evidence: "Evidence: proposal median check".to_string(),
+/// Opens the author's next proposal with a quorum of 3 and a fresh voting mint.
+fn open_proposal(svm: &mut LiteSVM, author: &Keypair, proposal_count: u64) -> ProposalKeys {
+    let keys = ProposalKeys {
+        author: author.pubkey(),
+        proposal_count,
+        mint: Pubkey::new_unique(),
+        token_program: TOKEN_PROGRAM_ID,
     };
+    set_mint(svm, keys.mint, author.pubkey());
+    let ix = instructions::initialize_proposal(
+        author.pubkey(),
+        proposal_count,
+        evidence(1, None),
+        VOTING_PERIOD,
+        3,
+    );
+    send(svm, &[ix], &[author]).unwrap();
+    keys
+}
 
-    // --- 4. Build account metas ---
-    let create_accounts = vec![
-        AccountMeta::new(author.pubkey(), true),   // signer
-        AccountMeta::new(author_state_pda, false), // author state
-        AccountMeta::new(proposal_pda, false),     // proposal
-        AccountMeta::new_readonly(solana_program::system_program::id(), false),
-    ];
+/// A wallet with SOL and voting tokens of `mint`.
+fn funded_voter(svm: &mut LiteSVM, mint: Pubkey) -> Keypair {
+    let voter = Keypair::new();
+    svm.airdrop(&voter.pubkey(), 10_000_000_000).unwrap();
+    set_token_account(
+        svm,
+        pda::ata(&voter.pubkey(), &mint, &TOKEN_PROGRAM_ID),
+        mint,
+        voter.pubkey(),
+        VOTER_TOKENS,
+    );
+    voter
+}
 
-    // --- 5. Get discriminant ---
-    let parsed_idl = AnchorIdlPartialData::parse(IDL_RAW_DATA).unwrap();
-    let discriminant = parsed_idl
-        .get_discriminant("initialize_proposal")
-        .unwrap_or_default();
-    println!("Discriminant: {:?}", discriminant);
+fn cast_vote(svm: &mut LiteSVM, voter: &Keypair, keys: &ProposalKeys, score: u8, conviction: u8) {
+    let ix = instructions::conviction_vote(voter.pubkey(), keys, score, conviction);
+    send(svm, &[ix], &[voter]).unwrap();
+}
 
-    let mut instruction_data = Vec::new();
-    instruction_data.extend_from_slice(&discriminant.to_vec());
-    args.serialize(&mut instruction_data).unwrap();
+/// Finalizes the proposal of `keys` once its voting period has passed.
+fn finalize(svm: &mut LiteSVM, payer: &Keypair, keys: &ProposalKeys) {
+    let ix = instructions::finalize_proposal(payer.pubkey(), keys.author, keys.proposal_count);
+    send(svm, &[ix], &[payer]).unwrap();
+}
 
-    let ix = Instruction::new_with_bytes(program_id, &instruction_data, create_accounts);
+fn voter_account(svm: &LiteSVM, voter: &Keypair, keys: &ProposalKeys) -> Voter {
+    let account = svm
+        .get_account(&pda::voter(&keys.proposal(), &voter.pubkey()))
+        .unwrap();
+    decode(&account.data).unwrap()
+}
 
-    // --- 6. Send transaction ---
-    let blockhash = svm.latest_blockhash();
-    let msg = Message::new_with_blockhash(&[ix], Some(&author.pubkey()), &blockhash);
-    let tx = VersionedTransaction::try_new(VersionedMessage::Legacy(msg), &[&author]).unwrap();
+fn voter_balance(svm: &LiteSVM, voter: &Keypair, keys: &ProposalKeys) -> u64 {
+    token_balance(
+        svm,
+        &pda::ata(&voter.pubkey(), &keys.mint, &keys.token_program),
+    )
+}
 
-    let sim_res = svm.simulate_transaction(tx.clone()).unwrap();
-    println!("Program logs: {:?}", sim_res.meta.logs);
-    let meta = svm.send_transaction(tx).unwrap();
-    assert_eq!(sim_res.meta, meta);
+fn reputation_score(svm: &LiteSVM, wallet: &Pubkey) -> u64 {
+    let account = svm.get_account(&pda::reputation(wallet)).unwrap();
+    decode::<Reputation>(&account.data).unwrap().score
+}
 
-    println!("✅ Proposal created successfully for {}", author.pubkey());
+#[test]
+fn test_create_proposal() {
+    let (mut svm, author) = setup();
+
+    let ix = instructions::initialize_proposal(
+        author.pubkey(),
+        0,
+        evidence(
+            7,
+            Some("ipfs://bafybeigdyrzt5sfp7udm7hu76uh7y26nf3efuylqabf3oclgtqy55fbzdi"),
+        ),
+        VOTING_PERIOD,
+        3,
+    );
+    let meta = send(&mut svm, &[ix], &[&author]).unwrap();
+    println!("Program logs: {:?}", meta.logs);
+
+    let account = svm
+        .get_account(&pda::proposal(&author.pubkey(), 0))
+        .unwrap();
+    let proposal: Proposal = decode(&account.data).unwrap();
+    assert_eq!(proposal.author, author.pubkey());
+    assert_eq!(proposal.evidence.digest, [7u8; 32]);
 }
 
 #[test]
 fn test_vote_proposal() {
-    let program_id = PROGRAM_ID;
-    let mut svm = LiteSVM::new();
-    svm.add_program(program_id, PROGRAM_BYTES).unwrap();
-
-    // --- 1. Create author and voter ---
-    let author = Keypair::new();
+    let (mut svm, author) = setup();
     let voter = Keypair::new();
-    svm.airdrop(&author.pubkey(), 10_000_000_000).unwrap();
     svm.airdrop(&voter.pubkey(), 10_000_000_000).unwrap();
 
-    // --- 2. Compute PDAs ---
-    let (author_state_pda, _) =
-        Pubkey::find_program_address(&[b"author_state", author.pubkey().as_ref()], &program_id);
-
-    let proposal_count = 0u64;
-    let (proposal_pda, _) = Pubkey::find_program_address(
-        &[
-            b"proposal",
-            author.pubkey().as_ref(),
-            &proposal_count.to_string().as_bytes(),
-        ],
-        &program_id,
+    let keys = ProposalKeys {
+        author: author.pubkey(),
+        proposal_count: 0,
+        mint: Pubkey::new_unique(),
+        token_program: TOKEN_PROGRAM_ID,
+    };
+    set_mint(&mut svm, keys.mint, author.pubkey());
+    set_token_account(
+        &mut svm,
+        pda::ata(&voter.pubkey(), &keys.mint, &keys.token_program),
+        keys.mint,
+        voter.pubkey(),
+        1_000_000_000_000,
     );
 
-    let (scores_pda, _) = Pubkey::find_program_address(
-        &[b"scores", &proposal_count.to_string().as_bytes()],
-        &program_id,
+    let create_ix =
+        instructions::initialize_proposal(author.pubkey(), 0, evidence(1, None), VOTING_PERIOD, 3);
+    send(&mut svm, &[create_ix], &[&author]).unwrap();
+
+    // Score between 0 and 10, conviction between 0 and 6
+    let vote_ix = instructions::conviction_vote(voter.pubkey(), &keys, 7, 3);
+    let meta = send(&mut svm, &[vote_ix], &[&voter]).unwrap();
+    println!("Program logs: {:?}", meta.logs);
+
+    let account = svm
+        .get_account(&pda::voter(&keys.proposal(), &voter.pubkey()))
+        .unwrap();
+    let voter_account: Voter = decode(&account.data).unwrap();
+    assert!(voter_account.voted);
+    assert_eq!(voter_account.score, 7);
+    assert!(voter_account.locked_amount > 0);
+}
+
+#[test]
+fn test_update_evidence_before_first_vote() {
+    let (mut svm, author) = setup();
+
+    let create_ix =
+        instructions::initialize_proposal(author.pubkey(), 0, evidence(1, None), VOTING_PERIOD, 3);
+    // Replace the evidence with a URI reference
+    let update_ix = instructions::update_evidence(
+        author.pubkey(),
+        0,
+        evidence(2, Some("https://example.org/evidence.pdf")),
+    );
+    let meta = send(&mut svm, &[create_ix, update_ix], &[&author]).unwrap();
+    println!("Program logs: {:?}", meta.logs);
+
+    let account = svm
+        .get_account(&pda::proposal(&author.pubkey(), 0))
+        .unwrap();
+    let proposal: Proposal = decode(&account.data).unwrap();
+    assert_eq!(proposal.evidence.digest, [2u8; 32]);
+    assert_eq!(
+        proposal.evidence.uri.as_deref(),
+        Some("https://example.org/evidence.pdf")
     );
+}
 
-    let (voter_account_pda, _) = Pubkey::find_program_address(
-        &[
-            b"voter",
-            &proposal_count.to_string().as_bytes(),
-            voter.pubkey().as_ref(),
-        ],
-        &program_id,
+#[test]
+fn test_update_evidence_after_retracted_vote_is_rejected() {
+    let (mut svm, author) = setup();
+    let keys = open_proposal(&mut svm, &author, 0);
+    let voter = funded_voter(&mut svm, keys.mint);
+    cast_vote(&mut svm, &voter, &keys, 7, 2);
+    let ix = instructions::retract_conviction_vote(voter.pubkey(), &keys);
+    send(&mut svm, &[ix], &[&voter]).unwrap();
+
+    // Nobody's vote stands any more, but voters have seen the evidence
+    let ix = instructions::update_evidence(author.pubkey(), 0, evidence(2, None));
+    assert_eq!(
+        custom_error(send(&mut svm, &[ix], &[&author])),
+        Some(VotingError::VotingStarted.into())
     );
+}
 
-    // --- 3. Create proposal first ---
-    #[derive(BorshSerialize)]
-    struct CreateProposalArgs {
-        evidence: String,
+#[test]
+fn test_voter_reputation_scales_with_conviction() {
+    let (mut svm, author) = setup();
+    let keys = open_proposal(&mut svm, &author, 0);
+    let strong = funded_voter(&mut svm, keys.mint);
+    let weak = funded_voter(&mut svm, keys.mint);
+    cast_vote(&mut svm, &strong, &keys, 7, 3);
+    cast_vote(&mut svm, &weak, &keys, 7, 1);
+
+    warp(&mut svm, VOTING_PERIOD);
+    finalize(&mut svm, &author, &keys);
+    for voter in [&strong, &weak] {
+        let ix = instructions::settle_voter_reputation(voter.pubkey(), author.pubkey(), 0);
+        send(&mut svm, &[ix], &[voter]).unwrap();
     }
-    let create_args = CreateProposalArgs {
-        evidence: "Test proposal".to_string(),
-    };
 
-    let create_accounts = vec![
-        AccountMeta::new(author.pubkey(), true),
-        AccountMeta::new(author_state_pda, false),
-        AccountMeta::new(proposal_pda, false),
-        AccountMeta::new_readonly(solana_program::system_program::id(), false),
-    ];
-
-    let parsed_idl = AnchorIdlPartialData::parse(IDL_RAW_DATA).unwrap();
-    let discriminant = parsed_idl
-        .get_discriminant("initialize_proposal")
-        .unwrap_or_default();
-
-    let mut instruction_data = Vec::new();
-    instruction_data.extend_from_slice(&discriminant.to_vec());
-    create_args.serialize(&mut instruction_data).unwrap();
-
-    let create_ix = Instruction::new_with_bytes(program_id, &instruction_data, create_accounts);
-    let blockhash = svm.latest_blockhash();
-    let msg = Message::new_with_blockhash(&[create_ix], Some(&author.pubkey()), &blockhash);
-    let tx = VersionedTransaction::try_new(VersionedMessage::Legacy(msg), &[&author]).unwrap();
-    svm.send_transaction(tx).unwrap();
-
-    // --- 4. Vote on proposal ---
-    #[derive(BorshSerialize)]
-    struct VoteProposalArgs {
-        proposal_count: u64,
-        score: u8,
-        conviction: u8,
-    }
+    // Both matched the final score of 7: 10 points of accuracy, 5 points each, times conviction
+    assert_eq!(reputation_score(&svm, &strong.pubkey()), 150);
+    assert_eq!(reputation_score(&svm, &weak.pubkey()), 50);
+}
 
-    let vote_args = VoteProposalArgs {
-        proposal_count,
-        score: 7,      // choose score between 0–10
-        conviction: 3, // choose conviction 0–6
-    };
+#[test]
+fn test_vote_without_conviction_earns_no_reputation() {
+    let (mut svm, author) = setup();
+    let keys = open_proposal(&mut svm, &author, 0);
+    let staked = funded_voter(&mut svm, keys.mint);
+    let idle = funded_voter(&mut svm, keys.mint);
+    cast_vote(&mut svm, &staked, &keys, 7, 3);
+    cast_vote(&mut svm, &idle, &keys, 7, 0);
+
+    warp(&mut svm, VOTING_PERIOD);
+    finalize(&mut svm, &author, &keys);
+
+    let ix = instructions::settle_voter_reputation(idle.pubkey(), author.pubkey(), 0);
+    assert!(send(&mut svm, &[ix], &[&idle]).is_err());
+    assert!(svm.get_account(&pda::reputation(&idle.pubkey())).is_none());
+}
 
-    let vote_accounts = vec![
-        AccountMeta::new(voter.pubkey(), true),
-        AccountMeta::new(scores_pda, false),
-        AccountMeta::new(voter_account_pda, false),
-        AccountMeta::new_readonly(solana_program::system_program::id(), false),
-    ];
+#[test]
+fn test_vote_settles_only_against_its_own_proposal() {
+    let (mut svm, author) = setup();
+    let other_author = Keypair::new();
+    svm.airdrop(&other_author.pubkey(), 10_000_000_000).unwrap();
+
+    // Both authors' first proposals share the index 0
+    let keys = open_proposal(&mut svm, &author, 0);
+    let other_keys = open_proposal(&mut svm, &other_author, 0);
+    let voter = funded_voter(&mut svm, keys.mint);
+    let other_voter = funded_voter(&mut svm, other_keys.mint);
+    cast_vote(&mut svm, &voter, &keys, 2, 3);
+    cast_vote(&mut svm, &other_voter, &other_keys, 9, 3);
+
+    warp(&mut svm, VOTING_PERIOD);
+    finalize(&mut svm, &author, &keys);
+    finalize(&mut svm, &author, &other_keys);
+
+    // The vote has no voter account under the other author's proposal
+    let ix = instructions::settle_voter_reputation(voter.pubkey(), other_author.pubkey(), 0);
+    assert!(send(&mut svm, &[ix], &[&voter]).is_err());
+
+    let ix = instructions::settle_voter_reputation(voter.pubkey(), author.pubkey(), 0);
+    send(&mut svm, &[ix], &[&voter]).unwrap();
+    assert_eq!(reputation_score(&svm, &voter.pubkey()), 150);
+}
 
-    let discriminant = parsed_idl
-        .get_discriminant("conviction_vote")
-        .unwrap_or_default();
+#[test]
+fn test_vote_after_voting_period_is_rejected() {
+    let (mut svm, author) = setup();
+    let keys = open_proposal(&mut svm, &author, 0);
+    let voter = funded_voter(&mut svm, keys.mint);
+
+    warp(&mut svm, VOTING_PERIOD);
+    let ix = instructions::conviction_vote(voter.pubkey(), &keys, 7, 3);
+    assert!(send(&mut svm, &[ix], &[&voter]).is_err());
+    assert!(svm
+        .get_account(&pda::voter(&keys.proposal(), &voter.pubkey()))
+        .is_none());
+}
 
-    let mut vote_data = Vec::new();
-    vote_data.extend_from_slice(&discriminant.to_vec());
-    vote_args.serialize(&mut vote_data).unwrap();
+#[test]
+fn test_finalize_before_voting_period_ends_is_rejected() {
+    let (mut svm, author) = setup();
+    let keys = open_proposal(&mut svm, &author, 0);
+    let voter = funded_voter(&mut svm, keys.mint);
+    cast_vote(&mut svm, &voter, &keys, 7, 3);
+
+    let ix = instructions::finalize_proposal(author.pubkey(), author.pubkey(), 0);
+    assert!(send(&mut svm, &[ix], &[&author]).is_err());
+}
 
-    let vote_ix = Instruction::new_with_bytes(program_id, &vote_data, vote_accounts);
-    let msg = Message::new_with_blockhash(&[vote_ix], Some(&voter.pubkey()), &blockhash);
-    let tx = VersionedTransaction::try_new(VersionedMessage::Legacy(msg), &[&voter]).unwrap();
+#[test]
+fn test_proposal_below_quorum_fails() {
+    let (mut svm, author) = setup();
+    let keys = open_proposal(&mut svm, &author, 0);
+    let voter = funded_voter(&mut svm, keys.mint);
+    // A conviction weight of 2 falls short of the quorum of 3
+    cast_vote(&mut svm, &voter, &keys, 7, 2);
+
+    warp(&mut svm, VOTING_PERIOD);
+    finalize(&mut svm, &author, &keys);
+
+    let account = svm.get_account(&keys.proposal()).unwrap();
+    let proposal: Proposal = decode(&account.data).unwrap();
+    assert!(proposal.status == ProposalStatus::Failed);
+    assert_eq!(proposal.final_score, None);
+    // The author earns nothing and voters have no final score to settle against
+    assert_eq!(reputation_score(&svm, &author.pubkey()), 0);
+    let ix = instructions::settle_voter_reputation(voter.pubkey(), author.pubkey(), 0);
+    assert!(send(&mut svm, &[ix], &[&voter]).is_err());
+}
 
-    let sim_res = svm.simulate_transaction(tx.clone()).unwrap();
-    println!("Program logs: {:?}", sim_res.meta.logs);
+#[test]
+fn test_proposal_without_votes_fails() {
+    let (mut svm, author) = setup();
+    let keys = open_proposal(&mut svm, &author, 0);
+
+    warp(&mut svm, VOTING_PERIOD);
+    finalize(&mut svm, &author, &keys);
+
+    let account = svm.get_account(&keys.proposal()).unwrap();
+    let proposal: Proposal = decode(&account.data).unwrap();
+    assert!(proposal.status == ProposalStatus::Failed);
+    assert_eq!(proposal.final_score, None);
+    assert_eq!(reputation_score(&svm, &author.pubkey()), 0);
+}
+
+#[test]
+fn test_change_vote_moves_weight_and_tops_up_lock() {
+    let (mut svm, author) = setup();
+    let keys = open_proposal(&mut svm, &author, 0);
+    let voter = funded_voter(&mut svm, keys.mint);
+    cast_vote(&mut svm, &voter, &keys, 7, 3);
+
+    let ix = instructions::change_conviction_vote(voter.pubkey(), &keys, 4, 5);
+    send(&mut svm, &[ix], &[&voter]).unwrap();
+
+    let account = svm.get_account(&pda::scores(&keys.proposal())).unwrap();
+    let scores: Scores = decode(&account.data).unwrap();
+    assert_eq!(scores.counts[7], 0);
+    assert_eq!(scores.counts[4], 4 * 5);
+    assert_eq!(scores.total_weight, 5);
+
+    // 10 tokens per point of conviction, the top-up covering the difference
+    let vote = voter_account(&svm, &voter, &keys);
+    assert_eq!((vote.score, vote.conviction), (4, 5));
+    assert_eq!(vote.locked_amount, 50_000_000);
+    assert_eq!(vote.pending_release, 0);
+    assert_eq!(
+        voter_balance(&svm, &voter, &keys),
+        VOTER_TOKENS - 50_000_000
+    );
+}
 
-    let meta = svm.send_transaction(tx).unwrap();
-    assert_eq!(sim_res.meta, meta);
+#[test]
+fn test_lowered_conviction_releases_when_the_lock_ends() {
+    let (mut svm, author) = setup();
+    let keys = open_proposal(&mut svm, &author, 0);
+    let voter = funded_voter(&mut svm, keys.mint);
+    cast_vote(&mut svm, &voter, &keys, 7, 3);
+
+    let ix = instructions::change_conviction_vote(voter.pubkey(), &keys, 7, 1);
+    send(&mut svm, &[ix], &[&voter]).unwrap();
+    let vote = voter_account(&svm, &voter, &keys);
+    assert_eq!(vote.locked_amount, 10_000_000);
+    assert_eq!(vote.pending_release, 20_000_000);
+
+    // The difference stays locked for the 30 days of the original conviction
+    let ix = instructions::unlock_conviction_tokens(voter.pubkey(), &keys);
+    assert!(send(&mut svm, &[ix.clone()], &[&voter]).is_err());
+    warp(&mut svm, 30 * DAY);
+    send(&mut svm, &[ix], &[&voter]).unwrap();
+
+    // Voting has ended by then and the remaining 10 day lock has expired with it
+    let vote = voter_account(&svm, &voter, &keys);
+    assert_eq!((vote.locked_amount, vote.pending_release), (0, 0));
+    assert_eq!(voter_balance(&svm, &voter, &keys), VOTER_TOKENS);
+}
+
+#[test]
+fn test_retracted_vote_returns_its_tokens() {
+    let (mut svm, author) = setup();
+    let keys = open_proposal(&mut svm, &author, 0);
+    let voter = funded_voter(&mut svm, keys.mint);
+    cast_vote(&mut svm, &voter, &keys, 7, 2);
+
+    let ix = instructions::retract_conviction_vote(voter.pubkey(), &keys);
+    send(&mut svm, &[ix], &[&voter]).unwrap();
+
+    let vote = voter_account(&svm, &voter, &keys);
+    assert!(!vote.voted);
+    assert_eq!(vote.locked_amount, 0);
+    assert_eq!(vote.pending_release, 20_000_000);
+    let account = svm.get_account(&keys.proposal()).unwrap();
+    let proposal: Proposal = decode(&account.data).unwrap();
+    assert_eq!(proposal.vote_count, 0);
+    let account = svm.get_account(&pda::scores(&keys.proposal())).unwrap();
+    let scores: Scores = decode(&account.data).unwrap();
+    assert_eq!(scores.total_weight, 0);
+
+    warp(&mut svm, 20 * DAY);
+    let ix = instructions::unlock_conviction_tokens(voter.pubkey(), &keys);
+    send(&mut svm, &[ix], &[&voter]).unwrap();
+    assert_eq!(voter_balance(&svm, &voter, &keys), VOTER_TOKENS);
+}
 
-    println!("✅ Voted successfully for proposal {}", proposal_count);
+#[test]
+fn test_vote_cannot_change_after_voting_period() {
+    let (mut svm, author) = setup();
+    let keys = open_proposal(&mut svm, &author, 0);
+    let voter = funded_voter(&mut svm, keys.mint);
+    cast_vote(&mut svm, &voter, &keys, 7, 3);
+
+    warp(&mut svm, VOTING_PERIOD);
+    let change_ix = instructions::change_conviction_vote(voter.pubkey(), &keys, 2, 3);
+    assert!(send(&mut svm, &[change_ix], &[&voter]).is_err());
+    let retract_ix = instructions::retract_conviction_vote(voter.pubkey(), &keys);
+    assert!(send(&mut svm, &[retract_ix], &[&voter]).is_err());
+}
+
+#[test]
+fn test_fee_mint_locks_and_releases_what_the_vault_holds() {
+    let (mut svm, author) = setup();
+    let mut keys = open_proposal(&mut svm, &author, 0);
+    // Vote with a Token-2022 mint that withholds 1% of every transfer instead
+    keys.mint = Pubkey::new_unique();
+    keys.token_program = TOKEN_2022_PROGRAM_ID;
+    set_fee_mint(&mut svm, keys.mint, author.pubkey(), 100);
+    let voter = Keypair::new();
+    svm.airdrop(&voter.pubkey(), 10_000_000_000).unwrap();
+    let voter_tokens = pda::ata(&voter.pubkey(), &keys.mint, &keys.token_program);
+    set_fee_token_account(
+        &mut svm,
+        voter_tokens,
+        keys.mint,
+        voter.pubkey(),
+        VOTER_TOKENS,
+    );
+    cast_vote(&mut svm, &voter, &keys, 7, 3);
+
+    // The voter paid the fee on top, and the vote records what actually reached the vault
+    let vault = pda::ata(
+        &pda::scores(&keys.proposal()),
+        &keys.mint,
+        &keys.token_program,
+    );
+    let vote = voter_account(&svm, &voter, &keys);
+    assert!(vote.locked_amount >= 30_000_000);
+    assert_eq!(token_balance(&svm, &vault), vote.locked_amount);
+    let paid = VOTER_TOKENS - voter_balance(&svm, &voter, &keys);
+    assert!(paid > vote.locked_amount);
+
+    let ix = instructions::retract_conviction_vote(voter.pubkey(), &keys);
+    send(&mut svm, &[ix], &[&voter]).unwrap();
+    warp(&mut svm, 30 * DAY);
+    let ix = instructions::unlock_conviction_tokens(voter.pubkey(), &keys);
+    send(&mut svm, &[ix], &[&voter]).unwrap();
+
+    // The release empties the vault; its own fee is withheld from what the voter receives
+    assert_eq!(token_balance(&svm, &vault), 0);
+    let returned = voter_balance(&svm, &voter, &keys) - (VOTER_TOKENS - paid);
+    assert!(returned < vote.locked_amount);
+    assert!(returned >= vote.locked_amount * 99 / 100);
 }
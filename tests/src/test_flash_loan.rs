@@ -0,0 +1,132 @@
+use rational_dex::state::pool_state::Pool;
+use rational_dex::{BPS_DENOMINATOR, FLASH_LOAN_FEE_BPS};
+use rational_dex_client::accounts::decode;
+use rational_dex_client::instructions::{self, PoolKeys};
+use rational_dex_client::pda;
+
+use {
+    litesvm::LiteSVM, solana_keypair::Keypair, solana_pubkey::Pubkey, solana_signer::Signer,
+    spl_token::ID as TOKEN_PROGRAM_ID,
+};
+
+use crate::common::{deploy, initialize_config, send, set_mint, set_token_account, token_balance};
+
+const WALLET_TOKENS: u64 = 1_000_000_000_000;
+const LOAN: u64 = 1_000_000;
+
+/// A pool holding 1e9 of each token and a borrower holding both tokens.
+fn setup() -> (LiteSVM, PoolKeys, Keypair) {
+    let admin = Keypair::new();
+    let mut svm = LiteSVM::new();
+    deploy(&mut svm, admin.pubkey());
+    svm.airdrop(&admin.pubkey(), 10_000_000_000).unwrap();
+    initialize_config(&mut svm, &admin, vec![]);
+
+    let keys = PoolKeys {
+        mint_a: Pubkey::new_unique(),
+        mint_b: Pubkey::new_unique(),
+        mint_lp: Pubkey::new_unique(),
+        token_program: TOKEN_PROGRAM_ID,
+    };
+    set_mint(&mut svm, keys.mint_a, admin.pubkey());
+    set_mint(&mut svm, keys.mint_b, admin.pubkey());
+    set_mint(&mut svm, keys.mint_lp, keys.pool());
+    let ix = instructions::initialize_pool(admin.pubkey(), &keys, 30, 10_000);
+    send(&mut svm, &[ix], &[&admin]).unwrap();
+
+    let lp = funded_user(&mut svm, &keys, WALLET_TOKENS);
+    let ix = instructions::add_liquidity(lp.pubkey(), &keys, 1_000_000_000, 1_000_000_000, 0);
+    send(&mut svm, &[ix], &[&lp]).unwrap();
+
+    let borrower = funded_user(&mut svm, &keys, WALLET_TOKENS);
+    (svm, keys, borrower)
+}
+
+fn funded_user(svm: &mut LiteSVM, keys: &PoolKeys, amount: u64) -> Keypair {
+    let user = Keypair::new();
+    svm.airdrop(&user.pubkey(), 10_000_000_000).unwrap();
+    for (mint, amount) in [
+        (keys.mint_a, amount),
+        (keys.mint_b, amount),
+        (keys.mint_lp, 0),
+    ] {
+        let address = pda::ata(&user.pubkey(), &mint, &keys.token_program);
+        set_token_account(svm, address, mint, user.pubkey(), amount);
+    }
+    user
+}
+
+fn pool(svm: &LiteSVM, keys: &PoolKeys) -> Pool {
+    decode(&svm.get_account(&keys.pool()).unwrap().data).unwrap()
+}
+
+#[test]
+fn test_borrow_and_repay_leaves_the_fee_with_lps() {
+    let (mut svm, keys, borrower) = setup();
+    let vault_a = pda::vault_a(&keys.pool());
+    let before = token_balance(&svm, &vault_a);
+
+    let ixs = [
+        instructions::flash_borrow(borrower.pubkey(), &keys, LOAN, true),
+        instructions::flash_repay(borrower.pubkey(), &keys),
+    ];
+    send(&mut svm, &ixs, &[&borrower]).unwrap();
+
+    // 0.09% of the loan, rounded up
+    let fee = (LOAN * FLASH_LOAN_FEE_BPS).div_ceil(BPS_DENOMINATOR);
+    assert_eq!(fee, 900);
+    assert_eq!(token_balance(&svm, &vault_a), before + fee);
+    let borrower_a = pda::ata(&borrower.pubkey(), &keys.mint_a, &keys.token_program);
+    assert_eq!(token_balance(&svm, &borrower_a), WALLET_TOKENS - fee);
+
+    let pool = pool(&svm, &keys);
+    assert!(!pool.locked);
+    assert_eq!(pool.flash_loan_amount, 0);
+    assert_eq!(pool.protocol_fees_a, 0);
+}
+
+#[test]
+fn test_borrow_without_repay_fails() {
+    let (mut svm, keys, borrower) = setup();
+    let ix = instructions::flash_borrow(borrower.pubkey(), &keys, LOAN, false);
+    assert!(send(&mut svm, &[ix], &[&borrower]).is_err());
+}
+
+#[test]
+fn test_repay_without_a_loan_fails() {
+    let (mut svm, keys, borrower) = setup();
+    let ix = instructions::flash_repay(borrower.pubkey(), &keys);
+    assert!(send(&mut svm, &[ix], &[&borrower]).is_err());
+}
+
+#[test]
+fn test_pool_is_locked_while_a_loan_is_out() {
+    let (mut svm, keys, borrower) = setup();
+    let ixs = [
+        instructions::flash_borrow(borrower.pubkey(), &keys, LOAN, true),
+        instructions::swap(borrower.pubkey(), &keys, LOAN, 0, true),
+        instructions::flash_repay(borrower.pubkey(), &keys),
+    ];
+    assert!(send(&mut svm, &ixs, &[&borrower]).is_err());
+
+    let ixs = [
+        instructions::flash_borrow(borrower.pubkey(), &keys, LOAN, true),
+        instructions::flash_borrow(borrower.pubkey(), &keys, LOAN, true),
+        instructions::flash_repay(borrower.pubkey(), &keys),
+    ];
+    assert!(send(&mut svm, &ixs, &[&borrower]).is_err());
+}
+
+#[test]
+fn test_borrower_that_cannot_cover_the_fee_fails() {
+    let (mut svm, keys, _) = setup();
+    let borrower = funded_user(&mut svm, &keys, 0);
+    let ixs = [
+        instructions::flash_borrow(borrower.pubkey(), &keys, LOAN, false),
+        instructions::flash_repay(borrower.pubkey(), &keys),
+    ];
+    assert!(send(&mut svm, &ixs, &[&borrower]).is_err());
+
+    let vault_b = pda::vault_b(&keys.pool());
+    assert_eq!(token_balance(&svm, &vault_b), 1_000_000_000);
+}
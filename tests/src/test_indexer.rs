@@ -0,0 +1,266 @@
+use anchor_lang::Event;
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use rational_dex::events::{OfferCancelled, OfferCreated, OfferTaken, PollCreated};
+use rational_dex::instructions::reveal::RevealResultEvent;
+use rational_dex::instructions::vote::VoteEvent;
+use rational_dex::EVENT_VERSION;
+use rational_dex_client::pda;
+use rational_dex_indexer::ingest::{ingest, last_indexed, TransactionRecord};
+use rational_dex_indexer::{api, db, source};
+use rusqlite::Connection;
+use serde_json::Value;
+use solana_pubkey::{pubkey, Pubkey};
+
+const PROGRAM_ID: Pubkey = pubkey!("EEL1Q3J9MjPxTWagTKE39jpUVBjUg7q283ztTVzbveDz");
+
+/// A successful top-level call of the program that logged `events`.
+fn record(signature: &str, slot: u64, events: &[Vec<u8>]) -> TransactionRecord {
+    let mut logs = vec![format!("Program {PROGRAM_ID} invoke [1]")];
+    logs.extend(
+        events
+            .iter()
+            .map(|data| format!("Program data: {}", STANDARD.encode(data))),
+    );
+    logs.push(format!("Program {PROGRAM_ID} success"));
+    TransactionRecord {
+        signature: signature.to_string(),
+        slot,
+        block_time: None,
+        failed: false,
+        logs,
+    }
+}
+
+fn offer_created(offer_id: u64, maker: Pubkey, offered_amount: u64) -> Vec<u8> {
+    OfferCreated {
+        version: EVENT_VERSION,
+        offer_id,
+        maker,
+        token_mint_a: Pubkey::new_unique(),
+        token_mint_b: Pubkey::new_unique(),
+        offered_amount,
+        wanted_amount: 2 * offered_amount,
+        is_private: false,
+    }
+    .data()
+}
+
+fn offer_taken(offer_id: u64, maker: Pubkey, amount_taken: u64, remaining_amount: u64) -> Vec<u8> {
+    OfferTaken {
+        version: EVENT_VERSION,
+        offer_id,
+        maker,
+        taker: Pubkey::new_unique(),
+        amount_taken,
+        remaining_amount,
+    }
+    .data()
+}
+
+/// Round-trips `records` through a dump before ingesting them.
+fn ingest_dump(conn: &mut Connection, records: &[TransactionRecord]) -> usize {
+    let mut dump = Vec::new();
+    source::write_dump(&mut dump, records).unwrap();
+    source::read_dump(dump.as_slice())
+        .unwrap()
+        .iter()
+        .filter(|record| ingest(conn, record).unwrap())
+        .count()
+}
+
+fn get(conn: &Connection, url: &str) -> Value {
+    let (status, body) = api::route(conn, url);
+    assert_eq!(status, 200, "{url}: {body}");
+    body
+}
+
+#[test]
+fn test_indexer_offers() {
+    let mut conn = db::open_in_memory().unwrap();
+    let maker = Pubkey::new_unique();
+    let other_maker = Pubkey::new_unique();
+
+    let records = [
+        record("create", 1, &[offer_created(1, maker, 100)]),
+        record("create-other", 2, &[offer_created(7, other_maker, 50)]),
+        record("partial-fill", 3, &[offer_taken(1, maker, 40, 60)]),
+        record("fill", 4, &[offer_taken(1, maker, 60, 0)]),
+        record(
+            "cancel",
+            5,
+            &[OfferCancelled {
+                version: EVENT_VERSION,
+                offer_id: 7,
+                maker: other_maker,
+                refunded_amount: 50,
+            }
+            .data()],
+        ),
+    ];
+    assert_eq!(ingest_dump(&mut conn, &records), records.len());
+    // Ingesting the same dump again changes nothing
+    assert_eq!(ingest_dump(&mut conn, &records), 0);
+    assert_eq!(
+        last_indexed(&conn).unwrap(),
+        Some((5, "cancel".to_string()))
+    );
+
+    let offer = get(&conn, &format!("/offers/{maker}/1"));
+    assert_eq!(offer["status"], "taken");
+    assert_eq!(offer["remaining_amount"], 0);
+    let fills = offer["fills"].as_array().unwrap();
+    assert_eq!(fills.len(), 2);
+    assert_eq!(fills[0]["amount_taken"], 40);
+    assert_eq!(fills[1]["remaining_amount"], 0);
+
+    let cancelled = get(&conn, "/offers?status=cancelled");
+    assert_eq!(cancelled.as_array().unwrap().len(), 1);
+    assert_eq!(cancelled[0]["maker"], other_maker.to_string());
+    assert!(get(&conn, "/offers?status=open")
+        .as_array()
+        .unwrap()
+        .is_empty());
+
+    let taken = get(&conn, "/events?name=OfferTaken&limit=1");
+    assert_eq!(taken.as_array().unwrap().len(), 1);
+    assert_eq!(taken[0]["signature"], "fill");
+    assert_eq!(taken[0]["version"], EVENT_VERSION);
+
+    assert_eq!(api::route(&conn, &format!("/offers/{maker}/2")).0, 404);
+    assert_eq!(api::route(&conn, "/unknown").0, 404);
+}
+
+#[test]
+fn test_indexer_fill_of_shared_offer_id() {
+    let mut conn = db::open_in_memory().unwrap();
+    let maker = Pubkey::new_unique();
+    let other_maker = Pubkey::new_unique();
+
+    let records = [
+        record("create", 1, &[offer_created(1, maker, 10)]),
+        record("create-other", 2, &[offer_created(1, other_maker, 10)]),
+        record("fill", 3, &[offer_taken(1, other_maker, 10, 0)]),
+    ];
+    ingest_dump(&mut conn, &records);
+
+    assert_eq!(get(&conn, &format!("/offers/{maker}/1"))["status"], "open");
+    let offer = get(&conn, &format!("/offers/{other_maker}/1"));
+    assert_eq!(offer["status"], "taken");
+    assert_eq!(offer["fills"].as_array().unwrap().len(), 1);
+}
+
+#[test]
+fn test_indexer_skips_amounts_above_i64_max() {
+    let mut conn = db::open_in_memory().unwrap();
+    let maker = Pubkey::new_unique();
+    let records = [
+        record("create", 1, &[offer_created(1, maker, 100)]),
+        record("huge-fill", 2, &[offer_taken(1, maker, u64::MAX, 0)]),
+        record("fill", 3, &[offer_taken(1, maker, 40, 60)]),
+    ];
+    assert_eq!(ingest_dump(&mut conn, &records), records.len());
+
+    // The huge fill left the offer untouched and later events still apply
+    let offer = get(&conn, &format!("/offers/{maker}/1"));
+    assert_eq!(offer["status"], "open");
+    assert_eq!(offer["remaining_amount"], 60);
+    assert_eq!(offer["fills"].as_array().unwrap().len(), 1);
+
+    let taken = get(&conn, "/events?name=OfferTaken");
+    assert_eq!(taken[0]["signature"], "fill");
+    assert_eq!(taken[0]["skipped"], Value::Null);
+    assert_eq!(taken[1]["signature"], "huge-fill");
+    assert!(taken[1]["skipped"].is_string());
+}
+
+#[test]
+fn test_indexer_polls() {
+    let mut conn = db::open_in_memory().unwrap();
+    let authority = Pubkey::new_unique();
+    let poll = pda::poll(&authority, 3);
+    let vote = |timestamp| {
+        VoteEvent {
+            version: EVENT_VERSION,
+            poll,
+            poll_id: 3,
+            vote_state: [[0; 32]; 7],
+            nonce: 0,
+            timestamp,
+        }
+        .data()
+    };
+
+    let mut failed = record("failed-vote", 4, &[vote(40)]);
+    failed.failed = true;
+    let records = [
+        record(
+            "create",
+            1,
+            &[PollCreated {
+                version: EVENT_VERSION,
+                poll,
+                poll_id: 3,
+                authority,
+                price: 1_000,
+                mint0: Pubkey::new_unique(),
+                mint1: Pubkey::new_unique(),
+                timestamp: 10,
+            }
+            .data()],
+        ),
+        record("vote", 2, &[vote(20)]),
+        record("vote-again", 3, &[vote(30)]),
+        failed,
+        record(
+            "reveal",
+            5,
+            &[RevealResultEvent {
+                version: EVENT_VERSION,
+                poll,
+                poll_id: 3,
+                output: [0, 0, 1, 1, 0, 0, 0],
+                consensus_price: 1_005,
+                revealed_at: 50,
+            }
+            .data()],
+        ),
+    ];
+    assert_eq!(ingest_dump(&mut conn, &records), records.len());
+
+    let polls = get(&conn, "/polls");
+    assert_eq!(polls.as_array().unwrap().len(), 1);
+    let history = get(&conn, &format!("/polls/{poll}"));
+    assert_eq!(history["authority"], authority.to_string());
+    // The failed transaction's vote was rolled back on chain
+    assert_eq!(history["votes_counted"], 2);
+    assert_eq!(history["consensus_price"], 1_005);
+    let reveals = history["reveals"].as_array().unwrap();
+    assert_eq!(reveals.len(), 1);
+    assert_eq!(
+        reveals[0]["counts"],
+        serde_json::json!([0, 0, 1, 1, 0, 0, 0])
+    );
+}
+
+#[test]
+fn test_indexer_skips_other_programs() {
+    let mut conn = db::open_in_memory().unwrap();
+    let other = Pubkey::new_unique();
+    let data = offer_created(1, Pubkey::new_unique(), 100);
+    let mut logged_elsewhere = record("other", 1, &[]);
+    logged_elsewhere.logs = vec![
+        format!("Program {other} invoke [1]"),
+        format!("Program data: {}", STANDARD.encode(data)),
+        format!("Program {other} success"),
+    ];
+    let unknown = record("unknown", 2, &[vec![0; 16]]);
+
+    assert_eq!(ingest_dump(&mut conn, &[logged_elsewhere, unknown]), 2);
+    assert!(get(&conn, "/offers").as_array().unwrap().is_empty());
+    // Undecodable program data is still kept, without a name
+    let events = get(&conn, "/events");
+    assert_eq!(events.as_array().unwrap().len(), 1);
+    assert_eq!(events[0]["signature"], "unknown");
+    assert_eq!(events[0]["name"], Value::Null);
+}
@@ -0,0 +1,170 @@
+use rational_dex::state::pool_state::LpPosition;
+use rational_dex::MINIMUM_LIQUIDITY;
+use rational_dex_client::accounts::decode;
+use rational_dex_client::instructions::{self, PoolKeys};
+use rational_dex_client::pda;
+
+use {
+    litesvm::LiteSVM, solana_keypair::Keypair, solana_pubkey::Pubkey, solana_signer::Signer,
+    spl_token::ID as TOKEN_PROGRAM_ID,
+};
+
+use crate::common::{deploy, initialize_config, send, set_mint, set_token_account, token_balance};
+
+const WALLET_TOKENS: u64 = 1_000_000_000_000;
+
+/// An empty pool with a 0.3% fee.
+fn setup() -> (LiteSVM, PoolKeys) {
+    let admin = Keypair::new();
+    let mut svm = LiteSVM::new();
+    deploy(&mut svm, admin.pubkey());
+    svm.airdrop(&admin.pubkey(), 10_000_000_000).unwrap();
+    initialize_config(&mut svm, &admin, vec![]);
+
+    let keys = PoolKeys {
+        mint_a: Pubkey::new_unique(),
+        mint_b: Pubkey::new_unique(),
+        mint_lp: Pubkey::new_unique(),
+        token_program: TOKEN_PROGRAM_ID,
+    };
+    set_mint(&mut svm, keys.mint_a, admin.pubkey());
+    set_mint(&mut svm, keys.mint_b, admin.pubkey());
+    set_mint(&mut svm, keys.mint_lp, keys.pool());
+    let ix = instructions::initialize_pool(admin.pubkey(), &keys, 30, 10_000);
+    send(&mut svm, &[ix], &[&admin]).unwrap();
+    (svm, keys)
+}
+
+fn funded_user(svm: &mut LiteSVM, keys: &PoolKeys) -> Keypair {
+    let user = Keypair::new();
+    svm.airdrop(&user.pubkey(), 10_000_000_000).unwrap();
+    for (mint, amount) in [
+        (keys.mint_a, WALLET_TOKENS),
+        (keys.mint_b, WALLET_TOKENS),
+        (keys.mint_lp, 0),
+    ] {
+        let address = pda::ata(&user.pubkey(), &mint, &keys.token_program);
+        set_token_account(svm, address, mint, user.pubkey(), amount);
+    }
+    user
+}
+
+fn deposit(svm: &mut LiteSVM, keys: &PoolKeys, user: &Keypair, amount_a: u64, amount_b: u64) {
+    let ix = instructions::add_liquidity(user.pubkey(), keys, amount_a, amount_b, 0);
+    send(svm, &[ix], &[user]).unwrap();
+}
+
+fn withdraw(svm: &mut LiteSVM, keys: &PoolKeys, user: &Keypair, lp_amount: u64) {
+    let ix = instructions::remove_liquidity(user.pubkey(), keys, lp_amount, 0, 0);
+    send(svm, &[ix], &[user]).unwrap();
+}
+
+fn position(svm: &LiteSVM, keys: &PoolKeys, user: &Keypair) -> LpPosition {
+    let address = pda::lp_position(&keys.pool(), &user.pubkey());
+    decode(&svm.get_account(&address).unwrap().data).unwrap()
+}
+
+#[test]
+fn test_deposits_add_to_the_position_basis() {
+    let (mut svm, keys) = setup();
+    let lp = funded_user(&mut svm, &keys);
+
+    deposit(&mut svm, &keys, &lp, 1_000_000_000, 1_000_000_000);
+    let first = position(&svm, &keys, &lp);
+    assert_eq!((first.pool, first.owner), (keys.pool(), lp.pubkey()));
+    // The locked minimum is not part of anyone's position
+    assert_eq!(first.lp_amount, 1_000_000_000 - MINIMUM_LIQUIDITY);
+    assert_eq!(
+        (first.deposited_a, first.deposited_b),
+        (1_000_000_000, 1_000_000_000)
+    );
+
+    deposit(&mut svm, &keys, &lp, 500_000_000, 500_000_000);
+    let second = position(&svm, &keys, &lp);
+    assert_eq!(second.lp_amount, first.lp_amount + 500_000_000);
+    assert_eq!(
+        (second.deposited_a, second.deposited_b),
+        (1_500_000_000, 1_500_000_000)
+    );
+    assert_eq!(second.opened_at, first.opened_at);
+}
+
+#[test]
+fn test_withdrawals_reduce_the_basis_pro_rata() {
+    let (mut svm, keys) = setup();
+    let lp = funded_user(&mut svm, &keys);
+    deposit(&mut svm, &keys, &lp, 1_000_000, 4_000_000);
+    let lp_amount = position(&svm, &keys, &lp).lp_amount;
+    assert_eq!(lp_amount, 1_999_000);
+
+    withdraw(&mut svm, &keys, &lp, lp_amount / 2);
+    let position = position(&svm, &keys, &lp);
+    assert_eq!(position.lp_amount, lp_amount / 2);
+    assert_eq!(
+        (position.deposited_a, position.deposited_b),
+        (500_000, 2_000_000)
+    );
+}
+
+#[test]
+fn test_fees_accrue_only_to_positions_open_during_the_swap() {
+    let (mut svm, keys) = setup();
+    let early = funded_user(&mut svm, &keys);
+    let trader = funded_user(&mut svm, &keys);
+    deposit(&mut svm, &keys, &early, 1_000_000_000, 1_000_000_000);
+
+    let ix = instructions::swap(trader.pubkey(), &keys, 1_000_000, 0, true);
+    send(&mut svm, &[ix], &[&trader]).unwrap();
+
+    let late = funded_user(&mut svm, &keys);
+    deposit(&mut svm, &keys, &late, 1_000_000_000, 1_000_000_000);
+
+    // Withdrawing settles the fees earned so far into the position
+    for user in [&early, &late] {
+        withdraw(&mut svm, &keys, user, 1);
+    }
+    // 3_000 fee, 600 of it to the protocol, shared by all but the locked LP tokens
+    let early = position(&svm, &keys, &early);
+    assert_eq!((early.fees_earned_a, early.fees_earned_b), (2_399, 0));
+    let late = position(&svm, &keys, &late);
+    assert_eq!((late.fees_earned_a, late.fees_earned_b), (0, 0));
+}
+
+#[test]
+fn test_transferred_lp_tokens_carry_no_basis() {
+    let (mut svm, keys) = setup();
+    let lp = funded_user(&mut svm, &keys);
+    let receiver = funded_user(&mut svm, &keys);
+    deposit(&mut svm, &keys, &lp, 1_000_000, 4_000_000);
+
+    // Move 1_000 LP tokens as a plain token transfer would
+    let lp_account = pda::ata(&lp.pubkey(), &keys.mint_lp, &keys.token_program);
+    let receiver_account = pda::ata(&receiver.pubkey(), &keys.mint_lp, &keys.token_program);
+    let balance = token_balance(&svm, &lp_account);
+    set_token_account(
+        &mut svm,
+        lp_account,
+        keys.mint_lp,
+        lp.pubkey(),
+        balance - 1_000,
+    );
+    set_token_account(
+        &mut svm,
+        receiver_account,
+        keys.mint_lp,
+        receiver.pubkey(),
+        1_000,
+    );
+
+    withdraw(&mut svm, &keys, &receiver, 1_000);
+    let position = position(&svm, &keys, &receiver);
+    assert_eq!(position.owner, receiver.pubkey());
+    assert_eq!(
+        (
+            position.lp_amount,
+            position.deposited_a,
+            position.deposited_b
+        ),
+        (0, 0, 0)
+    );
+}
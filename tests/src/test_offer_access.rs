@@ -0,0 +1,70 @@
+use rational_dex::instructions::swap::verify_taker;
+use rational_dex::state::swap_state::OfferAccess;
+
+use {solana_program::hash::hashv, solana_pubkey::Pubkey};
+
+fn leaf(taker: &Pubkey) -> [u8; 32] {
+    hashv(&[taker.as_ref()]).to_bytes()
+}
+
+fn parent(a: [u8; 32], b: [u8; 32]) -> [u8; 32] {
+    let (left, right) = if a <= b { (a, b) } else { (b, a) };
+    hashv(&[&left, &right]).to_bytes()
+}
+
+/// Root of a four-leaf tree over `takers` and the proof of each taker.
+fn tree(takers: &[Pubkey; 4]) -> ([u8; 32], Vec<Vec<[u8; 32]>>) {
+    let leaves = takers.map(|taker| leaf(&taker));
+    let left = parent(leaves[0], leaves[1]);
+    let right = parent(leaves[2], leaves[3]);
+    let proofs = vec![
+        vec![leaves[1], right],
+        vec![leaves[0], right],
+        vec![leaves[3], left],
+        vec![leaves[2], left],
+    ];
+    (parent(left, right), proofs)
+}
+
+#[test]
+fn test_public_offer_allows_anyone() {
+    assert!(verify_taker(&OfferAccess::Public, &Pubkey::new_unique(), &[]).is_ok());
+}
+
+#[test]
+fn test_private_offer_allows_only_its_taker() {
+    let taker = Pubkey::new_unique();
+    let access = OfferAccess::Taker(taker);
+    assert!(verify_taker(&access, &taker, &[]).is_ok());
+    assert!(verify_taker(&access, &Pubkey::new_unique(), &[]).is_err());
+}
+
+#[test]
+fn test_merkle_proof_admits_every_listed_taker() {
+    let takers = [0; 4].map(|_| Pubkey::new_unique());
+    let (root, proofs) = tree(&takers);
+    let access = OfferAccess::MerkleRoot(root);
+    for (taker, proof) in takers.iter().zip(&proofs) {
+        assert!(verify_taker(&access, taker, proof).is_ok());
+    }
+}
+
+#[test]
+fn test_merkle_proof_rejects_outsiders_and_borrowed_proofs() {
+    let takers = [0; 4].map(|_| Pubkey::new_unique());
+    let (root, proofs) = tree(&takers);
+    let access = OfferAccess::MerkleRoot(root);
+
+    assert!(verify_taker(&access, &Pubkey::new_unique(), &proofs[0]).is_err());
+    assert!(verify_taker(&access, &takers[0], &proofs[2]).is_err());
+    assert!(verify_taker(&access, &takers[0], &proofs[0][..1]).is_err());
+    assert!(verify_taker(&access, &takers[0], &[]).is_err());
+}
+
+#[test]
+fn test_single_taker_root_is_its_leaf() {
+    let taker = Pubkey::new_unique();
+    let access = OfferAccess::MerkleRoot(leaf(&taker));
+    assert!(verify_taker(&access, &taker, &[]).is_ok());
+    assert!(verify_taker(&access, &Pubkey::new_unique(), &[]).is_err());
+}
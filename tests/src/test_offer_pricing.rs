@@ -0,0 +1,181 @@
+use rational_dex::instructions::swap::compute_token_b_owed;
+use rational_dex::state::swap_state::{Offer, OfferAccess, Price, PricingMode};
+use rational_dex::PRICE_SCALE;
+use rational_dex_client::instructions::{self, OfferKeys};
+use rational_dex_client::pda;
+
+use {
+    litesvm::LiteSVM, solana_keypair::Keypair, solana_pubkey::Pubkey, solana_signer::Signer,
+    spl_token::ID as TOKEN_PROGRAM_ID,
+};
+
+use crate::common::{
+    deploy, initialize_config, pool_state, send, set_mint, set_program_account, set_token_account,
+    token_balance,
+};
+
+fn offer(offered_amount: u64, remaining_amount: u64, pricing: PricingMode) -> Offer {
+    Offer {
+        id: 0,
+        maker: Pubkey::default(),
+        token_mint_a: Pubkey::default(),
+        token_mint_b: Pubkey::default(),
+        offered_amount,
+        token_b_wanted_amount: 0,
+        remaining_amount,
+        pricing,
+        access: OfferAccess::Public,
+        bump: 0,
+    }
+}
+
+#[test]
+fn test_fixed_price_full_fill_owes_the_wanted_amount() {
+    let fixed = PricingMode::Fixed {
+        wanted_amount: 5_000,
+    };
+    assert_eq!(
+        compute_token_b_owed(&offer(1_000, 1_000, fixed), 1_000, None).unwrap(),
+        5_000
+    );
+}
+
+#[test]
+fn test_fixed_price_partial_fills_add_up_to_the_wanted_amount() {
+    let fixed = PricingMode::Fixed { wanted_amount: 10 };
+    let mut owed = Vec::new();
+    for remaining in [3, 2, 1] {
+        owed.push(compute_token_b_owed(&offer(3, remaining, fixed), 1, None).unwrap());
+    }
+    // Each fill rounds up, but only by what the earlier fills did not already pay
+    assert_eq!(owed, [4, 3, 3]);
+}
+
+#[test]
+fn test_oracle_price_adds_the_premium_rounded_up() {
+    let oracle = PricingMode::OracleRelative {
+        premium_bps: 100,
+        reference_pool: None,
+    };
+    let scale = PRICE_SCALE as u64;
+    let prices = Some((3 * scale, 2 * scale));
+    // 1_000 * 3 / 2 = 1_500, plus 1%
+    assert_eq!(
+        compute_token_b_owed(&offer(1_000, 1_000, oracle), 1_000, prices).unwrap(),
+        1_515
+    );
+    // 1 * 1.5 * 1.01 rounds up to 2
+    assert_eq!(
+        compute_token_b_owed(&offer(1_000, 1_000, oracle), 1, prices).unwrap(),
+        2
+    );
+}
+
+#[test]
+fn test_oracle_price_needs_both_prices() {
+    let oracle = PricingMode::OracleRelative {
+        premium_bps: 0,
+        reference_pool: None,
+    };
+    let offer = offer(1_000, 1_000, oracle);
+    assert!(compute_token_b_owed(&offer, 1_000, None).is_err());
+    assert!(compute_token_b_owed(&offer, 1_000, Some((0, 1))).is_err());
+    assert!(compute_token_b_owed(&offer, 1_000, Some((1, 0))).is_err());
+}
+
+#[test]
+fn test_oracle_price_overflow_is_an_error() {
+    let oracle = PricingMode::OracleRelative {
+        premium_bps: 0,
+        reference_pool: None,
+    };
+    let offer = offer(u64::MAX, u64::MAX, oracle);
+    assert!(compute_token_b_owed(&offer, u64::MAX, Some((u64::MAX, 1))).is_err());
+}
+
+/// An offer of `keys` funded with 1_000 token A, and a taker holding 10_000 token B.
+fn setup() -> (LiteSVM, OfferKeys, Keypair, Keypair) {
+    let admin = Keypair::new();
+    let mut svm = LiteSVM::new();
+    deploy(&mut svm, admin.pubkey());
+    svm.airdrop(&admin.pubkey(), 10_000_000_000).unwrap();
+    initialize_config(&mut svm, &admin, vec![]);
+
+    let maker = Keypair::new();
+    let taker = Keypair::new();
+    svm.airdrop(&maker.pubkey(), 10_000_000_000).unwrap();
+    svm.airdrop(&taker.pubkey(), 10_000_000_000).unwrap();
+    let keys = OfferKeys {
+        maker: maker.pubkey(),
+        id: 1,
+        token_mint_a: Pubkey::new_unique(),
+        token_mint_b: Pubkey::new_unique(),
+        token_program: TOKEN_PROGRAM_ID,
+    };
+    set_mint(&mut svm, keys.token_mint_a, admin.pubkey());
+    set_mint(&mut svm, keys.token_mint_b, admin.pubkey());
+    let maker_a = pda::ata(&maker.pubkey(), &keys.token_mint_a, &keys.token_program);
+    set_token_account(&mut svm, maker_a, keys.token_mint_a, maker.pubkey(), 1_000);
+    let taker_b = pda::ata(&taker.pubkey(), &keys.token_mint_b, &keys.token_program);
+    set_token_account(&mut svm, taker_b, keys.token_mint_b, taker.pubkey(), 10_000);
+    (svm, keys, maker, taker)
+}
+
+#[test]
+fn test_take_offer_respects_max_token_b() {
+    let (mut svm, keys, maker, taker) = setup();
+    let pricing = PricingMode::Fixed {
+        wanted_amount: 5_000,
+    };
+    let ix = instructions::make_offer(&keys, 1_000, pricing, OfferAccess::Public);
+    send(&mut svm, &[ix], &[&maker]).unwrap();
+
+    let take = |max_token_b| {
+        instructions::take_offer(taker.pubkey(), &keys, &pricing, 1_000, max_token_b, vec![])
+    };
+    assert!(send(&mut svm, &[take(4_999)], &[&taker]).is_err());
+    send(&mut svm, &[take(5_000)], &[&taker]).unwrap();
+    let taker_b = pda::ata(&taker.pubkey(), &keys.token_mint_b, &keys.token_program);
+    assert_eq!(token_balance(&svm, &taker_b), 5_000);
+}
+
+#[test]
+fn test_taker_cannot_pick_the_oracle_price_source() {
+    let (mut svm, keys, maker, taker) = setup();
+    let scale = PRICE_SCALE as u64;
+    // The pool prices token A at 2 token B, the `Price` PDAs at 1
+    let reference_pool = Pubkey::new_unique();
+    let mut pool = pool_state(0, 0, 0);
+    pool.mint_a = keys.token_mint_a;
+    pool.mint_b = keys.token_mint_b;
+    pool.reference_price = 2 * scale;
+    set_program_account(&mut svm, reference_pool, &pool);
+    for mint in [keys.token_mint_a, keys.token_mint_b] {
+        let price = Price {
+            token_mint: mint,
+            price: scale,
+            last_updated: 0,
+            bump: 0,
+        };
+        set_program_account(&mut svm, pda::price(&mint), &price);
+    }
+
+    let pricing = PricingMode::OracleRelative {
+        premium_bps: 0,
+        reference_pool: Some(reference_pool),
+    };
+    let ix = instructions::make_offer(&keys, 1_000, pricing, OfferAccess::Public);
+    send(&mut svm, &[ix], &[&maker]).unwrap();
+
+    let cheaper = PricingMode::OracleRelative {
+        premium_bps: 0,
+        reference_pool: None,
+    };
+    let ix = instructions::take_offer(taker.pubkey(), &keys, &cheaper, 1_000, u64::MAX, vec![]);
+    assert!(send(&mut svm, &[ix], &[&taker]).is_err());
+
+    let ix = instructions::take_offer(taker.pubkey(), &keys, &pricing, 1_000, u64::MAX, vec![]);
+    send(&mut svm, &[ix], &[&taker]).unwrap();
+    let taker_b = pda::ata(&taker.pubkey(), &keys.token_mint_b, &keys.token_program);
+    assert_eq!(token_balance(&svm, &taker_b), 8_000);
+}
@@ -0,0 +1,271 @@
+use rational_dex::error::ConfigError;
+use rational_dex::state::config_state::Subsystem;
+use rational_dex::state::order_book_state::{OpenOrders, Order, OrderBook, Side, SortedOrders};
+use rational_dex::{ORDER_BOOK_CAPACITY, PRICE_SCALE};
+use rational_dex_client::accounts::{decode, decode_zero_copy};
+use rational_dex_client::instructions::{self, MarketKeys};
+use rational_dex_client::{pda, PROGRAM_ID};
+
+use {
+    litesvm::LiteSVM, solana_account::Account, solana_keypair::Keypair, solana_pubkey::Pubkey,
+    solana_signer::Signer, spl_token::ID as TOKEN_PROGRAM_ID,
+};
+
+use crate::common::{
+    custom_error, deploy, initialize_config, send, set_mint, set_token_account, token_balance,
+};
+
+const WALLET_TOKENS: u64 = 1_000_000_000_000;
+const SCALE: u64 = PRICE_SCALE as u64;
+
+fn order(order_id: u64, price: u64) -> Order {
+    Order {
+        order_id,
+        open_orders: Pubkey::default(),
+        price,
+        quantity: 1,
+        locked: 1,
+    }
+}
+
+fn empty_side() -> SortedOrders {
+    SortedOrders {
+        len: 0,
+        orders: [order(0, 0); ORDER_BOOK_CAPACITY],
+    }
+}
+
+fn ids(side: &SortedOrders) -> Vec<u64> {
+    side.orders().iter().map(|order| order.order_id).collect()
+}
+
+#[test]
+fn test_bids_sort_by_highest_price_then_time() {
+    let mut side = empty_side();
+    for (id, price) in [(1, 100), (2, 300), (3, 200), (4, 300)] {
+        assert!(side.insert(order(id, price), Side::Bid));
+    }
+    assert_eq!(ids(&side), [2, 4, 3, 1]);
+}
+
+#[test]
+fn test_asks_sort_by_lowest_price_then_time() {
+    let mut side = empty_side();
+    for (id, price) in [(1, 300), (2, 100), (3, 200), (4, 100)] {
+        assert!(side.insert(order(id, price), Side::Ask));
+    }
+    assert_eq!(ids(&side), [2, 4, 3, 1]);
+    assert_eq!(side.best().unwrap().order_id, 2);
+}
+
+#[test]
+fn test_side_remove_and_find() {
+    let mut side = empty_side();
+    for id in 1..=3 {
+        side.insert(order(id, 100), Side::Ask);
+    }
+    assert_eq!(side.find(2), Some(1));
+    assert_eq!(side.remove(1).order_id, 2);
+    assert_eq!(ids(&side), [1, 3]);
+    assert_eq!(side.find(2), None);
+}
+
+#[test]
+fn test_full_side_rejects_inserts() {
+    let mut side = empty_side();
+    for id in 0..ORDER_BOOK_CAPACITY as u64 {
+        assert!(side.insert(order(id, 100), Side::Bid));
+    }
+    assert!(side.is_full());
+    assert!(!side.insert(order(u64::MAX, 1_000), Side::Bid));
+    assert_eq!(side.len as usize, ORDER_BOOK_CAPACITY);
+}
+
+/// A market whose order book the test allocated, as a client would, before creating it.
+fn setup() -> (LiteSVM, MarketKeys) {
+    let (svm, keys, _) = setup_with_admin();
+    (svm, keys)
+}
+
+fn setup_with_admin() -> (LiteSVM, MarketKeys, Keypair) {
+    let admin = Keypair::new();
+    let mut svm = LiteSVM::new();
+    deploy(&mut svm, admin.pubkey());
+    svm.airdrop(&admin.pubkey(), 10_000_000_000).unwrap();
+    initialize_config(&mut svm, &admin, vec![]);
+
+    let keys = MarketKeys {
+        base_mint: Pubkey::new_unique(),
+        quote_mint: Pubkey::new_unique(),
+        order_book: Pubkey::new_unique(),
+        token_program: TOKEN_PROGRAM_ID,
+    };
+    set_mint(&mut svm, keys.base_mint, admin.pubkey());
+    set_mint(&mut svm, keys.quote_mint, admin.pubkey());
+    let data = vec![0; 8 + std::mem::size_of::<OrderBook>()];
+    svm.set_account(
+        keys.order_book,
+        Account {
+            lamports: svm.minimum_balance_for_rent_exemption(data.len()),
+            data,
+            owner: PROGRAM_ID,
+            executable: false,
+            rent_epoch: 0,
+        },
+    )
+    .unwrap();
+
+    let ix = instructions::create_market(admin.pubkey(), &keys);
+    send(&mut svm, &[ix], &[&admin]).unwrap();
+    (svm, keys, admin)
+}
+
+/// A wallet holding base and quote tokens.
+fn trader(svm: &mut LiteSVM, keys: &MarketKeys) -> Keypair {
+    let trader = Keypair::new();
+    svm.airdrop(&trader.pubkey(), 10_000_000_000).unwrap();
+    for mint in [keys.base_mint, keys.quote_mint] {
+        let address = pda::ata(&trader.pubkey(), &mint, &keys.token_program);
+        set_token_account(svm, address, mint, trader.pubkey(), WALLET_TOKENS);
+    }
+    trader
+}
+
+fn place(svm: &mut LiteSVM, trader: &Keypair, keys: &MarketKeys, side: Side, price: u64, qty: u64) {
+    let ix = instructions::place_order(trader.pubkey(), keys, side, price, qty);
+    send(svm, &[ix], &[trader]).unwrap();
+}
+
+fn open_orders(svm: &LiteSVM, keys: &MarketKeys, trader: &Keypair) -> OpenOrders {
+    let address = pda::open_orders(&keys.market(), &trader.pubkey());
+    decode(&svm.get_account(&address).unwrap().data).unwrap()
+}
+
+fn book(svm: &LiteSVM, keys: &MarketKeys) -> OrderBook {
+    decode_zero_copy(&svm.get_account(&keys.order_book).unwrap().data).unwrap()
+}
+
+fn balance(svm: &LiteSVM, trader: &Keypair, keys: &MarketKeys, mint: &Pubkey) -> u64 {
+    token_balance(svm, &pda::ata(&trader.pubkey(), mint, &keys.token_program))
+}
+
+#[test]
+fn test_crossing_orders_fill_at_the_older_price_and_settle() {
+    let (mut svm, keys) = setup();
+    let bidder = trader(&mut svm, &keys);
+    let asker = trader(&mut svm, &keys);
+    let payer = trader(&mut svm, &keys);
+
+    place(&mut svm, &bidder, &keys, Side::Bid, 2 * SCALE, 1_000);
+    place(&mut svm, &asker, &keys, Side::Ask, 3 * SCALE / 2, 600);
+
+    let ix = instructions::match_orders(&keys, &[bidder.pubkey(), asker.pubkey()], 10);
+    send(&mut svm, &[ix], &[&payer]).unwrap();
+
+    // The bid came first, so 600 base trade at its price of 2
+    let bids = open_orders(&svm, &keys, &bidder);
+    assert_eq!(
+        (bids.base_free, bids.quote_free, bids.quote_locked),
+        (600, 0, 800)
+    );
+    let asks = open_orders(&svm, &keys, &asker);
+    assert_eq!((asks.quote_free, asks.base_locked), (1_200, 0));
+    let book = book(&svm, &keys);
+    assert_eq!(book.asks.len, 0);
+    assert_eq!(
+        (book.bids.orders[0].quantity, book.bids.orders[0].locked),
+        (400, 800)
+    );
+
+    for trader in [&bidder, &asker] {
+        let ix = instructions::settle_funds(trader.pubkey(), &keys);
+        send(&mut svm, &[ix], &[trader]).unwrap();
+    }
+    assert_eq!(
+        balance(&svm, &bidder, &keys, &keys.base_mint),
+        WALLET_TOKENS + 600
+    );
+    assert_eq!(
+        balance(&svm, &asker, &keys, &keys.quote_mint),
+        WALLET_TOKENS + 1_200
+    );
+
+    // Cancelling the rest of the bid frees its remaining reservation
+    let ix = instructions::cancel_order(bidder.pubkey(), &keys, Side::Bid, 1);
+    send(&mut svm, &[ix], &[&bidder]).unwrap();
+    let bids = open_orders(&svm, &keys, &bidder);
+    assert_eq!((bids.quote_free, bids.quote_locked), (800, 0));
+}
+
+#[test]
+fn test_match_rejects_duplicate_open_orders() {
+    let (mut svm, keys) = setup();
+    let bidder = trader(&mut svm, &keys);
+    let asker = trader(&mut svm, &keys);
+
+    place(&mut svm, &bidder, &keys, Side::Bid, SCALE, 1_000);
+    place(&mut svm, &asker, &keys, Side::Ask, SCALE, 1_000);
+
+    let owners = [bidder.pubkey(), asker.pubkey(), bidder.pubkey()];
+    let ix = instructions::match_orders(&keys, &owners, 10);
+    assert!(send(&mut svm, &[ix], &[&asker]).is_err());
+}
+
+#[test]
+fn test_offers_pause_stops_the_crank() {
+    let (mut svm, keys, admin) = setup_with_admin();
+    let bidder = trader(&mut svm, &keys);
+    let asker = trader(&mut svm, &keys);
+
+    place(&mut svm, &bidder, &keys, Side::Bid, SCALE, 1_000);
+    place(&mut svm, &asker, &keys, Side::Ask, SCALE, 1_000);
+
+    let ix = instructions::set_paused(admin.pubkey(), Subsystem::Offers, true);
+    send(&mut svm, &[ix], &[&admin]).unwrap();
+    let owners = [bidder.pubkey(), asker.pubkey()];
+    let ix = instructions::match_orders(&keys, &owners, 10);
+    assert_eq!(
+        custom_error(send(&mut svm, &[ix], &[&asker])),
+        Some(ConfigError::OffersPaused.into())
+    );
+
+    let ix = instructions::set_paused(admin.pubkey(), Subsystem::Offers, false);
+    send(&mut svm, &[ix], &[&admin]).unwrap();
+    let ix = instructions::match_orders(&keys, &owners, 10);
+    send(&mut svm, &[ix], &[&asker]).unwrap();
+    assert_eq!(open_orders(&svm, &keys, &bidder).base_free, 1_000);
+}
+
+#[test]
+fn test_orders_worth_less_than_one_quote_atom_are_rejected() {
+    let (mut svm, keys) = setup();
+    let bidder = trader(&mut svm, &keys);
+
+    let ix = instructions::place_order(bidder.pubkey(), &keys, Side::Bid, SCALE / 2, 1);
+    assert!(send(&mut svm, &[ix], &[&bidder]).is_err());
+}
+
+#[test]
+fn test_dust_fill_cancels_the_smaller_order() {
+    let (mut svm, keys) = setup();
+    let bidder = trader(&mut svm, &keys);
+    let asker = trader(&mut svm, &keys);
+
+    // At the older ask's price one base atom is worth half a quote atom
+    place(&mut svm, &asker, &keys, Side::Ask, SCALE / 2, 10);
+    place(&mut svm, &bidder, &keys, Side::Bid, SCALE, 1);
+
+    let ix = instructions::match_orders(&keys, &[bidder.pubkey(), asker.pubkey()], 10);
+    send(&mut svm, &[ix], &[&asker]).unwrap();
+
+    let bids = open_orders(&svm, &keys, &bidder);
+    assert_eq!(
+        (bids.base_free, bids.quote_free, bids.quote_locked),
+        (0, 1, 0)
+    );
+    let asks = open_orders(&svm, &keys, &asker);
+    assert_eq!((asks.quote_free, asks.base_locked), (0, 10));
+    let book = book(&svm, &keys);
+    assert_eq!(book.bids.len, 0);
+    assert_eq!(book.asks.orders[0].quantity, 10);
+}
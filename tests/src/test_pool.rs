@@ -0,0 +1,431 @@
+use rational_dex::state::poll_state::PollAccount;
+use rational_dex::state::pool_state::Pool;
+use rational_dex::MINIMUM_LIQUIDITY;
+use rational_dex_client::accounts::decode;
+use rational_dex_client::instructions::{self, PoolKeys};
+use rational_dex_client::pda;
+
+use {
+    litesvm::LiteSVM,
+    solana_keypair::Keypair,
+    solana_program::clock::Clock,
+    solana_pubkey::Pubkey,
+    solana_signer::Signer,
+    spl_token::{native_mint, ID as TOKEN_PROGRAM_ID},
+};
+
+use anchor_spl::token_2022::ID as TOKEN_2022_PROGRAM_ID;
+
+use crate::common::{
+    deploy, initialize_config, send, set_fee_mint, set_fee_token_account, set_mint,
+    set_program_account, set_token_account, set_wsol_account, token_balance, warp,
+};
+
+const WALLET_TOKENS: u64 = 1_000_000_000_000;
+
+/// A pool with a 0.3% fee whose LP mint is owned by the pool, created by the config admin.
+fn setup() -> (LiteSVM, Keypair, PoolKeys) {
+    let admin = Keypair::new();
+    let mut svm = LiteSVM::new();
+    deploy(&mut svm, admin.pubkey());
+    svm.airdrop(&admin.pubkey(), 10_000_000_000).unwrap();
+    initialize_config(&mut svm, &admin, vec![]);
+
+    // `new_unique` counts up, so the mints are already in canonical order
+    let keys = PoolKeys {
+        mint_a: Pubkey::new_unique(),
+        mint_b: Pubkey::new_unique(),
+        mint_lp: Pubkey::new_unique(),
+        token_program: TOKEN_PROGRAM_ID,
+    };
+    set_mint(&mut svm, keys.mint_a, admin.pubkey());
+    set_mint(&mut svm, keys.mint_b, admin.pubkey());
+    set_mint(&mut svm, keys.mint_lp, keys.pool());
+    let ix = instructions::initialize_pool(admin.pubkey(), &keys, 30, 10_000);
+    send(&mut svm, &[ix], &[&admin]).unwrap();
+    (svm, admin, keys)
+}
+
+/// A wallet holding both pool tokens and an empty LP token account.
+fn funded_user(svm: &mut LiteSVM, keys: &PoolKeys) -> Keypair {
+    let user = Keypair::new();
+    svm.airdrop(&user.pubkey(), 10_000_000_000).unwrap();
+    for (mint, amount) in [
+        (keys.mint_a, WALLET_TOKENS),
+        (keys.mint_b, WALLET_TOKENS),
+        (keys.mint_lp, 0),
+    ] {
+        let address = pda::ata(&user.pubkey(), &mint, &keys.token_program);
+        set_token_account(svm, address, mint, user.pubkey(), amount);
+    }
+    user
+}
+
+fn pool(svm: &LiteSVM, keys: &PoolKeys) -> Pool {
+    let account = svm.get_account(&keys.pool()).unwrap();
+    decode(&account.data).unwrap()
+}
+
+fn balance(svm: &LiteSVM, user: &Keypair, keys: &PoolKeys, mint: &Pubkey) -> u64 {
+    token_balance(svm, &pda::ata(&user.pubkey(), mint, &keys.token_program))
+}
+
+#[test]
+fn test_pool_mints_must_be_in_ascending_order() {
+    let admin = Keypair::new();
+    let mut svm = LiteSVM::new();
+    deploy(&mut svm, admin.pubkey());
+    svm.airdrop(&admin.pubkey(), 10_000_000_000).unwrap();
+    initialize_config(&mut svm, &admin, vec![]);
+
+    let (low, high) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let keys = PoolKeys {
+        mint_a: high,
+        mint_b: low,
+        mint_lp: Pubkey::new_unique(),
+        token_program: TOKEN_PROGRAM_ID,
+    };
+    set_mint(&mut svm, keys.mint_a, admin.pubkey());
+    set_mint(&mut svm, keys.mint_b, admin.pubkey());
+    set_mint(&mut svm, keys.mint_lp, keys.pool());
+    let ix = instructions::initialize_pool(admin.pubkey(), &keys, 30, 10_000);
+    assert!(send(&mut svm, &[ix], &[&admin]).is_err());
+}
+
+#[test]
+fn test_first_deposit_locks_minimum_liquidity() {
+    let (mut svm, _, keys) = setup();
+    let user = funded_user(&mut svm, &keys);
+
+    let ix = instructions::add_liquidity(user.pubkey(), &keys, 1_000_000, 4_000_000, 0);
+    send(&mut svm, &[ix], &[&user]).unwrap();
+
+    assert_eq!(
+        balance(&svm, &user, &keys, &keys.mint_lp),
+        2_000_000 - MINIMUM_LIQUIDITY
+    );
+    assert_eq!(pool(&svm, &keys).total_lp_supply, 2_000_000);
+}
+
+#[test]
+fn test_first_deposit_below_minimum_liquidity_fails() {
+    let (mut svm, _, keys) = setup();
+    let user = funded_user(&mut svm, &keys);
+
+    let ix = instructions::add_liquidity(user.pubkey(), &keys, 1_000, 1_000, 0);
+    assert!(send(&mut svm, &[ix], &[&user]).is_err());
+}
+
+#[test]
+fn test_donation_cannot_round_later_deposits_to_nothing() {
+    let (mut svm, _, keys) = setup();
+    let attacker = funded_user(&mut svm, &keys);
+    let victim = funded_user(&mut svm, &keys);
+
+    // Smallest possible first deposit, then a large donation straight into the vaults
+    let ix = instructions::add_liquidity(attacker.pubkey(), &keys, 1_001, 1_001, 0);
+    send(&mut svm, &[ix], &[&attacker]).unwrap();
+    let donation = 1_000_000_000;
+    for (vault, mint) in [
+        (pda::vault_a(&keys.pool()), keys.mint_a),
+        (pda::vault_b(&keys.pool()), keys.mint_b),
+    ] {
+        let amount = token_balance(&svm, &vault) + donation;
+        set_token_account(&mut svm, vault, mint, vault, amount);
+    }
+
+    let ix = instructions::add_liquidity(victim.pubkey(), &keys, donation, donation, 1);
+    send(&mut svm, &[ix], &[&victim]).unwrap();
+
+    // Without the locked minimum the attacker would own 1_001 of 2_001 LP tokens, and with
+    // them half the victim's deposit. It owns 1 and the victim can redeem what it paid.
+    let pool = pool(&svm, &keys);
+    let victim_lp = balance(&svm, &victim, &keys, &keys.mint_lp);
+    let paid = WALLET_TOKENS - balance(&svm, &victim, &keys, &keys.mint_a);
+    let redeemable = victim_lp as u128 * token_balance(&svm, &pda::vault_a(&keys.pool())) as u128
+        / pool.total_lp_supply as u128;
+    assert_eq!(balance(&svm, &attacker, &keys, &keys.mint_lp), 1);
+    assert!(redeemable + 1 >= paid as u128);
+}
+
+#[test]
+fn test_swap_pays_out_and_grows_the_product() {
+    let (mut svm, _, keys) = setup();
+    let lp = funded_user(&mut svm, &keys);
+    let trader = funded_user(&mut svm, &keys);
+    let ix = instructions::add_liquidity(lp.pubkey(), &keys, 1_000_000_000, 1_000_000_000, 0);
+    send(&mut svm, &[ix], &[&lp]).unwrap();
+
+    let vault_a = pda::vault_a(&keys.pool());
+    let vault_b = pda::vault_b(&keys.pool());
+    let k_before = token_balance(&svm, &vault_a) as u128 * token_balance(&svm, &vault_b) as u128;
+
+    // Slippage bound above what the pool can pay
+    let ix = instructions::swap(trader.pubkey(), &keys, 1_000_000, 1_000_000, true);
+    assert!(send(&mut svm, &[ix], &[&trader]).is_err());
+
+    let ix = instructions::swap(trader.pubkey(), &keys, 1_000_000, 990_000, true);
+    send(&mut svm, &[ix], &[&trader]).unwrap();
+    let received = balance(&svm, &trader, &keys, &keys.mint_b) - WALLET_TOKENS;
+    assert!((990_000..1_000_000).contains(&received));
+
+    let pool = pool(&svm, &keys);
+    let reserve_a = token_balance(&svm, &vault_a) - pool.protocol_fees_a;
+    let k_after = reserve_a as u128 * token_balance(&svm, &vault_b) as u128;
+    assert!(k_after > k_before);
+    assert!(pool.protocol_fees_a > 0);
+}
+
+#[test]
+fn test_swap_creates_a_missing_output_account() {
+    let (mut svm, _, keys) = setup();
+    let lp = funded_user(&mut svm, &keys);
+    let ix = instructions::add_liquidity(lp.pubkey(), &keys, 1_000_000_000, 1_000_000_000, 0);
+    send(&mut svm, &[ix], &[&lp]).unwrap();
+
+    // The trader only holds token A
+    let trader = Keypair::new();
+    svm.airdrop(&trader.pubkey(), 10_000_000_000).unwrap();
+    let token_a = pda::ata(&trader.pubkey(), &keys.mint_a, &keys.token_program);
+    set_token_account(
+        &mut svm,
+        token_a,
+        keys.mint_a,
+        trader.pubkey(),
+        WALLET_TOKENS,
+    );
+
+    let ix = instructions::swap(trader.pubkey(), &keys, 1_000_000, 990_000, true);
+    send(&mut svm, &[ix], &[&trader]).unwrap();
+    assert!(balance(&svm, &trader, &keys, &keys.mint_b) >= 990_000);
+}
+
+#[test]
+fn test_route_swap_through_two_pools() {
+    let (mut svm, admin, first) = setup();
+    let second = PoolKeys {
+        mint_a: first.mint_b,
+        mint_b: Pubkey::new_unique(),
+        mint_lp: Pubkey::new_unique(),
+        token_program: TOKEN_PROGRAM_ID,
+    };
+    set_mint(&mut svm, second.mint_b, admin.pubkey());
+    set_mint(&mut svm, second.mint_lp, second.pool());
+    let ix = instructions::initialize_pool(admin.pubkey(), &second, 30, 10_000);
+    send(&mut svm, &[ix], &[&admin]).unwrap();
+
+    let lp = funded_user(&mut svm, &first);
+    let trader = funded_user(&mut svm, &first);
+    for user in [&lp, &trader] {
+        for (mint, amount) in [(second.mint_b, 0), (second.mint_lp, 0)] {
+            let address = pda::ata(&user.pubkey(), &mint, &second.token_program);
+            set_token_account(&mut svm, address, mint, user.pubkey(), amount);
+        }
+    }
+    let lp_c = pda::ata(&lp.pubkey(), &second.mint_b, &second.token_program);
+    set_token_account(&mut svm, lp_c, second.mint_b, lp.pubkey(), WALLET_TOKENS);
+    for keys in [&first, &second] {
+        let ix = instructions::add_liquidity(lp.pubkey(), keys, 1_000_000_000, 1_000_000_000, 0);
+        send(&mut svm, &[ix], &[&lp]).unwrap();
+    }
+
+    let ix = instructions::route_swap(
+        trader.pubkey(),
+        first.mint_a,
+        &[first, second],
+        1_000_000,
+        0,
+    );
+    send(&mut svm, &[ix], &[&trader]).unwrap();
+
+    // Two 0.3% fees plus the price impact of both hops
+    let received = balance(&svm, &trader, &second, &second.mint_b);
+    assert!((980_000..1_000_000).contains(&received));
+    assert_eq!(
+        balance(&svm, &trader, &first, &first.mint_a),
+        WALLET_TOKENS - 1_000_000
+    );
+    assert_eq!(balance(&svm, &trader, &first, &first.mint_b), WALLET_TOKENS);
+}
+
+/// Writes a poll of the pool's pair opened by `authority`, revealed at `revealed_at` with a
+/// consensus price of `consensus_price`, and returns its address.
+fn set_revealed_poll(
+    svm: &mut LiteSVM,
+    keys: &PoolKeys,
+    authority: Pubkey,
+    consensus_price: u64,
+    revealed_at: i64,
+) -> Pubkey {
+    let address = pda::poll(&authority, 1);
+    let poll = PollAccount {
+        bump: 0,
+        vote_state: [[0; 32]; 7],
+        id: 1,
+        authority,
+        nonce: 0,
+        price: consensus_price,
+        mint0: keys.mint_a,
+        mint1: keys.mint_b,
+        consensus_price,
+        revealed_at,
+    };
+    set_program_account(svm, address, &poll);
+    address
+}
+
+#[test]
+fn test_only_polls_of_the_pool_authority_move_the_consensus_price() {
+    let (mut svm, admin, keys) = setup();
+    let stranger = Keypair::new();
+    svm.airdrop(&stranger.pubkey(), 10_000_000_000).unwrap();
+    warp(&mut svm, 100);
+    let now = svm.get_sysvar::<Clock>().unix_timestamp;
+
+    // Anyone can open, vote on and reveal a poll of the pair, but it cannot price the pool
+    let poll = set_revealed_poll(&mut svm, &keys, stranger.pubkey(), 7_000, now);
+    let ix = instructions::apply_poll_price(keys.pool(), poll);
+    assert!(send(&mut svm, &[ix], &[&stranger]).is_err());
+    assert_eq!(pool(&svm, &keys).consensus_price, 0);
+
+    // The admin's poll can, once per reveal, and anyone can crank it
+    let poll = set_revealed_poll(&mut svm, &keys, admin.pubkey(), 5_000, now);
+    let ix = instructions::apply_poll_price(keys.pool(), poll);
+    send(&mut svm, &[ix.clone()], &[&stranger]).unwrap();
+    assert_eq!(pool(&svm, &keys).consensus_price, 5_000);
+
+    warp(&mut svm, 10);
+    let ix = instructions::update_consensus_price(admin.pubkey(), keys.pool(), 6_000);
+    send(&mut svm, &[ix], &[&admin]).unwrap();
+    let ix = instructions::apply_poll_price(keys.pool(), poll);
+    assert!(send(&mut svm, &[ix], &[&stranger]).is_err());
+    assert_eq!(pool(&svm, &keys).consensus_price, 6_000);
+}
+
+/// A pool of two Token-2022 mints that withhold 1% of every transfer, funded with 10^9 of
+/// each, and a trader holding both.
+fn fee_mint_pool() -> (LiteSVM, PoolKeys, Keypair) {
+    let admin = Keypair::new();
+    let mut svm = LiteSVM::new();
+    deploy(&mut svm, admin.pubkey());
+    svm.airdrop(&admin.pubkey(), 10_000_000_000).unwrap();
+    initialize_config(&mut svm, &admin, vec![]);
+
+    let keys = PoolKeys {
+        mint_a: Pubkey::new_unique(),
+        mint_b: Pubkey::new_unique(),
+        mint_lp: Pubkey::new_unique(),
+        token_program: TOKEN_2022_PROGRAM_ID,
+    };
+    set_fee_mint(&mut svm, keys.mint_a, admin.pubkey(), 100);
+    set_fee_mint(&mut svm, keys.mint_b, admin.pubkey(), 100);
+    set_fee_mint(&mut svm, keys.mint_lp, keys.pool(), 0);
+    let ix = instructions::initialize_pool(admin.pubkey(), &keys, 30, 10_000);
+    send(&mut svm, &[ix], &[&admin]).unwrap();
+
+    let [lp, trader] = [Keypair::new(), Keypair::new()];
+    for user in [&lp, &trader] {
+        svm.airdrop(&user.pubkey(), 10_000_000_000).unwrap();
+        for (mint, amount) in [
+            (keys.mint_a, WALLET_TOKENS),
+            (keys.mint_b, WALLET_TOKENS),
+            (keys.mint_lp, 0),
+        ] {
+            let address = pda::ata(&user.pubkey(), &mint, &keys.token_program);
+            set_fee_token_account(&mut svm, address, mint, user.pubkey(), amount);
+        }
+    }
+    let ix = instructions::add_liquidity(lp.pubkey(), &keys, 1_000_000_000, 1_000_000_000, 0);
+    send(&mut svm, &[ix], &[&lp]).unwrap();
+    (svm, keys, trader)
+}
+
+#[test]
+fn test_swap_slippage_bound_is_net_of_the_outgoing_transfer_fee() {
+    let (mut svm, keys, trader) = fee_mint_pool();
+    let ix = instructions::swap(trader.pubkey(), &keys, 1_000_000, 0, true);
+    send(&mut svm, &[ix], &[&trader]).unwrap();
+    let received = balance(&svm, &trader, &keys, &keys.mint_b) - WALLET_TOKENS;
+    // 1% is withheld going in and again coming out, on top of the 0.3% swap fee
+    assert!(received < 980_000);
+
+    // The same swap on an identical pool, bounded by what the trader actually received
+    let (mut svm, keys, trader) = fee_mint_pool();
+    let ix = instructions::swap(trader.pubkey(), &keys, 1_000_000, received + 1, true);
+    assert!(send(&mut svm, &[ix], &[&trader]).is_err());
+    let ix = instructions::swap(trader.pubkey(), &keys, 1_000_000, received, true);
+    send(&mut svm, &[ix], &[&trader]).unwrap();
+    assert_eq!(
+        balance(&svm, &trader, &keys, &keys.mint_b),
+        WALLET_TOKENS + received
+    );
+}
+
+/// A pool of native SOL and another mint, funded with 10^9 of each, and a trader holding
+/// 10^12 of the other mint and 5 * 10^6 wSOL. Returns the other mint too.
+fn native_pool() -> (LiteSVM, PoolKeys, Keypair, Pubkey) {
+    let admin = Keypair::new();
+    let mut svm = LiteSVM::new();
+    deploy(&mut svm, admin.pubkey());
+    svm.airdrop(&admin.pubkey(), 10_000_000_000).unwrap();
+    initialize_config(&mut svm, &admin, vec![]);
+
+    let other = Pubkey::new_unique();
+    let (mint_a, mint_b) = if other < native_mint::ID {
+        (other, native_mint::ID)
+    } else {
+        (native_mint::ID, other)
+    };
+    let keys = PoolKeys {
+        mint_a,
+        mint_b,
+        mint_lp: Pubkey::new_unique(),
+        token_program: TOKEN_PROGRAM_ID,
+    };
+    set_mint(&mut svm, native_mint::ID, admin.pubkey());
+    set_mint(&mut svm, other, admin.pubkey());
+    set_mint(&mut svm, keys.mint_lp, keys.pool());
+    let ix = instructions::initialize_pool(admin.pubkey(), &keys, 30, 10_000);
+    send(&mut svm, &[ix], &[&admin]).unwrap();
+
+    let [lp, trader] = [Keypair::new(), Keypair::new()];
+    for user in [&lp, &trader] {
+        svm.airdrop(&user.pubkey(), 10_000_000_000).unwrap();
+        for (mint, amount) in [(other, WALLET_TOKENS), (keys.mint_lp, 0)] {
+            let address = pda::ata(&user.pubkey(), &mint, &keys.token_program);
+            set_token_account(&mut svm, address, mint, user.pubkey(), amount);
+        }
+    }
+    let wsol = pda::ata(&trader.pubkey(), &native_mint::ID, &keys.token_program);
+    set_wsol_account(&mut svm, wsol, trader.pubkey(), 5_000_000);
+    let ix = instructions::add_liquidity(lp.pubkey(), &keys, 1_000_000_000, 1_000_000_000, 0);
+    send(&mut svm, &[ix], &[&lp]).unwrap();
+    (svm, keys, trader, other)
+}
+
+#[test]
+fn test_native_sol_swaps_leave_the_wsol_account_alone() {
+    let (mut svm, keys, trader, other) = native_pool();
+    let wsol = pda::ata(&trader.pubkey(), &native_mint::ID, &keys.token_program);
+    let to_native = keys.mint_a == other;
+
+    // The SOL bought arrives as lamports; 5_000 lamports pay the transaction fee
+    let lamports = svm.get_balance(&trader.pubkey()).unwrap();
+    let ix = instructions::swap(trader.pubkey(), &keys, 1_000_000, 990_000, to_native);
+    send(&mut svm, &[ix], &[&trader]).unwrap();
+    let received = svm.get_balance(&trader.pubkey()).unwrap() + 5_000 - lamports;
+    assert!((990_000..1_000_000).contains(&received));
+    assert_eq!(token_balance(&svm, &wsol), 5_000_000);
+    let unwrap_account = pda::unwrap(&trader.pubkey());
+    assert_eq!(svm.get_balance(&unwrap_account).unwrap_or(0), 0);
+
+    // The SOL sold is wrapped from lamports rather than taken from the wSOL balance
+    let lamports = svm.get_balance(&trader.pubkey()).unwrap();
+    let ix = instructions::swap(trader.pubkey(), &keys, 1_000_000, 0, !to_native);
+    send(&mut svm, &[ix], &[&trader]).unwrap();
+    assert_eq!(
+        svm.get_balance(&trader.pubkey()).unwrap(),
+        lamports - 1_000_000 - 5_000
+    );
+    assert_eq!(token_balance(&svm, &wsol), 5_000_000);
+}
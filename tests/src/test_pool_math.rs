@@ -0,0 +1,85 @@
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use rational_dex::instructions::pool::{
+    constant_product_out, initial_lp, integer_sqrt, mul_div_ceil, mul_div_floor,
+    protocol_fee_share, swap_fee,
+};
+use rational_dex::MINIMUM_LIQUIDITY;
+
+const SEED: u64 = 0x5eed;
+
+#[test]
+fn test_swap_fee_rounds_down() {
+    assert_eq!(swap_fee(1_000, 30, 10_000).unwrap(), 3);
+    assert_eq!(swap_fee(333, 30, 10_000).unwrap(), 0);
+    assert_eq!(swap_fee(u64::MAX, 1, 1).unwrap(), u64::MAX);
+    assert!(swap_fee(1_000, 30, 0).is_err());
+    assert!(swap_fee(u64::MAX, 2, 1).is_err());
+}
+
+#[test]
+fn test_protocol_fee_share_is_a_fifth_of_the_fee() {
+    assert_eq!(protocol_fee_share(100), 20);
+    assert_eq!(protocol_fee_share(4), 0);
+    assert_eq!(protocol_fee_share(u64::MAX), u64::MAX / 5);
+}
+
+#[test]
+fn test_constant_product_never_decreases() {
+    let mut rng = StdRng::seed_from_u64(SEED);
+    for _ in 0..1_000 {
+        let reserve_in = rng.gen_range(1..=u64::MAX / 2);
+        let reserve_out = rng.gen_range(1..=u64::MAX);
+        let amount_in = rng.gen_range(0..=u64::MAX / 2);
+        let out = constant_product_out(amount_in, reserve_in, reserve_out).unwrap();
+        assert!(out < reserve_out);
+        let k = reserve_in as u128 * reserve_out as u128;
+        let new_k = (reserve_in + amount_in) as u128 * (reserve_out - out) as u128;
+        assert!(new_k >= k);
+    }
+}
+
+#[test]
+fn test_constant_product_needs_both_reserves() {
+    assert_eq!(constant_product_out(100, 1_000, 1_000).unwrap(), 90);
+    assert!(constant_product_out(100, 0, 1_000).is_err());
+    assert!(constant_product_out(100, 1_000, 0).is_err());
+}
+
+#[test]
+fn test_mul_div_rounding() {
+    assert_eq!(mul_div_floor(10, 10, 3).unwrap(), 33);
+    assert_eq!(mul_div_ceil(10, 10, 3).unwrap(), 34);
+    assert_eq!(mul_div_ceil(10, 9, 3).unwrap(), 30);
+    assert_eq!(
+        mul_div_floor(u64::MAX, u64::MAX, u64::MAX).unwrap(),
+        u64::MAX
+    );
+    assert!(mul_div_floor(u64::MAX, 2, 1).is_err());
+    assert!(mul_div_ceil(1, 1, 0).is_err());
+}
+
+#[test]
+fn test_integer_sqrt_is_the_floor() {
+    let mut rng = StdRng::seed_from_u64(SEED);
+    for value in (0..100).chain((0..1_000).map(|_| rng.gen_range(0..u128::MAX))) {
+        let root = integer_sqrt(value);
+        assert!(root * root <= value);
+        assert!((root + 1)
+            .checked_mul(root + 1)
+            .map_or(true, |next| next > value));
+    }
+    let max = u64::MAX as u128;
+    assert_eq!(integer_sqrt(max * max), max);
+}
+
+#[test]
+fn test_initial_lp_locks_minimum_liquidity() {
+    assert_eq!(
+        initial_lp(1_000_000, 4_000_000).unwrap(),
+        2_000_000 - MINIMUM_LIQUIDITY
+    );
+    // Deposits too small to cover the locked minimum mint nothing
+    assert!(initial_lp(MINIMUM_LIQUIDITY, MINIMUM_LIQUIDITY).is_err());
+    assert!(initial_lp(1, u64::MAX).unwrap() > 0);
+}
@@ -0,0 +1,138 @@
+use anchor_lang::prelude::Pubkey;
+use rational_dex::instructions::pool::{
+    accumulate_prices, blend_prices, check_reference_band, market_twap, validate_blend_policy,
+    write_observation,
+};
+use rational_dex::instructions::swap::reference_prices;
+use rational_dex::state::pool_state::BlendPolicy;
+use rational_dex::{PRICE_SCALE, REFERENCE_TWAP_WINDOW};
+
+use crate::common::{observation_buffer, pool_state};
+
+fn policy(consensus_weight_bps: u16, max_deviation_bps: u16) -> BlendPolicy {
+    BlendPolicy {
+        consensus_weight_bps,
+        max_deviation_bps,
+        circuit_breaker_bps: 2_000,
+        reject_outside_band: false,
+    }
+}
+
+#[test]
+fn test_blend_weights_both_prices() {
+    assert_eq!(
+        blend_prices(1_000, 2_000, &policy(5_000, 10_000)).unwrap(),
+        1_500
+    );
+    assert_eq!(
+        blend_prices(1_000, 2_000, &policy(10_000, 10_000)).unwrap(),
+        2_000
+    );
+    assert_eq!(
+        blend_prices(1_000, 2_000, &policy(0, 10_000)).unwrap(),
+        1_000
+    );
+}
+
+#[test]
+fn test_blend_stays_within_deviation_band() {
+    // 1_500 is 25% below the consensus price, the band allows 10%
+    assert_eq!(
+        blend_prices(1_000, 2_000, &policy(5_000, 1_000)).unwrap(),
+        1_800
+    );
+    assert_eq!(
+        blend_prices(3_000, 2_000, &policy(5_000, 1_000)).unwrap(),
+        2_200
+    );
+}
+
+#[test]
+fn test_blend_falls_back_to_the_price_that_is_set() {
+    assert_eq!(
+        blend_prices(1_000, 0, &policy(5_000, 1_000)).unwrap(),
+        1_000
+    );
+    assert_eq!(
+        blend_prices(0, 2_000, &policy(5_000, 1_000)).unwrap(),
+        2_000
+    );
+}
+
+#[test]
+fn test_market_twap_ignores_a_last_second_spike() {
+    let mut pool = pool_state(1_000, 0, 1);
+    let mut buffer = observation_buffer();
+    write_observation(&mut buffer, &pool);
+
+    // The price held for the whole window, then a swap spikes it tenfold
+    accumulate_prices(&mut pool, 1 + REFERENCE_TWAP_WINDOW).unwrap();
+    write_observation(&mut buffer, &pool);
+    pool.last_market_price = 10_000;
+    assert_eq!(market_twap(&pool, &buffer).unwrap(), 1_000);
+
+    // A minute later the spike weighs in for that minute only, over all the history there is
+    accumulate_prices(&mut pool, 61 + REFERENCE_TWAP_WINDOW).unwrap();
+    let expected = (1_000 * REFERENCE_TWAP_WINDOW + 10_000 * 60) / (REFERENCE_TWAP_WINDOW + 60);
+    assert_eq!(market_twap(&pool, &buffer).unwrap(), expected as u64);
+}
+
+#[test]
+fn test_market_twap_without_history_is_the_spot_price() {
+    let pool = pool_state(1_000, 0, 1);
+    assert_eq!(market_twap(&pool, &observation_buffer()).unwrap(), 1_000);
+}
+
+#[test]
+fn test_reference_band_only_bounds_swaps_when_enabled() {
+    let mut pool = pool_state(1_000, 0, 1);
+    pool.reference_price = 1_000;
+    pool.blend_policy = policy(5_000, 1_000);
+    assert!(check_reference_band(&pool, 1_000, 1_000_000).is_ok());
+
+    pool.blend_policy.reject_outside_band = true;
+
+    // Within 20% of the reference price
+    assert!(check_reference_band(&pool, 1_000, 1_200).is_ok());
+    assert!(check_reference_band(&pool, 1_000, 800).is_ok());
+    assert!(check_reference_band(&pool, 1_000, 1_201).is_err());
+    // Outside the band, but back towards the reference price
+    assert!(check_reference_band(&pool, 2_000, 1_500).is_ok());
+    assert!(check_reference_band(&pool, 1_500, 1_600).is_err());
+
+    pool.reference_price = 0;
+    assert!(check_reference_band(&pool, 1_000, 1_000_000).is_ok());
+}
+
+#[test]
+fn test_blend_policy_bounds() {
+    assert!(validate_blend_policy(&policy(10_000, 10_000)).is_ok());
+    assert!(validate_blend_policy(&policy(10_001, 1_000)).is_err());
+    assert!(validate_blend_policy(&policy(5_000, 10_001)).is_err());
+    for circuit_breaker_bps in [0, 10_001, u16::MAX] {
+        let policy = BlendPolicy {
+            circuit_breaker_bps,
+            ..policy(5_000, 1_000)
+        };
+        assert!(validate_blend_policy(&policy).is_err());
+    }
+}
+
+#[test]
+fn test_reference_prices_follow_the_pair_order() {
+    let mut pool = pool_state(0, 0, 1);
+    pool.mint_a = Pubkey::new_unique();
+    pool.mint_b = Pubkey::new_unique();
+    pool.reference_price = 2 * PRICE_SCALE as u64;
+
+    let scale = PRICE_SCALE as u64;
+    assert_eq!(
+        reference_prices(&pool, &pool.mint_a, &pool.mint_b).unwrap(),
+        (2 * scale, scale)
+    );
+    assert_eq!(
+        reference_prices(&pool, &pool.mint_b, &pool.mint_a).unwrap(),
+        (scale, 2 * scale)
+    );
+    assert!(reference_prices(&pool, &pool.mint_a, &Pubkey::new_unique()).is_err());
+}
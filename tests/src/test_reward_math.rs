@@ -0,0 +1,121 @@
+use rational_dex::instructions::rewards::{
+    accrue_rewards, settle_stake, vote_earned_before_expiry,
+};
+use rational_dex::state::reward_state::{RewardProgram, RewardStake};
+
+use solana_pubkey::Pubkey;
+
+const ONE_X64: u128 = 1 << 64;
+
+/// 100 tokens per second until `period_end`, a fifth of them to LP stakers.
+fn program(lp_total_weight: u64, vote_total_weight: u64, period_end: i64) -> RewardProgram {
+    RewardProgram {
+        pool: Pubkey::default(),
+        reward_mint: Pubkey::default(),
+        reward_vault: Pubkey::default(),
+        lp_vault: Pubkey::default(),
+        lp_share_bps: 2_000,
+        reward_rate: 100,
+        period_end,
+        last_update: 0,
+        lp_total_weight,
+        vote_total_weight,
+        reward_per_lp_x64: 0,
+        reward_per_vote_x64: 0,
+        bump: 0,
+    }
+}
+
+fn stake(lp_staked: u64, vote_weight: u64, vote_expires_at: i64) -> RewardStake {
+    RewardStake {
+        reward_program: Pubkey::default(),
+        owner: Pubkey::default(),
+        lp_staked,
+        vote_proposal: Pubkey::default(),
+        vote_weight,
+        vote_expires_at,
+        reward_per_lp_checkpoint_x64: 0,
+        reward_per_vote_checkpoint_x64: 0,
+        rewards_owed: 0,
+        settled_at: 0,
+        bump: 0,
+    }
+}
+
+#[test]
+fn test_accrual_splits_the_stream_by_lp_share() {
+    let mut program = program(100, 400, 1_000);
+    accrue_rewards(&mut program, 10).unwrap();
+    // 1_000 emitted: 200 over 100 LP tokens, 800 over 400 vote weight
+    assert_eq!(program.reward_per_lp_x64, 2 * ONE_X64);
+    assert_eq!(program.reward_per_vote_x64, 2 * ONE_X64);
+    assert_eq!(program.last_update, 10);
+}
+
+#[test]
+fn test_accrual_stops_at_the_period_end() {
+    let mut program = program(100, 400, 5);
+    accrue_rewards(&mut program, 10).unwrap();
+    assert_eq!(program.reward_per_lp_x64, ONE_X64);
+    assert_eq!(program.last_update, 10);
+
+    accrue_rewards(&mut program, 20).unwrap();
+    assert_eq!(program.reward_per_lp_x64, ONE_X64);
+}
+
+#[test]
+fn test_a_side_without_weight_earns_nothing() {
+    let mut program = program(0, 400, 1_000);
+    accrue_rewards(&mut program, 10).unwrap();
+    assert_eq!(program.reward_per_lp_x64, 0);
+    assert_eq!(program.reward_per_vote_x64, 2 * ONE_X64);
+}
+
+#[test]
+fn test_settling_credits_both_weights_and_moves_the_checkpoints() {
+    let mut program = program(100, 400, 1_000);
+    let mut stake = stake(50, 100, 1_000);
+    accrue_rewards(&mut program, 10).unwrap();
+    settle_stake(&mut stake, &mut program, 10).unwrap();
+
+    assert_eq!(stake.rewards_owed, 100 + 200);
+    assert_eq!(
+        stake.reward_per_lp_checkpoint_x64,
+        program.reward_per_lp_x64
+    );
+    assert_eq!(
+        stake.reward_per_vote_checkpoint_x64,
+        program.reward_per_vote_x64
+    );
+    assert_eq!(stake.settled_at, 10);
+
+    // Nothing new to credit at the same time
+    settle_stake(&mut stake, &mut program, 10).unwrap();
+    assert_eq!(stake.rewards_owed, 300);
+}
+
+#[test]
+fn test_an_expired_vote_earns_until_its_expiry_and_loses_its_weight() {
+    let mut program = program(0, 100, 1_000);
+    let mut stake = stake(0, 100, 5);
+    accrue_rewards(&mut program, 10).unwrap();
+    settle_stake(&mut stake, &mut program, 10).unwrap();
+
+    // 800 streamed to the vote over 10 seconds, of which the first 5 count
+    assert_eq!(stake.rewards_owed, 400);
+    assert_eq!(stake.vote_weight, 0);
+    assert_eq!(program.vote_total_weight, 0);
+
+    accrue_rewards(&mut program, 20).unwrap();
+    settle_stake(&mut stake, &mut program, 20).unwrap();
+    assert_eq!(stake.rewards_owed, 400);
+}
+
+#[test]
+fn test_vote_earnings_before_expiry() {
+    assert_eq!(vote_earned_before_expiry(1_000, 0, 20, 10).unwrap(), 1_000);
+    assert_eq!(vote_earned_before_expiry(1_000, 0, 10, 10).unwrap(), 1_000);
+    assert_eq!(vote_earned_before_expiry(1_000, 0, 5, 10).unwrap(), 500);
+    assert_eq!(vote_earned_before_expiry(1_000, 0, 1, 3).unwrap(), 333);
+    assert_eq!(vote_earned_before_expiry(1_000, 5, 5, 10).unwrap(), 0);
+}
@@ -0,0 +1,169 @@
+use encrypted_ixs::reference;
+use rational_dex::state::sealed_offer_state::{
+    SealedBid, SealedBidStatus, SealedOffer, SealedOfferStatus,
+};
+use rational_dex_client::instructions::{self, SealedOfferKeys};
+use rational_dex_client::{pda, PROGRAM_ID};
+
+use {
+    litesvm::LiteSVM, solana_keypair::Keypair, solana_pubkey::Pubkey, solana_signer::Signer,
+    spl_token::ID as TOKEN_PROGRAM_ID,
+};
+
+use crate::common::{
+    deploy, send, set_mint, set_program_account, set_token_account, token_balance,
+};
+
+const OFFERED: u64 = 1_000;
+const DEPOSIT: u64 = 5_000;
+
+#[test]
+fn test_bid_meeting_the_ask_settles_at_the_ask() {
+    assert_eq!(reference::match_sealed_bid(3_000, 5_000, 2_000), 2_000);
+    assert_eq!(reference::match_sealed_bid(2_000, 2_000, 2_000), 2_000);
+}
+
+#[test]
+fn test_bid_below_the_ask_or_deposit_does_not_match() {
+    assert_eq!(reference::match_sealed_bid(1_999, 5_000, 2_000), 0);
+    // The bid meets the ask, but the escrow could not pay it
+    assert_eq!(reference::match_sealed_bid(3_000, 1_999, 2_000), 0);
+    // An ask of nothing is never matched
+    assert_eq!(reference::match_sealed_bid(3_000, 5_000, 0), 0);
+}
+
+/// An offer in `status` with its token A escrowed, and `taker`'s bid resolved as `bid_status`
+/// with `settlement_amount`, written straight to the accounts MPC would have left behind.
+fn setup(
+    offer_status: SealedOfferStatus,
+    bid_status: SealedBidStatus,
+    settlement_amount: u64,
+) -> (LiteSVM, SealedOfferKeys, Keypair, Keypair) {
+    let mut svm = LiteSVM::new();
+    let maker = Keypair::new();
+    let taker = Keypair::new();
+    deploy(&mut svm, maker.pubkey());
+    for wallet in [&maker, &taker] {
+        svm.airdrop(&wallet.pubkey(), 10_000_000_000).unwrap();
+    }
+
+    let keys = SealedOfferKeys {
+        maker: maker.pubkey(),
+        id: 7,
+        token_mint_a: Pubkey::new_unique(),
+        token_mint_b: Pubkey::new_unique(),
+        token_program: TOKEN_PROGRAM_ID,
+    };
+    set_mint(&mut svm, keys.token_mint_a, maker.pubkey());
+    set_mint(&mut svm, keys.token_mint_b, maker.pubkey());
+
+    let (sealed_offer, offer_bump) = Pubkey::find_program_address(
+        &[
+            b"sealed_offer",
+            maker.pubkey().as_ref(),
+            &keys.id.to_le_bytes(),
+        ],
+        &PROGRAM_ID,
+    );
+    let offer = SealedOffer {
+        bump: offer_bump,
+        ask_state: [[0; 32]; 1],
+        nonce: 0,
+        id: keys.id,
+        maker: maker.pubkey(),
+        token_mint_a: keys.token_mint_a,
+        token_mint_b: keys.token_mint_b,
+        offered_amount: OFFERED,
+        status: offer_status,
+    };
+    set_program_account(&mut svm, sealed_offer, &offer);
+    set_token_account(
+        &mut svm,
+        keys.vault(),
+        keys.token_mint_a,
+        sealed_offer,
+        OFFERED,
+    );
+
+    let (sealed_bid, bid_bump) = Pubkey::find_program_address(
+        &[
+            b"sealed_bid",
+            sealed_offer.as_ref(),
+            taker.pubkey().as_ref(),
+        ],
+        &PROGRAM_ID,
+    );
+    let bid = SealedBid {
+        sealed_offer,
+        taker: taker.pubkey(),
+        deposit: DEPOSIT,
+        status: bid_status,
+        settlement_amount,
+        bump: bid_bump,
+    };
+    set_program_account(&mut svm, sealed_bid, &bid);
+    let bid_vault = pda::ata(&sealed_bid, &keys.token_mint_b, &keys.token_program);
+    set_token_account(&mut svm, bid_vault, keys.token_mint_b, sealed_bid, DEPOSIT);
+    let taker_a = pda::ata(&taker.pubkey(), &keys.token_mint_a, &keys.token_program);
+    set_token_account(&mut svm, taker_a, keys.token_mint_a, taker.pubkey(), 0);
+
+    (svm, keys, maker, taker)
+}
+
+fn balance(svm: &LiteSVM, owner: &Pubkey, mint: &Pubkey) -> u64 {
+    token_balance(svm, &pda::ata(owner, mint, &TOKEN_PROGRAM_ID))
+}
+
+#[test]
+fn test_matched_bid_pays_the_maker_and_refunds_the_rest() {
+    let (mut svm, keys, maker, taker) =
+        setup(SealedOfferStatus::Filled, SealedBidStatus::Matched, 2_000);
+
+    let ix = instructions::settle_sealed_bid(taker.pubkey(), &keys, taker.pubkey(), true);
+    send(&mut svm, &[ix], &[&taker]).unwrap();
+
+    assert_eq!(balance(&svm, &maker.pubkey(), &keys.token_mint_b), 2_000);
+    assert_eq!(balance(&svm, &taker.pubkey(), &keys.token_mint_a), OFFERED);
+    assert_eq!(
+        balance(&svm, &taker.pubkey(), &keys.token_mint_b),
+        DEPOSIT - 2_000
+    );
+    let sealed_bid = keys.sealed_bid(&taker.pubkey());
+    for closed in [keys.vault(), sealed_bid] {
+        assert!(svm
+            .get_account(&closed)
+            .map_or(true, |account| account.lamports == 0));
+    }
+}
+
+#[test]
+fn test_rejected_bid_is_refunded_in_full() {
+    let (mut svm, keys, maker, taker) =
+        setup(SealedOfferStatus::Open, SealedBidStatus::Rejected, 0);
+
+    let ix = instructions::settle_sealed_bid(maker.pubkey(), &keys, taker.pubkey(), false);
+    send(&mut svm, &[ix], &[&maker]).unwrap();
+
+    assert_eq!(balance(&svm, &taker.pubkey(), &keys.token_mint_b), DEPOSIT);
+    assert_eq!(balance(&svm, &maker.pubkey(), &keys.token_mint_b), 0);
+    // The offer keeps its escrow for other bids
+    assert_eq!(token_balance(&svm, &keys.vault()), OFFERED);
+}
+
+#[test]
+fn test_pending_bid_waits_until_the_offer_is_cancelled() {
+    let (mut svm, keys, maker, taker) = setup(SealedOfferStatus::Open, SealedBidStatus::Pending, 0);
+
+    let settle = instructions::settle_sealed_bid(taker.pubkey(), &keys, taker.pubkey(), false);
+    assert!(send(&mut svm, &[settle.clone()], &[&taker]).is_err());
+
+    let maker_a = pda::ata(&maker.pubkey(), &keys.token_mint_a, &keys.token_program);
+    set_token_account(&mut svm, maker_a, keys.token_mint_a, maker.pubkey(), 0);
+    let ix = instructions::cancel_sealed_offer(&keys);
+    send(&mut svm, &[ix], &[&maker]).unwrap();
+    assert_eq!(balance(&svm, &maker.pubkey(), &keys.token_mint_a), OFFERED);
+
+    svm.expire_blockhash();
+    send(&mut svm, &[settle], &[&taker]).unwrap();
+    assert_eq!(balance(&svm, &taker.pubkey(), &keys.token_mint_b), DEPOSIT);
+}
@@ -0,0 +1,88 @@
+use rational_dex::instructions::pool::{
+    accumulate_prices, average_price, market_price, observation_before, write_observation,
+};
+use rational_dex::{OBSERVATION_CAPACITY, PRICE_SCALE};
+
+use crate::common::{observation_buffer, pool_state};
+
+#[test]
+fn test_market_price() {
+    assert_eq!(market_price(0, 1_000), 0);
+    assert_eq!(market_price(2_000, 1_000), PRICE_SCALE as u64 / 2);
+    assert_eq!(market_price(1, 1), PRICE_SCALE as u64);
+}
+
+#[test]
+fn test_market_price_saturates() {
+    // 1e9 * 2^64 / 1 is far beyond 64 bits
+    assert_eq!(market_price(1, u64::MAX), u64::MAX);
+    assert_eq!(market_price(u64::MAX, 1), 0);
+}
+
+#[test]
+fn test_accumulators_weight_prices_by_time() {
+    let mut pool = pool_state(100, 300, 1_000);
+    accumulate_prices(&mut pool, 1_010).unwrap();
+    pool.last_market_price = 200;
+    accumulate_prices(&mut pool, 1_040).unwrap();
+
+    assert_eq!(pool.market_price_cumulative, 100 * 10 + 200 * 30);
+    assert_eq!(pool.consensus_price_cumulative, 300 * 40);
+    assert_eq!(pool.last_cumulative_update, 1_040);
+    // (1_000 + 6_000) / 40 seconds
+    assert_eq!(
+        average_price(pool.market_price_cumulative, 0, 40).unwrap(),
+        175
+    );
+}
+
+#[test]
+fn test_accumulators_start_on_first_update() {
+    let mut pool = pool_state(100, 300, 0);
+    accumulate_prices(&mut pool, 1_000).unwrap();
+    assert_eq!(pool.market_price_cumulative, 0);
+    assert_eq!(pool.last_cumulative_update, 1_000);
+}
+
+#[test]
+fn test_average_price_across_wrapped_accumulator() {
+    let then = u128::MAX - 50;
+    let now = then.wrapping_add(100 * 10);
+    assert_eq!(average_price(now, then, 10).unwrap(), 100);
+    assert!(average_price(now, then, 0).is_err());
+    assert!(average_price(u64::MAX as u128 * 2, 0, 1).is_err());
+}
+
+#[test]
+fn test_observations_keep_one_per_second() {
+    let mut buffer = observation_buffer();
+    let mut pool = pool_state(100, 100, 1);
+    write_observation(&mut buffer, &pool);
+    write_observation(&mut buffer, &pool);
+    assert_eq!(buffer.len, 1);
+
+    accumulate_prices(&mut pool, 2).unwrap();
+    write_observation(&mut buffer, &pool);
+    assert_eq!((buffer.head, buffer.len), (1, 2));
+}
+
+#[test]
+fn test_observation_ring_overwrites_the_oldest() {
+    let mut buffer = observation_buffer();
+    let mut pool = pool_state(100, 100, 1);
+    let writes = OBSERVATION_CAPACITY as i64 + 10;
+    for now in 1..=writes {
+        accumulate_prices(&mut pool, now).unwrap();
+        write_observation(&mut buffer, &pool);
+    }
+    assert_eq!(buffer.len as usize, OBSERVATION_CAPACITY);
+
+    // The first 10 observations are gone
+    assert!(observation_before(&buffer, 10).is_none());
+    assert_eq!(observation_before(&buffer, 11).unwrap().timestamp, 11);
+    assert_eq!(observation_before(&buffer, 50).unwrap().timestamp, 50);
+    assert_eq!(
+        observation_before(&buffer, i64::MAX).unwrap().timestamp,
+        writes
+    );
+}